@@ -0,0 +1,174 @@
+use lance_index::scalar::inverted::tokenizer::lance_tokenizer::LanceTokenizer;
+use libc::c_char;
+use lancedb::index::scalar::FtsIndexBuilder;
+use lancedb::index::IndexType;
+use lancedb::table::Table;
+use serde::Serialize;
+use sonic_rs::JsonValueTrait;
+use std::ffi::CString;
+
+use crate::ffi;
+
+/// Builds FTS index parameters from the `config_json` object accepted by
+/// `table_create_index` for an FTS index. Unset keys keep lancedb's defaults.
+pub(crate) fn fts_params(config: &sonic_rs::Value) -> Result<FtsIndexBuilder, String> {
+    let mut builder = FtsIndexBuilder::default();
+    if let Some(v) = config.get("with_position").and_then(|v| v.as_bool()) {
+        builder = builder.with_position(v);
+    }
+    if let Some(v) = config.get("base_tokenizer").and_then(|v| v.as_str()) {
+        builder = builder.base_tokenizer(v.to_string());
+    }
+    if let Some(v) = config.get("language").and_then(|v| v.as_str()) {
+        builder = builder.language(v).map_err(|e| e.to_string())?;
+    }
+    if let Some(v) = config.get("max_token_length").and_then(|v| v.as_u64()) {
+        builder = builder.max_token_length(Some(v as usize));
+    }
+    if let Some(v) = config.get("lower_case").and_then(|v| v.as_bool()) {
+        builder = builder.lower_case(v);
+    }
+    if let Some(v) = config.get("stem").and_then(|v| v.as_bool()) {
+        builder = builder.stem(v);
+    }
+    if let Some(v) = config.get("remove_stop_words").and_then(|v| v.as_bool()) {
+        builder = builder.remove_stop_words(v);
+    }
+    if let Some(v) = config.get("ascii_folding").and_then(|v| v.as_bool()) {
+        builder = builder.ascii_folding(v);
+    }
+    if let Some(v) = config.get("ngram_min_length").and_then(|v| v.as_u64()) {
+        builder = builder.ngram_min_length(v as u32);
+    }
+    if let Some(v) = config.get("ngram_max_length").and_then(|v| v.as_u64()) {
+        builder = builder.ngram_max_length(v as u32);
+    }
+    if let Some(v) = config.get("prefix_only").and_then(|v| v.as_bool()) {
+        builder = builder.ngram_prefix_only(v);
+    }
+    Ok(builder)
+}
+
+/// Identifies an FTS index of a table.
+pub(crate) enum FtsIndexRef<'a> {
+    /// The FTS index on this column.
+    Column(&'a str),
+    /// The index with this name, which must be an FTS index.
+    Name(&'a str),
+}
+
+/// Reads the tokenizer parameters an FTS index was built with, from the index
+/// details reported by `list_indices`.
+pub(crate) async fn index_fts_params(table: &Table, index: FtsIndexRef<'_>) -> Result<FtsIndexBuilder, String> {
+    let indices = table.list_indices().await.map_err(|e| e.to_string())?;
+    let found = match index {
+        FtsIndexRef::Column(column) => indices
+            .into_iter()
+            .find(|i| i.index_type == IndexType::FTS && i.columns == [column])
+            .ok_or_else(|| format!("Column '{}' has no FTS index", column))?,
+        FtsIndexRef::Name(name) => {
+            let found = indices
+                .into_iter()
+                .find(|i| i.name == name)
+                .ok_or_else(|| format!("Index '{}' does not exist", name))?;
+            if found.index_type != IndexType::FTS {
+                return Err(format!("Index '{}' is not an FTS index", name));
+            }
+            found
+        }
+    };
+    let details = found
+        .index_details
+        .ok_or_else(|| format!("Index '{}' does not report its tokenizer configuration", found.name))?;
+    sonic_rs::from_str(&details).map_err(|e| format!("Invalid details for index '{}': {}", found.name, e))
+}
+
+/// One token produced by an FTS tokenizer.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct AnalyzedToken {
+    pub text: String,
+    /// Position of the token in the token stream, as used by phrase queries.
+    pub position: usize,
+    /// Byte offset in the UTF-8 text where the token starts.
+    pub start: usize,
+    /// Byte offset in the UTF-8 text just past the end of the token.
+    pub end: usize,
+}
+
+/// Tokenizes text the way an FTS index with the given parameters does.
+pub(crate) struct Analyzer {
+    tokenizer: Box<dyn LanceTokenizer>,
+}
+
+impl Analyzer {
+    pub(crate) fn new(params: &FtsIndexBuilder) -> Result<Self, String> {
+        let tokenizer = params.build().map_err(|e| format!("Invalid FTS tokenizer configuration: {}", e))?;
+        Ok(Self { tokenizer })
+    }
+
+    /// Tokens of a document, as stored in the index.
+    pub(crate) fn document_tokens(&mut self, text: &str) -> Vec<AnalyzedToken> {
+        let mut tokens = Vec::new();
+        let mut stream = self.tokenizer.token_stream_for_doc(text);
+        while stream.advance() {
+            let token = stream.token();
+            tokens.push(AnalyzedToken {
+                text: token.text.clone(),
+                position: token.position,
+                start: token.offset_from,
+                end: token.offset_to,
+            });
+        }
+        tokens
+    }
+
+    /// Tokens of query text, as looked up in the index at search time.
+    pub(crate) fn query_tokens(&mut self, text: &str) -> Vec<AnalyzedToken> {
+        let mut tokens = Vec::new();
+        let mut stream = self.tokenizer.token_stream_for_search(text);
+        while stream.advance() {
+            let token = stream.token();
+            tokens.push(AnalyzedToken {
+                text: token.text.clone(),
+                position: token.position,
+                start: token.offset_from,
+                end: token.offset_to,
+            });
+        }
+        tokens
+    }
+}
+
+
+/// Tokenizes `text` the way `analyzer` indexes documents and returns the tokens
+/// as a JSON array of `{"text", "position", "start", "end"}` objects.
+pub(crate) fn analyze_to_json(analyzer: &mut Analyzer, text: &str) -> String {
+    sonic_rs::to_string(&analyzer.document_tokens(text)).unwrap_or_default()
+}
+
+fn analyze_with_config(config_json: *const c_char, text: &str) -> Result<String, String> {
+    let config: sonic_rs::Value = if config_json.is_null() {
+        sonic_rs::Value::new_object()
+    } else {
+        sonic_rs::from_str(&ffi::to_string(config_json)).map_err(|e| format!("Invalid FTS config JSON: {}", e))?
+    };
+    let mut analyzer = Analyzer::new(&fts_params(&config)?)?;
+    Ok(analyze_to_json(&mut analyzer, text))
+}
+
+/// Tokenizes `text` with an FTS tokenizer built from `config_json`, the same
+/// object `table_create_index` accepts for an FTS index (null for the defaults).
+/// Returns the tokens as a JSON array of `{"text", "position", "start", "end"}`
+/// objects, where `start` and `end` are UTF-8 byte offsets into `text`. The caller
+/// must free it with free_string(). Returns null and sets the last error when the
+/// configuration is invalid.
+#[unsafe(no_mangle)]
+pub extern "C" fn fts_analyze(config_json: *const c_char, text: *const c_char) -> *mut c_char {
+    match analyze_with_config(config_json, &ffi::to_string(text)) {
+        Ok(json) => CString::new(json).unwrap_or_default().into_raw(),
+        Err(e) => {
+            ffi::set_last_error(e);
+            std::ptr::null_mut()
+        }
+    }
+}
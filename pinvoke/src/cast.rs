@@ -0,0 +1,325 @@
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::compute::{cast_with_options, CastOptions};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Schema options accepted by the create-table exports as `create_options_json`.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct CreateSchemaOptions {
+    /// Vector columns and their dimensions. Each must be a list of floats in the
+    /// schema and is stored as a fixed-size list of that dimension.
+    #[serde(default)]
+    pub vector_columns: HashMap<String, i32>,
+    /// Key-value metadata stored in the table schema.
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+impl CreateSchemaOptions {
+    /// Parses `create_options_json`; a null pointer yields the defaults.
+    pub fn parse(json: *const libc::c_char) -> Result<Self, String> {
+        if json.is_null() {
+            return Ok(Self::default());
+        }
+        let json = crate::ffi::to_string(json);
+        if json.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        sonic_rs::from_str(&json).map_err(|e| format!("Invalid create options: {}", e))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vector_columns.is_empty() && self.metadata.is_empty()
+    }
+
+    /// Returns `schema` with the vector columns as fixed-size lists of their
+    /// dimension and the metadata merged into the schema metadata.
+    pub fn apply(&self, schema: &Schema) -> Result<Schema, String> {
+        for name in self.vector_columns.keys() {
+            if schema.field_with_name(name).is_err() {
+                return Err(format!("Vector column '{}' does not exist in the schema", name));
+            }
+        }
+        let fields = schema
+            .fields()
+            .iter()
+            .map(|field| match self.vector_columns.get(field.name()) {
+                Some(&dimension) => vector_field(field, dimension),
+                None => Ok(field.as_ref().clone()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut metadata = schema.metadata().clone();
+        metadata.extend(self.metadata.clone());
+        Ok(Schema::new_with_metadata(fields, metadata))
+    }
+}
+
+/// `field` as a fixed-size list of `dimension` floats.
+fn vector_field(field: &Field, dimension: i32) -> Result<Field, String> {
+    if dimension <= 0 {
+        return Err(format!("Vector column '{}' must have a positive dimension", field.name()));
+    }
+    let item = match field.data_type() {
+        DataType::List(item) | DataType::LargeList(item) => item,
+        DataType::FixedSizeList(item, n) if *n == dimension => item,
+        DataType::FixedSizeList(_, n) => {
+            return Err(format!(
+                "Vector column '{}' has dimension {} in the schema but {} was given",
+                field.name(),
+                n,
+                dimension
+            ));
+        }
+        other => {
+            return Err(format!("Vector column '{}' must be a list of floats, not {}", field.name(), other));
+        }
+    };
+    if !item.data_type().is_floating() {
+        return Err(format!(
+            "Vector column '{}' must be a list of floats, not a list of {}",
+            field.name(),
+            item.data_type()
+        ));
+    }
+    Ok(
+        Field::new(field.name(), DataType::FixedSizeList(item.clone(), dimension), field.is_nullable())
+            .with_metadata(field.metadata().clone()),
+    )
+}
+
+/// The schema of a new table: `target_schema` (the data's when `None`) with
+/// `options` applied.
+pub fn create_schema(
+    data_schema: &SchemaRef,
+    target_schema: Option<SchemaRef>,
+    options: &CreateSchemaOptions,
+) -> Result<SchemaRef, String> {
+    let target = target_schema.unwrap_or_else(|| data_schema.clone());
+    Ok(Arc::new(options.apply(&target)?))
+}
+
+/// Prepares the initial data of a new table: the data is cast into the schema from
+/// `create_schema`, and every batch carries that schema, including its metadata.
+pub fn prepare_create_data(
+    batches: Vec<RecordBatch>,
+    data_schema: &SchemaRef,
+    target_schema: Option<SchemaRef>,
+    options: &CreateSchemaOptions,
+) -> Result<Vec<RecordBatch>, String> {
+    let schema = create_schema(data_schema, target_schema, options)?;
+    let (batches, _) = cast_batches_to_schema(batches, data_schema, &schema, true)?;
+    batches
+        .into_iter()
+        .map(|batch| batch.with_schema(schema.clone()).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// Casts incoming batches to the table schema for writes that opt in to casting.
+///
+/// Columns are matched by name and reordered to the table's order. Only lossless
+/// conversions are applied: numeric widening, utf8 <-> large_utf8, binary <->
+/// large_binary, list <-> large_list, and list to fixed-size list when every list
+/// has the table's dimension (float elements may be narrowed there, since vectors are
+/// commonly produced as float64). Anything else fails with an error naming the column.
+///
+/// With `fill_missing`, nullable table columns absent from the data are filled with
+/// nulls and the result has the full table schema; otherwise absent columns are left
+/// out, so partial-column writes such as merge insert updates keep their meaning.
+pub fn cast_batches_to_schema(
+    batches: Vec<RecordBatch>,
+    source_schema: &SchemaRef,
+    table_schema: &Schema,
+    fill_missing: bool,
+) -> Result<(Vec<RecordBatch>, SchemaRef), String> {
+    let schema = cast_schema(source_schema, table_schema, fill_missing)?;
+    let batches = batches
+        .iter()
+        .map(|batch| cast_batch(batch, &schema))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((batches, schema))
+}
+
+/// The schema `cast_batches_to_schema` casts data with `source_schema` to, checked
+/// the same way, for streams that are cast batch by batch.
+pub fn cast_schema(source_schema: &SchemaRef, table_schema: &Schema, fill_missing: bool) -> Result<SchemaRef, String> {
+    for field in source_schema.fields() {
+        if table_schema.field_with_name(field.name()).is_err() {
+            return Err(format!("Column '{}' does not exist in the table schema", field.name()));
+        }
+    }
+    let mut fields = Vec::new();
+    for field in table_schema.fields() {
+        match source_schema.field_with_name(field.name()) {
+            Ok(source) => {
+                if !can_cast(source.data_type(), field.data_type(), false) {
+                    return Err(format!(
+                        "Cannot cast column '{}' from {} to {}",
+                        field.name(),
+                        source.data_type(),
+                        field.data_type()
+                    ));
+                }
+                fields.push(field.clone());
+            }
+            Err(_) if !fill_missing => {}
+            Err(_) if field.is_nullable() => fields.push(field.clone()),
+            Err(_) => {
+                return Err(format!(
+                    "Column '{}' is required by the table schema but missing from the data",
+                    field.name()
+                ));
+            }
+        }
+    }
+    Ok(Arc::new(Schema::new(fields)))
+}
+
+pub(crate) fn cast_batch(batch: &RecordBatch, schema: &SchemaRef) -> Result<RecordBatch, String> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| match batch.column_by_name(field.name()) {
+            Some(column) => cast_column(column, field),
+            None => Ok(arrow_array::new_null_array(field.data_type(), batch.num_rows())),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    RecordBatch::try_new(schema.clone(), columns).map_err(|e| e.to_string())
+}
+
+fn cast_column(column: &ArrayRef, field: &Field) -> Result<ArrayRef, String> {
+    let nulls = column.logical_null_count();
+    if !field.is_nullable() && nulls > 0 {
+        return Err(format!(
+            "Column '{}' is not nullable but contains {} null value(s)",
+            field.name(),
+            nulls
+        ));
+    }
+    if let DataType::FixedSizeList(_, size) = field.data_type() {
+        check_list_lengths(column, field.name(), *size)?;
+    }
+    if column.data_type() == field.data_type() {
+        return Ok(column.clone());
+    }
+    // Unsafe mode turns a value that does not fit into an error instead of a null.
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    cast_with_options(column, field.data_type(), &options)
+        .map_err(|e| format!("Cannot cast column '{}' to {}: {}", field.name(), field.data_type(), e))
+}
+
+/// Errors when a list in `column` does not have the fixed-size list `size`.
+fn check_list_lengths(column: &ArrayRef, name: &str, size: i32) -> Result<(), String> {
+    use arrow_array::cast::AsArray;
+
+    let mismatch = match column.data_type() {
+        DataType::List(_) => first_mismatch(column.as_list::<i32>(), size),
+        DataType::LargeList(_) => first_mismatch(column.as_list::<i64>(), size),
+        DataType::FixedSizeList(_, n) if *n != size => {
+            return Err(format!(
+                "Column '{}' has dimension {} but the table expects a dimension of {}",
+                name, n, size
+            ));
+        }
+        _ => None,
+    };
+    match mismatch {
+        Some((row, length)) => Err(format!(
+            "Column '{}' row {} has {} values but the table expects a dimension of {}",
+            name, row, length, size
+        )),
+        None => Ok(()),
+    }
+}
+
+fn first_mismatch<O: arrow_array::OffsetSizeTrait>(
+    list: &arrow_array::GenericListArray<O>,
+    size: i32,
+) -> Option<(usize, usize)> {
+    (0..list.len())
+        .filter(|&i| list.is_valid(i))
+        .map(|i| (i, list.value_length(i).as_usize()))
+        .find(|&(_, length)| length != size as usize)
+}
+
+/// Whether `from` converts to `to` without losing information. `vector_element`
+/// also allows narrowing between float types, for list elements cast into a
+/// fixed-size list.
+fn can_cast(from: &DataType, to: &DataType, vector_element: bool) -> bool {
+    use DataType::*;
+
+    if from == to {
+        return true;
+    }
+    match (from, to) {
+        (Utf8, LargeUtf8) | (LargeUtf8, Utf8) | (Binary, LargeBinary) | (LargeBinary, Binary) => true,
+        (Null, _) => true,
+        (List(a), List(b)) | (List(a), LargeList(b)) | (LargeList(a), List(b)) | (LargeList(a), LargeList(b)) => {
+            can_cast(a.data_type(), b.data_type(), false)
+        }
+        (List(a), FixedSizeList(b, _)) | (LargeList(a), FixedSizeList(b, _)) | (FixedSizeList(a, _), FixedSizeList(b, _)) => {
+            can_cast(a.data_type(), b.data_type(), true)
+        }
+        _ if vector_element && from.is_floating() && to.is_floating() => true,
+        _ => widens(from, to),
+    }
+}
+
+/// Numeric widening: to a wider integer of the same signedness, unsigned to a wider
+/// signed integer, or to a float that represents every value exactly.
+fn widens(from: &DataType, to: &DataType) -> bool {
+    use DataType::*;
+
+    fn int_bits(t: &DataType) -> Option<(u32, bool)> {
+        Some(match t {
+            Int8 => (8, true),
+            Int16 => (16, true),
+            Int32 => (32, true),
+            Int64 => (64, true),
+            UInt8 => (8, false),
+            UInt16 => (16, false),
+            UInt32 => (32, false),
+            UInt64 => (64, false),
+            _ => return None,
+        })
+    }
+    fn mantissa_bits(t: &DataType) -> Option<u32> {
+        match t {
+            Float16 => Some(11),
+            Float32 => Some(24),
+            Float64 => Some(53),
+            _ => None,
+        }
+    }
+
+    match (int_bits(from), int_bits(to)) {
+        (Some((a, signed_a)), Some((b, signed_b))) => {
+            if signed_a == signed_b {
+                b > a
+            } else {
+                !signed_a && signed_b && b > a
+            }
+        }
+        (Some((a, _)), None) => mantissa_bits(to).is_some_and(|m| m >= a),
+        (None, None) => match (mantissa_bits(from), mantissa_bits(to)) {
+            (Some(a), Some(b)) => b > a,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// `cast_batches_to_schema` against the current schema of `table`.
+pub async fn cast_to_table(
+    table: &lancedb::table::Table,
+    batches: Vec<RecordBatch>,
+    source_schema: &SchemaRef,
+    fill_missing: bool,
+) -> Result<(Vec<RecordBatch>, SchemaRef), String> {
+    let table_schema = table.schema().await.map_err(|e| e.to_string())?;
+    cast_batches_to_schema(batches, source_schema, &table_schema, fill_missing)
+}
@@ -1,12 +1,20 @@
+use async_trait::async_trait;
+use datafusion::catalog::{SchemaProvider, TableProvider};
+use datafusion::error::DataFusionError;
+use datafusion::prelude::SessionContext;
 use lance_namespace::models::{
     CreateNamespaceRequest, DescribeNamespaceRequest, DropNamespaceRequest,
     ListNamespacesRequest, ListTablesRequest,
 };
+use lancedb::arrow::{SendableRecordBatchStream, SimpleRecordBatchStream};
 use lancedb::connection::Connection;
 use lancedb::database::CreateTableMode;
+use lancedb::table::datafusion::BaseTableAdapter;
 use libc::c_char;
 use std::ffi::CString;
 
+use crate::cast::{cast_schema, create_schema, prepare_create_data, CreateSchemaOptions};
+use crate::files::{write_stream, FileFormat, FileSource, ReadOptions};
 use crate::ffi;
 use crate::ffi::{callback_error, FfiCallback, UserData};
 
@@ -145,6 +153,8 @@ pub extern "C" fn connection_open_table(
     });
 }
 
+/// Creates a table without data.
+/// schema_cdata: the table schema (null for a lone `id: Int32` column).
 #[unsafe(no_mangle)]
 pub extern "C" fn connection_create_empty_table(
     connection_ptr: *const Connection,
@@ -157,6 +167,39 @@ pub extern "C" fn connection_create_empty_table(
     exist_ok: bool,
     completion: FfiCallback,
     user_data: *mut std::ffi::c_void,
+) {
+    connection_create_empty_table_with_options(
+        connection_ptr,
+        table_name,
+        schema_cdata,
+        mode,
+        storage_options_json,
+        location,
+        namespace_json,
+        exist_ok,
+        std::ptr::null(),
+        completion,
+        user_data,
+    );
+}
+
+/// Creates a table without data, as connection_create_empty_table.
+/// create_options_json: optional JSON object with `vector_columns` (column name to
+/// dimension; each must be a list of floats and is stored as a fixed-size list) and
+/// `metadata` (string key-value pairs stored in the schema metadata). Null for none.
+#[unsafe(no_mangle)]
+pub extern "C" fn connection_create_empty_table_with_options(
+    connection_ptr: *const Connection,
+    table_name: *const c_char,
+    schema_cdata: *mut arrow_schema::ffi::FFI_ArrowSchema,
+    mode: *const c_char,
+    storage_options_json: *const c_char,
+    location: *const c_char,
+    namespace_json: *const c_char,
+    exist_ok: bool,
+    create_options_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
 ) {
     let user_data = UserData(user_data);
     let table_name = ffi::to_string(table_name);
@@ -173,6 +216,13 @@ pub extern "C" fn connection_create_empty_table(
             }
         }
     };
+    let schema = match CreateSchemaOptions::parse(create_options_json).and_then(|o| o.apply(&schema)) {
+        Ok(s) => std::sync::Arc::new(s),
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
 
     let storage_opts = ffi::parse_optional_json_map(storage_options_json);
     let location_str = ffi::parse_optional_string(location);
@@ -226,6 +276,46 @@ pub extern "C" fn connection_create_table(
     exist_ok: bool,
     completion: FfiCallback,
     user_data: *mut std::ffi::c_void,
+) {
+    connection_create_table_with_options(
+        connection_ptr,
+        table_name,
+        arrays,
+        schema,
+        batch_count,
+        mode,
+        storage_options_json,
+        location,
+        namespace_json,
+        exist_ok,
+        std::ptr::null_mut(),
+        std::ptr::null(),
+        completion,
+        user_data,
+    );
+}
+
+/// Creates a table from Arrow C Data Interface arrays, as connection_create_table.
+/// target_schema: optional table schema, separate from the data's (null to use the
+/// data's). The data is cast into it as by table_add_with_options with cast_to_table_schema.
+/// create_options_json: as for connection_create_empty_table_with_options; the options
+/// are applied to the table schema and the data is validated against it.
+#[unsafe(no_mangle)]
+pub extern "C" fn connection_create_table_with_options(
+    connection_ptr: *const Connection,
+    table_name: *const c_char,
+    arrays: *mut arrow_data::ffi::FFI_ArrowArray,
+    schema: *mut arrow_schema::ffi::FFI_ArrowSchema,
+    batch_count: usize,
+    mode: *const c_char,
+    storage_options_json: *const c_char,
+    location: *const c_char,
+    namespace_json: *const c_char,
+    exist_ok: bool,
+    target_schema: *mut arrow_schema::ffi::FFI_ArrowSchema,
+    create_options_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
 ) {
     let user_data = UserData(user_data);
     let table_name = ffi::to_string(table_name);
@@ -234,13 +324,42 @@ pub extern "C" fn connection_create_table(
     let location_str = ffi::parse_optional_string(location);
     let namespace_list = ffi::parse_optional_json_list(namespace_json);
 
-    let (batches, _schema_ref) = match ffi::import_batches(arrays, schema, batch_count) {
+    let (batches, schema_ref) = match ffi::import_batches(arrays, schema, batch_count) {
         Ok(r) => r,
         Err(e) => {
             callback_error(completion, user_data, e);
             return;
         }
     };
+    let target_schema = if target_schema.is_null() {
+        None
+    } else {
+        match ffi::import_schema(target_schema) {
+            Ok(s) => Some(s),
+            Err(e) => {
+                callback_error(completion, user_data, e);
+                return;
+            }
+        }
+    };
+    let create_options = match CreateSchemaOptions::parse(create_options_json) {
+        Ok(o) => o,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let batches = if target_schema.is_some() || !create_options.is_empty() {
+        match prepare_create_data(batches, &schema_ref, target_schema, &create_options) {
+            Ok(b) => b,
+            Err(e) => {
+                callback_error(completion, user_data, e);
+                return;
+            }
+        }
+    } else {
+        batches
+    };
 
     let create_mode = if exist_ok {
         CreateTableMode::exist_ok(|req| req)
@@ -278,6 +397,174 @@ pub extern "C" fn connection_create_table(
     });
 }
 
+/// Creates a table from Parquet, CSV or newline-delimited JSON files.
+/// paths_json: JSON array of local paths or object store URIs, read with
+/// `storage_options_json` (the connection's storage options).
+/// target_schema: optional table schema; CSV and JSON are parsed with it and the
+/// file data is cast into it. Without one, Parquet keeps its schema and the types
+/// of CSV and JSON are inferred.
+/// mode: "create" (default, fails if the table exists) or "overwrite".
+/// A new table is created empty and the files are streamed into it as the next
+/// commit; if reading or writing them fails, the table is dropped again. With
+/// "overwrite", an existing table is replaced by the files in a single commit, so
+/// it keeps its data if the import fails. With exist_ok, an existing table is
+/// returned without reading the files.
+#[unsafe(no_mangle)]
+pub extern "C" fn connection_create_table_from_files(
+    connection_ptr: *const Connection,
+    table_name: *const c_char,
+    paths_json: *const c_char,
+    format: *const c_char,
+    read_options_json: *const c_char,
+    target_schema: *mut arrow_schema::ffi::FFI_ArrowSchema,
+    mode: *const c_char,
+    storage_options_json: *const c_char,
+    location: *const c_char,
+    namespace_json: *const c_char,
+    create_options_json: *const c_char,
+    exist_ok: bool,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table_name = ffi::to_string(table_name);
+    let connection = ffi_clone_arc!(connection_ptr, Connection);
+    let storage_opts = ffi::parse_optional_json_map(storage_options_json);
+    let location_str = ffi::parse_optional_string(location);
+    let namespace_list = ffi::parse_optional_json_list(namespace_json);
+
+    let Some(paths) = ffi::parse_optional_json_list(paths_json) else {
+        callback_error(completion, user_data, "paths_json must be a JSON array of strings");
+        return;
+    };
+    let format = match FileFormat::parse(&ffi::to_string(format)) {
+        Ok(f) => f,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let read_options = match ReadOptions::parse(read_options_json) {
+        Ok(o) => o,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let target_schema = if target_schema.is_null() {
+        None
+    } else {
+        match ffi::import_schema(target_schema) {
+            Ok(s) => Some(s),
+            Err(e) => {
+                callback_error(completion, user_data, e);
+                return;
+            }
+        }
+    };
+    let create_options = match CreateSchemaOptions::parse(create_options_json) {
+        Ok(o) => o,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let overwrite = match ffi::parse_optional_string(mode).as_deref() {
+        None | Some("create") => false,
+        Some("overwrite") => true,
+        Some(other) => {
+            callback_error(
+                completion,
+                user_data,
+                format!("Unknown mode '{}', expected 'create' or 'overwrite'", other),
+            );
+            return;
+        }
+    };
+
+    crate::spawn(async move {
+        let result = async {
+            let open_existing = || async {
+                let mut builder = connection.open_table(table_name.clone());
+                if let Some(opts) = &storage_opts {
+                    builder = builder.storage_options(opts.clone());
+                }
+                if let Some(loc) = &location_str {
+                    builder = builder.location(loc.clone());
+                }
+                if let Some(ns) = &namespace_list {
+                    builder = builder.namespace(ns.clone());
+                }
+                match builder.execute().await {
+                    Ok(table) => Ok(Some(table)),
+                    Err(lancedb::Error::TableNotFound { .. }) => Ok(None),
+                    Err(e) => Err(e.to_string()),
+                }
+            };
+            if exist_ok {
+                if let Some(table) = open_existing().await? {
+                    return Ok(table);
+                }
+            }
+
+            let source = FileSource::open(
+                paths,
+                format,
+                &read_options,
+                target_schema.as_ref(),
+                storage_opts.clone(),
+            )
+            .await?;
+            let schema = create_schema(&source.schema(), target_schema, &create_options)?;
+            let file_schema = cast_schema(&source.schema(), &schema, true)?;
+
+            if overwrite {
+                if let Some(table) = open_existing().await? {
+                    let stream = source.stream(Some(file_schema), schema).await?;
+                    write_stream(&table, stream, lance::dataset::WriteMode::Overwrite, None).await?;
+                    return Ok(table);
+                }
+            }
+
+            let mut builder = connection
+                .create_empty_table(table_name.clone(), schema.clone())
+                .mode(CreateTableMode::Create);
+            if let Some(opts) = storage_opts.clone() {
+                builder = builder.storage_options(opts);
+            }
+            if let Some(loc) = location_str.clone() {
+                builder = builder.location(loc);
+            }
+            if let Some(ns) = namespace_list.clone() {
+                builder = builder.namespace(ns);
+            }
+            let table = builder.execute().await.map_err(|e| e.to_string())?;
+
+            let written = async {
+                let stream = source.stream(Some(file_schema), schema).await?;
+                write_stream(&table, stream, lance::dataset::WriteMode::Append, None).await
+            }
+            .await;
+            if let Err(e) = written {
+                let namespace = namespace_list.unwrap_or_default();
+                if let Err(drop_error) = connection.drop_table(&table_name, &namespace).await {
+                    return Err(format!("{}; dropping the new table also failed: {}", e, drop_error));
+                }
+                return Err(e);
+            }
+            Ok(table)
+        }
+        .await;
+        match result {
+            Ok(table) => {
+                let ptr = std::sync::Arc::into_raw(std::sync::Arc::new(table));
+                completion(ptr as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn connection_close(connection_ptr: *const Connection) {
     ffi_free!(connection_ptr, Connection);
@@ -318,6 +605,119 @@ pub extern "C" fn connection_table_names(
     });
 }
 
+/// Execute a SQL statement against the tables of a connection and return a
+/// stream handle (consumed with `stream_next` / `stream_close`).
+///
+/// The tables of the given namespace (root when null) are visible to the
+/// statement under their table names, so it may join, aggregate, group and sort
+/// across them. Only the tables the statement references are opened.
+#[unsafe(no_mangle)]
+pub extern "C" fn connection_sql(
+    connection_ptr: *const Connection,
+    sql: *const c_char,
+    namespace_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let connection = ffi_clone_arc!(connection_ptr, Connection);
+    let sql = ffi::to_string(sql);
+    let namespace_list = ffi::parse_optional_json_list(namespace_json);
+    crate::spawn(async move {
+        match execute_sql(&connection, &sql, namespace_list).await {
+            Ok(stream) => {
+                let handle = std::sync::Arc::new(tokio::sync::Mutex::new(stream));
+                let ptr = std::sync::Arc::into_raw(handle);
+                completion(ptr as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
+async fn execute_sql(
+    connection: &Connection,
+    sql: &str,
+    namespace: Option<Vec<String>>,
+) -> Result<SendableRecordBatchStream, String> {
+    use futures::TryStreamExt;
+
+    let ctx = SessionContext::new();
+    let mut names_builder = connection.table_names();
+    if let Some(ns) = &namespace {
+        names_builder = names_builder.namespace(ns.clone());
+    }
+    let names = names_builder.execute().await.map_err(|e| e.to_string())?;
+    let tables = ConnectionSchema {
+        connection: connection.clone(),
+        namespace,
+        names,
+    };
+    let default_catalog = ctx.state().config_options().catalog.default_catalog.clone();
+    let default_schema = ctx.state().config_options().catalog.default_schema.clone();
+    ctx.catalog(&default_catalog)
+        .ok_or_else(|| "DataFusion default catalog is missing".to_string())?
+        .register_schema(&default_schema, std::sync::Arc::new(tables))
+        .map_err(|e| e.to_string())?;
+
+    let df = ctx.sql(sql).await.map_err(|e| e.to_string())?;
+    let stream = df.execute_stream().await.map_err(|e| e.to_string())?;
+    let schema = stream.schema();
+    let stream = stream.map_err(|e| lancedb::Error::Runtime { message: e.to_string() });
+    Ok(Box::pin(SimpleRecordBatchStream { schema, stream }))
+}
+
+/// The tables of a connection namespace as a DataFusion schema. Tables are opened
+/// when the planner resolves a reference to them, so a statement only opens the
+/// tables it uses.
+struct ConnectionSchema {
+    connection: Connection,
+    namespace: Option<Vec<String>>,
+    names: Vec<String>,
+}
+
+impl std::fmt::Debug for ConnectionSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionSchema")
+            .field("namespace", &self.namespace)
+            .field("names", &self.names)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl SchemaProvider for ConnectionSchema {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        self.names.clone()
+    }
+
+    async fn table(&self, name: &str) -> Result<Option<std::sync::Arc<dyn TableProvider>>, DataFusionError> {
+        if !self.table_exist(name) {
+            return Ok(None);
+        }
+        let mut open_builder = self.connection.open_table(name);
+        if let Some(ns) = &self.namespace {
+            open_builder = open_builder.namespace(ns.clone());
+        }
+        let table = open_builder
+            .execute()
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let adapter = BaseTableAdapter::try_new(table.base_table().clone())
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        Ok(Some(std::sync::Arc::new(adapter)))
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        self.names.iter().any(|n| n == name)
+    }
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn connection_list_tables(
     connection_ptr: *const Connection,
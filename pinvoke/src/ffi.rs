@@ -46,8 +46,61 @@ impl UserData {
 
 /// Helper to invoke a callback with an error string.
 pub fn callback_error(completion: FfiCallback, user_data: UserData, err: impl std::fmt::Display) {
+    callback_error_with_details(completion, user_data, FfiErrorDetails::default(), err);
+}
+
+/// Error code for errors without a more specific classification.
+pub const ERROR_CODE_GENERIC: i32 = 0;
+/// Error code for a write whose commit conflicted with a concurrent writer.
+pub const ERROR_CODE_COMMIT_CONFLICT: i32 = 1;
+
+/// Machine-readable details of the error being passed to a callback.
+/// Only valid while the callback runs; read it with ffi_error_details().
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct FfiErrorDetails {
+    /// One of the `ERROR_CODE_*` constants.
+    pub code: i32,
+    /// For commit conflicts, the version committed by the concurrent writer,
+    /// or -1 when it is not known.
+    pub conflict_version: i64,
+    /// For commit conflicts, the number of times the write was attempted.
+    pub attempts: u32,
+}
+
+impl Default for FfiErrorDetails {
+    fn default() -> Self {
+        Self {
+            code: ERROR_CODE_GENERIC,
+            conflict_version: -1,
+            attempts: 0,
+        }
+    }
+}
+
+thread_local! {
+    static ERROR_DETAILS: std::cell::Cell<FfiErrorDetails> = std::cell::Cell::new(FfiErrorDetails::default());
+}
+
+/// Invokes a callback with an error string, making `details` available to the
+/// callback through ffi_error_details().
+pub fn callback_error_with_details(
+    completion: FfiCallback,
+    user_data: UserData,
+    details: FfiErrorDetails,
+    err: impl std::fmt::Display,
+) {
     let msg = CString::new(err.to_string()).unwrap_or_default();
+    ERROR_DETAILS.with(|d| d.set(details));
     completion(std::ptr::null(), msg.into_raw(), user_data.0);
+    ERROR_DETAILS.with(|d| d.set(FfiErrorDetails::default()));
+}
+
+/// Returns the details of the error currently being passed to a callback.
+/// Must be called from inside the callback, on the thread that invoked it.
+#[unsafe(no_mangle)]
+pub extern "C" fn ffi_error_details() -> FfiErrorDetails {
+    ERROR_DETAILS.with(|d| d.get())
 }
 
 thread_local! {
@@ -222,6 +275,26 @@ pub fn import_record_batch(
     }
 }
 
+/// Imports a single Arrow array of any type from Arrow C Data Interface pointers.
+/// Ownership and zeroing follow `import_record_batch`.
+pub fn import_array(
+    array_ptr: *mut arrow_data::ffi::FFI_ArrowArray,
+    schema_ptr: *mut arrow_schema::ffi::FFI_ArrowSchema,
+) -> Result<arrow_array::ArrayRef, String> {
+    if array_ptr.is_null() || schema_ptr.is_null() {
+        return Err("C Data array or schema pointer is null".to_string());
+    }
+    unsafe {
+        let ffi_array = std::ptr::read(array_ptr);
+        std::ptr::write_bytes(array_ptr, 0, 1);
+        let ffi_schema = std::ptr::read(schema_ptr);
+        std::ptr::write_bytes(schema_ptr, 0, 1);
+        let data = arrow_array::ffi::from_ffi(ffi_array, &ffi_schema)
+            .map_err(|e| format!("Failed to import C Data: {}", e))?;
+        Ok(arrow_array::make_array(data))
+    }
+}
+
 /// Imports a Schema from an Arrow C Data Interface pointer.
 /// Takes ownership by reading from the pointer and zeroing the source.
 pub fn import_schema(
@@ -298,6 +371,33 @@ pub struct FfiCData {
     pub schema: *mut arrow_schema::ffi::FFI_ArrowSchema,
 }
 
+/// Concatenates collected batches into one RecordBatch (empty when there are none).
+pub fn concat_or_empty(
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+) -> Result<RecordBatch, arrow_schema::ArrowError> {
+    match batches.len() {
+        0 => Ok(RecordBatch::new_empty(schema)),
+        1 => Ok(batches.into_iter().next().unwrap()),
+        _ => arrow_select::concat::concat_batches(&schema, &batches),
+    }
+}
+
+/// Exports a RecordBatch as a heap-allocated FfiCData.
+/// The caller must free the result with free_ffi_cdata.
+pub fn export_record_batch(batch: RecordBatch) -> Result<*mut FfiCData, arrow_schema::ArrowError> {
+    use arrow_array::Array;
+
+    let struct_array: StructArray = batch.into();
+    let data = struct_array.to_data();
+    let ffi_schema = arrow_schema::ffi::FFI_ArrowSchema::try_from(data.data_type())?;
+    let ffi_array = arrow_data::ffi::FFI_ArrowArray::new(&data);
+    Ok(Box::into_raw(Box::new(FfiCData {
+        array: Box::into_raw(Box::new(ffi_array)),
+        schema: Box::into_raw(Box::new(ffi_schema)),
+    })))
+}
+
 /// Frees an FfiCData struct and its contained FFI_ArrowArray and FFI_ArrowSchema.
 #[unsafe(no_mangle)]
 pub extern "C" fn free_ffi_cdata(ptr: *mut FfiCData) {
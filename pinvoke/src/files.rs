@@ -0,0 +1,498 @@
+use arrow_array::RecordBatch;
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use datafusion::datasource::object_store::ObjectStoreUrl;
+use datafusion::execution::SendableRecordBatchStream;
+use datafusion::parquet::arrow::ArrowWriter;
+use datafusion::parquet::basic::Compression;
+use datafusion::parquet::file::properties::WriterProperties;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::prelude::{CsvReadOptions, DataFrame, NdJsonReadOptions, ParquetReadOptions, SessionContext};
+use futures::StreamExt;
+use lancedb::table::Table;
+use object_store::WriteMultipart;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Format of the files read by the file import exports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileFormat {
+    Parquet,
+    Csv,
+    /// Newline-delimited JSON, one object per line.
+    Json,
+}
+
+impl FileFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format {
+            "parquet" => Ok(Self::Parquet),
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            other => Err(format!("Unknown file format '{}', expected 'parquet', 'csv' or 'json'", other)),
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Parquet => ".parquet",
+            Self::Csv => ".csv",
+            Self::Json => ".json",
+        }
+    }
+}
+
+/// Options accepted by the file import exports as `read_options_json`.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ReadOptions {
+    /// Whether CSV files start with a header row. Defaults to true.
+    #[serde(default)]
+    pub has_header: Option<bool>,
+    /// Single-character CSV field delimiter. Defaults to ','.
+    #[serde(default)]
+    pub delimiter: Option<String>,
+}
+
+impl ReadOptions {
+    /// Parses `read_options_json`; a null pointer yields the defaults.
+    pub fn parse(json: *const libc::c_char) -> Result<Self, String> {
+        if json.is_null() {
+            return Ok(Self::default());
+        }
+        let json = crate::ffi::to_string(json);
+        if json.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let options: Self = sonic_rs::from_str(&json).map_err(|e| format!("Invalid read options: {}", e))?;
+        if options.delimiter.as_ref().is_some_and(|d| d.len() != 1) {
+            return Err("delimiter must be a single ASCII character".to_string());
+        }
+        Ok(options)
+    }
+}
+
+/// Files to import, opened as a DataFusion data frame.
+pub struct FileSource {
+    frame: DataFrame,
+}
+
+impl FileSource {
+    /// Opens `paths` (local paths or object store URIs, files or directories).
+    /// Object stores are reached with `storage_options`. CSV and JSON files are parsed
+    /// with `schema` when given (CSV columns by position, JSON fields by name), and
+    /// their types are inferred otherwise; Parquet files always use their own schema.
+    pub async fn open(
+        paths: Vec<String>,
+        format: FileFormat,
+        options: &ReadOptions,
+        schema: Option<&SchemaRef>,
+        storage_options: Option<HashMap<String, String>>,
+    ) -> Result<Self, String> {
+        if paths.is_empty() {
+            return Err("At least one path is required".to_string());
+        }
+        let ctx = SessionContext::new();
+        register_object_stores(&ctx, &paths, storage_options).await?;
+        let schema = schema.map(read_schema);
+
+        // Listed directories are filtered by extension; explicitly named files are
+        // read whatever their extension.
+        let extension = if paths.iter().all(|p| p.ends_with('/') || p.ends_with(format.extension())) {
+            format.extension()
+        } else {
+            ""
+        };
+        let frame = match format {
+            FileFormat::Parquet => {
+                let options = ParquetReadOptions {
+                    file_extension: extension,
+                    ..Default::default()
+                };
+                ctx.read_parquet(paths, options).await
+            }
+            FileFormat::Csv => {
+                let mut read = CsvReadOptions::new()
+                    .has_header(options.has_header.unwrap_or(true))
+                    .file_extension(extension);
+                if let Some(delimiter) = &options.delimiter {
+                    read = read.delimiter(delimiter.as_bytes()[0]);
+                }
+                if let Some(schema) = &schema {
+                    read = read.schema(schema);
+                }
+                ctx.read_csv(paths, read).await
+            }
+            FileFormat::Json => {
+                let mut read = NdJsonReadOptions::default().file_extension(extension);
+                if let Some(schema) = &schema {
+                    read = read.schema(schema);
+                }
+                ctx.read_json(paths, read).await
+            }
+        }
+        .map_err(|e| e.to_string())?;
+        Ok(Self { frame })
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        Arc::new(self.frame.schema().as_arrow().clone())
+    }
+
+    /// Streams the file data, split into partitions that are read concurrently, with
+    /// every batch cast into `cast_schema` and relabelled with `output_schema`.
+    pub async fn stream(
+        self,
+        cast_schema: Option<SchemaRef>,
+        output_schema: SchemaRef,
+    ) -> Result<SendableRecordBatchStream, String> {
+        let stream = self.frame.execute_stream().await.map_err(|e| e.to_string())?;
+        let Some(cast_schema) = cast_schema else {
+            return Ok(stream);
+        };
+        let schema = output_schema.clone();
+        let cast = stream.map(move |batch| {
+            let batch = batch?;
+            crate::cast::cast_batch(&batch, &cast_schema)
+                .and_then(|b| b.with_schema(schema.clone()).map_err(|e| e.to_string()))
+                .map_err(datafusion::error::DataFusionError::Execution)
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(output_schema, cast)))
+    }
+}
+
+/// `schema` with fixed-size lists read as plain lists, which text formats cannot
+/// express; the cast into the table schema checks their dimension afterwards.
+fn read_schema(schema: &SchemaRef) -> Schema {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| match field.data_type() {
+            DataType::FixedSizeList(item, _) => {
+                Field::new(field.name(), DataType::List(item.clone()), field.is_nullable())
+            }
+            _ => field.as_ref().clone(),
+        })
+        .collect::<Vec<_>>();
+    Schema::new(fields)
+}
+
+/// Registers the object store behind every non-local path with `ctx`.
+async fn register_object_stores(
+    ctx: &SessionContext,
+    paths: &[String],
+    storage_options: Option<HashMap<String, String>>,
+) -> Result<(), String> {
+    let params = lance::io::ObjectStoreParams {
+        storage_options,
+        ..Default::default()
+    };
+    let registry = Arc::new(lance::io::ObjectStoreRegistry::default());
+    for path in paths {
+        let Some((scheme, rest)) = path.split_once("://") else {
+            continue;
+        };
+        if scheme == "file" {
+            continue;
+        }
+        let authority = rest.split('/').next().unwrap_or_default();
+        let url = ObjectStoreUrl::parse(format!("{}://{}", scheme, authority)).map_err(|e| e.to_string())?;
+        let (store, _) = lance::io::ObjectStore::from_uri_and_params(registry.clone(), path, &params)
+            .await
+            .map_err(|e| e.to_string())?;
+        ctx.register_object_store(url.as_ref(), store.inner.clone());
+    }
+    Ok(())
+}
+
+/// Writes `stream` to a local table as a single commit and returns the new version.
+/// `properties` are stored in the commit's transaction properties.
+pub async fn write_stream(
+    table: &Table,
+    stream: SendableRecordBatchStream,
+    mode: lance::dataset::WriteMode,
+    properties: Option<HashMap<String, String>>,
+) -> Result<u64, String> {
+    if table.as_native().is_none() {
+        return Err("File imports are only supported for local tables".to_string());
+    }
+    let dataset = Arc::new(crate::write::open_dataset(table).await?);
+    let params = lance::dataset::WriteParams {
+        mode,
+        transaction_properties: properties.map(Arc::new),
+        store_params: Some(lance::io::ObjectStoreParams {
+            storage_options: crate::write::storage_options(table).await?,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let written = lance::dataset::InsertBuilder::new(dataset)
+        .with_params(&params)
+        .execute_stream(stream)
+        .await
+        .map_err(|e| e.to_string())?;
+    // The commit bypassed the table handle, so reload it to see the new version.
+    table.checkout_latest().await.map_err(|e| e.to_string())?;
+    Ok(written.version().version)
+}
+
+/// Options accepted by the export functions as `export_options_json`.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ExportOptions {
+    /// Maximum rows per Parquet row group.
+    #[serde(default)]
+    pub row_group_size: Option<usize>,
+    /// Parquet compression codec, e.g. "snappy", "zstd(3)", "gzip(6)" or "uncompressed".
+    #[serde(default)]
+    pub compression: Option<String>,
+    /// Maximum rows per file; the output is split into several files beyond that.
+    #[serde(default)]
+    pub max_rows_per_file: Option<usize>,
+    /// Whether CSV files start with a header row. Defaults to true.
+    #[serde(default)]
+    pub has_header: Option<bool>,
+    /// Single-character CSV field delimiter. Defaults to ','.
+    #[serde(default)]
+    pub delimiter: Option<String>,
+}
+
+impl ExportOptions {
+    /// Parses `export_options_json`; a null pointer yields the defaults.
+    pub fn parse(json: *const libc::c_char, format: FileFormat) -> Result<Self, String> {
+        let options: Self = if json.is_null() {
+            Self::default()
+        } else {
+            let json = crate::ffi::to_string(json);
+            if json.trim().is_empty() {
+                Self::default()
+            } else {
+                sonic_rs::from_str(&json).map_err(|e| format!("Invalid export options: {}", e))?
+            }
+        };
+        if format != FileFormat::Parquet && (options.compression.is_some() || options.row_group_size.is_some()) {
+            return Err("compression and row_group_size are only supported for parquet".to_string());
+        }
+        if options.row_group_size == Some(0) || options.max_rows_per_file == Some(0) {
+            return Err("row_group_size and max_rows_per_file must be positive".to_string());
+        }
+        if options.delimiter.as_ref().is_some_and(|d| d.len() != 1) {
+            return Err("delimiter must be a single ASCII character".to_string());
+        }
+        Ok(options)
+    }
+
+    fn parquet_properties(&self) -> Result<WriterProperties, String> {
+        let mut builder = WriterProperties::builder();
+        if let Some(size) = self.row_group_size {
+            builder = builder.set_max_row_group_size(size);
+        }
+        if let Some(compression) = &self.compression {
+            let compression = compression
+                .parse::<Compression>()
+                .map_err(|e| format!("Invalid compression '{}': {}", compression, e))?;
+            builder = builder.set_compression(compression);
+        }
+        Ok(builder.build())
+    }
+}
+
+/// The files written by an export.
+#[derive(Serialize, Debug, Default)]
+pub struct ExportSummary {
+    pub files: Vec<String>,
+    pub num_rows: u64,
+}
+
+/// Parts of one export file uploaded at the same time.
+const MAX_CONCURRENT_UPLOADS: usize = 8;
+
+/// One output file being uploaded.
+struct ExportFile {
+    upload: WriteMultipart,
+    encoder: Encoder,
+    num_rows: usize,
+}
+
+enum Encoder {
+    Parquet(ArrowWriter<Vec<u8>>),
+    Csv { header: bool, delimiter: u8 },
+    Json,
+}
+
+impl ExportFile {
+    async fn write(&mut self, batch: &RecordBatch) -> Result<(), String> {
+        let mut buffer = Vec::new();
+        match &mut self.encoder {
+            // Row groups are encoded into the writer's buffer as they fill up.
+            Encoder::Parquet(writer) => {
+                writer.write(batch).map_err(|e| e.to_string())?;
+                buffer = std::mem::take(writer.inner_mut());
+            }
+            Encoder::Csv { header, delimiter } => {
+                let mut writer = datafusion::arrow::csv::WriterBuilder::new()
+                    .with_header(*header && self.num_rows == 0)
+                    .with_delimiter(*delimiter)
+                    .build(&mut buffer);
+                writer.write(batch).map_err(|e| e.to_string())?;
+            }
+            Encoder::Json => {
+                let mut writer = datafusion::arrow::json::LineDelimitedWriter::new(&mut buffer);
+                writer.write(batch).map_err(|e| e.to_string())?;
+                writer.finish().map_err(|e| e.to_string())?;
+            }
+        }
+        self.upload.write(&buffer);
+        self.num_rows += batch.num_rows();
+        // Bound the memory held by parts still being uploaded.
+        self.upload
+            .wait_for_capacity(MAX_CONCURRENT_UPLOADS)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn finish(mut self) -> Result<(), String> {
+        if let Encoder::Parquet(writer) = self.encoder {
+            let buffer = writer.into_inner().map_err(|e| e.to_string())?;
+            self.upload.write(&buffer);
+        }
+        self.upload.finish().await.map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+/// Writes `stream` to `uri` (a local directory or object store URI) as files named
+/// `part-00000.<format>`, `part-00001.<format>`, ..., reached with `storage_options`.
+/// A new file is started every `max_rows_per_file` rows; an empty stream still writes
+/// one file, so the schema is kept. If the export fails part way, the file being
+/// uploaded is aborted and the finished ones are deleted; any that cannot be are
+/// named in the error.
+pub async fn export_stream<S, E>(
+    mut stream: S,
+    schema: SchemaRef,
+    uri: &str,
+    format: FileFormat,
+    options: &ExportOptions,
+    storage_options: Option<HashMap<String, String>>,
+) -> Result<ExportSummary, String>
+where
+    S: futures::Stream<Item = Result<RecordBatch, E>> + Unpin,
+    E: std::fmt::Display,
+{
+    let params = lance::io::ObjectStoreParams {
+        storage_options,
+        ..Default::default()
+    };
+    let registry = Arc::new(lance::io::ObjectStoreRegistry::default());
+    let (store, base) = lance::io::ObjectStore::from_uri_and_params(registry, uri, &params)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let target = ExportTarget {
+        store,
+        base,
+        uri: uri.trim_end_matches('/'),
+        format,
+        schema,
+        options,
+    };
+    let mut summary = ExportSummary::default();
+    let mut file: Option<ExportFile> = None;
+    let written: Result<(), String> = async {
+        while let Some(batch) = stream.next().await {
+            let mut batch = batch.map_err(|e| e.to_string())?;
+            summary.num_rows += batch.num_rows() as u64;
+            while batch.num_rows() > 0 {
+                if file.is_none() {
+                    file = Some(target.open(&mut summary).await?);
+                }
+                let current = file.as_mut().unwrap();
+                let room = options
+                    .max_rows_per_file
+                    .map_or(batch.num_rows(), |max| max - current.num_rows);
+                let take = room.min(batch.num_rows());
+                current.write(&batch.slice(0, take)).await?;
+                batch = batch.slice(take, batch.num_rows() - take);
+                if options.max_rows_per_file.is_some_and(|max| current.num_rows >= max) {
+                    file.take().unwrap().finish().await?;
+                }
+            }
+        }
+        match file.take() {
+            Some(current) => current.finish().await,
+            None if summary.files.is_empty() => target.open(&mut summary).await?.finish().await,
+            None => Ok(()),
+        }
+    }
+    .await;
+    match written {
+        Ok(()) => Ok(summary),
+        Err(e) => Err(target.discard(file, &summary, e).await),
+    }
+}
+
+/// Where and how `export_stream` writes its files.
+struct ExportTarget<'a> {
+    store: Arc<lance::io::ObjectStore>,
+    base: object_store::path::Path,
+    uri: &'a str,
+    format: FileFormat,
+    schema: SchemaRef,
+    options: &'a ExportOptions,
+}
+
+impl ExportTarget<'_> {
+    fn part_name(&self, index: usize) -> String {
+        format!("part-{:05}{}", index, self.format.extension())
+    }
+
+    /// Starts the next part file and records its URI in `summary`.
+    async fn open(&self, summary: &mut ExportSummary) -> Result<ExportFile, String> {
+        let name = self.part_name(summary.files.len());
+        let encoder = match self.format {
+            FileFormat::Parquet => {
+                let properties = self.options.parquet_properties()?;
+                let writer = ArrowWriter::try_new(Vec::new(), self.schema.clone(), Some(properties))
+                    .map_err(|e| e.to_string())?;
+                Encoder::Parquet(writer)
+            }
+            FileFormat::Csv => Encoder::Csv {
+                header: self.options.has_header.unwrap_or(true),
+                delimiter: self.options.delimiter.as_ref().map_or(b',', |d| d.as_bytes()[0]),
+            },
+            FileFormat::Json => Encoder::Json,
+        };
+        let upload = self
+            .store
+            .inner
+            .put_multipart(&self.base.child(name.as_str()))
+            .await
+            .map_err(|e| e.to_string())?;
+        summary.files.push(format!("{}/{}", self.uri, name));
+        Ok(ExportFile {
+            upload: WriteMultipart::new(upload),
+            encoder,
+            num_rows: 0,
+        })
+    }
+
+    /// Aborts `file`, the upload in flight, and deletes the finished part files
+    /// after `error` stopped an export. Returns the error to report, naming the
+    /// files that could not be deleted.
+    async fn discard(&self, file: Option<ExportFile>, summary: &ExportSummary, error: String) -> String {
+        if let Some(file) = file {
+            // Best effort: the upload may be what failed.
+            let _ = file.upload.abort().await;
+        }
+        let mut remaining = Vec::new();
+        for (index, uri) in summary.files.iter().enumerate() {
+            match self.store.inner.delete(&self.base.child(self.part_name(index).as_str())).await {
+                Ok(()) | Err(object_store::Error::NotFound { .. }) => {}
+                Err(_) => remaining.push(uri.as_str()),
+            }
+        }
+        if remaining.is_empty() {
+            error
+        } else {
+            format!("{}; these exported files could not be deleted: {}", error, remaining.join(", "))
+        }
+    }
+}
@@ -0,0 +1,523 @@
+//! Multi-term full-text queries: `prefix`, `wildcard`, `regex`, `term_range` and
+//! `exists`.
+//!
+//! lance has no query types for these. `prefix`, `wildcard`, `regex` and
+//! `term_range` are rewritten into a boolean query over the matching terms of their
+//! column when the query runs. lance does not expose the term dictionary of an FTS
+//! index, so the terms are collected by reading the column and tokenizing it with
+//! the configuration of its FTS index, and matching applies to terms as they are
+//! indexed (lower-cased, stemmed, folded). The column is read once per table
+//! version the query runs against, keeping only the terms some node matches.
+//!
+//! Each expanded term becomes an exact `match` clause carrying the node's boost. A
+//! node that matches more than `max_expansions` terms fails the query instead of
+//! dropping terms.
+//!
+//! `exists` is a row filter, `column IS NOT NULL` (`IS NULL` as a `must_not`
+//! clause), so it may only appear where every result has to satisfy it: as the
+//! query itself, or as a `must` or `must_not` clause of a boolean query that is.
+//! A query made only of `exists` runs as a plain filtered scan, without `_score`.
+
+use arrow_array::cast::AsArray;
+use arrow_schema::DataType;
+use futures::TryStreamExt;
+use lancedb::index::scalar::FullTextSearchQuery;
+use lancedb::query::{ExecutableQuery, Query, QueryBase, Select};
+use lancedb::table::Table;
+use regex::Regex;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::ops::Bound;
+use std::sync::{Arc, Mutex};
+
+use crate::analyzer::{index_fts_params, Analyzer, FtsIndexRef};
+use crate::query::{
+    deferred_full_text_query, quote_identifier, validate_boost, BooleanJson, BoostJson, FtsQueryJson, MatchJson,
+    MinimumShouldMatch, QueryParams,
+};
+
+/// Number of terms a multi-term node may expand into when `max_expansions` is unset.
+const DEFAULT_MAX_EXPANSIONS: usize = 50;
+
+pub(crate) fn validate_column(column: &str, kind: &str) -> Result<(), String> {
+    if column.is_empty() {
+        return Err(format!("{} query needs a column", kind));
+    }
+    Ok(())
+}
+
+enum TermPattern {
+    Prefix(String),
+    Regex(Regex),
+    Range(Bound<String>, Bound<String>),
+}
+
+impl TermPattern {
+    fn matches(&self, term: &str) -> bool {
+        match self {
+            TermPattern::Prefix(prefix) => term.starts_with(prefix.as_str()),
+            TermPattern::Regex(regex) => regex.is_match(term),
+            TermPattern::Range(lower, upper) => {
+                let above = match lower {
+                    Bound::Included(bound) => term >= bound.as_str(),
+                    Bound::Excluded(bound) => term > bound.as_str(),
+                    Bound::Unbounded => true,
+                };
+                let below = match upper {
+                    Bound::Included(bound) => term <= bound.as_str(),
+                    Bound::Excluded(bound) => term < bound.as_str(),
+                    Bound::Unbounded => true,
+                };
+                above && below
+            }
+        }
+    }
+}
+
+/// Compiles `pattern` so that it has to match a whole term.
+fn term_regex(pattern: &str, kind: &str) -> Result<Regex, String> {
+    Regex::new(&format!("^(?:{})$", pattern))
+        .map_err(|e| format!("Invalid {} pattern '{}': {}", kind, pattern, e))
+}
+
+/// Translates a wildcard pattern into a regular expression.
+fn wildcard_regex(pattern: &str) -> Result<Regex, String> {
+    let mut regex = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '\\' => match chars.next() {
+                Some(escaped) => regex.push_str(&regex::escape(escaped.encode_utf8(&mut [0; 4]))),
+                None => return Err(format!("Wildcard pattern '{}' ends with an unescaped '\\'", pattern)),
+            },
+            c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    term_regex(&regex, "wildcard")
+}
+
+fn range_bound(exclusive: &Option<String>, inclusive: &Option<String>, names: (&str, &str)) -> Result<Bound<String>, String> {
+    match (exclusive, inclusive) {
+        (Some(_), Some(_)) => Err(format!("term_range query takes {} or {}, not both", names.0, names.1)),
+        (Some(value), None) => Ok(Bound::Excluded(value.clone())),
+        (None, Some(value)) => Ok(Bound::Included(value.clone())),
+        (None, None) => Ok(Bound::Unbounded),
+    }
+}
+
+/// A `prefix`, `wildcard`, `regex` or `term_range` node, ready to match terms.
+pub(crate) struct MultiTermQuery {
+    kind: &'static str,
+    column: String,
+    pattern: TermPattern,
+    boost: Option<f32>,
+    max_expansions: usize,
+}
+
+impl MultiTermQuery {
+    /// Validates and compiles a multi-term node.
+    pub(crate) fn new(query: &FtsQueryJson) -> Result<Self, String> {
+        let (column, pattern, boost, max_expansions) = match query {
+            FtsQueryJson::Prefix(p) => {
+                if p.prefix.is_empty() {
+                    return Err("prefix query needs a non-empty prefix".to_string());
+                }
+                (&p.column, TermPattern::Prefix(p.prefix.clone()), p.boost, p.max_expansions)
+            }
+            FtsQueryJson::Wildcard(w) => {
+                if w.pattern.is_empty() {
+                    return Err("wildcard query needs a non-empty pattern".to_string());
+                }
+                (&w.column, TermPattern::Regex(wildcard_regex(&w.pattern)?), w.boost, w.max_expansions)
+            }
+            FtsQueryJson::Regex(r) => {
+                (&r.column, TermPattern::Regex(term_regex(&r.pattern, "regex")?), r.boost, r.max_expansions)
+            }
+            FtsQueryJson::TermRange(r) => {
+                let lower = range_bound(&r.gt, &r.gte, ("gt", "gte"))?;
+                let upper = range_bound(&r.lt, &r.lte, ("lt", "lte"))?;
+                if let (
+                    Bound::Included(low) | Bound::Excluded(low),
+                    Bound::Included(high) | Bound::Excluded(high),
+                ) = (&lower, &upper)
+                {
+                    let inclusive = matches!(lower, Bound::Included(_)) && matches!(upper, Bound::Included(_));
+                    match low.cmp(high) {
+                        Ordering::Greater => {
+                            return Err(format!(
+                                "term_range query is empty: lower bound '{}' is above upper bound '{}'",
+                                low, high
+                            ));
+                        }
+                        Ordering::Equal if !inclusive => {
+                            return Err(format!(
+                                "term_range query is empty: an exclusive bound equals the other bound '{}'",
+                                low
+                            ));
+                        }
+                        _ => {}
+                    }
+                } else if matches!((&lower, &upper), (Bound::Unbounded, Bound::Unbounded)) {
+                    return Err("term_range query needs at least one of gt, gte, lt and lte".to_string());
+                }
+                (&r.column, TermPattern::Range(lower, upper), r.boost, r.max_expansions)
+            }
+            other => return Err(format!("{} is not a multi-term query", other.kind())),
+        };
+        let kind = query.kind();
+        validate_column(column, kind)?;
+        validate_boost(boost, kind)?;
+        if max_expansions == Some(0) {
+            return Err(format!("{} max_expansions must be positive", kind));
+        }
+        Ok(Self {
+            kind,
+            column: column.clone(),
+            pattern,
+            boost,
+            max_expansions: max_expansions.unwrap_or(DEFAULT_MAX_EXPANSIONS),
+        })
+    }
+
+    /// The matching terms, in order. Fails when there are more than `max_expansions`.
+    fn expand(&self, vocabulary: &Vocabulary) -> Result<Vec<String>, String> {
+        let mut terms: Vec<String> =
+            vocabulary.terms.iter().filter(|term| self.pattern.matches(term)).cloned().collect();
+        if terms.len() > self.max_expansions {
+            return Err(format!(
+                "{} query on column '{}' matches {} terms, more than max_expansions ({}); raise max_expansions or \
+                 narrow the query",
+                self.kind,
+                self.column,
+                terms.len(),
+                self.max_expansions
+            ));
+        }
+        terms.sort();
+        Ok(terms)
+    }
+}
+
+/// The indexed terms of a column that some node of a query matches.
+struct Vocabulary {
+    terms: HashSet<String>,
+}
+
+impl Vocabulary {
+    async fn read(table: &Table, column: &str, patterns: &[&TermPattern]) -> Result<Self, String> {
+        let params = index_fts_params(table, FtsIndexRef::Column(column)).await?;
+        let mut analyzer = Analyzer::new(&params)?;
+        let mut stream = table
+            .query()
+            .select(Select::columns(&[column]))
+            .execute()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut terms = HashSet::new();
+        let mut rejected = HashSet::new();
+        while let Some(batch) = stream.try_next().await.map_err(|e| e.to_string())? {
+            let values = batch.column(0);
+            let texts: Vec<Option<&str>> = match values.data_type() {
+                DataType::Utf8 => values.as_string::<i32>().iter().collect(),
+                DataType::LargeUtf8 => values.as_string::<i64>().iter().collect(),
+                other => {
+                    return Err(format!(
+                        "Column '{}' must be a string column to expand multi-term queries, found {}",
+                        column, other
+                    ));
+                }
+            };
+            for text in texts.into_iter().flatten() {
+                for token in analyzer.document_tokens(text) {
+                    if terms.contains(&token.text) || rejected.contains(&token.text) {
+                        continue;
+                    }
+                    if patterns.iter().any(|pattern| pattern.matches(&token.text)) {
+                        terms.insert(token.text);
+                    } else {
+                        rejected.insert(token.text);
+                    }
+                }
+            }
+        }
+        Ok(Vocabulary { terms })
+    }
+}
+
+/// An exact match on each term, OR-ed together. `None` when there are no terms.
+///
+/// The terms are matched as query text, so they go through the tokenizer again;
+/// indexed terms are normally left unchanged by it.
+fn any_term(column: &str, terms: Vec<String>, boost: Option<f32>) -> Option<FtsQueryJson> {
+    let mut clauses: Vec<FtsQueryJson> = terms
+        .into_iter()
+        .map(|term| {
+            FtsQueryJson::Match(MatchJson {
+                column: Some(column.to_string()),
+                terms: term,
+                boost,
+                fuzziness: Some(0),
+                max_expansions: None,
+                operator: None,
+                prefix_length: None,
+            })
+        })
+        .collect();
+    match clauses.len() {
+        0 => None,
+        1 => clauses.pop(),
+        _ => Some(FtsQueryJson::Boolean(BooleanJson {
+            should: clauses,
+            ..Default::default()
+        })),
+    }
+}
+
+fn contains_exists(query: &FtsQueryJson) -> bool {
+    match query {
+        FtsQueryJson::Exists(_) => true,
+        FtsQueryJson::Boost(b) => contains_exists(&b.positive) || contains_exists(&b.negative),
+        FtsQueryJson::Boolean(b) => b.should.iter().chain(&b.must).chain(&b.must_not).any(contains_exists),
+        _ => false,
+    }
+}
+
+fn reject_exists(query: &FtsQueryJson) -> Result<(), String> {
+    if contains_exists(query) {
+        return Err("exists can only be the query itself or a must or must_not clause of a boolean query that is \
+             one, since it filters rows rather than scoring them"
+            .to_string());
+    }
+    Ok(())
+}
+
+/// Moves the `exists` nodes of `query` into `filters` as SQL predicates. Returns
+/// the scoring query that is left, `None` when `exists` was all there was.
+fn lift_exists(query: FtsQueryJson, filters: &mut Vec<String>) -> Result<Option<FtsQueryJson>, String> {
+    match query {
+        FtsQueryJson::Exists(e) => {
+            filters.push(format!("{} IS NOT NULL", quote_identifier(&e.column)));
+            Ok(None)
+        }
+        FtsQueryJson::Boolean(b) => {
+            let had_must = !b.must.is_empty();
+            let mut lifted = BooleanJson {
+                minimum_should_match: b.minimum_should_match,
+                ..Default::default()
+            };
+            for clause in b.must {
+                lifted.must.extend(lift_exists(clause, filters)?);
+            }
+            for clause in b.must_not {
+                match clause {
+                    FtsQueryJson::Exists(e) => filters.push(format!("{} IS NULL", quote_identifier(&e.column))),
+                    clause => {
+                        reject_exists(&clause)?;
+                        lifted.must_not.push(clause);
+                    }
+                }
+            }
+            for clause in b.should {
+                reject_exists(&clause)?;
+                lifted.should.push(clause);
+            }
+            if lifted.must.is_empty() && lifted.should.is_empty() {
+                if !lifted.must_not.is_empty() {
+                    return Err("A boolean query needs a must or should clause other than exists".to_string());
+                }
+                return Ok(None);
+            }
+            // Should clauses are optional next to must clauses, but required without
+            // them, so they cannot stand in for must clauses that were all exists.
+            let required = match &lifted.minimum_should_match {
+                Some(minimum) => minimum.resolve(lifted.should.len())?,
+                None => 0,
+            };
+            if had_must && lifted.must.is_empty() && required == 0 {
+                return Err("exists as the only must clause of a boolean query needs minimum_should_match for its \
+                     should clauses"
+                    .to_string());
+            }
+            Ok(Some(FtsQueryJson::Boolean(lifted)))
+        }
+        query => {
+            reject_exists(&query)?;
+            Ok(Some(query))
+        }
+    }
+}
+
+fn multi_term_nodes<'a>(query: &'a FtsQueryJson, nodes: &mut Vec<&'a FtsQueryJson>) {
+    match query {
+        FtsQueryJson::Prefix(_) | FtsQueryJson::Wildcard(_) | FtsQueryJson::Regex(_) | FtsQueryJson::TermRange(_) => {
+            nodes.push(query)
+        }
+        FtsQueryJson::Boost(b) => {
+            multi_term_nodes(&b.positive, nodes);
+            multi_term_nodes(&b.negative, nodes);
+        }
+        FtsQueryJson::Boolean(b) => {
+            for clause in b.should.iter().chain(&b.must).chain(&b.must_not) {
+                multi_term_nodes(clause, nodes);
+            }
+        }
+        FtsQueryJson::Match(_) | FtsQueryJson::Phrase(_) | FtsQueryJson::MultiMatch(_) | FtsQueryJson::Exists(_) => {}
+    }
+}
+
+/// Rewrites the multi-term nodes of `query` into the terms they match. `None`
+/// when the query cannot match any row.
+fn expand(
+    query: FtsQueryJson,
+    vocabularies: &HashMap<String, Arc<Vocabulary>>,
+) -> Result<Option<FtsQueryJson>, String> {
+    match query {
+        FtsQueryJson::Prefix(_) | FtsQueryJson::Wildcard(_) | FtsQueryJson::Regex(_) | FtsQueryJson::TermRange(_) => {
+            let multi_term = MultiTermQuery::new(&query)?;
+            let terms = multi_term.expand(&vocabularies[&multi_term.column])?;
+            Ok(any_term(&multi_term.column, terms, multi_term.boost))
+        }
+        FtsQueryJson::Boost(BoostJson { positive, negative, negative_boost }) => {
+            let Some(positive) = expand(*positive, vocabularies)? else {
+                return Ok(None);
+            };
+            match expand(*negative, vocabularies)? {
+                Some(negative) => Ok(Some(FtsQueryJson::Boost(BoostJson {
+                    positive: Box::new(positive),
+                    negative: Box::new(negative),
+                    negative_boost,
+                }))),
+                None => Ok(Some(positive)),
+            }
+        }
+        FtsQueryJson::Boolean(b) => {
+            // Resolved against the original clauses: a clause that matches no
+            // term still counts as a should clause that does not match.
+            let required = match &b.minimum_should_match {
+                Some(minimum) => minimum.resolve(b.should.len())?,
+                None => 0,
+            };
+            let mut expanded = BooleanJson::default();
+            for clause in b.must {
+                match expand(clause, vocabularies)? {
+                    Some(clause) => expanded.must.push(clause),
+                    None => return Ok(None),
+                }
+            }
+            for clause in b.should {
+                expanded.should.extend(expand(clause, vocabularies)?);
+            }
+            for clause in b.must_not {
+                expanded.must_not.extend(expand(clause, vocabularies)?);
+            }
+            if required > expanded.should.len() || (expanded.must.is_empty() && expanded.should.is_empty()) {
+                return Ok(None);
+            }
+            if required > 0 {
+                expanded.minimum_should_match = Some(MinimumShouldMatch::Count(required as i64));
+            }
+            Ok(Some(FtsQueryJson::Boolean(expanded)))
+        }
+        other => Ok(Some(other)),
+    }
+}
+
+/// A full-text query with multi-term nodes, expanded against the table each
+/// time the query runs, and its `exists` filters.
+pub(crate) struct TermExpansion {
+    table: Table,
+    /// The scoring part of the query, `None` when it was only `exists`.
+    query: Option<FtsQueryJson>,
+    /// The `exists` filters.
+    filters: Vec<String>,
+    /// Vocabularies by column, with the table version they were read at.
+    vocabularies: Mutex<HashMap<String, (u64, Arc<Vocabulary>)>>,
+}
+
+impl TermExpansion {
+    /// `None` when the query params have no multi-term full-text query.
+    pub(crate) fn new(table: &Table, params: &QueryParams) -> Result<Option<Self>, String> {
+        let Some(query) = deferred_full_text_query(params)? else {
+            return Ok(None);
+        };
+        let mut filters = Vec::new();
+        let query = lift_exists(query, &mut filters)?;
+        Ok(Some(Self {
+            table: table.clone(),
+            query,
+            filters,
+            vocabularies: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    /// The vocabulary of each column with multi-term nodes, read again only once
+    /// the table has moved to another version.
+    async fn vocabularies(&self, query: &FtsQueryJson) -> Result<HashMap<String, Arc<Vocabulary>>, String> {
+        let mut nodes = Vec::new();
+        multi_term_nodes(query, &mut nodes);
+        let nodes = nodes.into_iter().map(MultiTermQuery::new).collect::<Result<Vec<_>, _>>()?;
+        let mut columns: Vec<&str> = Vec::new();
+        for node in &nodes {
+            if !columns.contains(&node.column.as_str()) {
+                columns.push(&node.column);
+            }
+        }
+        let version = self.table.version().await.map_err(|e| e.to_string())?;
+        let mut vocabularies = HashMap::new();
+        for column in columns {
+            let cached = match self.vocabularies.lock().unwrap().get(column) {
+                Some((read_at, vocabulary)) if *read_at == version => Some(vocabulary.clone()),
+                _ => None,
+            };
+            let vocabulary = match cached {
+                Some(vocabulary) => vocabulary,
+                None => {
+                    let patterns: Vec<&TermPattern> =
+                        nodes.iter().filter(|n| n.column == column).map(|n| &n.pattern).collect();
+                    let vocabulary = Arc::new(Vocabulary::read(&self.table, column, &patterns).await?);
+                    self.vocabularies.lock().unwrap().insert(column.to_string(), (version, vocabulary.clone()));
+                    vocabulary
+                }
+            };
+            vocabularies.insert(column.to_string(), vocabulary);
+        }
+        Ok(vocabularies)
+    }
+
+    /// Adds the `exists` filters to `query`, whose `where` predicate is `predicate`.
+    pub(crate) fn filter(&self, query: Query, predicate: Option<&str>) -> Query {
+        if self.filters.is_empty() {
+            return query;
+        }
+        let filters: Vec<String> = predicate
+            .map(|p| format!("({})", p))
+            .into_iter()
+            .chain(self.filters.iter().cloned())
+            .collect();
+        query.only_if(filters.join(" AND "))
+    }
+
+    /// Sets the expanded full-text search on `query`, which has the other query
+    /// params and `filter` applied.
+    pub(crate) async fn apply(&self, query: Query) -> Result<Query, String> {
+        let Some(fts) = &self.query else {
+            return Ok(query);
+        };
+        let vocabularies = self.vocabularies(fts).await?;
+        match expand(fts.clone(), &vocabularies)? {
+            Some(expanded) => Ok(query.full_text_search(FullTextSearchQuery::new_query(expanded.into_fts()?))),
+            // No row can match, so every row is filtered out. The search still
+            // runs, on the column name, so the result has the usual columns.
+            None => {
+                let column = vocabularies.keys().next().expect("an expanded column").clone();
+                let placeholder = any_term(&column, vec![column.clone()], None).expect("one term");
+                Ok(query
+                    .full_text_search(FullTextSearchQuery::new_query(placeholder.into_fts()?))
+                    .only_if("FALSE"))
+            }
+        }
+    }
+}
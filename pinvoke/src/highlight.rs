@@ -0,0 +1,346 @@
+//! Highlighted snippets for full-text search results.
+//!
+//! A plain query with a full-text search and a `highlight` object in its params gets
+//! one extra `_highlight_<column>` column per highlighted text column: a list of
+//! fragments of the column's text, with every token matching the search wrapped in
+//! the configured tags. Matching tokenizes the text and the searched terms with the
+//! tokenizer configuration of the column's FTS index, so stemming, case and ASCII
+//! folding match the way the index scored the row.
+
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use futures::StreamExt;
+use lancedb::index::IndexType;
+use lancedb::table::Table;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use crate::analyzer::{index_fts_params, Analyzer, FtsIndexRef};
+use crate::query::{FtsQueryJson, QueryParams};
+
+/// Prefix of the highlight column added for each highlighted text column.
+pub const HIGHLIGHT_COLUMN_PREFIX: &str = "_highlight_";
+
+/// Highlighting options accepted as `highlight` in the query params.
+#[derive(Deserialize, Clone, Debug)]
+pub struct HighlightParams {
+    /// Text columns to highlight. Defaults to the columns searched by the query.
+    #[serde(default)]
+    pub columns: Option<Vec<String>>,
+    #[serde(default = "default_pre_tag")]
+    pub pre_tag: String,
+    #[serde(default = "default_post_tag")]
+    pub post_tag: String,
+    /// Approximate length in bytes of each fragment, before tags are added.
+    #[serde(default = "default_fragment_size")]
+    pub fragment_size: usize,
+    /// Maximum number of fragments per row and column.
+    #[serde(default = "default_max_fragments")]
+    pub max_fragments: usize,
+}
+
+fn default_pre_tag() -> String {
+    "<em>".to_string()
+}
+
+fn default_post_tag() -> String {
+    "</em>".to_string()
+}
+
+fn default_fragment_size() -> usize {
+    100
+}
+
+fn default_max_fragments() -> usize {
+    3
+}
+
+/// The text searched by a full-text query: `(column, text)` pairs, where a `None`
+/// column stands for every column with an FTS index.
+type SearchTargets = Vec<(Option<String>, String)>;
+
+fn collect_targets(query: &FtsQueryJson, targets: &mut SearchTargets) {
+    match query {
+        FtsQueryJson::Match(m) => targets.push((m.column.clone(), m.terms.clone())),
+        FtsQueryJson::Phrase(p) => targets.push((p.column.clone(), p.terms.clone())),
+        FtsQueryJson::Boost(b) => collect_targets(&b.positive, targets),
+        FtsQueryJson::MultiMatch(mm) => {
+            for column in &mm.columns {
+                targets.push((Some(column.clone()), mm.query.clone()));
+            }
+        }
+        FtsQueryJson::Boolean(b) => {
+            for clause in b.should.iter().chain(&b.must) {
+                collect_targets(clause, targets);
+            }
+        }
+        // Multi-term nodes have no query text to tokenize, so the terms they
+        // expand into are not highlighted.
+        FtsQueryJson::Prefix(_)
+        | FtsQueryJson::Wildcard(_)
+        | FtsQueryJson::Regex(_)
+        | FtsQueryJson::TermRange(_)
+        | FtsQueryJson::Exists(_) => {}
+    }
+}
+
+fn search_targets(params: &QueryParams) -> Result<SearchTargets, String> {
+    let mut targets = Vec::new();
+    if let Some(json) = &params.full_text_query {
+        let query: FtsQueryJson =
+            sonic_rs::from_str(json).map_err(|e| format!("Invalid full_text_query JSON: {}", e))?;
+        collect_targets(&query, &mut targets);
+    } else if let Some(text) = &params.full_text_search {
+        match &params.full_text_search_columns {
+            Some(columns) if !columns.is_empty() => {
+                targets.extend(columns.iter().map(|c| (Some(c.clone()), text.clone())));
+            }
+            _ => targets.push((None, text.clone())),
+        }
+    } else {
+        return Err("highlight requires a full-text search".to_string());
+    }
+    Ok(targets)
+}
+
+/// Highlighting requested for a query, resolved against the table's FTS indices
+/// when the query runs.
+pub(crate) struct HighlightSpec {
+    table: Table,
+    params: HighlightParams,
+    targets: SearchTargets,
+}
+
+impl HighlightSpec {
+    /// `None` when `params` does not request highlighting.
+    pub(crate) fn new(table: &Table, params: &QueryParams) -> Result<Option<Self>, String> {
+        let Some(highlight) = &params.highlight else {
+            return Ok(None);
+        };
+        if highlight.fragment_size == 0 {
+            return Err("highlight fragment_size must be positive".to_string());
+        }
+        if highlight.max_fragments == 0 {
+            return Err("highlight max_fragments must be positive".to_string());
+        }
+        Ok(Some(Self {
+            table: table.clone(),
+            params: highlight.clone(),
+            targets: search_targets(params)?,
+        }))
+    }
+
+    /// The highlighted columns, in order and without duplicates.
+    async fn columns(&self) -> Result<Vec<String>, String> {
+        let mut columns = Vec::new();
+        if let Some(explicit) = &self.params.columns {
+            columns.extend(explicit.iter().cloned());
+        } else {
+            for (column, _) in &self.targets {
+                match column {
+                    Some(column) => columns.push(column.clone()),
+                    None => {
+                        let indices = self.table.list_indices().await.map_err(|e| e.to_string())?;
+                        columns.extend(
+                            indices
+                                .into_iter()
+                                .filter(|index| index.index_type == IndexType::FTS)
+                                .flat_map(|index| index.columns),
+                        );
+                    }
+                }
+            }
+        }
+        let mut seen = HashSet::new();
+        columns.retain(|c| seen.insert(c.clone()));
+        Ok(columns)
+    }
+
+    /// `schema` with the highlight columns appended.
+    pub(crate) async fn output_schema(&self, schema: SchemaRef) -> Result<SchemaRef, String> {
+        let columns = self.columns().await?;
+        Ok(with_highlight_fields(&schema, &columns))
+    }
+
+    async fn highlighter(&self) -> Result<Highlighter, String> {
+        let mut columns = Vec::new();
+        for column in self.columns().await? {
+            let params = index_fts_params(&self.table, FtsIndexRef::Column(&column)).await?;
+            let mut analyzer = Analyzer::new(&params)?;
+            let mut texts: Vec<&String> = self
+                .targets
+                .iter()
+                .filter(|(c, _)| c.as_ref().is_none_or(|c| *c == column))
+                .map(|(_, text)| text)
+                .collect();
+            if texts.is_empty() {
+                // An explicitly requested column that the query does not search.
+                texts = self.targets.iter().map(|(_, text)| text).collect();
+            }
+            let terms = texts
+                .into_iter()
+                .flat_map(|text| analyzer.query_tokens(text))
+                .map(|token| token.text)
+                .collect();
+            columns.push(ColumnHighlighter {
+                column,
+                analyzer: Mutex::new(analyzer),
+                terms,
+            });
+        }
+        Ok(Highlighter {
+            columns,
+            params: self.params.clone(),
+        })
+    }
+
+    /// Adds the highlight columns to every batch of `stream`.
+    pub(crate) async fn apply(
+        &self,
+        stream: lancedb::arrow::SendableRecordBatchStream,
+    ) -> Result<lancedb::arrow::SendableRecordBatchStream, String> {
+        let highlighter = Arc::new(self.highlighter().await?);
+        let names: Vec<String> = highlighter.columns.iter().map(|c| c.column.clone()).collect();
+        let schema = with_highlight_fields(&stream.schema(), &names);
+        let output_schema = schema.clone();
+        let stream = stream.map(move |batch| {
+            batch.and_then(|batch| {
+                highlighter
+                    .apply(batch, output_schema.clone())
+                    .map_err(|e| lancedb::Error::Runtime { message: e.to_string() })
+            })
+        });
+        Ok(Box::pin(lancedb::arrow::SimpleRecordBatchStream { schema, stream }))
+    }
+}
+
+fn highlight_field(column: &str) -> Field {
+    Field::new(
+        format!("{}{}", HIGHLIGHT_COLUMN_PREFIX, column),
+        DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true))),
+        true,
+    )
+}
+
+fn with_highlight_fields(schema: &Schema, columns: &[String]) -> SchemaRef {
+    let mut fields: Vec<Arc<Field>> = schema.fields().iter().cloned().collect();
+    fields.extend(columns.iter().map(|c| Arc::new(highlight_field(c))));
+    Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+struct ColumnHighlighter {
+    column: String,
+    analyzer: Mutex<Analyzer>,
+    /// Query tokens, as produced by the column's tokenizer.
+    terms: HashSet<String>,
+}
+
+struct Highlighter {
+    columns: Vec<ColumnHighlighter>,
+    params: HighlightParams,
+}
+
+impl Highlighter {
+    fn apply(&self, batch: RecordBatch, schema: SchemaRef) -> Result<RecordBatch, ArrowError> {
+        let mut arrays: Vec<ArrayRef> = batch.columns().to_vec();
+        for column in &self.columns {
+            let values = batch.column_by_name(&column.column).ok_or_else(|| {
+                ArrowError::InvalidArgumentError(format!(
+                    "Highlighted column '{}' is not in the query output; add it to the selected columns",
+                    column.column
+                ))
+            })?;
+            let texts: Vec<Option<&str>> = match values.data_type() {
+                DataType::Utf8 => values.as_string::<i32>().iter().collect(),
+                DataType::LargeUtf8 => values.as_string::<i64>().iter().collect(),
+                other => {
+                    return Err(ArrowError::InvalidArgumentError(format!(
+                        "Highlighted column '{}' must be a string column, found {}",
+                        column.column, other
+                    )));
+                }
+            };
+            let mut analyzer = column.analyzer.lock().unwrap();
+            let mut builder = ListBuilder::new(StringBuilder::new());
+            for text in texts {
+                match text {
+                    Some(text) => {
+                        let matches: Vec<(usize, usize)> = analyzer
+                            .document_tokens(text)
+                            .into_iter()
+                            .filter(|token| column.terms.contains(&token.text))
+                            .map(|token| (token.start, token.end))
+                            .collect();
+                        for fragment in self.fragments(text, &matches) {
+                            builder.values().append_value(fragment);
+                        }
+                        builder.append(true);
+                    }
+                    None => builder.append(false),
+                }
+            }
+            arrays.push(Arc::new(builder.finish()));
+        }
+        RecordBatch::try_new(schema, arrays)
+    }
+
+    /// Up to `max_fragments` windows of about `fragment_size` bytes around the
+    /// matches, trimmed to whole words, with every match inside wrapped in tags.
+    fn fragments(&self, text: &str, matches: &[(usize, usize)]) -> Vec<String> {
+        let size = self.params.fragment_size;
+        let mut fragments = Vec::new();
+        let mut i = 0;
+        while i < matches.len() && fragments.len() < self.params.max_fragments {
+            let (first_start, first_end) = matches[i];
+            let context = size.saturating_sub(first_end - first_start) / 2;
+            let mut start = floor_boundary(text, first_start.saturating_sub(context));
+            let mut end = ceil_boundary(text, (start + size).clamp(first_end, text.len()));
+            if start > 0 {
+                if let Some((offset, c)) = text[start..first_start].char_indices().find(|(_, c)| c.is_whitespace()) {
+                    start += offset + c.len_utf8();
+                }
+            }
+
+            let mut fragment = String::new();
+            let mut pos = start;
+            while i < matches.len() && matches[i].1 <= end {
+                let (match_start, match_end) = matches[i];
+                i += 1;
+                if match_start < pos {
+                    // Overlaps the previous match, e.g. with ngram tokenizers.
+                    continue;
+                }
+                fragment.push_str(&text[pos..match_start]);
+                fragment.push_str(&self.params.pre_tag);
+                fragment.push_str(&text[match_start..match_end]);
+                fragment.push_str(&self.params.post_tag);
+                pos = match_end;
+            }
+            if end < text.len() {
+                if let Some(offset) = text[pos..end].rfind(char::is_whitespace) {
+                    end = pos + offset;
+                }
+            }
+            fragment.push_str(&text[pos..end]);
+            fragments.push(fragment);
+        }
+        fragments
+    }
+}
+
+fn floor_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
@@ -4,44 +4,76 @@ use tokio::runtime::Runtime;
 #[macro_use]
 mod macros;
 pub mod ffi;
+mod analyzer;
+mod cast;
 mod connection;
+mod files;
+mod fts_terms;
+mod highlight;
 mod query;
+mod query_string;
+mod scalar_index;
 mod table;
+mod transaction;
+mod vector_index;
+mod write;
 
 // Re-export FFI functions for integration tests
 pub use connection::{
     connection_clone_table, connection_close, connection_connect, connection_connect_namespace,
     connection_create_empty_table, connection_create_namespace, connection_create_table,
+    connection_create_empty_table_with_options, connection_create_table_from_files,
+    connection_create_table_with_options,
     connection_describe_namespace, connection_drop_all_tables, connection_drop_namespace,
     connection_drop_table, connection_list_namespaces, connection_list_tables,
     connection_open_table, connection_rename_table, connection_table_names,
+    connection_sql,
 };
 pub use query::{
     build_full_text_search, parse_fts_query_json, query_analyze_plan, query_execute,
     query_execute_stream, query_explain_plan, query_output_schema, stream_close, stream_next,
     vector_query_analyze_plan, vector_query_execute, vector_query_execute_stream,
     vector_query_explain_plan, vector_query_output_schema, QueryParams,
+    query_execute_page, query_page_free, OrderByParam,
 };
+pub use analyzer::{fts_analyze, AnalyzedToken};
+pub use query_string::{fts_parse_query_string, parse_fts_query_string, QueryStringError};
 pub use table::{
     table_add, table_add_columns, table_add_columns_null, table_alter_columns,
+    table_add_columns_null_with_options, table_add_columns_with_options, table_alter_columns_with_options,
+    table_add_deduplicated, table_add_from_files, table_add_result_free, table_add_with_options,
+    table_aggregate,
     table_checkout, table_checkout_latest, table_checkout_tag, table_close,
     table_close_lsm_writers, table_count_rows,
     table_create_index, table_delete, table_delete_result_free, table_drop_columns,
+    table_drop_columns_with_options,
+    table_delete_row_ids, table_delete_row_ids_result_free, table_delete_with_options,
     table_drop_index, table_get_name, table_index_stats, table_index_stats_free,
+    table_export, table_find_idempotency_key, table_fts_analyze, table_get_by_keys,
     table_initial_storage_options, table_is_open, table_latest_storage_options,
     table_list_indices, table_list_versions, table_merge_insert, table_merge_result_free,
+    table_merge_insert_clauses,
     table_migrate_manifest_paths_v2, table_optimize, table_prewarm_index,
     table_replace_field_metadata, table_restore, table_schema,
     table_set_lsm_write_spec, table_set_unenforced_primary_key, table_stats, table_stats_free,
     table_tags_create, table_tags_delete, table_tags_get_version, table_tags_list,
     table_tags_update, table_take_offsets, table_take_row_ids, table_unset_lsm_write_spec,
     table_update, table_update_result_free, table_uri, table_uses_v2_manifest_paths,
+    table_update_from_batch, table_update_with_options,
     table_version, table_wait_for_index,
 };
 pub use table::{
     FfiDeleteResult, FfiIndexStats, FfiMergeResult, FfiTableStats, FfiUpdateResult,
+    FfiAddResult, FfiDeleteRowIdsResult,
 };
+pub use transaction::{
+    table_begin_transaction, transaction_add, transaction_add_columns, transaction_base_version,
+    transaction_close, transaction_commit, transaction_commit_with_options, transaction_delete,
+    transaction_rollback, transaction_update, Transaction,
+};
+pub use query::FfiQueryPage;
 pub use ffi::{free_ffi_cdata, free_ffi_schema, free_string, FfiCData};
+pub use ffi::{ffi_error_details, FfiErrorDetails, ERROR_CODE_COMMIT_CONFLICT, ERROR_CODE_GENERIC};
 
 /// Pool of Tokio runtimes (one per physical CPU core, each with 1 worker thread).
 /// Async FFI calls are dispatched to the least-loaded runtime to avoid
@@ -1,3 +1,16 @@
+use datafusion::arrow::compute::SortOptions;
+use datafusion::execution::memory_pool::FairSpillPool;
+use datafusion::execution::runtime_env::RuntimeEnvBuilder;
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::logical_expr::Expr;
+use datafusion::physical_expr::expressions::col;
+use datafusion::physical_expr::{LexOrdering, PhysicalSortExpr};
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::display::DisplayableExecutionPlan;
+use datafusion::physical_plan::limit::GlobalLimitExec;
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::{displayable, execute_stream, ExecutionPlan};
 use lancedb::index::scalar::{
     BooleanQuery, BoostQuery, FtsQuery, FullTextSearchQuery, MatchQuery, MultiMatchQuery, Occur,
     Operator, PhraseQuery,
@@ -5,12 +18,16 @@ use lancedb::index::scalar::{
 use lancedb::query::{ExecutableQuery, Query, QueryBase, QueryExecutionOptions, Select, VectorQuery};
 use lancedb::table::Table;
 use libc::{c_char, c_float, size_t};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sonic_rs::{JsonContainerTrait, JsonValueTrait};
+use std::borrow::Cow;
 use std::slice;
 use std::sync::Arc;
 
 use crate::ffi;
+use crate::files::{export_stream, ExportOptions, FileFormat};
+use crate::fts_terms::TermExpansion;
+use crate::highlight::{HighlightParams, HighlightSpec};
 use crate::ffi::{callback_error, FfiCallback, UserData};
 
 /// Parses a JSON string into a Select enum.
@@ -46,7 +63,7 @@ fn parse_select(json: &str) -> Result<Select, String> {
 
 /// All parameters for building a query, deserialized from JSON.
 /// Used by the query_* and vector_query_* FFI functions.
-#[derive(Deserialize, Default)]
+#[derive(Deserialize, Default, Clone)]
 pub struct QueryParams {
     pub select: Option<sonic_rs::Value>,
     #[serde(rename = "where")]
@@ -59,6 +76,9 @@ pub struct QueryParams {
     pub full_text_query: Option<String>,
     pub fast_search: Option<bool>,
     pub postfilter: Option<bool>,
+    pub order_by: Option<Vec<OrderByParam>>,
+    /// Adds highlighted snippets of the searched text (plain full-text queries only).
+    pub highlight: Option<HighlightParams>,
     // Vector-specific
     pub column: Option<String>,
     pub distance_type: Option<i32>,
@@ -74,6 +94,22 @@ pub struct QueryParams {
     pub additional_vectors: Option<Vec<Vec<f32>>>,
 }
 
+/// One `order_by` entry. `ascending` defaults to `true`; `nulls_first`
+/// defaults to `false` for ascending and `true` for descending sorts.
+#[derive(Deserialize, Clone)]
+pub struct OrderByParam {
+    pub column: String,
+    pub ascending: Option<bool>,
+    pub nulls_first: Option<bool>,
+}
+
+impl QueryParams {
+    /// Whether a non-empty `order_by` was requested.
+    fn is_sorted(&self) -> bool {
+        self.order_by.as_ref().is_some_and(|o| !o.is_empty())
+    }
+}
+
 /// Parses a JSON string into QueryParams.
 pub(crate) fn parse_query_params(json: *const c_char) -> Result<QueryParams, String> {
     if json.is_null() {
@@ -93,66 +129,322 @@ pub(crate) fn parse_query_params(json: *const c_char) -> Result<QueryParams, Str
 /// [`parse_fts_query_json`]) rather than via lance-index's `from_json`,
 /// because lance-index's hand-written `MultiMatchQuery` serde does not
 /// round-trip the per-query `operator`. Building the query natively preserves
-/// `operator` at any nesting depth.
-#[derive(Deserialize)]
+/// `operator` at any nesting depth. It is also serialized by the query-string
+/// parser in [`crate::query_string`].
+///
+/// `prefix`, `wildcard`, `regex`, `term_range` and `exists` have no lance
+/// counterpart: they are expanded into the matching terms of their column when
+/// the query runs (see [`crate::fts_terms`]).
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
-enum FtsQueryJson {
+pub(crate) enum FtsQueryJson {
     Match(MatchJson),
     Phrase(PhraseJson),
     Boost(BoostJson),
     MultiMatch(MultiMatchJson),
     Boolean(BooleanJson),
+    Prefix(PrefixJson),
+    Wildcard(WildcardJson),
+    Regex(RegexJson),
+    TermRange(TermRangeJson),
+    Exists(ExistsJson),
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct MatchJson {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) column: Option<String>,
+    pub(crate) terms: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) boost: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) fuzziness: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_expansions: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) operator: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) prefix_length: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct PhraseJson {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) column: Option<String>,
+    pub(crate) terms: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) slop: Option<u32>,
+    /// Only read so that `validate` can reject it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) boost: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct BoostJson {
+    pub(crate) positive: Box<FtsQueryJson>,
+    pub(crate) negative: Box<FtsQueryJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) negative_boost: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct MultiMatchJson {
+    pub(crate) query: String,
+    pub(crate) columns: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) boost: Option<Vec<f32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) operator: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub(crate) struct BooleanJson {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) should: Vec<FtsQueryJson>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) must: Vec<FtsQueryJson>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) must_not: Vec<FtsQueryJson>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) minimum_should_match: Option<MinimumShouldMatch>,
+}
+
+/// How many `should` clauses of a boolean query must match: a count, a
+/// negative count of clauses that may be missing, or a percentage such as
+/// `"75%"` or `"-25%"` of the `should` clauses, rounded down.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub(crate) enum MinimumShouldMatch {
+    Count(i64),
+    Text(String),
+}
+
+impl MinimumShouldMatch {
+    /// The number of clauses out of `clauses` that must match.
+    pub(crate) fn resolve(&self, clauses: usize) -> Result<usize, String> {
+        let n = clauses as i64;
+        let required = match self {
+            MinimumShouldMatch::Count(count) => *count,
+            MinimumShouldMatch::Text(text) => {
+                let text = text.trim();
+                let invalid = || {
+                    format!(
+                        "Invalid minimum_should_match '{}': expected a count such as 2 or -1, or a percentage such as 75% or -25%",
+                        text
+                    )
+                };
+                match text.strip_suffix('%') {
+                    Some(percent) => {
+                        let percent: i64 = percent.trim().parse().map_err(|_| invalid())?;
+                        if !(-100..=100).contains(&percent) {
+                            return Err(invalid());
+                        }
+                        percent.signum() * (n * percent.abs() / 100)
+                    }
+                    None => text.parse().map_err(|_| invalid())?,
+                }
+            }
+        };
+        let required = if required < 0 { (n + required).max(0) } else { required };
+        if required > n {
+            return Err(format!(
+                "minimum_should_match requires {} should clauses to match, but the boolean query has {}",
+                required, n
+            ));
+        }
+        Ok(required as usize)
+    }
 }
 
-#[derive(Deserialize)]
-struct MatchJson {
-    column: Option<String>,
-    terms: String,
-    boost: Option<f32>,
-    fuzziness: Option<u32>,
-    max_expansions: Option<usize>,
-    operator: Option<String>,
-    prefix_length: Option<u32>,
+/// Terms of `column` starting with `prefix`.
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct PrefixJson {
+    pub(crate) column: String,
+    pub(crate) prefix: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) boost: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_expansions: Option<usize>,
 }
 
-#[derive(Deserialize)]
-struct PhraseJson {
-    column: Option<String>,
-    terms: String,
-    slop: Option<u32>,
+/// Terms of `column` matching a pattern where `*` stands for any run of
+/// characters and `?` for a single one. `\` escapes the next character.
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct WildcardJson {
+    pub(crate) column: String,
+    pub(crate) pattern: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) boost: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_expansions: Option<usize>,
 }
 
-#[derive(Deserialize)]
-struct BoostJson {
-    positive: Box<FtsQueryJson>,
-    negative: Box<FtsQueryJson>,
-    negative_boost: Option<f32>,
+/// Terms of `column` entirely matched by a regular expression.
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct RegexJson {
+    pub(crate) column: String,
+    pub(crate) pattern: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) boost: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_expansions: Option<usize>,
 }
 
-#[derive(Deserialize)]
-struct MultiMatchJson {
-    query: String,
-    columns: Vec<String>,
-    boost: Option<Vec<f32>>,
-    operator: Option<String>,
+/// Terms of `column` between two bounds, compared as strings.
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct TermRangeJson {
+    pub(crate) column: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) gt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) gte: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) lt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) lte: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) boost: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_expansions: Option<usize>,
 }
 
-#[derive(Deserialize)]
-struct BooleanJson {
-    #[serde(default)]
-    should: Vec<FtsQueryJson>,
-    #[serde(default)]
-    must: Vec<FtsQueryJson>,
-    #[serde(default)]
-    must_not: Vec<FtsQueryJson>,
+/// Rows with at least one indexed term in `column`.
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct ExistsJson {
+    pub(crate) column: String,
 }
 
 fn parse_operator(value: &str) -> Result<Operator, String> {
     Operator::try_from(value).map_err(|e| e.to_string())
 }
 
+pub(crate) fn validate_boost(boost: Option<f32>, what: &str) -> Result<(), String> {
+    match boost {
+        Some(boost) if !boost.is_finite() || boost <= 0.0 => {
+            Err(format!("{} boost must be a positive number, got {}", what, boost))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Upper bound on the clause combinations a `minimum_should_match` is
+/// rewritten into.
+const MAX_MINIMUM_SHOULD_MATCH_COMBINATIONS: usize = 1024;
+
+/// The number of ways to pick `k` out of `n` clauses, or `None` above `limit`.
+fn combinations_within(n: usize, k: usize, limit: usize) -> Option<usize> {
+    let k = k.min(n - k);
+    let mut count: u128 = 1;
+    for i in 0..k {
+        count = count * (n - i) as u128 / (i + 1) as u128;
+        if count > limit as u128 {
+            return None;
+        }
+    }
+    Some(count as usize)
+}
+
+/// A query matching rows where at least `required` of `clauses` match: every
+/// `required`-sized combination of the clauses as a conjunction, OR-ed together.
+fn at_least(clauses: Vec<FtsQueryJson>, required: usize) -> Result<FtsQueryJson, String> {
+    let n = clauses.len();
+    if required == n {
+        return Ok(FtsQueryJson::Boolean(BooleanJson {
+            must: clauses,
+            ..Default::default()
+        }));
+    }
+    if combinations_within(n, required, MAX_MINIMUM_SHOULD_MATCH_COMBINATIONS).is_none() {
+        return Err(format!(
+            "minimum_should_match {} of {} should clauses expands to more than {} clause combinations",
+            required, n, MAX_MINIMUM_SHOULD_MATCH_COMBINATIONS
+        ));
+    }
+    let mut combinations = Vec::new();
+    let mut picked: Vec<usize> = (0..required).collect();
+    loop {
+        combinations.push(FtsQueryJson::Boolean(BooleanJson {
+            must: picked.iter().map(|&i| clauses[i].clone()).collect(),
+            ..Default::default()
+        }));
+        // Advance to the next combination in lexicographic order.
+        let Some(slot) = (0..required).rev().find(|&slot| picked[slot] < n - required + slot) else {
+            break;
+        };
+        picked[slot] += 1;
+        for next in slot + 1..required {
+            picked[next] = picked[next - 1] + 1;
+        }
+    }
+    Ok(FtsQueryJson::Boolean(BooleanJson {
+        should: combinations,
+        ..Default::default()
+    }))
+}
+
 impl FtsQueryJson {
-    fn into_fts(self) -> Result<FtsQuery, String> {
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            FtsQueryJson::Match(_) => "match",
+            FtsQueryJson::Phrase(_) => "phrase",
+            FtsQueryJson::Boost(_) => "boost",
+            FtsQueryJson::MultiMatch(_) => "multi_match",
+            FtsQueryJson::Boolean(_) => "boolean",
+            FtsQueryJson::Prefix(_) => "prefix",
+            FtsQueryJson::Wildcard(_) => "wildcard",
+            FtsQueryJson::Regex(_) => "regex",
+            FtsQueryJson::TermRange(_) => "term_range",
+            FtsQueryJson::Exists(_) => "exists",
+        }
+    }
+
+    /// Checks the options of every node, so that a malformed query is reported
+    /// when it is built rather than when it runs.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        match self {
+            FtsQueryJson::Match(_) | FtsQueryJson::MultiMatch(_) => Ok(()),
+            // Lance phrase queries carry no boost, so one would be silently dropped.
+            FtsQueryJson::Phrase(p) if p.boost.is_some() => Err("phrase queries do not support a boost".to_string()),
+            FtsQueryJson::Phrase(_) => Ok(()),
+            FtsQueryJson::Boost(b) => {
+                b.positive.validate()?;
+                b.negative.validate()
+            }
+            FtsQueryJson::Boolean(b) => {
+                for clause in b.should.iter().chain(&b.must).chain(&b.must_not) {
+                    clause.validate()?;
+                }
+                if let Some(minimum) = &b.minimum_should_match {
+                    minimum.resolve(b.should.len())?;
+                }
+                Ok(())
+            }
+            FtsQueryJson::Exists(e) => crate::fts_terms::validate_column(&e.column, "exists"),
+            FtsQueryJson::Prefix(_)
+            | FtsQueryJson::Wildcard(_)
+            | FtsQueryJson::Regex(_)
+            | FtsQueryJson::TermRange(_) => crate::fts_terms::MultiTermQuery::new(self).map(|_| ()),
+        }
+    }
+
+    /// Whether the query has nodes that must be expanded against the table's
+    /// terms before it can run.
+    pub(crate) fn needs_terms(&self) -> bool {
+        match self {
+            FtsQueryJson::Match(_) | FtsQueryJson::Phrase(_) | FtsQueryJson::MultiMatch(_) => false,
+            FtsQueryJson::Boost(b) => b.positive.needs_terms() || b.negative.needs_terms(),
+            FtsQueryJson::Boolean(b) => {
+                b.should.iter().chain(&b.must).chain(&b.must_not).any(|q| q.needs_terms())
+            }
+            FtsQueryJson::Prefix(_)
+            | FtsQueryJson::Wildcard(_)
+            | FtsQueryJson::Regex(_)
+            | FtsQueryJson::TermRange(_)
+            | FtsQueryJson::Exists(_) => true,
+        }
+    }
+
+    pub(crate) fn into_fts(self) -> Result<FtsQuery, String> {
         match self {
             FtsQueryJson::Match(m) => {
                 let mut q = MatchQuery::new(m.terms).with_column(m.column);
@@ -195,28 +487,48 @@ impl FtsQueryJson {
                 Ok(q.into())
             }
             FtsQueryJson::Boolean(b) => {
+                let required = match &b.minimum_should_match {
+                    Some(minimum) => minimum.resolve(b.should.len())?,
+                    None => 0,
+                };
+                let BooleanJson { mut should, mut must, must_not, .. } = b;
+                // Without must clauses, lance already requires one should clause.
+                if required > 1 || (required == 1 && !must.is_empty()) {
+                    must.push(at_least(std::mem::take(&mut should), required)?);
+                }
                 let mut pairs: Vec<(Occur, FtsQuery)> = Vec::new();
-                for q in b.should {
+                for q in should {
                     pairs.push((Occur::Should, q.into_fts()?));
                 }
-                for q in b.must {
+                for q in must {
                     pairs.push((Occur::Must, q.into_fts()?));
                 }
-                for q in b.must_not {
+                for q in must_not {
                     pairs.push((Occur::MustNot, q.into_fts()?));
                 }
                 Ok(BooleanQuery::new(pairs).into())
             }
+            other => Err(format!(
+                "{} queries are expanded against the table's terms and are only supported by plain queries",
+                other.kind()
+            )),
         }
     }
 }
 
-/// Parses the structured `full_text_query` JSON (as emitted by the C#
-/// `FullTextQuery` types) into a native `FtsQuery`.
-pub fn parse_fts_query_json(json: &str) -> Result<FtsQuery, String> {
+/// Parses and validates the structured `full_text_query` JSON.
+pub(crate) fn parse_fts_query_tree(json: &str) -> Result<FtsQueryJson, String> {
     let parsed: FtsQueryJson =
         sonic_rs::from_str(json).map_err(|e| format!("Invalid full_text_query JSON: {}", e))?;
-    parsed.into_fts()
+    parsed.validate()?;
+    Ok(parsed)
+}
+
+/// Parses the structured `full_text_query` JSON (as emitted by the C#
+/// `FullTextQuery` types) into a native `FtsQuery`. Queries with multi-term
+/// nodes such as `prefix` are rejected, as they need the table's terms.
+pub fn parse_fts_query_json(json: &str) -> Result<FtsQuery, String> {
+    parse_fts_query_tree(json)?.into_fts()
 }
 
 /// Builds a FullTextSearchQuery from the FTS-related query params.
@@ -245,6 +557,18 @@ pub fn build_full_text_search(params: &QueryParams) -> Result<Option<FullTextSea
     }
 }
 
+/// The structured `full_text_query`, when it has nodes that are expanded into
+/// the table's terms when the query runs.
+pub(crate) fn deferred_full_text_query(params: &QueryParams) -> Result<Option<FtsQueryJson>, String> {
+    match (&params.full_text_query, &params.full_text_search) {
+        (Some(json), None) => {
+            let query = parse_fts_query_tree(json)?;
+            Ok(query.needs_terms().then_some(query))
+        }
+        _ => Ok(None),
+    }
+}
+
 /// Applies base query parameters (shared between Query and VectorQuery).
 pub(crate) fn apply_base_params(mut query: Query, params: &QueryParams) -> Result<Query, String> {
     if let Some(ref select) = params.select {
@@ -254,17 +578,23 @@ pub(crate) fn apply_base_params(mut query: Query, params: &QueryParams) -> Resul
     if let Some(ref pred) = params.predicate {
         query = query.only_if(pred);
     }
-    if let Some(limit) = params.limit {
-        query = query.limit(limit as usize);
-    }
-    if let Some(offset) = params.offset {
-        query = query.offset(offset as usize);
+    // With order_by, limit and offset are applied after the sort (see SortedQuery).
+    if !params.is_sorted() {
+        if let Some(limit) = params.limit {
+            query = query.limit(limit as usize);
+        }
+        if let Some(offset) = params.offset {
+            query = query.offset(offset as usize);
+        }
     }
     if params.with_row_id == Some(true) {
         query = query.with_row_id();
     }
-    if let Some(fts) = build_full_text_search(params)? {
-        query = query.full_text_search(fts);
+    // Multi-term full-text queries are set by SortedQuery once expanded.
+    if deferred_full_text_query(params)?.is_none() {
+        if let Some(fts) = build_full_text_search(params)? {
+            query = query.full_text_search(fts);
+        }
     }
     if params.fast_search == Some(true) {
         query = query.fast_search();
@@ -355,9 +685,26 @@ pub(crate) fn apply_vector_params(
 }
 
 /// Builds a Query from a table and applies all base params.
-fn build_query(table: &Table, params: &QueryParams) -> Result<Query, String> {
-    let query = table.query().clone();
-    apply_base_params(query, params)
+fn build_query(table: &Table, params: &QueryParams) -> Result<SortedQuery, String> {
+    let terms = TermExpansion::new(table, params)?;
+    let mut query = apply_base_params(table.query().clone(), params)?;
+    if let Some(terms) = &terms {
+        query = terms.filter(query, params.predicate.as_deref());
+    }
+    let select = match params.select {
+        Some(ref select) => Some(parse_select(&select.to_string())?),
+        None => None,
+    };
+    Ok(SortedQuery {
+        query,
+        select,
+        order_by: params.order_by.clone().unwrap_or_default(),
+        keep_sort_columns: false,
+        limit: params.limit.map(|l| l as usize),
+        offset: params.offset.unwrap_or(0) as usize,
+        highlight: HighlightSpec::new(table, params)?,
+        terms,
+    })
 }
 
 /// Builds a VectorQuery from a table, query vector, and all params.
@@ -366,6 +713,12 @@ fn build_vector_query(
     vector: &[f32],
     params: &QueryParams,
 ) -> Result<VectorQuery, String> {
+    if params.is_sorted() {
+        return Err("order_by is only supported for plain queries".to_string());
+    }
+    if params.highlight.is_some() {
+        return Err("highlight is only supported for plain queries".to_string());
+    }
     let query = table.query().clone();
     let vq = query
         .nearest_to(vector)
@@ -373,6 +726,593 @@ fn build_vector_query(
     apply_vector_params(vq, params)
 }
 
+// ---------------------------------------------------------------------------
+// ORDER BY
+// ---------------------------------------------------------------------------
+
+/// A plain Query with an optional native sort on top of its plan.
+///
+/// When `order_by` is set, limit and offset are taken off the scan and
+/// applied after the sort instead (as a top-k sort when a limit is set), so
+/// paging through the result is deterministic. Without `order_by` every
+/// call is forwarded to the inner Query unchanged.
+///
+/// The sort runs before the projection: `order_by` columns missing from the
+/// selection are scanned too and dropped once the rows are ordered. It uses a
+/// bounded memory pool that spills to disk, and the timeout applies while the
+/// sort consumes its input, not only between output batches.
+///
+/// Highlight columns are added to the executed batches and the output schema,
+/// but are not part of the plan. A full-text query with multi-term nodes is
+/// expanded and set on the inner Query each time the query runs.
+pub(crate) struct SortedQuery {
+    query: Query,
+    select: Option<Select>,
+    order_by: Vec<OrderByParam>,
+    /// Keep `order_by` columns that are not selected in the output.
+    keep_sort_columns: bool,
+    limit: Option<usize>,
+    offset: usize,
+    highlight: Option<HighlightSpec>,
+    terms: Option<TermExpansion>,
+}
+
+fn runtime_error(e: impl std::fmt::Display) -> lancedb::Error {
+    lancedb::Error::Runtime { message: e.to_string() }
+}
+
+/// Columns lance adds to a scan's output; they are never part of a selection.
+const SYSTEM_COLUMNS: [&str; 4] = ["_rowid", "_rowaddr", "_distance", "_score"];
+
+/// Sort memory when `LANCE_MEM_POOL_SIZE` is unset, matching lance's scanner.
+const DEFAULT_SORT_POOL_SIZE: usize = 100 * 1024 * 1024;
+
+/// Task context for the sort. It keeps the session config lance executes the
+/// unsorted query with, including the batch size from `options`, and only
+/// replaces the memory pool with one of `LANCE_MEM_POOL_SIZE` bytes that spills
+/// to the OS temp directory once it is exhausted.
+fn sort_task_context(options: &QueryExecutionOptions) -> lancedb::Result<Arc<TaskContext>> {
+    use datafusion::prelude::SessionContext;
+    use lance_datafusion::exec::{get_session_context, LanceExecutionOptions};
+
+    let session = get_session_context(&LanceExecutionOptions {
+        batch_size: Some(options.max_batch_length as usize),
+        ..Default::default()
+    });
+    let pool_size = std::env::var("LANCE_MEM_POOL_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_SORT_POOL_SIZE);
+    let runtime = RuntimeEnvBuilder::from_runtime_env(session.runtime_env().as_ref())
+        .with_memory_pool(Arc::new(FairSpillPool::new(pool_size)))
+        .build_arc()
+        .map_err(runtime_error)?;
+    Ok(SessionContext::new_with_config_rt(session.copied_config(), runtime).task_ctx())
+}
+
+/// Executes `plan`, failing with a timeout error if any poll of the stream,
+/// including the first one that drives the sort over its whole input,
+/// runs past `deadline`.
+fn execute_with_deadline(
+    plan: Arc<dyn ExecutionPlan>,
+    task_ctx: Arc<TaskContext>,
+    deadline: Option<tokio::time::Instant>,
+) -> lancedb::Result<lancedb::arrow::SendableRecordBatchStream> {
+    use futures::StreamExt;
+
+    let stream = execute_stream(plan, task_ctx).map_err(runtime_error)?;
+    let schema = stream.schema();
+    let stream = futures::stream::unfold(
+        Some(stream),
+        move |state: Option<SendableRecordBatchStream>| async move {
+            let mut stream = state?;
+            let next = match deadline {
+                Some(deadline) => match tokio::time::timeout_at(deadline, stream.next()).await {
+                    Ok(next) => next,
+                    Err(_) => return Some((Err(runtime_error("Query timed out")), None)),
+                },
+                None => stream.next().await,
+            };
+            next.map(|batch| (batch.map_err(runtime_error), Some(stream)))
+        },
+    );
+    Ok(Box::pin(lancedb::arrow::SimpleRecordBatchStream { schema, stream: Box::pin(stream) }))
+}
+
+impl SortedQuery {
+    /// The inner Query, with the expanded full-text search when there is one.
+    async fn base_query(&self) -> lancedb::Result<Cow<'_, Query>> {
+        match &self.terms {
+            Some(terms) => Ok(Cow::Owned(terms.apply(self.query.clone()).await.map_err(runtime_error)?)),
+            None => Ok(Cow::Borrowed(&self.query)),
+        }
+    }
+
+    /// The `order_by` columns the selection leaves out.
+    fn hidden_sort_columns(&self) -> Vec<String> {
+        let outputs: Vec<&str> = match &self.select {
+            Some(Select::Columns(columns)) => columns.iter().map(String::as_str).collect(),
+            Some(Select::Dynamic(pairs)) => pairs.iter().map(|(name, _)| name.as_str()).collect(),
+            _ => return Vec::new(),
+        };
+        let mut hidden: Vec<String> = Vec::new();
+        for entry in &self.order_by {
+            let column = entry.column.as_str();
+            if !outputs.contains(&column)
+                && !SYSTEM_COLUMNS.contains(&column)
+                && !hidden.iter().any(|h| h == column)
+            {
+                hidden.push(column.to_string());
+            }
+        }
+        hidden
+    }
+
+    /// Extends the selection with the `order_by` columns it leaves out.
+    /// Returns the query to scan and the names of the added columns.
+    fn with_sort_columns(&self, query: Query) -> (Query, Vec<String>) {
+        let hidden = self.hidden_sort_columns();
+        if hidden.is_empty() {
+            return (query, hidden);
+        }
+        let select = match self.select.clone() {
+            Some(Select::Columns(mut columns)) => {
+                columns.extend(hidden.iter().cloned());
+                Select::Columns(columns)
+            }
+            Some(Select::Dynamic(mut pairs)) => {
+                pairs.extend(hidden.iter().map(|c| (c.clone(), quote_identifier(c))));
+                Select::Dynamic(pairs)
+            }
+            _ => unreachable!("only an explicit selection hides columns"),
+        };
+        (query.select(select), hidden)
+    }
+
+    async fn sorted_plan(
+        &self,
+        options: QueryExecutionOptions,
+    ) -> lancedb::Result<Arc<dyn ExecutionPlan>> {
+        let (query, hidden) = self.with_sort_columns(self.base_query().await?.into_owned());
+        let input = query.create_plan(options).await?;
+        let schema = input.schema();
+        let mut sort_exprs = Vec::with_capacity(self.order_by.len());
+        for entry in &self.order_by {
+            let descending = entry.ascending == Some(false);
+            let expr = col(&entry.column, &schema)
+                .map_err(|e| runtime_error(format!("Invalid order_by column '{}': {}", entry.column, e)))?;
+            sort_exprs.push(PhysicalSortExpr::new(
+                expr,
+                SortOptions {
+                    descending,
+                    nulls_first: entry.nulls_first.unwrap_or(descending),
+                },
+            ));
+        }
+        let ordering = LexOrdering::new(sort_exprs)
+            .ok_or_else(|| runtime_error("order_by must not be empty"))?;
+        let input: Arc<dyn ExecutionPlan> = Arc::new(CoalescePartitionsExec::new(input));
+        let fetch = self.limit.map(|l| l + self.offset);
+        let mut plan: Arc<dyn ExecutionPlan> =
+            Arc::new(SortExec::new(ordering, input).with_fetch(fetch));
+        if self.offset > 0 {
+            plan = Arc::new(GlobalLimitExec::new(plan, self.offset, self.limit));
+        }
+        if !hidden.is_empty() && !self.keep_sort_columns {
+            let mut exprs = Vec::with_capacity(schema.fields().len() - hidden.len());
+            for field in schema.fields() {
+                if !hidden.contains(field.name()) {
+                    exprs.push((col(field.name(), &schema).map_err(runtime_error)?, field.name().clone()));
+                }
+            }
+            plan = Arc::new(ProjectionExec::try_new(exprs, plan).map_err(runtime_error)?);
+        }
+        Ok(plan)
+    }
+
+    async fn sorted_stream(
+        &self,
+        options: QueryExecutionOptions,
+    ) -> lancedb::Result<lancedb::arrow::SendableRecordBatchStream> {
+        let deadline = options.timeout.map(|t| tokio::time::Instant::now() + t);
+        let task_ctx = sort_task_context(&options)?;
+        let plan = self.sorted_plan(options).await?;
+        execute_with_deadline(plan, task_ctx, deadline)
+    }
+}
+
+impl ExecutableQuery for SortedQuery {
+    async fn create_plan(
+        &self,
+        options: QueryExecutionOptions,
+    ) -> lancedb::Result<Arc<dyn ExecutionPlan>> {
+        if self.order_by.is_empty() {
+            return self.base_query().await?.create_plan(options).await;
+        }
+        self.sorted_plan(options).await
+    }
+
+    async fn execute_with_options(
+        &self,
+        options: QueryExecutionOptions,
+    ) -> lancedb::Result<lancedb::arrow::SendableRecordBatchStream> {
+        let stream = if self.order_by.is_empty() {
+            self.base_query().await?.execute_with_options(options).await?
+        } else {
+            self.sorted_stream(options).await?
+        };
+        match &self.highlight {
+            Some(highlight) => highlight.apply(stream).await.map_err(runtime_error),
+            None => Ok(stream),
+        }
+    }
+
+    async fn explain_plan(&self, verbose: bool) -> lancedb::Result<String> {
+        if self.order_by.is_empty() {
+            return self.base_query().await?.explain_plan(verbose).await;
+        }
+        let plan = self.sorted_plan(QueryExecutionOptions::default()).await?;
+        Ok(displayable(plan.as_ref()).indent(verbose).to_string())
+    }
+
+    async fn analyze_plan_with_options(
+        &self,
+        options: QueryExecutionOptions,
+    ) -> lancedb::Result<String> {
+        use futures::TryStreamExt;
+
+        if self.order_by.is_empty() {
+            return self.base_query().await?.analyze_plan_with_options(options).await;
+        }
+        let deadline = options.timeout.map(|t| tokio::time::Instant::now() + t);
+        let task_ctx = sort_task_context(&options)?;
+        let plan = self.sorted_plan(options).await?;
+        let _: Vec<_> = execute_with_deadline(plan.clone(), task_ctx, deadline)?.try_collect().await?;
+        Ok(DisplayableExecutionPlan::with_metrics(plan.as_ref()).indent(true).to_string())
+    }
+
+    async fn output_schema(&self) -> lancedb::Result<arrow_schema::SchemaRef> {
+        let schema = self.base_query().await?.output_schema().await?;
+        match &self.highlight {
+            Some(highlight) => highlight.output_schema(schema).await.map_err(runtime_error),
+            None => Ok(schema),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Keyset pagination
+// ---------------------------------------------------------------------------
+
+/// Continuation state carried between pages.
+///
+/// Handed to the caller as an opaque string: a one-row Arrow IPC stream,
+/// base64url-encoded, holding the last row's `order_by` keys and `_rowid`
+/// with their own types, and the table version in the schema metadata.
+struct PageToken {
+    /// Table version the page was read at.
+    version: u64,
+    /// The last row's `order_by` keys, then its `_rowid`.
+    keys: arrow_array::RecordBatch,
+}
+
+const PAGE_TOKEN_VERSION_KEY: &str = "version";
+
+impl PageToken {
+    fn encode(&self) -> Result<String, String> {
+        use base64::Engine;
+
+        let mut buf = Vec::new();
+        let mut writer = arrow_ipc::writer::StreamWriter::try_new(&mut buf, &self.keys.schema())
+            .map_err(|e| e.to_string())?;
+        writer.write(&self.keys).map_err(|e| e.to_string())?;
+        writer.finish().map_err(|e| e.to_string())?;
+        drop(writer);
+        Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(buf))
+    }
+
+    fn decode(token: &str) -> Result<Self, String> {
+        use base64::Engine;
+
+        let invalid = |e: &dyn std::fmt::Display| format!("Invalid page token: {}", e);
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(token)
+            .map_err(|e| invalid(&e))?;
+        let mut reader = arrow_ipc::reader::StreamReader::try_new(bytes.as_slice(), None)
+            .map_err(|e| invalid(&e))?;
+        let keys = match reader.next() {
+            Some(batch) => batch.map_err(|e| invalid(&e))?,
+            None => return Err(invalid(&"no keys")),
+        };
+        let version = keys
+            .schema()
+            .metadata()
+            .get(PAGE_TOKEN_VERSION_KEY)
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| invalid(&"missing version"))?;
+        if keys.num_rows() != 1 {
+            return Err(invalid(&"expected one row of keys"));
+        }
+        Ok(Self { version, keys })
+    }
+}
+
+/// C-compatible struct for a page of query results, passed across FFI.
+/// Must be freed with query_page_free.
+#[repr(C)]
+pub struct FfiQueryPage {
+    pub data: *mut ffi::FfiCData,
+    /// Token for the next page, or null when this was the last page.
+    pub next_page_token: *mut c_char,
+}
+
+pub(crate) fn quote_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// Formats one value of a key column as a SQL literal.
+pub(crate) fn sql_literal(array: &dyn arrow_array::Array, row: usize) -> Result<String, String> {
+    use arrow_array::Array;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::*;
+    use arrow_schema::{DataType, TimeUnit};
+
+    fn float_literal(v: f64) -> Result<String, String> {
+        if v.is_finite() {
+            Ok(format!("{:?}", v))
+        } else {
+            Err("Cannot use a non-finite float as a key".to_string())
+        }
+    }
+
+    Ok(match array.data_type() {
+        DataType::Int8 => array.as_primitive::<Int8Type>().value(row).to_string(),
+        DataType::Int16 => array.as_primitive::<Int16Type>().value(row).to_string(),
+        DataType::Int32 => array.as_primitive::<Int32Type>().value(row).to_string(),
+        DataType::Int64 => array.as_primitive::<Int64Type>().value(row).to_string(),
+        DataType::UInt8 => array.as_primitive::<UInt8Type>().value(row).to_string(),
+        DataType::UInt16 => array.as_primitive::<UInt16Type>().value(row).to_string(),
+        DataType::UInt32 => array.as_primitive::<UInt32Type>().value(row).to_string(),
+        DataType::UInt64 => array.as_primitive::<UInt64Type>().value(row).to_string(),
+        DataType::Float32 => float_literal(array.as_primitive::<Float32Type>().value(row) as f64)?,
+        DataType::Float64 => float_literal(array.as_primitive::<Float64Type>().value(row))?,
+        DataType::Boolean => array.as_boolean().value(row).to_string(),
+        DataType::Utf8 => format!("'{}'", array.as_string::<i32>().value(row).replace('\'', "''")),
+        DataType::LargeUtf8 => format!("'{}'", array.as_string::<i64>().value(row).replace('\'', "''")),
+        DataType::Date32 => {
+            let days = array.as_primitive::<Date32Type>().value(row);
+            let date = chrono::NaiveDate::from_num_days_from_ce_opt(days + 719_163)
+                .ok_or_else(|| format!("Date out of range: {}", days))?;
+            format!("DATE '{}'", date.format("%Y-%m-%d"))
+        }
+        DataType::Timestamp(unit, _) => {
+            let (func, value) = match unit {
+                TimeUnit::Second => ("to_timestamp_seconds", array.as_primitive::<TimestampSecondType>().value(row)),
+                TimeUnit::Millisecond => ("to_timestamp_millis", array.as_primitive::<TimestampMillisecondType>().value(row)),
+                TimeUnit::Microsecond => ("to_timestamp_micros", array.as_primitive::<TimestampMicrosecondType>().value(row)),
+                TimeUnit::Nanosecond => ("to_timestamp_nanos", array.as_primitive::<TimestampNanosecondType>().value(row)),
+            };
+            format!("{}({})", func, value)
+        }
+        DataType::Binary => hex_literal(array.as_binary::<i32>().value(row)),
+        DataType::LargeBinary => hex_literal(array.as_binary::<i64>().value(row)),
+        DataType::FixedSizeBinary(_) => hex_literal(array.as_fixed_size_binary().value(row)),
+        DataType::Decimal128(precision, scale) => format!(
+            "decimal({},{}) '{}'",
+            precision,
+            scale,
+            array.as_primitive::<Decimal128Type>().value_as_string(row)
+        ),
+        other => return Err(format!("Unsupported key column type: {}", other)),
+    })
+}
+
+/// Formats bytes as a hex literal, which filters compare with binary columns.
+fn hex_literal(bytes: &[u8]) -> String {
+    let hex = bytes.iter().map(|b| format!("{:02X}", b)).collect::<String>();
+    format!("X'{}'", hex)
+}
+
+/// Builds the token that resumes after the last row of `batch`.
+fn next_page_token(
+    batch: &arrow_array::RecordBatch,
+    order_by: &[OrderByParam],
+    version: u64,
+) -> Result<String, String> {
+    let last = batch.num_rows() - 1;
+    let mut fields = Vec::with_capacity(order_by.len() + 1);
+    let mut columns = Vec::with_capacity(order_by.len() + 1);
+    let names = order_by.iter().map(|e| e.column.as_str()).chain(["_rowid"]);
+    for name in names {
+        let index = batch
+            .schema()
+            .index_of(name)
+            .map_err(|_| format!("Paginated result is missing column '{}'", name))?;
+        fields.push(batch.schema().field(index).clone());
+        columns.push(batch.column(index).slice(last, 1));
+    }
+    let metadata = [(PAGE_TOKEN_VERSION_KEY.to_string(), version.to_string())].into();
+    let schema = Arc::new(arrow_schema::Schema::new_with_metadata(fields, metadata));
+    let keys = arrow_array::RecordBatch::try_new(schema, columns).map_err(|e| e.to_string())?;
+    PageToken { version, keys }.encode()
+}
+
+/// Builds the filter selecting rows strictly after the token's position:
+/// `(k1 > v1) OR (k1 = v1 AND k2 > v2) OR ... OR (k1 = v1 AND ... AND _rowid > r)`.
+///
+/// Keys are bound as typed literals. Comparisons follow the sort: nulls sit
+/// before or after every value as `nulls_first` says, and floats compare in
+/// total order, so NaN keys resume like any other value.
+///
+/// `keys` holds the expression each `order_by` entry sorts on (see `order_by_exprs`).
+fn keyset_filter(order_by: &[OrderByParam], keys: &[Expr], token: &PageToken) -> Result<Expr, String> {
+    use datafusion::common::ScalarValue;
+    use datafusion::logical_expr::utils::{conjunction, disjunction};
+    use datafusion::logical_expr::{ident, lit};
+
+    let schema = token.keys.schema();
+    let matches = schema.fields().len() == order_by.len() + 1
+        && order_by
+            .iter()
+            .map(|e| e.column.as_str())
+            .chain(["_rowid"])
+            .zip(schema.fields())
+            .all(|(name, field)| field.name() == name);
+    if !matches {
+        return Err("Page token does not match the query's order_by".to_string());
+    }
+
+    let mut disjuncts = Vec::with_capacity(order_by.len() + 1);
+    let mut equal_prefix: Vec<Expr> = Vec::with_capacity(order_by.len());
+    for (i, (entry, column)) in order_by.iter().zip(keys).enumerate() {
+        let column = column.clone();
+        let key = ScalarValue::try_from_array(token.keys.column(i), 0).map_err(|e| e.to_string())?;
+        let descending = entry.ascending == Some(false);
+        let nulls_first = entry.nulls_first.unwrap_or(descending);
+        let after = if key.is_null() {
+            nulls_first.then(|| column.clone().is_not_null())
+        } else {
+            let cmp = if descending {
+                column.clone().lt(lit(key.clone()))
+            } else {
+                column.clone().gt(lit(key.clone()))
+            };
+            Some(if nulls_first { cmp } else { cmp.or(column.clone().is_null()) })
+        };
+        if let Some(after) = after {
+            disjuncts.push(conjunction(equal_prefix.iter().cloned().chain([after])).unwrap());
+        }
+        equal_prefix.push(if key.is_null() { column.is_null() } else { column.eq(lit(key)) });
+    }
+    let row_id = ScalarValue::try_from_array(token.keys.column(order_by.len()), 0)
+        .map_err(|e| e.to_string())?;
+    let after_row = ident("_rowid").gt(lit(row_id));
+    disjuncts.push(conjunction(equal_prefix.into_iter().chain([after_row])).unwrap());
+    Ok(disjunction(disjuncts).unwrap())
+}
+
+/// The expression each `order_by` entry sorts on, usable in a filter over the
+/// table: an alias of a dynamic selection resolves to the SQL expression it
+/// names, since the alias itself only exists in the query's output.
+async fn order_by_exprs(
+    table: &Table,
+    select: Option<&Select>,
+    order_by: &[OrderByParam],
+) -> Result<Vec<Expr>, String> {
+    use datafusion::common::DFSchema;
+    use datafusion::logical_expr::ident;
+
+    let pairs = match select {
+        Some(Select::Dynamic(pairs)) => pairs.as_slice(),
+        _ => &[],
+    };
+    let ctx = datafusion::prelude::SessionContext::new();
+    let mut schema: Option<DFSchema> = None;
+    let mut exprs = Vec::with_capacity(order_by.len());
+    for entry in order_by {
+        let Some((_, sql)) = pairs.iter().find(|(name, _)| *name == entry.column) else {
+            exprs.push(ident(&entry.column));
+            continue;
+        };
+        if schema.is_none() {
+            let table_schema = table.schema().await.map_err(|e| e.to_string())?;
+            schema = Some(DFSchema::try_from(table_schema.as_ref().clone()).map_err(|e| e.to_string())?);
+        }
+        let expr = ctx
+            .parse_sql_expr(sql, schema.as_ref().unwrap())
+            .map_err(|e| format!("Cannot paginate on order_by alias '{}': {}", entry.column, e))?;
+        exprs.push(expr);
+    }
+    Ok(exprs)
+}
+
+/// A handle reading `table` at `version`, leaving `table` itself where it is.
+async fn pinned_table(table: &Table, version: u64) -> Result<Table, String> {
+    if table.as_native().is_none() {
+        let current = table.version().await.map_err(|e| e.to_string())?;
+        if current != version {
+            return Err(format!(
+                "Page token was issued at table version {}, but the table is at version {}",
+                version, current
+            ));
+        }
+        return Ok(table.clone());
+    }
+    table
+        .checkout_branch(lance::dataset::refs::MAIN_BRANCH, Some(version))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn execute_page(
+    table: &Table,
+    mut params: QueryParams,
+    page_token: Option<String>,
+    options: QueryExecutionOptions,
+) -> Result<FfiQueryPage, String> {
+    use futures::TryStreamExt;
+
+    let page_size = match params.limit {
+        Some(limit) if limit > 0 => limit as usize,
+        _ => return Err("Paginated queries require a positive limit".to_string()),
+    };
+    if params.offset.is_some() {
+        return Err("offset cannot be combined with keyset pagination".to_string());
+    }
+    let token = page_token.as_deref().map(PageToken::decode).transpose()?;
+    let version = match &token {
+        Some(token) => token.version,
+        None => table.version().await.map_err(|e| e.to_string())?,
+    };
+    let table = pinned_table(table, version).await?;
+    let user_order = params.order_by.take().unwrap_or_default();
+
+    let keep_row_id = params.with_row_id == Some(true);
+    params.with_row_id = Some(true);
+    let mut order_by = user_order.clone();
+    order_by.push(OrderByParam {
+        column: "_rowid".to_string(),
+        ascending: Some(true),
+        nulls_first: None,
+    });
+    params.order_by = Some(order_by);
+
+    let mut query = build_query(&table, &params)?;
+    if let Some(token) = &token {
+        let keys = order_by_exprs(&table, query.select.as_ref(), &user_order).await?;
+        query.query = query.query.only_if_expr(keyset_filter(&user_order, &keys, token)?);
+    }
+    // The token needs the keys of the last row, selected or not.
+    let hidden = query.hidden_sort_columns();
+    query.keep_sort_columns = true;
+    let stream = query.execute_with_options(options).await.map_err(|e| e.to_string())?;
+    let schema = stream.schema().clone();
+    let batches: Vec<arrow_array::RecordBatch> =
+        stream.try_collect().await.map_err(|e| e.to_string())?;
+    let mut batch = ffi::concat_or_empty(schema, batches).map_err(|e| e.to_string())?;
+
+    let next = if batch.num_rows() == page_size {
+        Some(next_page_token(&batch, &user_order, version)?)
+    } else {
+        None
+    };
+    if !keep_row_id {
+        let idx = batch.schema().index_of("_rowid").map_err(|e| e.to_string())?;
+        batch.remove_column(idx);
+    }
+    for name in &hidden {
+        let idx = batch.schema().index_of(name).map_err(|e| e.to_string())?;
+        batch.remove_column(idx);
+    }
+
+    let data = ffi::export_record_batch(batch).map_err(|e| e.to_string())?;
+    let next_page_token = match next {
+        Some(token) => std::ffi::CString::new(token).unwrap_or_default().into_raw(),
+        None => std::ptr::null_mut(),
+    };
+    Ok(FfiQueryPage { data, next_page_token })
+}
+
 // ---------------------------------------------------------------------------
 // Shared execution helpers
 // ---------------------------------------------------------------------------
@@ -565,6 +1505,62 @@ pub extern "C" fn query_execute(
     execute_to_cdata_with_options(Arc::new(query), options, completion, user_data);
 }
 
+/// Executes one page of a plain Query using keyset pagination.
+///
+/// `params_json` must set `limit` (the page size). With a null `page_token`
+/// the first page is read; otherwise reading resumes right after the last row
+/// of the page that produced the token. Rows are ordered by `order_by` (if
+/// any) and then `_rowid`, and each page filters on those keys instead of
+/// skipping an offset, so deep pages cost the same as the first one.
+///
+/// The token pins the table version it was read at, and later pages read
+/// that version even after the table has moved on. `order_by` columns need
+/// not be selected. Returns an FfiQueryPage freed with query_page_free.
+#[unsafe(no_mangle)]
+pub extern "C" fn query_execute_page(
+    table_ptr: *const Table,
+    params_json: *const c_char,
+    page_token: *const c_char,
+    timeout_ms: i64,
+    max_batch_length: u32,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let params = match parse_query_params(params_json) {
+        Ok(p) => p,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let page_token = ffi::parse_optional_string(page_token);
+    let options = build_execution_options(timeout_ms, max_batch_length);
+    crate::spawn(async move {
+        match execute_page(&table, params, page_token, options).await {
+            Ok(page) => {
+                let ptr = Box::into_raw(Box::new(page));
+                completion(ptr as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
+/// Frees an FfiQueryPage pointer returned by query_execute_page, including
+/// its Arrow data and page token.
+#[unsafe(no_mangle)]
+pub extern "C" fn query_page_free(ptr: *mut FfiQueryPage) {
+    if !ptr.is_null() {
+        unsafe {
+            let page = Box::from_raw(ptr);
+            ffi::free_ffi_cdata(page.data);
+            ffi::free_string(page.next_page_token);
+        }
+    }
+}
+
 /// Builds a Query from table + JSON params and returns the explain plan.
 #[unsafe(no_mangle)]
 pub extern "C" fn query_explain_plan(
@@ -946,3 +1942,95 @@ pub extern "C" fn stream_close(stream_ptr: *const StreamHandle) {
         unsafe { drop(Arc::from_raw(stream_ptr)); }
     }
 }
+
+// ---------------------------------------------------------------------------
+// Export FFI
+// ---------------------------------------------------------------------------
+
+/// Shared helper: execute any query and write its result to files.
+fn export_impl<Q>(
+    query: Arc<Q>,
+    table: Table,
+    uri: String,
+    format: FileFormat,
+    options: ExportOptions,
+    completion: FfiCallback,
+    user_data: UserData,
+) where
+    Q: ExecutableQuery + Send + Sync + 'static,
+{
+    crate::spawn(async move {
+        let result = async {
+            let storage_options = crate::write::storage_options(&table).await?;
+            let stream = query.execute().await.map_err(|e| e.to_string())?;
+            let schema = stream.schema();
+            export_stream(stream, schema, &uri, format, &options, storage_options).await
+        }
+        .await;
+        match result {
+            Ok(summary) => {
+                let json = sonic_rs::to_string(&summary).unwrap_or_default();
+                let c_str = std::ffi::CString::new(json).unwrap_or_default();
+                completion(c_str.into_raw() as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
+/// Executes a Query (or a VectorQuery when `vector_ptr` is non-null) and writes the
+/// result to Parquet, CSV or newline-delimited JSON files under `uri`, a local
+/// directory or object store URI reached with the table's storage options.
+/// `export_options_json` is as for table_export.
+/// Returns `{"files": [...], "num_rows": N}` as a JSON string; caller must free it
+/// with free_string().
+#[unsafe(no_mangle)]
+pub extern "C" fn query_export(
+    table_ptr: *const Table,
+    vector_ptr: *const c_float,
+    vector_len: size_t,
+    params_json: *const c_char,
+    uri: *const c_char,
+    format: *const c_char,
+    export_options_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_borrow!(table_ptr, Table);
+    let uri = ffi::to_string(uri);
+    let params = match parse_query_params(params_json) {
+        Ok(p) => p,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let format = match FileFormat::parse(&ffi::to_string(format)) {
+        Ok(f) => f,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let options = match ExportOptions::parse(export_options_json, format) {
+        Ok(o) => o,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+
+    if vector_ptr.is_null() {
+        match build_query(table, &params) {
+            Ok(q) => export_impl(Arc::new(q), table.clone(), uri, format, options, completion, user_data),
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    } else {
+        let vector = unsafe { slice::from_raw_parts(vector_ptr, vector_len as usize) };
+        match build_vector_query(table, vector, &params) {
+            Ok(q) => export_impl(Arc::new(q), table.clone(), uri, format, options, completion, user_data),
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    }
+}
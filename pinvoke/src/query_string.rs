@@ -0,0 +1,445 @@
+//! Lucene-style full-text query strings, parsed into the `FtsQueryJson` tree.
+//!
+//! Supported syntax:
+//! - terms (`rust`) and phrases (`"vector db"`), with backslash escapes
+//! - field prefixes on terms, phrases and groups (`title:rust`, `title:(a b)`)
+//! - `AND` / `&&`, `OR` / `||`, `NOT` / `!`, and the `+` (must) / `-` (must not) prefixes
+//! - grouping with parentheses
+//! - fuzziness on terms (`java~2`, `java~` for 2) and slop on phrases (`"a b"~3`)
+//! - boosts on terms (`rust^2`); lance phrase queries carry no boost, so `^` on a phrase is rejected
+//!
+//! Adjacent clauses without an operator are combined with OR, and AND binds tighter
+//! than OR. Unfielded terms search every default column.
+
+use libc::c_char;
+use std::ffi::CString;
+
+use crate::ffi;
+use crate::query::{BooleanJson, FtsQueryJson, MatchJson, PhraseJson};
+
+/// Fuzziness of a term written as `term~` without a distance.
+const DEFAULT_FUZZINESS: u32 = 2;
+
+/// A query string error, at a character offset into the query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryStringError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for QueryStringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Query string parse error at position {}: {}", self.position, self.message)
+    }
+}
+
+fn error<T>(position: usize, message: impl Into<String>) -> Result<T, QueryStringError> {
+    Err(QueryStringError {
+        position,
+        message: message.into(),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Term(String),
+    Phrase(String),
+    LParen,
+    RParen,
+    Colon,
+    And,
+    Or,
+    Not,
+    Plus,
+    Minus,
+    Tilde(Option<u32>),
+    Caret(f32),
+}
+
+fn is_special(c: char) -> bool {
+    matches!(c, '(' | ')' | '"' | ':' | '~' | '^')
+}
+
+fn tokenize(query: &str) -> Result<Vec<(Token, usize)>, QueryStringError> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let token = match c {
+            '(' => {
+                i += 1;
+                Token::LParen
+            }
+            ')' => {
+                i += 1;
+                Token::RParen
+            }
+            ':' => {
+                i += 1;
+                Token::Colon
+            }
+            '+' => {
+                i += 1;
+                Token::Plus
+            }
+            '-' => {
+                i += 1;
+                Token::Minus
+            }
+            '!' => {
+                i += 1;
+                Token::Not
+            }
+            '&' | '|' if chars.get(i + 1) == Some(&c) => {
+                i += 2;
+                if c == '&' { Token::And } else { Token::Or }
+            }
+            '"' => {
+                i += 1;
+                let mut text = String::new();
+                loop {
+                    match chars.get(i) {
+                        None => return error(start, "unterminated phrase"),
+                        Some('"') => break,
+                        Some('\\') if i + 1 < chars.len() => {
+                            text.push(chars[i + 1]);
+                            i += 2;
+                        }
+                        Some(&ch) => {
+                            text.push(ch);
+                            i += 1;
+                        }
+                    }
+                }
+                i += 1;
+                Token::Phrase(text)
+            }
+            '~' => {
+                i += 1;
+                let digits: String = chars[i..].iter().take_while(|c| c.is_ascii_digit()).collect();
+                i += digits.len();
+                if digits.is_empty() {
+                    Token::Tilde(None)
+                } else {
+                    match digits.parse() {
+                        Ok(n) => Token::Tilde(Some(n)),
+                        Err(_) => return error(start + 1, format!("invalid distance '{}'", digits)),
+                    }
+                }
+            }
+            '^' => {
+                i += 1;
+                let number: String = chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit() || **c == '.')
+                    .collect();
+                i += number.len();
+                match number.parse::<f32>() {
+                    Ok(boost) => Token::Caret(boost),
+                    Err(_) => return error(start + 1, "expected a number after '^'"),
+                }
+            }
+            _ => {
+                let mut text = String::new();
+                while i < chars.len() && !chars[i].is_whitespace() && !is_special(chars[i]) {
+                    if chars[i] == '\\' && i + 1 < chars.len() {
+                        text.push(chars[i + 1]);
+                        i += 2;
+                    } else {
+                        text.push(chars[i]);
+                        i += 1;
+                    }
+                }
+                match text.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Term(text),
+                }
+            }
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+/// How a clause takes part in the enclosing boolean query.
+#[derive(Clone, Copy, PartialEq)]
+enum Occur {
+    Should,
+    Must,
+    MustNot,
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(t, _)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.index).map_or(self.end, |(_, p)| *p)
+    }
+
+    fn next(&mut self) -> Option<(Token, usize)> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    /// Clauses separated by OR or by nothing.
+    fn parse_or(&mut self, fields: &[String]) -> Result<FtsQueryJson, QueryStringError> {
+        let mut clauses = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some(Token::RParen) => break,
+                Some(Token::Or) if !clauses.is_empty() => {
+                    self.index += 1;
+                }
+                _ => {}
+            }
+            clauses.push(self.parse_and(fields)?);
+        }
+        if clauses.is_empty() {
+            return error(self.position(), "expected a term, phrase or group");
+        }
+        Ok(combine(clauses))
+    }
+
+    /// Clauses joined by AND.
+    fn parse_and(&mut self, fields: &[String]) -> Result<(Occur, FtsQueryJson), QueryStringError> {
+        let first = self.parse_unary(fields)?;
+        if self.peek() != Some(&Token::And) {
+            return Ok(first);
+        }
+        let mut clauses = vec![first];
+        while self.peek() == Some(&Token::And) {
+            self.index += 1;
+            clauses.push(self.parse_unary(fields)?);
+        }
+        let clauses = clauses
+            .into_iter()
+            .map(|(occur, q)| (if occur == Occur::MustNot { occur } else { Occur::Must }, q))
+            .collect();
+        Ok((Occur::Should, combine(clauses)))
+    }
+
+    fn parse_unary(&mut self, fields: &[String]) -> Result<(Occur, FtsQueryJson), QueryStringError> {
+        let occur = match self.peek() {
+            Some(Token::Plus) => Occur::Must,
+            Some(Token::Minus) | Some(Token::Not) => Occur::MustNot,
+            _ => return Ok((Occur::Should, self.parse_primary(fields)?)),
+        };
+        self.index += 1;
+        Ok((occur, self.parse_primary(fields)?))
+    }
+
+    /// An optionally fielded term, phrase or group, with its `~` and `^` modifiers.
+    fn parse_primary(&mut self, fields: &[String]) -> Result<FtsQueryJson, QueryStringError> {
+        let field_fields;
+        let mut fields = fields;
+        if let (Some((Token::Term(name), _)), Some((Token::Colon, colon))) =
+            (self.tokens.get(self.index), self.tokens.get(self.index + 1))
+        {
+            let colon = *colon;
+            field_fields = vec![name.clone()];
+            fields = &field_fields;
+            self.index += 2;
+            if matches!(self.peek(), None | Some(Token::RParen)) {
+                return error(colon + 1, format!("expected a term, phrase or group after '{}:'", field_fields[0]));
+            }
+        }
+
+        let start = self.position();
+        match self.next() {
+            Some((Token::Term(text), _)) => {
+                let (fuzziness, boost) = self.parse_term_modifiers()?;
+                leaf(fields, start, &text, |column| {
+                    FtsQueryJson::Match(MatchJson {
+                        column: Some(column),
+                        terms: text.clone(),
+                        boost,
+                        fuzziness: Some(fuzziness),
+                        max_expansions: None,
+                        operator: None,
+                        prefix_length: None,
+                    })
+                })
+            }
+            Some((Token::Phrase(text), _)) => {
+                let slop = self.parse_phrase_slop()?;
+                self.reject_modifiers("phrase")?;
+                leaf(fields, start, &text, |column| {
+                    FtsQueryJson::Phrase(PhraseJson {
+                        column: Some(column),
+                        terms: text.clone(),
+                        slop,
+                        boost: None,
+                    })
+                })
+            }
+            Some((Token::LParen, _)) => {
+                let inner = self.parse_or(fields)?;
+                match self.next() {
+                    Some((Token::RParen, _)) => {}
+                    _ => return error(start, "unclosed '('"),
+                }
+                self.reject_modifiers("group")?;
+                Ok(inner)
+            }
+            Some((Token::RParen, p)) => error(p, "unexpected ')'"),
+            Some((token, p)) => error(p, format!("unexpected {}", describe(&token))),
+            None => error(self.end, "unexpected end of query"),
+        }
+    }
+
+    /// `~` and `^` after a term, in either order.
+    fn parse_term_modifiers(&mut self) -> Result<(u32, Option<f32>), QueryStringError> {
+        let mut fuzziness = 0;
+        let mut boost = None;
+        loop {
+            match self.peek() {
+                Some(Token::Tilde(distance)) => {
+                    fuzziness = distance.unwrap_or(DEFAULT_FUZZINESS);
+                    self.index += 1;
+                }
+                Some(Token::Caret(value)) => {
+                    boost = Some(*value);
+                    self.index += 1;
+                }
+                _ => return Ok((fuzziness, boost)),
+            }
+        }
+    }
+
+    /// `~slop` after a phrase.
+    fn parse_phrase_slop(&mut self) -> Result<Option<u32>, QueryStringError> {
+        match self.peek() {
+            Some(Token::Tilde(distance)) => {
+                let position = self.position();
+                match distance {
+                    Some(distance) => {
+                        let slop = *distance;
+                        self.index += 1;
+                        Ok(Some(slop))
+                    }
+                    None => error(position + 1, "expected a slop after '~' on a phrase"),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn reject_modifiers(&self, what: &str) -> Result<(), QueryStringError> {
+        match self.peek() {
+            Some(Token::Tilde(_)) => error(self.position(), format!("'~' is not supported on a {}", what)),
+            Some(Token::Caret(_)) => error(self.position(), format!("'^' is not supported on a {}", what)),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Colon => "':'".to_string(),
+        Token::And => "AND".to_string(),
+        Token::Or => "OR".to_string(),
+        Token::Tilde(_) => "'~'".to_string(),
+        Token::Caret(_) => "'^'".to_string(),
+        Token::Plus | Token::Minus | Token::Not => "operator".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// One leaf per field, OR-ed together when there are several.
+fn leaf(
+    fields: &[String],
+    position: usize,
+    text: &str,
+    build: impl Fn(String) -> FtsQueryJson,
+) -> Result<FtsQueryJson, QueryStringError> {
+    match fields {
+        [] => error(position, format!("'{}' has no field and no default columns were given", text)),
+        [field] => Ok(build(field.clone())),
+        _ => Ok(combine(fields.iter().map(|f| (Occur::Should, build(f.clone()))).collect())),
+    }
+}
+
+/// A single optional clause as itself, anything else as a boolean query.
+fn combine(mut clauses: Vec<(Occur, FtsQueryJson)>) -> FtsQueryJson {
+    if clauses.len() == 1 && clauses[0].0 != Occur::MustNot {
+        return clauses.pop().unwrap().1;
+    }
+    let mut boolean = BooleanJson::default();
+    for (occur, query) in clauses {
+        match occur {
+            Occur::Should => boolean.should.push(query),
+            Occur::Must => boolean.must.push(query),
+            Occur::MustNot => boolean.must_not.push(query),
+        }
+    }
+    FtsQueryJson::Boolean(boolean)
+}
+
+/// Parses a Lucene-style query string into the structured `full_text_query` JSON
+/// accepted by [`crate::query::parse_fts_query_json`]. Unfielded terms and phrases
+/// search every column in `default_columns`.
+pub fn parse_fts_query_string(query: &str, default_columns: &[String]) -> Result<String, QueryStringError> {
+    let tokens = tokenize(query)?;
+    let mut parser = Parser {
+        tokens,
+        index: 0,
+        end: query.chars().count(),
+    };
+    if parser.tokens.is_empty() {
+        return error(0, "empty query");
+    }
+    let parsed = parser.parse_or(default_columns)?;
+    if let Some((_, position)) = parser.tokens.get(parser.index) {
+        return error(*position, "unexpected ')'");
+    }
+    sonic_rs::to_string(&parsed).map_err(|e| QueryStringError {
+        position: 0,
+        message: e.to_string(),
+    })
+}
+
+/// Parses a Lucene-style full-text query string, such as
+/// `title:"vector db" AND (rust OR csharp) -java~2`, into the structured
+/// `full_text_query` JSON. `default_columns_json` is a JSON array of the columns
+/// searched by unfielded terms (null for none).
+/// Returns the JSON as a C string (free with free_string()), or null with the last
+/// error set to "Query string parse error at position N: ..." (N counts characters).
+#[unsafe(no_mangle)]
+pub extern "C" fn fts_parse_query_string(query: *const c_char, default_columns_json: *const c_char) -> *mut c_char {
+    let query = ffi::to_string(query);
+    let default_columns = if default_columns_json.is_null() {
+        Vec::new()
+    } else {
+        match ffi::parse_optional_json_list(default_columns_json) {
+            Some(columns) => columns,
+            None => {
+                ffi::set_last_error("default_columns_json must be a JSON array of strings");
+                return std::ptr::null_mut();
+            }
+        }
+    };
+    match parse_fts_query_string(&query, &default_columns) {
+        Ok(json) => CString::new(json).unwrap_or_default().into_raw(),
+        Err(e) => {
+            ffi::set_last_error(e);
+            std::ptr::null_mut()
+        }
+    }
+}
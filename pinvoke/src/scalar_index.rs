@@ -0,0 +1,194 @@
+//! Scalar indices built with lance's index parameters.
+//!
+//! lancedb builds BTree, Bitmap, LabelList and FM indices with fixed defaults. When
+//! the `config_json` of `table_create_index` sets one of the options below, the
+//! index is built on the table's lance dataset instead:
+//! - `zone_size` (BTree): number of rows per BTree page, 4096 by default.
+//! - `variant` (BTree on a string column): `"ngram"` builds an n-gram index, which
+//!   accelerates `contains()` filters, and `"inverted"` builds an inverted index
+//!   configured with the same tokenizer keys as an FTS index.
+//! - `partial_build_fragments`: a partial build that only indexes these fragments.
+//!   Rows of other fragments stay unindexed, and are scanned by queries, until the
+//!   index is optimized.
+//!
+//! There is no build filter selecting the rows to index: a lance index covers every
+//! row of the fragments it indexes, so a predicate cannot be applied at build time.
+//! - `memory_limit_mb`: memory the index build may use before spilling to disk.
+
+use lance::index::DatasetIndexExt;
+use lance_index::scalar::ScalarIndexParams;
+use lancedb::index::IndexType;
+use lancedb::table::Table;
+use sonic_rs::{JsonContainerTrait, JsonValueMutTrait, JsonValueTrait};
+
+use crate::analyzer::fts_params;
+
+/// Keys of `config_json` that select the lance build path.
+const BUILD_OPTIONS: [&str; 4] = ["zone_size", "variant", "partial_build_fragments", "memory_limit_mb"];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum StringVariant {
+    Ngram,
+    Inverted,
+}
+
+impl StringVariant {
+    fn name(self) -> &'static str {
+        match self {
+            StringVariant::Ngram => "ngram",
+            StringVariant::Inverted => "inverted",
+        }
+    }
+}
+
+fn positive_integer(config: &sonic_rs::Value, key: &str) -> Result<Option<u64>, String> {
+    match config.get(key) {
+        None => Ok(None),
+        Some(v) => match v.as_u64() {
+            Some(n) if n > 0 => Ok(Some(n)),
+            _ => Err(format!("{} must be a positive integer", key)),
+        },
+    }
+}
+
+/// A scalar index to build with lance index parameters.
+pub(crate) struct ScalarIndexBuild {
+    /// Name of the lance scalar index plugin, e.g. "btree".
+    plugin: &'static str,
+    /// Parameters passed to the plugin, as a JSON object.
+    params: sonic_rs::Value,
+    /// Fragments of a partial build; `None` indexes all fragments.
+    fragment_ids: Option<Vec<u32>>,
+    variant: Option<StringVariant>,
+}
+
+impl ScalarIndexBuild {
+    /// `None` when `config` sets none of the build options, or `index_type` is not
+    /// a BTree, Bitmap, LabelList or FM index; lancedb builds those indices itself.
+    pub(crate) fn parse(index_type: &IndexType, config: &sonic_rs::Value) -> Result<Option<Self>, String> {
+        let (mut plugin, type_name) = match index_type {
+            IndexType::BTree => ("btree", "BTree"),
+            IndexType::Bitmap => ("bitmap", "Bitmap"),
+            IndexType::LabelList => ("labellist", "LabelList"),
+            IndexType::Fm => ("fm", "FM"),
+            _ => return Ok(None),
+        };
+        if !BUILD_OPTIONS.iter().any(|key| config.get(key).is_some()) {
+            return Ok(None);
+        }
+        let btree_only = |key: &str| {
+            if *index_type != IndexType::BTree {
+                return Err(format!("{} is only supported by BTree indices, not {} indices", key, type_name));
+            }
+            Ok(())
+        };
+
+        let mut params = sonic_rs::Value::new_object();
+        let zone_size = positive_integer(config, "zone_size")?;
+        if let Some(zone_size) = zone_size {
+            btree_only("zone_size")?;
+            params.as_object_mut().unwrap().insert("zone_size", zone_size);
+        }
+
+        let variant = match config.get("variant") {
+            None => None,
+            Some(v) => {
+                btree_only("variant")?;
+                match v.as_str() {
+                    Some("ngram") => Some(StringVariant::Ngram),
+                    Some("inverted") => Some(StringVariant::Inverted),
+                    _ => return Err("variant must be \"ngram\" or \"inverted\"".to_string()),
+                }
+            }
+        };
+        if let Some(variant) = variant {
+            if zone_size.is_some() {
+                return Err(format!("zone_size does not apply to the {} variant", variant.name()));
+            }
+            plugin = variant.name();
+            if variant == StringVariant::Inverted {
+                params = sonic_rs::to_value(&fts_params(config)?).map_err(|e| e.to_string())?;
+            }
+        }
+
+        if let Some(limit) = positive_integer(config, "memory_limit_mb")? {
+            params.as_object_mut().unwrap().insert("memory_limit_mb", limit);
+        }
+
+        let fragment_ids = match config.get("partial_build_fragments") {
+            None => None,
+            Some(v) => {
+                let invalid = || "partial_build_fragments must be a non-empty array of fragment ids".to_string();
+                let ids = v.as_array().ok_or_else(invalid)?;
+                let ids = ids
+                    .iter()
+                    .map(|id| id.as_u64().and_then(|id| u32::try_from(id).ok()).ok_or_else(invalid))
+                    .collect::<Result<Vec<u32>, String>>()?;
+                if ids.is_empty() {
+                    return Err(invalid());
+                }
+                Some(ids)
+            }
+        };
+
+        Ok(Some(Self {
+            plugin,
+            params,
+            fragment_ids,
+            variant,
+        }))
+    }
+
+    /// Builds the index on the table's dataset and reloads the table.
+    pub(crate) async fn create(
+        &self,
+        table: &Table,
+        columns: &[String],
+        name: Option<String>,
+        replace: bool,
+        train: bool,
+    ) -> Result<(), String> {
+        if table.as_native().is_none() {
+            return Err("Scalar index build options are only supported for local tables".to_string());
+        }
+        let [column] = columns else {
+            return Err(format!("A {} index is built on a single column", self.plugin));
+        };
+        if let Some(variant) = self.variant {
+            let schema = table.schema().await.map_err(|e| e.to_string())?;
+            let field = schema.field_with_name(column).map_err(|e| e.to_string())?;
+            if !matches!(
+                field.data_type(),
+                arrow_schema::DataType::Utf8 | arrow_schema::DataType::LargeUtf8
+            ) {
+                return Err(format!(
+                    "The {} variant needs a string column, but '{}' is {}",
+                    variant.name(),
+                    column,
+                    field.data_type()
+                ));
+            }
+        }
+
+        let mut dataset = crate::write::open_dataset(table).await?;
+        let params = ScalarIndexParams {
+            index_type: self.plugin.to_string(),
+            params: Some(self.params.to_string()),
+        };
+        let columns = [column.as_str()];
+        let mut builder = dataset
+            .create_index_builder(&columns, lance_index::IndexType::Scalar, &params)
+            .replace(replace)
+            .train(train);
+        if let Some(name) = name {
+            builder = builder.name(name);
+        }
+        if let Some(ids) = &self.fragment_ids {
+            builder = builder.fragments(ids.clone());
+        }
+        builder.await.map_err(|e| e.to_string())?;
+        // The index was committed on the dataset, so reload the table to see it.
+        table.checkout_latest().await.map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
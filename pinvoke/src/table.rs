@@ -5,8 +5,17 @@ use libc::c_char;
 use sonic_rs::JsonValueTrait;
 use std::ffi::CString;
 
+use crate::analyzer::{analyze_to_json, index_fts_params, Analyzer, FtsIndexRef};
 use crate::ffi::{callback_error, FfiCallback, UserData};
 use crate::ffi;
+use crate::scalar_index::ScalarIndexBuild;
+use crate::vector_index::{ModelSource, VectorIndexBuild};
+use crate::files::{export_stream, write_stream, ExportOptions, FileFormat, FileSource, ReadOptions};
+use crate::write::{
+    add_batches, commit_staged, parse_commit_metadata, stage_add_columns, stage_add_null_columns, stage_alter_columns,
+    stage_delete, stage_drop_columns, stage_update, with_conflict_retries, Committed, StagedWrite, WriteError,
+    WriteOptions,
+};
 
 /// C-compatible struct for update results, passed across FFI.
 #[repr(C)]
@@ -15,7 +24,17 @@ pub struct FfiUpdateResult {
     pub rows_updated: u64,
 }
 
+/// C-compatible struct for add results, passed across FFI.
+#[repr(C)]
+pub struct FfiAddResult {
+    pub version: u64,
+    pub num_inserted_rows: u64,
+    pub num_updated_rows: u64,
+    pub num_skipped_rows: u64,
+}
+
 /// C-compatible struct for merge insert results, passed across FFI.
+/// `affected_rows` is null unless the affected rows were requested.
 #[repr(C)]
 pub struct FfiMergeResult {
     pub version: u64,
@@ -24,6 +43,7 @@ pub struct FfiMergeResult {
     pub num_deleted_rows: u64,
     pub num_attempts: u32,
     pub num_rows: u64,
+    pub affected_rows: *mut ffi::FfiCData,
 }
 
 /// C-compatible struct for delete results, passed across FFI.
@@ -79,6 +99,115 @@ pub extern "C" fn table_count_rows(
     });
 }
 
+/// JSON spec for table_aggregate.
+#[derive(serde::Deserialize)]
+struct AggregateSpec {
+    filter: Option<String>,
+    #[serde(default)]
+    group_by: Vec<String>,
+    aggregates: Vec<AggregateJson>,
+}
+
+#[derive(serde::Deserialize)]
+struct AggregateJson {
+    /// One of count, sum, min, max, avg, count_distinct.
+    function: String,
+    /// Input column. Only `count` may omit it, meaning COUNT(*).
+    column: Option<String>,
+    /// Output column name. Defaults to `<function>_<column>` (or `count`).
+    alias: Option<String>,
+}
+
+fn aggregate_expr(agg: &AggregateJson) -> Result<datafusion::prelude::Expr, String> {
+    use datafusion::functions_aggregate::expr_fn::{avg, count, count_distinct, max, min, sum};
+    use datafusion::prelude::{ident, lit};
+
+    let input = agg.column.as_deref().map(ident);
+    let expr = match (agg.function.as_str(), input) {
+        ("count", None) => count(lit(1)),
+        ("count", Some(c)) => count(c),
+        ("count_distinct", Some(c)) => count_distinct(c),
+        ("sum", Some(c)) => sum(c),
+        ("min", Some(c)) => min(c),
+        ("max", Some(c)) => max(c),
+        ("avg", Some(c)) => avg(c),
+        (f @ ("count_distinct" | "sum" | "min" | "max" | "avg"), None) => {
+            return Err(format!("Aggregate '{}' requires a column", f));
+        }
+        (f, _) => return Err(format!("Unknown aggregate function: {}", f)),
+    };
+    let alias = match (&agg.alias, &agg.column) {
+        (Some(alias), _) => alias.clone(),
+        (None, Some(column)) => format!("{}_{}", agg.function, column),
+        (None, None) => agg.function.clone(),
+    };
+    Ok(expr.alias(alias))
+}
+
+/// Computes aggregates natively over an optionally filtered scan.
+///
+/// `spec_json` is `{"filter": "...", "group_by": [...], "aggregates": [{"function",
+/// "column", "alias"}]}`. The filter is pushed down into the Lance scan, so it
+/// can use scalar indices. Returns FfiCData with one row per group (or a single
+/// row without group_by); group columns come first.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_aggregate(
+    table_ptr: *const Table,
+    spec_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let spec: AggregateSpec = match sonic_rs::from_str(&ffi::to_string(spec_json)) {
+        Ok(s) => s,
+        Err(e) => {
+            callback_error(completion, user_data, format!("Invalid aggregate spec JSON: {}", e));
+            return;
+        }
+    };
+    if spec.aggregates.is_empty() && spec.group_by.is_empty() {
+        callback_error(completion, user_data, "Aggregate spec must contain aggregates or group_by");
+        return;
+    }
+    crate::spawn(async move {
+        match aggregate_impl(&table, spec).await {
+            Ok(ptr) => completion(ptr as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr()),
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
+async fn aggregate_impl(table: &Table, spec: AggregateSpec) -> Result<*mut ffi::FfiCData, String> {
+    use datafusion::prelude::{ident, SessionContext};
+    use lancedb::table::datafusion::BaseTableAdapter;
+
+    let aggr_exprs = spec
+        .aggregates
+        .iter()
+        .map(aggregate_expr)
+        .collect::<Result<Vec<_>, _>>()?;
+    let group_exprs = spec.group_by.iter().map(ident).collect::<Vec<_>>();
+
+    let adapter = BaseTableAdapter::try_new(table.base_table().clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    let ctx = SessionContext::new();
+    let mut df = ctx
+        .read_table(std::sync::Arc::new(adapter))
+        .map_err(|e| e.to_string())?;
+    if let Some(filter) = &spec.filter {
+        let predicate = df.parse_sql_expr(filter).map_err(|e| e.to_string())?;
+        df = df.filter(predicate).map_err(|e| e.to_string())?;
+    }
+    df = df.aggregate(group_exprs, aggr_exprs).map_err(|e| e.to_string())?;
+
+    let schema = std::sync::Arc::new(df.schema().as_arrow().clone());
+    let batches = df.collect().await.map_err(|e| e.to_string())?;
+    let batch = ffi::concat_or_empty(schema, batches).map_err(|e| e.to_string())?;
+    ffi::export_record_batch(batch).map_err(|e| e.to_string())
+}
+
 /// Deletes rows from the table matching the given SQL predicate.
 #[unsafe(no_mangle)]
 pub extern "C" fn table_delete(
@@ -104,6 +233,70 @@ pub extern "C" fn table_delete(
     });
 }
 
+/// Deletes rows matching the given SQL predicate through lancedb's delete, as
+/// table_delete, re-run on commit conflicts as `write_options_json` directs.
+/// An idempotency key or commit metadata, which lancedb's delete cannot carry,
+/// stages the delete on a local table instead (see `commit_staged`).
+/// write_options_json: optional JSON object of write options (null for defaults),
+/// see `WriteOptions`. With an `idempotency_key` that is already committed, nothing
+/// is deleted and the original version is returned with zero deleted rows. A
+/// conflict that is not retried is reported with `ERROR_CODE_COMMIT_CONFLICT`
+/// (see ffi_error_details).
+/// commit_metadata_json: optional JSON object of string values recorded with the
+/// commit (null for none); table_list_versions reports it as `commit_metadata`.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_delete_with_options(
+    table_ptr: *const Table,
+    predicate: *const c_char,
+    write_options_json: *const c_char,
+    commit_metadata_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let commit_metadata = match parse_commit_metadata(commit_metadata_json) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let predicate = crate::ffi::to_string(predicate);
+    let write_options = match WriteOptions::parse(write_options_json) {
+        Ok(o) => o,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    crate::spawn(async move {
+        // lancedb's delete cannot carry transaction properties, so only a key or
+        // commit metadata stages the delete here.
+        let result = if write_options.idempotency_key.is_none() && commit_metadata.is_none() {
+            with_conflict_retries(&write_options, || table.delete(&predicate))
+                .await
+                .map(|result| (result.version, result.num_deleted_rows))
+        } else {
+            commit_staged(&table, &write_options, commit_metadata.as_ref(), |dataset| {
+                stage_delete(dataset, &predicate)
+            })
+            .await
+            .map(|committed| {
+                let (version, num_deleted_rows) = committed.into_version_and_output();
+                (version, num_deleted_rows.unwrap_or(0))
+            })
+        };
+        match result {
+            Ok((version, num_deleted_rows)) => {
+                let ffi = Box::new(FfiDeleteResult { version, num_deleted_rows });
+                completion(Box::into_raw(ffi) as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => e.report(completion, user_data),
+        }
+    });
+}
+
 /// Frees an FfiDeleteResult pointer returned by table_delete.
 #[unsafe(no_mangle)]
 pub extern "C" fn table_delete_result_free(ptr: *mut FfiDeleteResult) {
@@ -112,6 +305,200 @@ pub extern "C" fn table_delete_result_free(ptr: *mut FfiDeleteResult) {
     }
 }
 
+/// C-compatible struct for delete-by-row-id results, passed across FFI.
+/// `deleted_rows` is null unless the deleted rows were requested.
+#[repr(C)]
+pub struct FfiDeleteRowIdsResult {
+    pub version: u64,
+    pub num_deleted_rows: u64,
+    pub deleted_rows: *mut ffi::FfiCData,
+}
+
+/// Deletes rows by their `_rowid` values.
+/// row_ids: pointer to array of u64 row ID values.
+/// row_ids_len: number of row IDs.
+/// return_rows: when true, the deleted rows are returned as Arrow in
+/// `deleted_rows`, read from the same version the delete applies to.
+/// columns_json: optional JSON array of columns to return (null for all table
+/// columns; list `_rowid` to include it); only used when return_rows is true.
+/// A conflicting concurrent write is retried as for table_delete_with_options,
+/// and reported with `ERROR_CODE_COMMIT_CONFLICT` once the retries run out.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_delete_row_ids(
+    table_ptr: *const Table,
+    row_ids: *const u64,
+    row_ids_len: usize,
+    return_rows: bool,
+    columns_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    if row_ids.is_null() && row_ids_len > 0 {
+        callback_error(completion, user_data, "Row ID pointer is null");
+        return;
+    }
+    let id_vec: Vec<u64> = if row_ids_len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(row_ids, row_ids_len) }.to_vec()
+    };
+    let columns = match ffi::parse_optional_json_list(columns_json) {
+        Some(c) => Some(c),
+        None if !columns_json.is_null() => {
+            callback_error(completion, user_data, "Invalid columns JSON");
+            return;
+        }
+        None => None,
+    };
+
+    crate::spawn(async move {
+        match delete_row_ids_impl(&table, id_vec, return_rows, columns).await {
+            Ok(result) => {
+                completion(Box::into_raw(Box::new(result)) as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => e.report(completion, user_data),
+        }
+    });
+}
+
+/// Deletes the rows at `row_ids` by extending the deletion vectors of their
+/// fragments. The rows are taken and the delete is staged from one snapshot of
+/// the table, so the returned rows are exactly the ones deleted. A concurrent
+/// write makes the commit conflict; it is then staged again on the new latest
+/// version, as for the other writes (see `commit_staged`).
+async fn delete_row_ids_impl(
+    table: &Table,
+    row_ids: Vec<u64>,
+    return_rows: bool,
+    columns: Option<Vec<String>>,
+) -> Result<FfiDeleteRowIdsResult, WriteError> {
+    if table.as_native().is_none() {
+        return Err("Deleting by row id is only supported for local tables".to_string().into());
+    }
+    let mut seen = std::collections::HashSet::with_capacity(row_ids.len());
+    let row_ids: Vec<u64> = row_ids.into_iter().filter(|id| seen.insert(*id)).collect();
+
+    let (version, rows) = if row_ids.is_empty() {
+        let dataset = crate::write::open_dataset(table).await?;
+        let projection = delete_row_ids_projection(&dataset, columns.as_deref())?;
+        let schema = std::sync::Arc::new(arrow_schema::Schema::from(&projection));
+        (dataset.manifest.version, arrow_array::RecordBatch::new_empty(schema))
+    } else {
+        let committed = commit_staged(table, &WriteOptions::default(), None, |dataset| {
+            stage_delete_row_ids(dataset, &row_ids, columns.as_deref())
+        })
+        .await?;
+        let (version, rows) = committed.into_version_and_output();
+        (version, rows.ok_or_else(|| "Deleting by row id was not committed".to_string())?)
+    };
+    let num_deleted_rows = rows.num_rows() as u64;
+    let deleted_rows = if return_rows {
+        ffi::export_record_batch(rows).map_err(|e| e.to_string())?
+    } else {
+        std::ptr::null_mut()
+    };
+    Ok(FfiDeleteRowIdsResult { version, num_deleted_rows, deleted_rows })
+}
+
+/// The schema of the rows returned by `table_delete_row_ids`: `columns`, or every
+/// table column when `None`. `_rowid` is only included when listed.
+fn delete_row_ids_projection(
+    dataset: &lance::Dataset,
+    columns: Option<&[String]>,
+) -> Result<lance::datatypes::Schema, String> {
+    let projection = match columns {
+        Some(columns) => columns.to_vec(),
+        None => dataset.schema().fields.iter().map(|f| f.name.clone()).collect(),
+    };
+    dataset
+        .schema()
+        .project_preserve_system_columns(&projection)
+        .map_err(|e| e.to_string())
+}
+
+/// Stages deleting the rows at `row_ids` of `dataset`. The output is the deleted
+/// rows, projected by `delete_row_ids_projection`.
+async fn stage_delete_row_ids(
+    dataset: std::sync::Arc<lance::Dataset>,
+    row_ids: &[u64],
+    columns: Option<&[String]>,
+) -> Result<StagedWrite<arrow_array::RecordBatch>, String> {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::UInt64Type;
+    use lance::dataset::transaction::{Operation, Transaction};
+    use lance::dataset::ProjectionRequest;
+
+    let projection = delete_row_ids_projection(&dataset, columns)?;
+    let mut rows = dataset
+        .take_builder(row_ids, ProjectionRequest::from_schema(projection))
+        .map_err(|e| e.to_string())?
+        .with_row_address(true)
+        .execute()
+        .await
+        .map_err(|e| e.to_string())?;
+    let addr_index = rows.schema().index_of("_rowaddr").map_err(|e| e.to_string())?;
+    let addresses = rows.remove_column(addr_index).as_primitive::<UInt64Type>().values().to_vec();
+
+    let (updated_fragments, deleted_fragment_ids) = delete_addresses(&dataset, &addresses).await?;
+    let operation = Operation::Delete {
+        updated_fragments,
+        deleted_fragment_ids,
+        predicate: format!("_rowid IN <{} row ids>", row_ids.len()),
+    };
+    Ok(StagedWrite {
+        transaction: Transaction::new(dataset.manifest.version, operation, None),
+        affected_rows: addresses,
+        output: rows,
+    })
+}
+
+/// Writes deletion vectors for the given row addresses of `dataset`, returning
+/// the fragments that still hold rows and the ids of the fragments left empty.
+pub(crate) async fn delete_addresses(
+    dataset: &lance::Dataset,
+    addresses: &[u64],
+) -> Result<(Vec<lance::table::format::Fragment>, Vec<u64>), String> {
+    // A row address is the fragment id in the high 32 bits and the row offset
+    // within the fragment in the low 32 bits.
+    let mut offsets: std::collections::BTreeMap<u32, Vec<u32>> = std::collections::BTreeMap::new();
+    for address in addresses {
+        offsets.entry((address >> 32) as u32).or_default().push(*address as u32);
+    }
+    let mut updates = Vec::with_capacity(offsets.len());
+    for (fragment_id, fragment_offsets) in offsets {
+        let fragment = dataset
+            .get_fragment(fragment_id as usize)
+            .ok_or_else(|| format!("Fragment {} not found", fragment_id))?;
+        updates.push(async move {
+            let updated = fragment.extend_deletions(fragment_offsets).await?;
+            Ok::<_, lance::Error>((fragment_id as u64, updated.map(|f| f.metadata().clone())))
+        });
+    }
+    let mut updated_fragments = Vec::new();
+    let mut deleted_fragment_ids = Vec::new();
+    for (fragment_id, updated) in futures::future::try_join_all(updates).await.map_err(|e| e.to_string())? {
+        match updated {
+            Some(fragment) => updated_fragments.push(fragment),
+            None => deleted_fragment_ids.push(fragment_id),
+        }
+    }
+    Ok((updated_fragments, deleted_fragment_ids))
+}
+
+/// Frees an FfiDeleteRowIdsResult pointer returned by table_delete_row_ids,
+/// including any returned rows.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_delete_row_ids_result_free(ptr: *mut FfiDeleteRowIdsResult) {
+    if !ptr.is_null() {
+        unsafe {
+            let result = Box::from_raw(ptr);
+            ffi::free_ffi_cdata(result.deleted_rows);
+        }
+    }
+}
+
 /// Updates rows in the table. column_sqlexprs_json is a JSON array of [name, expr] pairs.
 /// filter is an optional SQL predicate (null for all rows).
 #[unsafe(no_mangle)]
@@ -161,6 +548,93 @@ pub extern "C" fn table_update(
     });
 }
 
+/// Updates rows through lancedb's update, as table_update, re-run on commit
+/// conflicts as `write_options_json` directs. An idempotency key or commit metadata
+/// stages the update on a local table instead, streaming the matching rows into
+/// new fragments (see `stage_update`).
+/// write_options_json: optional JSON object of write options (null for defaults),
+/// see `WriteOptions`. With an `idempotency_key` that is already committed, nothing
+/// is updated and the original version is returned with zero updated rows. A
+/// conflict that is not retried is reported with `ERROR_CODE_COMMIT_CONFLICT`
+/// (see ffi_error_details).
+/// commit_metadata_json: optional JSON object of string values recorded with the
+/// commit (null for none); see table_list_versions.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_update_with_options(
+    table_ptr: *const Table,
+    filter: *const c_char,
+    column_sqlexprs_json: *const c_char,
+    write_options_json: *const c_char,
+    commit_metadata_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let commit_metadata = match parse_commit_metadata(commit_metadata_json) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let write_options = match WriteOptions::parse(write_options_json) {
+        Ok(o) => o,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let filter = if filter.is_null() {
+        None
+    } else {
+        Some(crate::ffi::to_string(filter))
+    };
+    let column_sqlexprs_str = crate::ffi::to_string(column_sqlexprs_json);
+
+    crate::spawn(async move {
+        let column_sqlexprs: Vec<(String, String)> = match sonic_rs::from_str(&column_sqlexprs_str) {
+            Ok(c) => c,
+            Err(e) => {
+                callback_error(completion, user_data, e);
+                return;
+            }
+        };
+
+        // As for deletes, only a key or commit metadata stages the update here.
+        let result = if write_options.idempotency_key.is_none() && commit_metadata.is_none() {
+            with_conflict_retries(&write_options, || {
+                let mut builder = table.update();
+                if let Some(f) = &filter {
+                    builder = builder.only_if(f.clone());
+                }
+                for (column, expr) in &column_sqlexprs {
+                    builder = builder.column(column.clone(), expr.clone());
+                }
+                builder.execute()
+            })
+            .await
+            .map(|result| (result.version, result.rows_updated))
+        } else {
+            commit_staged(&table, &write_options, commit_metadata.as_ref(), |dataset| {
+                stage_update(dataset, filter.as_deref(), &column_sqlexprs)
+            })
+            .await
+            .map(|committed| {
+                let (version, rows_updated) = committed.into_version_and_output();
+                (version, rows_updated.unwrap_or(0))
+            })
+        };
+        match result {
+            Ok((version, rows_updated)) => {
+                let ffi = Box::new(FfiUpdateResult { version, rows_updated });
+                completion(Box::into_raw(ffi) as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => e.report(completion, user_data),
+        }
+    });
+}
+
 /// Frees an FfiUpdateResult pointer returned by table_update.
 #[unsafe(no_mangle)]
 pub extern "C" fn table_update_result_free(ptr: *mut FfiUpdateResult) {
@@ -169,6 +643,103 @@ pub extern "C" fn table_update_result_free(ptr: *mut FfiUpdateResult) {
     }
 }
 
+/// Updates rows from Arrow values rather than SQL expressions.
+/// on_columns_json: JSON array of key column names, e.g. '["id"]'.
+/// arrays/schema/batch_count: Arrow C Data Interface arrays holding the key
+/// columns plus the new values for each column to update.
+/// Source rows are matched to target rows by key and matched rows are
+/// overwritten; source rows with no match are ignored (nothing is inserted).
+/// Returns an FfiUpdateResult pointer (free with table_update_result_free).
+/// write_options_json: optional JSON object of write options (null for defaults),
+/// as for table_merge_insert_clauses. A conflict that is not retried is reported
+/// with `ERROR_CODE_COMMIT_CONFLICT` (see ffi_error_details).
+/// commit_metadata_json: optional JSON object of string values recorded with the
+/// commit (null for none); see table_list_versions.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_update_from_batch(
+    table_ptr: *const Table,
+    on_columns_json: *const c_char,
+    arrays: *mut arrow_data::ffi::FFI_ArrowArray,
+    schema: *mut arrow_schema::ffi::FFI_ArrowSchema,
+    batch_count: usize,
+    write_options_json: *const c_char,
+    commit_metadata_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let commit_metadata = match parse_commit_metadata(commit_metadata_json) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let write_options = match WriteOptions::parse(write_options_json) {
+        Ok(o) => o,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let on_columns_str = crate::ffi::to_string(on_columns_json);
+
+    let (batches, schema_ref) = match ffi::import_batches(arrays, schema, batch_count) {
+        Ok(r) => r,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+
+    crate::spawn(async move {
+        let on_columns: Vec<String> = match sonic_rs::from_str(&on_columns_str) {
+            Ok(c) => c,
+            Err(e) => {
+                callback_error(completion, user_data, e);
+                return;
+            }
+        };
+        if on_columns.is_empty() {
+            callback_error(completion, user_data, "on_columns must not be empty");
+            return;
+        }
+        if let Some(missing) = on_columns.iter().find(|c| schema_ref.field_with_name(c).is_err()) {
+            callback_error(
+                completion,
+                user_data,
+                format!("key column '{}' is missing from the update batch", missing),
+            );
+            return;
+        }
+        if schema_ref.fields().len() <= on_columns.len() {
+            callback_error(completion, user_data, "update batch has no value columns besides the keys");
+            return;
+        }
+
+        let clauses = vec![MergeClause::WhenMatchedUpdate { condition: None, set: None }];
+        let options = MergeOptions {
+            use_index: true,
+            timeout_ms: -1,
+            use_lsm_write: -1,
+            return_affected: false,
+            write: write_options,
+            commit_metadata,
+        };
+        match merge_insert_clauses_impl(&table, on_columns, clauses, batches, schema_ref, &options).await {
+            Ok(result) => {
+                let ffi = Box::new(FfiUpdateResult {
+                    version: result.version,
+                    rows_updated: result.num_updated_rows,
+                });
+                completion(Box::into_raw(ffi) as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => e.report(completion, user_data),
+        }
+    });
+}
+
 /// Returns the table's Arrow schema via the C Data Interface.
 /// The callback receives a pointer to a heap-allocated FFI_ArrowSchema
 /// (caller must free with free_ffi_schema).
@@ -244,17 +815,553 @@ pub extern "C" fn table_add(
     });
 }
 
-/// Returns the current version of the table as a u64 via the callback.
+/// Parses an add mode, "append" (default) or "overwrite" (null = "append"), for the
+/// exports added after table_add, which reject other modes rather than appending.
+fn parse_add_mode(mode: *const c_char) -> Result<lancedb::table::AddDataMode, String> {
+    match ffi::parse_optional_string(mode).as_deref() {
+        None | Some("append") => Ok(lancedb::table::AddDataMode::Append),
+        Some("overwrite") => Ok(lancedb::table::AddDataMode::Overwrite),
+        Some(other) => Err(format!("Unknown add mode '{}', expected 'append' or 'overwrite'", other)),
+    }
+}
+
+/// Adds data to the table, as table_add, committed as `write_options_json` directs.
+/// write_options_json: optional JSON object of write options (null for defaults),
+/// see `WriteOptions`. With an `idempotency_key` that is already committed, nothing is
+/// written and the version of the original commit is returned with zero inserted
+/// rows. A conflict that is not retried is reported with `ERROR_CODE_COMMIT_CONFLICT`
+/// (see ffi_error_details).
+/// cast_to_table_schema: if true, the data is cast to the table schema first (see
+/// `cast::cast_batches_to_schema`), filling missing nullable columns with nulls.
+/// commit_metadata_json: optional JSON object of string values stored in the commit's
+/// transaction properties (null for none); see table_list_versions.
+/// Returns an FfiAddResult pointer (free with table_add_result_free).
 #[unsafe(no_mangle)]
-pub extern "C" fn table_version(
+pub extern "C" fn table_add_with_options(
     table_ptr: *const Table,
+    arrays: *mut arrow_data::ffi::FFI_ArrowArray,
+    schema: *mut arrow_schema::ffi::FFI_ArrowSchema,
+    batch_count: usize,
+    mode: *const c_char,
+    write_options_json: *const c_char,
+    cast_to_table_schema: bool,
+    commit_metadata_json: *const c_char,
     completion: FfiCallback,
     user_data: *mut std::ffi::c_void,
 ) {
     let user_data = UserData(user_data);
     let table = ffi_clone_arc!(table_ptr, Table);
-    crate::spawn(async move {
-        match table.version().await {
+    let commit_metadata = match parse_commit_metadata(commit_metadata_json) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let write_options = match WriteOptions::parse(write_options_json) {
+        Ok(o) => o,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+
+    let (batches, schema_ref) = match ffi::import_batches(arrays, schema, batch_count) {
+        Ok(r) => r,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+
+    let add_mode = match parse_add_mode(mode) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+
+    crate::spawn(async move {
+        let batches = if cast_to_table_schema {
+            match crate::cast::cast_to_table(&table, batches, &schema_ref, true).await {
+                Ok((batches, _)) => batches,
+                Err(e) => {
+                    callback_error(completion, user_data, e);
+                    return;
+                }
+            }
+        } else {
+            batches
+        };
+        let num_rows = batches.iter().map(|b| b.num_rows() as u64).sum::<u64>();
+        match add_batches(&table, batches, add_mode, &write_options, commit_metadata.as_ref()).await {
+            Ok(added) => {
+                let ffi = Box::new(FfiAddResult {
+                    version: added.version,
+                    num_inserted_rows: if added.replayed { 0 } else { num_rows },
+                    num_updated_rows: 0,
+                    num_skipped_rows: 0,
+                });
+                completion(Box::into_raw(ffi) as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => e.report(completion, user_data),
+        }
+    });
+}
+
+/// Appends (or with mode "overwrite", replaces) the table data with the contents of
+/// Parquet, CSV or newline-delimited JSON files, streamed into a single commit.
+/// `paths_json` is a JSON array of local paths or object store URIs, read with the
+/// table's storage options. CSV and JSON are parsed with the table schema; Parquet
+/// data must match it unless `cast_to_table_schema` is set.
+/// Calls completion with the new version.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_add_from_files(
+    table_ptr: *const Table,
+    paths_json: *const c_char,
+    format: *const c_char,
+    read_options_json: *const c_char,
+    mode: *const c_char,
+    cast_to_table_schema: bool,
+    commit_metadata_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let Some(paths) = ffi::parse_optional_json_list(paths_json) else {
+        callback_error(completion, user_data, "paths_json must be a JSON array of strings");
+        return;
+    };
+    let format = match FileFormat::parse(&ffi::to_string(format)) {
+        Ok(f) => f,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let read_options = match ReadOptions::parse(read_options_json) {
+        Ok(o) => o,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let commit_metadata = match parse_commit_metadata(commit_metadata_json) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let write_mode = match parse_add_mode(mode) {
+        Ok(lancedb::table::AddDataMode::Append) => lance::dataset::WriteMode::Append,
+        Ok(lancedb::table::AddDataMode::Overwrite) => lance::dataset::WriteMode::Overwrite,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+
+    crate::spawn(async move {
+        let result = async {
+            let table_schema = table.schema().await.map_err(|e| e.to_string())?;
+            let storage_options = crate::write::storage_options(&table).await?;
+            let text_schema = (format != FileFormat::Parquet).then_some(&table_schema);
+            let source = FileSource::open(paths, format, &read_options, text_schema, storage_options).await?;
+            let cast_schema = if cast_to_table_schema || format != FileFormat::Parquet {
+                Some(crate::cast::cast_schema(&source.schema(), &table_schema, true)?)
+            } else {
+                None
+            };
+            let stream = source.stream(cast_schema, table_schema).await?;
+            write_stream(&table, stream, write_mode, commit_metadata).await
+        }
+        .await;
+        match result {
+            Ok(version) => {
+                completion(version as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
+/// Writes the whole table, at `version` (the current version when negative), to
+/// Parquet, CSV or newline-delimited JSON files under `uri`, a local directory or
+/// object store URI reached with the table's storage options.
+/// `export_options_json` sets `row_group_size` and `compression` (Parquet),
+/// `max_rows_per_file`, and `has_header` and `delimiter` (CSV). Past versions can
+/// only be exported from local tables.
+/// Returns `{"files": [...], "num_rows": N}` as a JSON string; caller must free it
+/// with free_string().
+#[unsafe(no_mangle)]
+pub extern "C" fn table_export(
+    table_ptr: *const Table,
+    version: i64,
+    uri: *const c_char,
+    format: *const c_char,
+    export_options_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let uri = ffi::to_string(uri);
+    let format = match FileFormat::parse(&ffi::to_string(format)) {
+        Ok(f) => f,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let options = match ExportOptions::parse(export_options_json, format) {
+        Ok(o) => o,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+
+    crate::spawn(async move {
+        let result = async {
+            let storage_options = crate::write::storage_options(&table).await?;
+            if version >= 0 {
+                if table.as_native().is_none() {
+                    return Err("Exporting a past version is only supported for local tables".to_string());
+                }
+                let dataset = crate::write::open_dataset(&table)
+                    .await?
+                    .checkout_version(version as u64)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let schema = std::sync::Arc::new(arrow_schema::Schema::from(dataset.schema()));
+                let stream = dataset.scan().try_into_stream().await.map_err(|e| e.to_string())?;
+                export_stream(stream, schema, &uri, format, &options, storage_options).await
+            } else {
+                let stream = table.query().execute().await.map_err(|e| e.to_string())?;
+                let schema = stream.schema();
+                export_stream(stream, schema, &uri, format, &options, storage_options).await
+            }
+        }
+        .await;
+        match result {
+            Ok(summary) => {
+                let json = sonic_rs::to_string(&summary).unwrap_or_default();
+                let c_str = CString::new(json).unwrap_or_default();
+                completion(c_str.into_raw() as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
+/// What a deduplicating add does with rows whose primary key already exists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DuplicateKeyMode {
+    Skip,
+    Error,
+    Overwrite,
+}
+
+impl DuplicateKeyMode {
+    fn parse(mode: &str) -> Result<Self, String> {
+        match mode {
+            "skip" => Ok(Self::Skip),
+            "error" => Ok(Self::Error),
+            "overwrite" => Ok(Self::Overwrite),
+            other => Err(format!(
+                "Unknown on_duplicate mode '{}', expected 'skip', 'error' or 'overwrite'",
+                other
+            )),
+        }
+    }
+}
+
+/// Returns the table's unenforced primary key columns, in key order.
+pub(crate) async fn primary_key_columns(table: &Table) -> Result<Vec<String>, String> {
+    let native = table
+        .as_native()
+        .ok_or_else(|| "Primary key lookups are only supported for local tables".to_string())?;
+    let manifest = native.manifest().await.map_err(|e| e.to_string())?;
+    let columns: Vec<String> = manifest
+        .schema
+        .unenforced_primary_key()
+        .iter()
+        .map(|f| f.name.clone())
+        .collect();
+    if columns.is_empty() {
+        return Err("Table has no primary key; set one with table_set_unenforced_primary_key".to_string());
+    }
+    Ok(columns)
+}
+
+/// Drops rows of `batch` whose primary key repeats within the batch, keeping the
+/// first occurrence (`Skip`) or the last (`Overwrite`). Returns the remaining rows
+/// and the number dropped; `Error` fails on the first repeated key.
+fn dedup_input(
+    batch: &arrow_array::RecordBatch,
+    key_columns: &[arrow_array::ArrayRef],
+    mode: DuplicateKeyMode,
+) -> Result<(arrow_array::RecordBatch, u64), String> {
+    let rows = key_rows(key_columns)?;
+    let mut keep = vec![true; batch.num_rows()];
+    let mut seen = std::collections::HashMap::new();
+    for row in 0..batch.num_rows() {
+        if has_null_key(key_columns, row) {
+            return Err(format!("Row {} has a null primary key", row));
+        }
+        let key = rows.row(row);
+        match seen.get(&key) {
+            None => {
+                seen.insert(key, row);
+            }
+            Some(&first) => match mode {
+                DuplicateKeyMode::Error => {
+                    return Err(format!(
+                        "Primary key ({}) appears more than once in the data",
+                        display_key(key_columns, row)?
+                    ));
+                }
+                DuplicateKeyMode::Skip => keep[row] = false,
+                DuplicateKeyMode::Overwrite => {
+                    keep[first] = false;
+                    seen.insert(key, row);
+                }
+            },
+        }
+    }
+    let dropped = keep.iter().filter(|k| !**k).count() as u64;
+    if dropped == 0 {
+        return Ok((batch.clone(), 0));
+    }
+    let mask = arrow_array::BooleanArray::from(keep);
+    let kept = arrow_select::filter::filter_record_batch(batch, &mask).map_err(|e| e.to_string())?;
+    Ok((kept, dropped))
+}
+
+async fn add_deduplicated_impl(
+    table: &Table,
+    batches: Vec<arrow_array::RecordBatch>,
+    schema: arrow_schema::SchemaRef,
+    mode: DuplicateKeyMode,
+    write_options: &WriteOptions,
+    cast_to_table_schema: bool,
+    commit_metadata: Option<&std::collections::HashMap<String, String>>,
+) -> Result<FfiAddResult, WriteError> {
+    let primary_key = primary_key_columns(table).await?;
+    let (batches, schema) = if cast_to_table_schema {
+        crate::cast::cast_to_table(table, batches, &schema, true).await?
+    } else {
+        (batches, schema)
+    };
+    let batch = ffi::concat_or_empty(schema.clone(), batches).map_err(|e| e.to_string())?;
+    let key_columns = primary_key
+        .iter()
+        .map(|c| {
+            batch
+                .column_by_name(c)
+                .cloned()
+                .ok_or_else(|| format!("Primary key column '{}' is missing from the data", c))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let (batch, mut skipped) = dedup_input(&batch, &key_columns, mode)?;
+    if batch.num_rows() == 0 {
+        let version = table.version().await.map_err(|e| e.to_string())?;
+        return Ok(FfiAddResult {
+            version,
+            num_inserted_rows: 0,
+            num_updated_rows: 0,
+            num_skipped_rows: skipped,
+        });
+    }
+
+    if mode == DuplicateKeyMode::Error {
+        // An insert-only merge skips rows whose key exists; fewer inserted rows than
+        // given means some keys did, and failing before the commit writes nothing.
+        // Retries stage the merge again against the newer version.
+        let merge = ResolvedMerge { matched: None, insert: true, by_source: None };
+        let rows = vec![batch];
+        let num_rows = rows[0].num_rows() as u64;
+        let committed = commit_staged(table, write_options, commit_metadata, |dataset| {
+            let (primary_key, merge, rows, schema) = (&primary_key, &merge, &rows, &schema);
+            async move {
+                let staged = stage_merge(dataset, primary_key, merge, true, rows, schema).await?;
+                let existing = num_rows - staged.output.num_inserted_rows;
+                if existing > 0 {
+                    return Err(format!("{} rows have primary keys that already exist in the table", existing));
+                }
+                Ok(staged)
+            }
+        })
+        .await?;
+        return Ok(FfiAddResult {
+            version: committed.version(),
+            num_inserted_rows: num_rows,
+            num_updated_rows: 0,
+            num_skipped_rows: skipped,
+        });
+    }
+
+    let (version, num_inserted_rows, num_updated_rows) = if commit_metadata.is_some() {
+        // lancedb's merge insert cannot carry transaction properties, so the merge
+        // is staged instead.
+        let mut clauses = vec![MergeClause::WhenNotMatchedInsert { condition: None }];
+        if mode == DuplicateKeyMode::Overwrite {
+            clauses.push(MergeClause::WhenMatchedUpdate { condition: None, set: None });
+        }
+        let options = MergeOptions {
+            use_index: true,
+            timeout_ms: -1,
+            use_lsm_write: -1,
+            return_affected: false,
+            write: write_options.clone(),
+            commit_metadata: commit_metadata.cloned(),
+        };
+        let batches = vec![batch.clone()];
+        let result = merge_insert_clauses_impl(table, primary_key.clone(), clauses, batches, schema, &options).await?;
+        (result.version, result.num_inserted_rows, result.num_updated_rows)
+    } else {
+        let on: Vec<&str> = primary_key.iter().map(|s| s.as_str()).collect();
+        let result = with_conflict_retries(write_options, || {
+            let mut builder = table.merge_insert(&on);
+            builder.when_not_matched_insert_all();
+            if mode == DuplicateKeyMode::Overwrite {
+                builder.when_matched_update_all(None);
+            }
+            let reader = arrow_array::RecordBatchIterator::new(vec![Ok(batch.clone())], schema.clone());
+            builder.execute(Box::new(reader))
+        })
+        .await?;
+        (result.version, result.num_inserted_rows, result.num_updated_rows)
+    };
+    skipped += (batch.num_rows() as u64).saturating_sub(num_inserted_rows + num_updated_rows);
+    Ok(FfiAddResult {
+        version,
+        num_inserted_rows,
+        num_updated_rows,
+        num_skipped_rows: skipped,
+    })
+}
+
+/// Adds data to the table, using the unenforced primary key (see
+/// table_set_unenforced_primary_key) to handle rows whose key already exists.
+/// on_duplicate: "skip" leaves existing rows untouched and drops the new ones,
+/// "error" fails without writing anything, "overwrite" replaces the existing rows.
+/// Rows repeating a key within the data collapse to the first ("skip") or last
+/// ("overwrite") occurrence; the dropped rows count as skipped. Null keys are rejected.
+/// "error" runs as one insert-only merge that is checked before it commits, so a
+/// concurrent writer adding a conflicting key either fails the add or is seen by it.
+/// write_options_json, cast_to_table_schema and commit_metadata_json are as for
+/// table_add_with_options, except that idempotency keys are not supported.
+/// Returns an FfiAddResult pointer (free with table_add_result_free).
+#[unsafe(no_mangle)]
+pub extern "C" fn table_add_deduplicated(
+    table_ptr: *const Table,
+    arrays: *mut arrow_data::ffi::FFI_ArrowArray,
+    schema: *mut arrow_schema::ffi::FFI_ArrowSchema,
+    batch_count: usize,
+    on_duplicate: *const c_char,
+    write_options_json: *const c_char,
+    cast_to_table_schema: bool,
+    commit_metadata_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let mode = match DuplicateKeyMode::parse(&ffi::to_string(on_duplicate)) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let commit_metadata = match parse_commit_metadata(commit_metadata_json) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let write_options = match WriteOptions::parse(write_options_json)
+        .and_then(|o| o.reject_idempotency_key("table_add_deduplicated").map(|_| o))
+    {
+        Ok(o) => o,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let (batches, schema_ref) = match ffi::import_batches(arrays, schema, batch_count) {
+        Ok(r) => r,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+
+    crate::spawn(async move {
+        match add_deduplicated_impl(
+            &table, batches, schema_ref, mode, &write_options, cast_to_table_schema, commit_metadata.as_ref(),
+        )
+        .await
+        {
+            Ok(result) => {
+                completion(Box::into_raw(Box::new(result)) as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => e.report(completion, user_data),
+        }
+    });
+}
+
+/// Frees an FfiAddResult pointer returned by table_add_with_options or
+/// table_add_deduplicated.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_add_result_free(ptr: *mut FfiAddResult) {
+    if !ptr.is_null() {
+        unsafe { drop(Box::from_raw(ptr)); }
+    }
+}
+
+/// Looks up the version committed by a write with the given idempotency key among
+/// the latest `window` versions (0 for the default window writes search, see
+/// `WriteOptions::idempotency_window`).
+/// Returns the version as a u64 via the callback, or 0 if no commit recorded the key.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_find_idempotency_key(
+    table_ptr: *const Table,
+    key: *const c_char,
+    window: u64,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let key = ffi::to_string(key);
+    crate::spawn(async move {
+        let window = if window == 0 { crate::write::DEFAULT_IDEMPOTENCY_WINDOW } else { window };
+        match crate::write::find_idempotency_key(&table, &key, window).await {
+            Ok(version) => {
+                completion(version.unwrap_or(0) as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
+/// Returns the current version of the table as a u64 via the callback.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_version(
+    table_ptr: *const Table,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    crate::spawn(async move {
+        match table.version().await {
             Ok(version) => {
                 completion(version as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
             }
@@ -392,6 +1499,9 @@ pub extern "C" fn table_replace_field_metadata(
 }
 
 /// Returns the table versions as a JSON string.
+/// For local tables each entry also has `operation` (e.g. "Append", "Delete",
+/// "Update", "Merge"; null if the transaction was not recorded), `num_rows`,
+/// `num_rows_delta` (change from the previous listed version) and `commit_metadata`.
 /// Caller must free the returned string with free_string().
 #[unsafe(no_mangle)]
 pub extern "C" fn table_list_versions(
@@ -404,14 +1514,31 @@ pub extern "C" fn table_list_versions(
     crate::spawn(async move {
         match table.list_versions().await {
             Ok(versions) => {
+                let numbers: Vec<u64> = versions.iter().map(|v| v.version).collect();
+                let details = match crate::write::version_details(&table, &numbers).await {
+                    Ok(d) => d,
+                    Err(e) => {
+                        callback_error(completion, user_data, e);
+                        return;
+                    }
+                };
                 let json_versions: Vec<sonic_rs::Value> = versions
                     .iter()
-                    .map(|v| {
-                        sonic_rs::json!({
+                    .map(|v| match details.get(&v.version) {
+                        Some(d) => sonic_rs::json!({
                             "version": v.version,
                             "timestamp": v.timestamp.to_rfc3339(),
                             "metadata": v.metadata,
-                        })
+                            "operation": d.operation,
+                            "num_rows": d.num_rows,
+                            "num_rows_delta": d.num_rows_delta,
+                            "commit_metadata": d.commit_metadata,
+                        }),
+                        None => sonic_rs::json!({
+                            "version": v.version,
+                            "timestamp": v.timestamp.to_rfc3339(),
+                            "metadata": v.metadata,
+                        }),
                     })
                     .collect();
                 let json = sonic_rs::to_string(&json_versions).unwrap_or_default();
@@ -528,6 +1655,8 @@ pub extern "C" fn table_uri(
 /// index_type: integer matching the IndexType enum mapping in `ffi_to_index_type`
 /// (0=IvfFlat, 1=IvfSq, ..., 9=LabelList, 10=FTS, 11=Fm).
 /// config_json: JSON object with index-specific parameters (can be null for defaults).
+/// BTree, Bitmap, LabelList and FM indices accept the build options described in
+/// `scalar_index`, and IVF-SQ, IVF-HNSW-SQ and IVF-RQ indices those in `vector_index`.
 /// replace: whether to replace an existing index on the same columns.
 /// name: optional custom index name (null for auto-generated).
 /// train: whether to train the index with existing data.
@@ -575,6 +1704,40 @@ pub extern "C" fn table_create_index(
             }
         };
 
+        let scalar_build = ffi::ffi_to_index_type(index_type)
+            .and_then(|t| ScalarIndexBuild::parse(&t, &config));
+        match scalar_build {
+            // Built synchronously, so there is nothing to wait for.
+            Ok(Some(build)) => {
+                match build.create(&table, &columns, index_name, replace, train).await {
+                    Ok(()) => completion(1 as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr()),
+                    Err(e) => callback_error(completion, user_data, e),
+                }
+                return;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                callback_error(completion, user_data, e);
+                return;
+            }
+        }
+        let vector_build = ffi::ffi_to_index_type(index_type)
+            .and_then(|t| VectorIndexBuild::parse(&t, &config));
+        match vector_build {
+            Ok(Some(build)) => {
+                match build.create(&table, &columns, index_name, replace, train).await {
+                    Ok(()) => completion(1 as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr()),
+                    Err(e) => callback_error(completion, user_data, e),
+                }
+                return;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                callback_error(completion, user_data, e);
+                return;
+            }
+        }
+
         let index = match build_index(index_type, &config) {
             Ok(idx) => idx,
             Err(e) => {
@@ -603,7 +1766,91 @@ pub extern "C" fn table_create_index(
     });
 }
 
-fn build_index(index_type: i32, config: &sonic_rs::Value) -> Result<LanceIndex, String> {
+/// Creates an IVF vector index from a trained model instead of training one with
+/// kmeans (see `vector_index`).
+/// Takes the same arguments as table_create_index, without wait_timeout_ms, plus
+/// the model, which is either:
+/// - centroids_array/centroids_schema: a fixed size list array with one centroid
+///   per IVF partition, and optionally codebook_array/codebook_schema, the PQ
+///   codebook trained with those centroids, for IVF-PQ and IVF-HNSW-PQ indices;
+/// - source_table_ptr and source_index_name: a vector index on another table to
+///   copy the centroids, and for PQ indices the codebook, from.
+/// The index is built before the callback fires.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_create_index_with_model(
+    table_ptr: *const Table,
+    columns_json: *const c_char,
+    index_type: i32,
+    config_json: *const c_char,
+    replace: bool,
+    name: *const c_char,
+    train: bool,
+    centroids_array: *mut arrow_data::ffi::FFI_ArrowArray,
+    centroids_schema: *mut arrow_schema::ffi::FFI_ArrowSchema,
+    codebook_array: *mut arrow_data::ffi::FFI_ArrowArray,
+    codebook_schema: *mut arrow_schema::ffi::FFI_ArrowSchema,
+    source_table_ptr: *const Table,
+    source_index_name: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let columns_str = ffi::to_string(columns_json);
+    let config_str = if config_json.is_null() {
+        "{}".to_string()
+    } else {
+        ffi::to_string(config_json)
+    };
+    let index_name = ffi::parse_optional_string(name);
+    let model = (|| {
+        if !source_table_ptr.is_null() {
+            if !centroids_array.is_null() || !codebook_array.is_null() {
+                return Err("Pass either the model arrays or a source index, not both".to_string());
+            }
+            let index_name = ffi::parse_optional_string(source_index_name)
+                .ok_or_else(|| "source_index_name is required with a source table".to_string())?;
+            return Ok(ModelSource::Index {
+                table: ffi_clone_arc!(source_table_ptr, Table),
+                index_name,
+            });
+        }
+        if centroids_array.is_null() {
+            return Err("Either centroids or a source index is required".to_string());
+        }
+        let centroids = ffi::import_array(centroids_array, centroids_schema)?;
+        let codebook = if codebook_array.is_null() {
+            None
+        } else {
+            Some(ffi::import_array(codebook_array, codebook_schema)?)
+        };
+        Ok(ModelSource::Arrays { centroids, codebook })
+    })();
+    let model = match model {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+
+    crate::spawn(async move {
+        let result = async {
+            let columns: Vec<String> = sonic_rs::from_str(&columns_str).map_err(|e| e.to_string())?;
+            let config: sonic_rs::Value = sonic_rs::from_str(&config_str).map_err(|e| e.to_string())?;
+            let index_type = ffi::ffi_to_index_type(index_type)?;
+            let build = VectorIndexBuild::with_model(&index_type, &config, Some(model))?;
+            build.create(&table, &columns, index_name, replace, train).await
+        }
+        .await;
+        match result {
+            Ok(()) => completion(1 as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr()),
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
+fn build_index(index_type: i32, config: &sonic_rs::Value) -> Result<LanceIndex, String> {
     use lancedb::index::scalar::*;
     use lancedb::index::vector::*;
     use lancedb::index::IndexType;
@@ -615,43 +1862,7 @@ fn build_index(index_type: i32, config: &sonic_rs::Value) -> Result<LanceIndex,
         IndexType::Bitmap => Ok(LanceIndex::Bitmap(BitmapIndexBuilder::default())),
         IndexType::LabelList => Ok(LanceIndex::LabelList(LabelListIndexBuilder::default())),
         IndexType::Fm => Ok(LanceIndex::Fm(FmIndexBuilder::default())),
-        IndexType::FTS => {
-            let mut builder = FtsIndexBuilder::default();
-            if let Some(v) = config.get("with_position").and_then(|v| v.as_bool()) {
-                builder = builder.with_position(v);
-            }
-            if let Some(v) = config.get("base_tokenizer").and_then(|v| v.as_str()) {
-                builder = builder.base_tokenizer(v.to_string());
-            }
-            if let Some(v) = config.get("language").and_then(|v| v.as_str()) {
-                builder = builder.language(v).map_err(|e| e.to_string())?;
-            }
-            if let Some(v) = config.get("max_token_length").and_then(|v| v.as_u64()) {
-                builder = builder.max_token_length(Some(v as usize));
-            }
-            if let Some(v) = config.get("lower_case").and_then(|v| v.as_bool()) {
-                builder = builder.lower_case(v);
-            }
-            if let Some(v) = config.get("stem").and_then(|v| v.as_bool()) {
-                builder = builder.stem(v);
-            }
-            if let Some(v) = config.get("remove_stop_words").and_then(|v| v.as_bool()) {
-                builder = builder.remove_stop_words(v);
-            }
-            if let Some(v) = config.get("ascii_folding").and_then(|v| v.as_bool()) {
-                builder = builder.ascii_folding(v);
-            }
-            if let Some(v) = config.get("ngram_min_length").and_then(|v| v.as_u64()) {
-                builder = builder.ngram_min_length(v as u32);
-            }
-            if let Some(v) = config.get("ngram_max_length").and_then(|v| v.as_u64()) {
-                builder = builder.ngram_max_length(v as u32);
-            }
-            if let Some(v) = config.get("prefix_only").and_then(|v| v.as_bool()) {
-                builder = builder.ngram_prefix_only(v);
-            }
-            Ok(LanceIndex::FTS(builder))
-        }
+        IndexType::FTS => Ok(LanceIndex::FTS(crate::analyzer::fts_params(config)?)),
         IndexType::IvfPq => {
             let mut builder = IvfPqIndexBuilder::default();
             if let Some(v) = config.get("distance_type").and_then(|v| v.as_i64()) {
@@ -821,6 +2032,39 @@ fn build_index(index_type: i32, config: &sonic_rs::Value) -> Result<LanceIndex,
     }
 }
 
+/// Tokenizes `text` with the tokenizer configuration of the FTS index named
+/// `index_name`, the way the index tokenized documents when it was built.
+/// Returns the tokens as a JSON array of `{"text", "position", "start", "end"}`
+/// objects (see fts_analyze); caller must free it with free_string().
+#[unsafe(no_mangle)]
+pub extern "C" fn table_fts_analyze(
+    table_ptr: *const Table,
+    index_name: *const c_char,
+    text: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let index_name = ffi::to_string(index_name);
+    let text = ffi::to_string(text);
+    crate::spawn(async move {
+        let result = async {
+            let params = index_fts_params(&table, FtsIndexRef::Name(&index_name)).await?;
+            let mut analyzer = Analyzer::new(&params)?;
+            Ok::<_, String>(analyze_to_json(&mut analyzer, &text))
+        }
+        .await;
+        match result {
+            Ok(json) => {
+                let c_str = CString::new(json).unwrap_or_default();
+                completion(c_str.into_raw() as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
 /// Returns the table's indices as a JSON string.
 /// Caller must free the returned string with free_string().
 #[unsafe(no_mangle)]
@@ -862,6 +2106,23 @@ pub extern "C" fn table_list_indices(
     });
 }
 
+/// Commits a schema change staged by `stage` with `commit_metadata` in its
+/// transaction properties, which lancedb's schema evolution cannot carry.
+async fn commit_schema_change<F, Fut>(
+    table: &Table,
+    commit_metadata: &std::collections::HashMap<String, String>,
+    stage: F,
+) -> Result<u64, String>
+where
+    F: FnMut(std::sync::Arc<lance::Dataset>) -> Fut,
+    Fut: std::future::Future<Output = Result<StagedWrite<()>, String>>,
+{
+    commit_staged(table, &WriteOptions::default(), Some(commit_metadata), stage)
+        .await
+        .map(|committed| committed.version())
+        .map_err(|e| e.to_string())
+}
+
 /// Add new columns to the table using SQL expressions.
 /// transforms_json is a JSON array of [name, expression] pairs, e.g. [["doubled","id * 2"]].
 #[unsafe(no_mangle)]
@@ -870,18 +2131,46 @@ pub extern "C" fn table_add_columns(
     transforms_json: *const c_char,
     completion: FfiCallback,
     user_data: *mut std::ffi::c_void,
+) {
+    table_add_columns_with_options(table_ptr, transforms_json, std::ptr::null(), completion, user_data);
+}
+
+/// Add new columns to the table using SQL expressions, as table_add_columns.
+/// commit_metadata_json: optional JSON object of string values recorded with the
+/// commit (null for none); see table_list_versions.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_add_columns_with_options(
+    table_ptr: *const Table,
+    transforms_json: *const c_char,
+    commit_metadata_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
 ) {
     let user_data = UserData(user_data);
     let table = ffi_clone_arc!(table_ptr, Table);
+    let commit_metadata = match parse_commit_metadata(commit_metadata_json) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
     let transforms_str = crate::ffi::to_string(transforms_json);
     let pairs: Vec<(String, String)> = sonic_rs::from_str(&transforms_str).unwrap_or_default();
 
     crate::spawn(async move {
-        let transform = NewColumnTransform::SqlExpressions(pairs);
-        match table.add_columns(transform, None).await {
-            Ok(result) => {
-                completion(result.version as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+        let result = match &commit_metadata {
+            Some(metadata) => {
+                commit_schema_change(&table, metadata, |dataset| stage_add_columns(dataset, &pairs)).await
             }
+            None => table
+                .add_columns(NewColumnTransform::SqlExpressions(pairs), None)
+                .await
+                .map(|r| r.version)
+                .map_err(|e| e.to_string()),
+        };
+        match result {
+            Ok(version) => completion(version as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr()),
             Err(e) => callback_error(completion, user_data, e),
         }
     });
@@ -894,9 +2183,30 @@ pub extern "C" fn table_add_columns_null(
     schema_ptr: *mut arrow_schema::ffi::FFI_ArrowSchema,
     completion: FfiCallback,
     user_data: *mut std::ffi::c_void,
+) {
+    table_add_columns_null_with_options(table_ptr, schema_ptr, std::ptr::null(), completion, user_data);
+}
+
+/// Add new null-filled columns to the table, as table_add_columns_null.
+/// commit_metadata_json: optional JSON object of string values recorded with the
+/// commit (null for none); see table_list_versions.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_add_columns_null_with_options(
+    table_ptr: *const Table,
+    schema_ptr: *mut arrow_schema::ffi::FFI_ArrowSchema,
+    commit_metadata_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
 ) {
     let user_data = UserData(user_data);
     let table = ffi_clone_arc!(table_ptr, Table);
+    let commit_metadata = match parse_commit_metadata(commit_metadata_json) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
 
     let schema = match ffi::import_schema(schema_ptr) {
         Ok(s) => s,
@@ -907,28 +2217,56 @@ pub extern "C" fn table_add_columns_null(
     };
 
     crate::spawn(async move {
-        let transform = NewColumnTransform::AllNulls(schema);
-        match table.add_columns(transform, None).await {
-            Ok(result) => {
-                completion(result.version as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+        let result = match &commit_metadata {
+            Some(metadata) => {
+                commit_schema_change(&table, metadata, |dataset| stage_add_null_columns(dataset, &schema)).await
             }
+            None => table
+                .add_columns(NewColumnTransform::AllNulls(schema), None)
+                .await
+                .map(|r| r.version)
+                .map_err(|e| e.to_string()),
+        };
+        match result {
+            Ok(version) => completion(version as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr()),
             Err(e) => callback_error(completion, user_data, e),
         }
     });
 }
 
-/// Alter existing columns (rename, set nullable, cast type).
-/// alterations_json is a JSON array of objects with "path", optional "rename",
-/// optional "nullable", optional "data_type" (as Arrow DataType string).
+/// Alter existing columns (rename, set nullable).
+/// alterations_json is a JSON array of objects with "path", optional "rename" and
+/// optional "nullable".
 #[unsafe(no_mangle)]
 pub extern "C" fn table_alter_columns(
     table_ptr: *const Table,
     alterations_json: *const c_char,
     completion: FfiCallback,
     user_data: *mut std::ffi::c_void,
+) {
+    table_alter_columns_with_options(table_ptr, alterations_json, std::ptr::null(), completion, user_data);
+}
+
+/// Alter existing columns, as table_alter_columns.
+/// commit_metadata_json: optional JSON object of string values recorded with the
+/// commit (null for none); see table_list_versions.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_alter_columns_with_options(
+    table_ptr: *const Table,
+    alterations_json: *const c_char,
+    commit_metadata_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
 ) {
     let user_data = UserData(user_data);
     let table = ffi_clone_arc!(table_ptr, Table);
+    let commit_metadata = match parse_commit_metadata(commit_metadata_json) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
     let json_str = crate::ffi::to_string(alterations_json);
     let raw: Vec<sonic_rs::Value> = sonic_rs::from_str(&json_str).unwrap_or_default();
 
@@ -948,10 +2286,14 @@ pub extern "C" fn table_alter_columns(
         .collect();
 
     crate::spawn(async move {
-        match table.alter_columns(&alterations).await {
-            Ok(result) => {
-                completion(result.version as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+        let result = match &commit_metadata {
+            Some(metadata) => {
+                commit_schema_change(&table, metadata, |dataset| stage_alter_columns(dataset, &alterations)).await
             }
+            None => table.alter_columns(&alterations).await.map(|r| r.version).map_err(|e| e.to_string()),
+        };
+        match result {
+            Ok(version) => completion(version as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr()),
             Err(e) => callback_error(completion, user_data, e),
         }
     });
@@ -965,18 +2307,45 @@ pub extern "C" fn table_drop_columns(
     columns_json: *const c_char,
     completion: FfiCallback,
     user_data: *mut std::ffi::c_void,
+) {
+    table_drop_columns_with_options(table_ptr, columns_json, std::ptr::null(), completion, user_data);
+}
+
+/// Drop columns from the table, as table_drop_columns.
+/// commit_metadata_json: optional JSON object of string values recorded with the
+/// commit (null for none); see table_list_versions.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_drop_columns_with_options(
+    table_ptr: *const Table,
+    columns_json: *const c_char,
+    commit_metadata_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
 ) {
     let user_data = UserData(user_data);
     let table = ffi_clone_arc!(table_ptr, Table);
+    let commit_metadata = match parse_commit_metadata(commit_metadata_json) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
     let json_str = crate::ffi::to_string(columns_json);
     let columns: Vec<String> = sonic_rs::from_str(&json_str).unwrap_or_default();
 
     crate::spawn(async move {
-        let col_refs: Vec<&str> = columns.iter().map(|s| s.as_str()).collect();
-        match table.drop_columns(&col_refs).await {
-            Ok(result) => {
-                completion(result.version as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+        let result = match &commit_metadata {
+            Some(metadata) => {
+                commit_schema_change(&table, metadata, |dataset| stage_drop_columns(dataset, &columns)).await
             }
+            None => {
+                let col_refs: Vec<&str> = columns.iter().map(|s| s.as_str()).collect();
+                table.drop_columns(&col_refs).await.map(|r| r.version).map_err(|e| e.to_string())
+            }
+        };
+        match result {
+            Ok(version) => completion(version as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr()),
             Err(e) => callback_error(completion, user_data, e),
         }
     });
@@ -1408,147 +2777,1134 @@ pub extern "C" fn table_unset_lsm_write_spec(
     });
 }
 
-/// Drain and close every cached MemWAL shard writer for the table. A no-op
-/// when no writers are cached. Required before the table is allowed to route
-/// `merge_insert` to a different shard.
-#[unsafe(no_mangle)]
-pub extern "C" fn table_close_lsm_writers(
-    table_ptr: *const Table,
-    completion: FfiCallback,
-    user_data: *mut std::ffi::c_void,
-) {
-    let user_data = UserData(user_data);
-    let table = ffi_clone_arc!(table_ptr, Table);
-    crate::spawn(async move {
-        match table.close_lsm_writers().await {
-            Ok(()) => completion(std::ptr::null(), std::ptr::null(), user_data.as_ptr()),
-            Err(e) => callback_error(completion, user_data, e),
-        }
-    });
+/// Drain and close every cached MemWAL shard writer for the table. A no-op
+/// when no writers are cached. Required before the table is allowed to route
+/// `merge_insert` to a different shard.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_close_lsm_writers(
+    table_ptr: *const Table,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    crate::spawn(async move {
+        match table.close_lsm_writers().await {
+            Ok(()) => completion(std::ptr::null(), std::ptr::null(), user_data.as_ptr()),
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
+/// C-compatible struct for fragment row count summary statistics.
+#[repr(C)]
+pub struct FfiFragmentSummaryStats {
+    pub min: u64,
+    pub max: u64,
+    pub mean: u64,
+    pub p25: u64,
+    pub p50: u64,
+    pub p75: u64,
+    pub p99: u64,
+}
+
+/// C-compatible struct for fragment-level statistics.
+#[repr(C)]
+pub struct FfiFragmentStats {
+    pub num_fragments: u64,
+    pub num_small_fragments: u64,
+    pub lengths: FfiFragmentSummaryStats,
+}
+
+/// C-compatible struct for table statistics, passed across FFI without JSON overhead.
+#[repr(C)]
+pub struct FfiTableStats {
+    pub total_bytes: u64,
+    pub num_rows: u64,
+    pub num_indices: u64,
+    pub fragment_stats: FfiFragmentStats,
+}
+
+/// Returns table statistics as a heap-allocated FfiTableStats struct.
+/// Caller must free the returned pointer with table_stats_free.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_stats(
+    table_ptr: *const Table,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    crate::spawn(async move {
+        match table.stats().await {
+            Ok(stats) => {
+                let ffi_stats = Box::new(FfiTableStats {
+                    total_bytes: stats.total_bytes as u64,
+                    num_rows: stats.num_rows as u64,
+                    num_indices: stats.num_indices as u64,
+                    fragment_stats: FfiFragmentStats {
+                        num_fragments: stats.fragment_stats.num_fragments as u64,
+                        num_small_fragments: stats.fragment_stats.num_small_fragments as u64,
+                        lengths: FfiFragmentSummaryStats {
+                            min: stats.fragment_stats.lengths.min as u64,
+                            max: stats.fragment_stats.lengths.max as u64,
+                            mean: stats.fragment_stats.lengths.mean as u64,
+                            p25: stats.fragment_stats.lengths.p25 as u64,
+                            p50: stats.fragment_stats.lengths.p50 as u64,
+                            p75: stats.fragment_stats.lengths.p75 as u64,
+                            p99: stats.fragment_stats.lengths.p99 as u64,
+                        },
+                    },
+                });
+                let ptr = Box::into_raw(ffi_stats);
+                completion(ptr as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
+/// Frees an FfiTableStats pointer returned by table_stats.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_stats_free(ptr: *mut FfiTableStats) {
+    if !ptr.is_null() {
+        unsafe { drop(Box::from_raw(ptr)); }
+    }
+}
+
+/// Merge insert (upsert) operation on the table.
+/// on_columns_json: JSON array of column names to match on, e.g. '["id"]'.
+/// when_matched_update_all: if true, update matched rows.
+/// when_matched_update_all_filter: optional SQL filter for matched update (null for none).
+/// when_not_matched_insert_all: if true, insert non-matching source rows.
+/// when_not_matched_by_source_delete: if true, delete target rows not in source.
+/// when_not_matched_by_source_delete_filter: optional SQL filter for source delete (null for none).
+/// arrays/schema/batch_count: Arrow C Data Interface arrays containing the new data.
+/// use_lsm_write: sentinel `-1` leaves the routing default unset; `0` opts out
+/// of the LSM write path; `1` requires the LSM path (and errors if no
+/// LsmWriteSpec is installed on the table).
+/// Affected rows, the cast to the table schema and commit metadata are available
+/// through table_merge_insert_clauses.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_merge_insert(
+    table_ptr: *const Table,
+    on_columns_json: *const c_char,
+    when_matched_update_all: bool,
+    when_matched_update_all_filter: *const c_char,
+    when_not_matched_insert_all: bool,
+    when_not_matched_by_source_delete: bool,
+    when_not_matched_by_source_delete_filter: *const c_char,
+    arrays: *mut arrow_data::ffi::FFI_ArrowArray,
+    schema: *mut arrow_schema::ffi::FFI_ArrowSchema,
+    batch_count: usize,
+    use_index: bool,
+    timeout_ms: i64,
+    use_lsm_write: i32,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let on_columns_str = crate::ffi::to_string(on_columns_json);
+
+    let matched_filter = if when_matched_update_all_filter.is_null() {
+        None
+    } else {
+        Some(crate::ffi::to_string(when_matched_update_all_filter))
+    };
+
+    let source_delete_filter = if when_not_matched_by_source_delete_filter.is_null() {
+        None
+    } else {
+        Some(crate::ffi::to_string(when_not_matched_by_source_delete_filter))
+    };
+
+    let (batches, _schema_ref) = match ffi::import_batches(arrays, schema, batch_count) {
+        Ok(r) => r,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+
+    let schema_ref = if batches.is_empty() {
+        arrow_schema::SchemaRef::new(arrow_schema::Schema::empty())
+    } else {
+        batches[0].schema()
+    };
+
+    let mut clauses = Vec::new();
+    if when_matched_update_all {
+        clauses.push(MergeClause::WhenMatchedUpdate { condition: matched_filter, set: None });
+    }
+    if when_not_matched_insert_all {
+        clauses.push(MergeClause::WhenNotMatchedInsert { condition: None });
+    }
+    if when_not_matched_by_source_delete {
+        clauses.push(MergeClause::WhenNotMatchedBySourceDelete { condition: source_delete_filter });
+    }
+
+    crate::spawn(async move {
+        let on_columns: Vec<String> = match sonic_rs::from_str(&on_columns_str) {
+            Ok(c) => c,
+            Err(e) => {
+                callback_error(completion, user_data, e);
+                return;
+            }
+        };
+
+        let options = MergeOptions {
+            use_index,
+            timeout_ms,
+            use_lsm_write,
+            return_affected: false,
+            write: WriteOptions::default(),
+            commit_metadata: None,
+        };
+        match merge_insert_clauses_impl(&table, on_columns, clauses, batches, schema_ref, &options).await {
+            Ok(result) => {
+                let ffi = Box::new(result);
+                completion(Box::into_raw(ffi) as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => e.report(completion, user_data),
+        }
+    });
+}
+
+/// Frees an FfiMergeResult pointer returned by table_merge_insert or
+/// table_merge_insert_clauses, including any affected rows.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_merge_result_free(ptr: *mut FfiMergeResult) {
+    if !ptr.is_null() {
+        unsafe {
+            let result = Box::from_raw(ptr);
+            ffi::free_ffi_cdata(result.affected_rows);
+        }
+    }
+}
+
+/// Applies the execution options shared by the merge insert exports.
+fn apply_merge_options(builder: &mut lancedb::table::merge::MergeInsertBuilder, options: &MergeOptions) {
+    builder.use_index(options.use_index);
+    if let Some(timeout) = options.timeout() {
+        builder.timeout(timeout);
+    }
+    if options.use_lsm_write == 0 {
+        builder.use_lsm_write(false);
+    } else if options.use_lsm_write == 1 {
+        builder.use_lsm_write(true);
+    }
+}
+
+/// One clause of a structured merge insert.
+///
+/// A clause list holds at most one matched clause, one insert clause and one
+/// by-source clause, which together run as a single lance merge insert: conditions
+/// are evaluated while the merge joins the source with the target, so a merge
+/// retried after a conflict sees the rows it commits against. Conditions use
+/// `target.` and `source.` to qualify columns. `set` is a list of
+/// `[column, sql_expr]` pairs; see `resolve_against_target` for what they may
+/// reference.
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MergeClause {
+    /// Update matched rows: every column from the source when `set` is omitted,
+    /// otherwise only the listed columns.
+    WhenMatchedUpdate {
+        condition: Option<String>,
+        set: Option<Vec<(String, String)>>,
+    },
+    /// Delete matched rows. Lance deletes every match, so no condition is accepted.
+    WhenMatchedDelete {
+        condition: Option<String>,
+    },
+    /// Insert source rows that match no target row. No condition is accepted.
+    WhenNotMatchedInsert {
+        condition: Option<String>,
+    },
+    WhenNotMatchedBySourceDelete {
+        condition: Option<String>,
+    },
+    /// Set the listed columns of target rows that match no source row, with the
+    /// condition and expressions evaluated over the target row.
+    WhenNotMatchedBySourceUpdate {
+        condition: Option<String>,
+        set: Vec<(String, String)>,
+    },
+}
+
+/// What a merge does with matched target rows.
+#[derive(Clone)]
+enum MatchedAction {
+    Update {
+        condition: Option<String>,
+        set: Option<Vec<(String, String)>>,
+    },
+    Delete,
+}
+
+/// What a merge does with target rows that match no source row.
+#[derive(Clone)]
+enum BySourceAction {
+    Delete {
+        condition: Option<String>,
+    },
+    Update {
+        condition: Option<String>,
+        set: Vec<(String, String)>,
+    },
+}
+
+/// A clause list resolved onto the behaviours of one lance merge insert.
+struct ResolvedMerge {
+    matched: Option<MatchedAction>,
+    insert: bool,
+    by_source: Option<BySourceAction>,
+}
+
+impl ResolvedMerge {
+    /// Whether lancedb's merge insert builder can run this merge, which also
+    /// covers remote tables and the LSM write path.
+    fn is_update_all(&self) -> bool {
+        matches!(self.matched, None | Some(MatchedAction::Update { set: None, .. }))
+            && !matches!(self.by_source, Some(BySourceAction::Update { .. }))
+    }
+
+    /// Whether the source rows must be resolved against the target before lance
+    /// merges them, whatever the `set` expressions reference.
+    fn needs_target(&self) -> bool {
+        let sets_columns = matches!(self.matched, Some(MatchedAction::Update { set: Some(_), .. }));
+        matches!(self.by_source, Some(BySourceAction::Update { .. })) || (sets_columns && self.insert)
+    }
+
+    /// The merge lance runs over the rows `resolve_against_target` produced, which
+    /// already hold the updated values of every row to update.
+    fn after_resolution(&self) -> ResolvedMerge {
+        ResolvedMerge {
+            matched: Some(MatchedAction::Update { condition: None, set: None }),
+            insert: self.insert,
+            by_source: match &self.by_source {
+                Some(BySourceAction::Delete { condition }) => {
+                    Some(BySourceAction::Delete { condition: condition.clone() })
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+fn resolve_clauses(clauses: Vec<MergeClause>) -> Result<ResolvedMerge, String> {
+    if clauses.is_empty() {
+        return Err("At least one merge clause is required".to_string());
+    }
+    let mut merge = ResolvedMerge { matched: None, insert: false, by_source: None };
+    for clause in clauses {
+        match clause {
+            MergeClause::WhenMatchedUpdate { .. } | MergeClause::WhenMatchedDelete { .. }
+                if merge.matched.is_some() =>
+            {
+                return Err("A merge insert takes at most one when_matched clause".to_string());
+            }
+            MergeClause::WhenMatchedUpdate { condition, set } => {
+                merge.matched = Some(MatchedAction::Update { condition, set });
+            }
+            MergeClause::WhenMatchedDelete { condition: Some(_) } => {
+                return Err("when_matched_delete does not support a condition".to_string());
+            }
+            MergeClause::WhenMatchedDelete { condition: None } => {
+                merge.matched = Some(MatchedAction::Delete);
+            }
+            MergeClause::WhenNotMatchedInsert { .. } if merge.insert => {
+                return Err("A merge insert takes at most one when_not_matched_insert clause".to_string());
+            }
+            MergeClause::WhenNotMatchedInsert { condition: Some(_) } => {
+                return Err("when_not_matched_insert does not support a condition".to_string());
+            }
+            MergeClause::WhenNotMatchedInsert { condition: None } => merge.insert = true,
+            MergeClause::WhenNotMatchedBySourceDelete { .. } | MergeClause::WhenNotMatchedBySourceUpdate { .. }
+                if merge.by_source.is_some() =>
+            {
+                return Err("A merge insert takes at most one when_not_matched_by_source clause".to_string());
+            }
+            MergeClause::WhenNotMatchedBySourceDelete { condition } => {
+                merge.by_source = Some(BySourceAction::Delete { condition });
+            }
+            MergeClause::WhenNotMatchedBySourceUpdate { set, .. } if set.is_empty() => {
+                return Err("when_not_matched_by_source_update needs at least one column to set".to_string());
+            }
+            MergeClause::WhenNotMatchedBySourceUpdate { condition, set } => {
+                merge.by_source = Some(BySourceAction::Update { condition, set });
+            }
+        }
+    }
+    // Resolved rows update every row they hold, so matched rows cannot be deleted next to them.
+    if matches!(merge.matched, Some(MatchedAction::Delete))
+        && matches!(merge.by_source, Some(BySourceAction::Update { .. }))
+    {
+        return Err(
+            "when_matched_delete cannot be combined with when_not_matched_by_source_update".to_string(),
+        );
+    }
+    Ok(merge)
+}
+
+/// Execution options for `merge_insert_clauses_impl` and `table_merge_insert`.
+/// use_lsm_write: sentinel `-1` leaves the routing default unset.
+pub(crate) struct MergeOptions {
+    pub use_index: bool,
+    pub timeout_ms: i64,
+    pub use_lsm_write: i32,
+    pub return_affected: bool,
+    pub write: WriteOptions,
+    /// Stored in the commit's transaction properties.
+    pub commit_metadata: Option<std::collections::HashMap<String, String>>,
+}
+
+impl MergeOptions {
+    fn timeout(&self) -> Option<std::time::Duration> {
+        (self.timeout_ms >= 0).then(|| std::time::Duration::from_millis(self.timeout_ms as u64))
+    }
+}
+
+/// Selects the schema's columns from a batch by name and casts them to the
+/// schema's types, so computed or caller-supplied rows line up with the table.
+pub(crate) fn cast_to_schema(
+    batch: &arrow_array::RecordBatch,
+    schema: &arrow_schema::SchemaRef,
+) -> Result<arrow_array::RecordBatch, String> {
+    use datafusion::arrow::compute::{cast_with_options, CastOptions};
+
+    let options = CastOptions { safe: false, ..Default::default() };
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let column = batch
+                .column_by_name(field.name())
+                .ok_or_else(|| format!("Column '{}' is missing from the data", field.name()))?;
+            // A value the target type cannot hold is an error rather than a null.
+            cast_with_options(column, field.data_type(), &options).map_err(|e| e.to_string())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    arrow_array::RecordBatch::try_new(schema.clone(), columns).map_err(|e| e.to_string())
+}
+
+/// Encodes the key values of each row so that equal keys compare equal, whatever
+/// the key column types.
+pub(crate) fn key_rows(columns: &[arrow_array::ArrayRef]) -> Result<datafusion::arrow::row::Rows, String> {
+    use datafusion::arrow::row::{RowConverter, SortField};
+
+    let fields = columns.iter().map(|c| SortField::new(c.data_type().clone())).collect();
+    let converter = RowConverter::new(fields).map_err(|e| e.to_string())?;
+    converter.convert_columns(columns).map_err(|e| e.to_string())
+}
+
+/// Whether any key value of the row is null; such a key matches no row.
+pub(crate) fn has_null_key(columns: &[arrow_array::ArrayRef], row: usize) -> bool {
+    use arrow_array::Array;
+
+    columns.iter().any(|c| c.is_null(row))
+}
+
+/// Formats the key values of one row for error messages.
+fn display_key(columns: &[arrow_array::ArrayRef], row: usize) -> Result<String, String> {
+    use datafusion::arrow::util::display::array_value_to_string;
+
+    let values = columns
+        .iter()
+        .map(|c| array_value_to_string(c, row))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(values.join(", "))
+}
+
+/// Builds a predicate matching the target rows whose keys appear in `keys`.
+pub(crate) fn key_predicate(on: &[String], keys: &[arrow_array::RecordBatch]) -> Result<Option<String>, String> {
+    use crate::query::{quote_identifier, sql_literal};
+    use arrow_array::Array;
+
+    let mut tuples = Vec::new();
+    for batch in keys {
+        for row in 0..batch.num_rows() {
+            if batch.columns().iter().any(|c| c.is_null(row)) {
+                continue;
+            }
+            let values = batch
+                .columns()
+                .iter()
+                .map(|c| sql_literal(c.as_ref(), row))
+                .collect::<Result<Vec<_>, _>>()?;
+            tuples.push(values);
+        }
+    }
+    if tuples.is_empty() {
+        return Ok(None);
+    }
+    if on.len() == 1 {
+        let list = tuples.into_iter().map(|mut t| t.remove(0)).collect::<Vec<_>>().join(", ");
+        return Ok(Some(format!("{} IN ({})", quote_identifier(&on[0]), list)));
+    }
+    let disjuncts = tuples
+        .iter()
+        .map(|values| {
+            let terms = on
+                .iter()
+                .zip(values)
+                .map(|(column, value)| format!("{} = {}", quote_identifier(column), value))
+                .collect::<Vec<_>>();
+            format!("({})", terms.join(" AND "))
+        })
+        .collect::<Vec<_>>();
+    Ok(Some(disjuncts.join(" OR ")))
+}
+
+/// Checks that `set` names table columns other than the keys.
+fn check_set_columns(
+    target_schema: &arrow_schema::Schema,
+    on: &[String],
+    set: &[(String, String)],
+) -> Result<(), String> {
+    for (column, _) in set {
+        if target_schema.field_with_name(column).is_err() {
+            return Err(format!("Column '{}' in merge clause does not exist in the table", column));
+        }
+        if on.contains(column) {
+            return Err(format!("Merge clauses cannot set the key column '{}'", column));
+        }
+    }
+    Ok(())
+}
+
+/// Evaluates `set` expressions over the source rows. The result holds the key
+/// columns and the set columns in the table's types, which lance merges as a
+/// partial-schema update that leaves the other columns unchanged. `None` when the
+/// expressions read more than the source row, such as `target.` columns; the
+/// rows are then resolved against the target (see `resolve_against_target`).
+async fn set_source_columns(
+    table: &Table,
+    on: &[String],
+    set: &[(String, String)],
+    batches: &[arrow_array::RecordBatch],
+    schema: &arrow_schema::SchemaRef,
+) -> Result<Option<(Vec<arrow_array::RecordBatch>, arrow_schema::SchemaRef)>, String> {
+    use crate::query::quote_identifier;
+    use datafusion::datasource::MemTable;
+    use datafusion::prelude::SessionContext;
+
+    let target_schema = table.schema().await.map_err(|e| e.to_string())?;
+    check_set_columns(&target_schema, on, set)?;
+    let columns = on.iter().chain(set.iter().map(|(column, _)| column)).collect::<Vec<_>>();
+    let indices = columns
+        .iter()
+        .map(|c| target_schema.index_of(c))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let projected = std::sync::Arc::new(target_schema.project(&indices).map_err(|e| e.to_string())?);
+
+    let ctx = SessionContext::new();
+    let mem = MemTable::try_new(schema.clone(), vec![batches.to_vec()]).map_err(|e| e.to_string())?;
+    ctx.register_table("source", std::sync::Arc::new(mem)).map_err(|e| e.to_string())?;
+    let select = on
+        .iter()
+        .map(|k| format!("source.{q} AS {q}", q = quote_identifier(k)))
+        .chain(set.iter().map(|(column, expr)| format!("({}) AS {}", expr, quote_identifier(column))))
+        .collect::<Vec<_>>()
+        .join(", ");
+    // Planning fails when an expression names anything but a source column.
+    let Ok(df) = ctx.sql(&format!("SELECT {} FROM source", select)).await else {
+        return Ok(None);
+    };
+    let rows = df.collect().await.map_err(|e| e.to_string())?;
+    let rows = rows
+        .iter()
+        .filter(|b| b.num_rows() > 0)
+        .map(|b| cast_to_schema(b, &projected))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some((rows, projected)))
+}
+
+/// Resolves the updates of `merge` into whole target rows, read from `dataset`, the
+/// version the merge is staged against, so lance can run them as an update-all
+/// merge (see `ResolvedMerge::after_resolution`):
+/// - matched source rows that pass the matched condition, with the `set`
+///   expressions (or, without `set`, the source columns) applied over the target
+///   row. The expressions and condition may use `source.` and `target.` columns;
+/// - when inserting, the source rows that match no target row, with nulls for the
+///   columns the source lacks;
+/// - for a by-source update, the target rows no source row matches that pass its
+///   condition, with its `set` expressions evaluated over the target row.
+///
+/// The by-source rows need a full scan of the target, as a by-source delete does.
+async fn resolve_against_target(
+    dataset: &std::sync::Arc<lance::Dataset>,
+    on: &[String],
+    merge: &ResolvedMerge,
+    batches: &[arrow_array::RecordBatch],
+    schema: &arrow_schema::SchemaRef,
+) -> Result<(Vec<arrow_array::RecordBatch>, arrow_schema::SchemaRef), String> {
+    use crate::query::quote_identifier as q;
+    use datafusion::datasource::MemTable;
+    use datafusion::prelude::SessionContext;
+    use lance::datafusion::LanceTableProvider;
+
+    let table_schema = std::sync::Arc::new(arrow_schema::Schema::from(dataset.schema()));
+    let ctx = SessionContext::new();
+    let mem = MemTable::try_new(schema.clone(), vec![batches.to_vec()]).map_err(|e| e.to_string())?;
+    ctx.register_table("source", std::sync::Arc::new(mem)).map_err(|e| e.to_string())?;
+    let target = LanceTableProvider::new(dataset.clone(), false, false);
+    ctx.register_table("target", std::sync::Arc::new(target)).map_err(|e| e.to_string())?;
+
+    let join = on
+        .iter()
+        .map(|k| format!("source.{k} = target.{k}", k = q(k)))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    // Keys never match through a null, so a null target key marks an unmatched row.
+    let matched = format!("target.{} IS NOT NULL", q(&on[0]));
+    let mut queries = Vec::new();
+
+    let update = match &merge.matched {
+        Some(MatchedAction::Update { condition, set }) => {
+            if let Some(set) = set {
+                check_set_columns(&table_schema, on, set)?;
+            }
+            Some((condition, set))
+        }
+        _ => None,
+    };
+    if update.is_some() || merge.insert {
+        let columns = table_schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let name = field.name();
+                let in_source = schema.field_with_name(name).is_ok();
+                let updated = match update {
+                    Some((_, Some(set))) => match set.iter().find(|(column, _)| column == name) {
+                        Some((_, expr)) => format!("({})", expr),
+                        None => format!("target.{}", q(name)),
+                    },
+                    Some((_, None)) if in_source => format!("source.{}", q(name)),
+                    _ => format!("target.{}", q(name)),
+                };
+                let inserted = if in_source { format!("source.{}", q(name)) } else { "NULL".to_string() };
+                format!("CASE WHEN {} THEN {} ELSE {} END AS {}", matched, updated, inserted, q(name))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut keep = Vec::new();
+        match update {
+            Some((Some(condition), _)) => keep.push(format!("({} AND COALESCE(({}), FALSE))", matched, condition)),
+            Some((None, _)) => keep.push(matched.clone()),
+            None => {}
+        }
+        if merge.insert {
+            keep.push(format!("NOT ({})", matched));
+        }
+        queries.push(format!(
+            "SELECT {} FROM source LEFT JOIN target ON {} WHERE {}",
+            columns,
+            join,
+            keep.join(" OR ")
+        ));
+    }
+
+    if let Some(BySourceAction::Update { condition, set }) = &merge.by_source {
+        check_set_columns(&table_schema, on, set)?;
+        let columns = table_schema
+            .fields()
+            .iter()
+            .map(|field| match set.iter().find(|(column, _)| column == field.name()) {
+                Some((_, expr)) => format!("({}) AS {}", expr, q(field.name())),
+                None => format!("target.{c} AS {c}", c = q(field.name())),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut filter = format!("NOT EXISTS (SELECT 1 FROM source WHERE {})", join);
+        if let Some(condition) = condition {
+            filter = format!("{} AND COALESCE(({}), FALSE)", filter, condition);
+        }
+        queries.push(format!("SELECT {} FROM target WHERE {}", columns, filter));
+    }
+
+    let mut rows = Vec::new();
+    for query in queries {
+        let df = ctx.sql(&query).await.map_err(|e| format!("Invalid merge clause: {}", e))?;
+        for batch in df.collect().await.map_err(|e| e.to_string())? {
+            if batch.num_rows() > 0 {
+                rows.push(cast_to_schema(&batch, &table_schema)?);
+            }
+        }
+    }
+    Ok((rows, table_schema))
+}
+
+/// Builds the affected-rows batch of a committed merge from the fragments the
+/// commit changed: the `on` key columns, an `action` column (`updated`,
+/// `inserted` or `deleted`) and the `_rowid` each written row has at the
+/// committed version (null for deleted rows). Rows the commit removed are live
+/// in the previous version and deleted in this one; a removed key that the
+/// commit also wrote was updated. In fragments whose columns were rewritten in
+/// place, the rows whose key is in `source` were updated.
+async fn affected_rows(
+    committed: &std::sync::Arc<lance::Dataset>,
+    on: &[String],
+    source: &[arrow_array::RecordBatch],
+) -> Result<arrow_array::RecordBatch, String> {
+    use arrow_array::{ArrayRef, BooleanArray, RecordBatch, StringArray, UInt64Array};
+    use arrow_schema::Schema;
+    use datafusion::arrow::compute::{concat, filter_record_batch};
+    use datafusion::arrow::row::{RowConverter, SortField};
+    use futures::TryStreamExt;
+    use lance::dataset::{ProjectionRequest, TakeBuilder};
+
+    let version = committed.manifest.version;
+    let previous = std::sync::Arc::new(
+        committed.checkout_version(version - 1).await.map_err(|e| e.to_string())?,
+    );
+    let previous_ids: std::collections::HashSet<u64> =
+        previous.manifest.fragments.iter().map(|f| f.id).collect();
+
+    let key_fields = affected_key_fields(&Schema::from(committed.schema()), on)?;
+    let key_schema = std::sync::Arc::new(Schema::new(key_fields.clone()));
+
+    // Written rows are the rows of the fragments the commit added.
+    let added = committed
+        .manifest
+        .fragments
+        .iter()
+        .filter(|f| !previous_ids.contains(&f.id))
+        .cloned()
+        .collect::<Vec<_>>();
+    let written = if added.is_empty() {
+        Vec::new()
+    } else {
+        let mut scanner = committed.scan();
+        scanner.with_fragments(added).project(on).map_err(|e| e.to_string())?.with_row_id();
+        let stream = scanner.try_into_stream().await.map_err(|e| e.to_string())?;
+        stream.try_collect::<Vec<_>>().await.map_err(|e| e.to_string())?
+    };
+    let (written_keys, written_ids) = key_columns(&written, on, &key_schema)?;
+
+    // A row address is the fragment id in the high 32 bits and the row offset
+    // within the fragment in the low 32 bits.
+    let mut removed_addresses = Vec::new();
+    let mut rewritten = Vec::new();
+    for fragment in previous.get_fragments() {
+        let id = fragment.id();
+        let current = committed.get_fragment(id);
+        if let Some(current) = &current {
+            if current.metadata().files != fragment.metadata().files {
+                rewritten.push(current.metadata().clone());
+            }
+            if current.metadata().deletion_file == fragment.metadata().deletion_file {
+                continue;
+            }
+        }
+        let before = fragment.get_deletion_vector().await.map_err(|e| e.to_string())?;
+        let after = match &current {
+            Some(current) => current.get_deletion_vector().await.map_err(|e| e.to_string())?,
+            None => None,
+        };
+        let physical_rows = fragment.physical_rows().await.map_err(|e| e.to_string())?;
+        for offset in 0..physical_rows as u32 {
+            let was_live = !before.as_ref().is_some_and(|d| d.contains(offset));
+            let is_deleted = current.is_none() || after.as_ref().is_some_and(|d| d.contains(offset));
+            if was_live && is_deleted {
+                removed_addresses.push(((id as u64) << 32) | offset as u64);
+            }
+        }
+    }
+    let removed_keys = if removed_addresses.is_empty() {
+        RecordBatch::new_empty(key_schema.clone())
+    } else {
+        let projection = previous.schema().project(on).map_err(|e| e.to_string())?;
+        let plan = ProjectionRequest::from_schema(projection)
+            .into_projection_plan(previous.clone())
+            .map_err(|e| e.to_string())?;
+        let plan = std::sync::Arc::new(plan);
+        let batch = TakeBuilder::try_new_from_addresses(previous.clone(), removed_addresses, plan)
+            .map_err(|e| e.to_string())?
+            .execute()
+            .await
+            .map_err(|e| e.to_string())?;
+        RecordBatch::try_new(key_schema.clone(), batch.columns().to_vec()).map_err(|e| e.to_string())?
+    };
+
+    let converter = RowConverter::new(key_fields.iter().map(|f| SortField::new(f.data_type().clone())).collect())
+        .map_err(|e| e.to_string())?;
+    let written_rows = converter.convert_columns(written_keys.columns()).map_err(|e| e.to_string())?;
+    let removed_rows = converter.convert_columns(removed_keys.columns()).map_err(|e| e.to_string())?;
+    let written_set: std::collections::HashSet<_> = written_rows.iter().collect();
+    let removed_set: std::collections::HashSet<_> = removed_rows.iter().collect();
+
+    // Rows updated in place keep their row ids, so they are found by key.
+    let (updated_keys, updated_ids) = if rewritten.is_empty() {
+        (RecordBatch::new_empty(key_schema.clone()), Vec::new())
+    } else {
+        let source_keys = source
+            .iter()
+            .map(|batch| cast_to_schema(batch, &key_schema))
+            .collect::<Result<Vec<_>, _>>()?;
+        let source_keys = ffi::concat_or_empty(key_schema.clone(), source_keys).map_err(|e| e.to_string())?;
+        let source_rows = converter.convert_columns(source_keys.columns()).map_err(|e| e.to_string())?;
+        let source_set: std::collections::HashSet<_> = source_rows.iter().collect();
+
+        let mut scanner = committed.scan();
+        scanner.with_fragments(rewritten).project(on).map_err(|e| e.to_string())?.with_row_id();
+        let stream = scanner.try_into_stream().await.map_err(|e| e.to_string())?;
+        let scanned = stream.try_collect::<Vec<_>>().await.map_err(|e| e.to_string())?;
+        let (keys, ids) = key_columns(&scanned, on, &key_schema)?;
+        let rows = converter.convert_columns(keys.columns()).map_err(|e| e.to_string())?;
+        let matched = rows.iter().map(|row| source_set.contains(&row)).collect::<Vec<_>>();
+        let ids = ids.into_iter().zip(&matched).filter(|(_, m)| **m).map(|(id, _)| id).collect::<Vec<_>>();
+        let keys = filter_record_batch(&keys, &BooleanArray::from(matched)).map_err(|e| e.to_string())?;
+        (keys, ids)
+    };
+
+    let mut actions: Vec<&str> = written_rows
+        .iter()
+        .map(|row| if removed_set.contains(&row) { "updated" } else { "inserted" })
+        .collect();
+    actions.extend(std::iter::repeat_n("updated", updated_keys.num_rows()));
+    let deleted = BooleanArray::from(removed_rows.iter().map(|row| !written_set.contains(&row)).collect::<Vec<_>>());
+    let deleted_keys = filter_record_batch(&removed_keys, &deleted).map_err(|e| e.to_string())?;
+    actions.extend(std::iter::repeat_n("deleted", deleted_keys.num_rows()));
+    let row_ids = written_ids
+        .into_iter()
+        .chain(updated_ids)
+        .map(Some)
+        .chain(std::iter::repeat_n(None, deleted_keys.num_rows()))
+        .collect::<Vec<_>>();
+
+    let mut columns = (0..key_schema.fields().len())
+        .map(|i| {
+            let parts = [written_keys.column(i), updated_keys.column(i), deleted_keys.column(i)];
+            concat(&parts.map(|c| c.as_ref())).map_err(|e| e.to_string())
+        })
+        .collect::<Result<Vec<ArrayRef>, _>>()?;
+    columns.push(std::sync::Arc::new(StringArray::from(actions)) as ArrayRef);
+    columns.push(std::sync::Arc::new(UInt64Array::from(row_ids)) as ArrayRef);
+    RecordBatch::try_new(affected_rows_schema(key_fields), columns).map_err(|e| e.to_string())
+}
+
+/// Splits scanned `batches` into their `on` key columns, as one batch of
+/// `key_schema`, and their `_rowid` values.
+fn key_columns(
+    batches: &[arrow_array::RecordBatch],
+    on: &[String],
+    key_schema: &arrow_schema::SchemaRef,
+) -> Result<(arrow_array::RecordBatch, Vec<u64>), String> {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::UInt64Type;
+
+    let mut ids = Vec::new();
+    let mut keys = Vec::with_capacity(batches.len());
+    for batch in batches {
+        let row_ids = batch
+            .column_by_name("_rowid")
+            .ok_or_else(|| "Scan result is missing _rowid".to_string())?;
+        ids.extend(row_ids.as_primitive::<UInt64Type>().values().iter().copied());
+        let columns = on
+            .iter()
+            .map(|k| batch.column_by_name(k).cloned().ok_or_else(|| format!("Missing key column '{}'", k)))
+            .collect::<Result<Vec<_>, _>>()?;
+        keys.push(arrow_array::RecordBatch::try_new(key_schema.clone(), columns).map_err(|e| e.to_string())?);
+    }
+    let keys = ffi::concat_or_empty(key_schema.clone(), keys).map_err(|e| e.to_string())?;
+    Ok((keys, ids))
+}
+
+/// The `on` columns of `schema`, made nullable since deleted rows have no row id.
+fn affected_key_fields(schema: &arrow_schema::Schema, on: &[String]) -> Result<Vec<arrow_schema::Field>, String> {
+    on.iter()
+        .map(|k| schema.field_with_name(k).map(|f| f.clone().with_nullable(true)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
 }
 
-/// C-compatible struct for fragment row count summary statistics.
-#[repr(C)]
-pub struct FfiFragmentSummaryStats {
-    pub min: u64,
-    pub max: u64,
-    pub mean: u64,
-    pub p25: u64,
-    pub p50: u64,
-    pub p75: u64,
-    pub p99: u64,
+/// Schema of the affected-rows batch: `key_fields`, `action` and `_rowid`.
+fn affected_rows_schema(mut key_fields: Vec<arrow_schema::Field>) -> arrow_schema::SchemaRef {
+    use arrow_schema::{DataType, Field};
+
+    key_fields.push(Field::new("action", DataType::Utf8, false));
+    key_fields.push(Field::new("_rowid", DataType::UInt64, true));
+    std::sync::Arc::new(arrow_schema::Schema::new(key_fields))
 }
 
-/// C-compatible struct for fragment-level statistics.
-#[repr(C)]
-pub struct FfiFragmentStats {
-    pub num_fragments: u64,
-    pub num_small_fragments: u64,
-    pub lengths: FfiFragmentSummaryStats,
+/// Stages `merge` of `rows` into `dataset` as one lance merge insert. The output
+/// is the merge statistics.
+async fn stage_merge(
+    dataset: std::sync::Arc<lance::Dataset>,
+    on: &[String],
+    merge: &ResolvedMerge,
+    use_index: bool,
+    rows: &[arrow_array::RecordBatch],
+    schema: &arrow_schema::SchemaRef,
+) -> Result<StagedWrite<lance::dataset::MergeStats>, String> {
+    use lance::dataset::{MergeInsertBuilder, WhenMatched, WhenNotMatched, WhenNotMatchedBySource};
+
+    let mut builder = MergeInsertBuilder::try_new(dataset.clone(), on.to_vec()).map_err(|e| e.to_string())?;
+    builder.when_matched(match &merge.matched {
+        None => WhenMatched::DoNothing,
+        Some(MatchedAction::Update { condition: None, .. }) => WhenMatched::UpdateAll,
+        Some(MatchedAction::Update { condition: Some(condition), .. }) => WhenMatched::UpdateIf(condition.clone()),
+        Some(MatchedAction::Delete) => WhenMatched::Delete,
+    });
+    builder.when_not_matched(if merge.insert { WhenNotMatched::InsertAll } else { WhenNotMatched::DoNothing });
+    // By-source updates are resolved into the source rows beforehand.
+    builder.when_not_matched_by_source(match &merge.by_source {
+        None | Some(BySourceAction::Update { .. }) => WhenNotMatchedBySource::Keep,
+        Some(BySourceAction::Delete { condition: None }) => WhenNotMatchedBySource::Delete,
+        Some(BySourceAction::Delete { condition: Some(condition) }) => {
+            WhenNotMatchedBySource::delete_if(&dataset, condition).map_err(|e| e.to_string())?
+        }
+    });
+    builder.use_index(use_index);
+    let job = builder.try_build().map_err(|e| e.to_string())?;
+    let reader = arrow_array::RecordBatchIterator::new(rows.to_vec().into_iter().map(Ok), schema.clone());
+    let staged = job.execute_uncommitted(reader).await.map_err(|e| e.to_string())?;
+    // Without row addresses (whole fragments were affected) conflicts are
+    // detected per fragment instead.
+    let affected_rows = staged
+        .affected_rows
+        .as_ref()
+        .and_then(|rows| rows.row_addrs())
+        .map(|addresses| addresses.map(u64::from).collect())
+        .unwrap_or_default();
+    Ok(StagedWrite {
+        transaction: staged.transaction,
+        affected_rows,
+        output: staged.stats,
+    })
 }
 
-/// C-compatible struct for table statistics, passed across FFI without JSON overhead.
-#[repr(C)]
-pub struct FfiTableStats {
-    pub total_bytes: u64,
-    pub num_rows: u64,
-    pub num_indices: u64,
-    pub fragment_stats: FfiFragmentStats,
+/// Whether `transaction` updates columns inside existing fragments, as lance's
+/// indexed merge of a subset of the columns does.
+fn rewrites_columns(transaction: &lance::dataset::transaction::Transaction) -> bool {
+    use lance::dataset::transaction::{Operation, UpdateMode};
+
+    matches!(
+        transaction.operation,
+        Operation::Update { update_mode: Some(UpdateMode::RewriteColumns), .. }
+    )
 }
 
-/// Returns table statistics as a heap-allocated FfiTableStats struct.
-/// Caller must free the returned pointer with table_stats_free.
-#[unsafe(no_mangle)]
-pub extern "C" fn table_stats(
-    table_ptr: *const Table,
-    completion: FfiCallback,
-    user_data: *mut std::ffi::c_void,
-) {
-    let user_data = UserData(user_data);
-    let table = ffi_clone_arc!(table_ptr, Table);
-    crate::spawn(async move {
-        match table.stats().await {
-            Ok(stats) => {
-                let ffi_stats = Box::new(FfiTableStats {
-                    total_bytes: stats.total_bytes as u64,
-                    num_rows: stats.num_rows as u64,
-                    num_indices: stats.num_indices as u64,
-                    fragment_stats: FfiFragmentStats {
-                        num_fragments: stats.fragment_stats.num_fragments as u64,
-                        num_small_fragments: stats.fragment_stats.num_small_fragments as u64,
-                        lengths: FfiFragmentSummaryStats {
-                            min: stats.fragment_stats.lengths.min as u64,
-                            max: stats.fragment_stats.lengths.max as u64,
-                            mean: stats.fragment_stats.lengths.mean as u64,
-                            p25: stats.fragment_stats.lengths.p25 as u64,
-                            p50: stats.fragment_stats.lengths.p50 as u64,
-                            p75: stats.fragment_stats.lengths.p75 as u64,
-                            p99: stats.fragment_stats.lengths.p99 as u64,
-                        },
-                    },
-                });
-                let ptr = Box::into_raw(ffi_stats);
-                completion(ptr as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+async fn merge_insert_clauses_impl(
+    table: &Table,
+    on: Vec<String>,
+    clauses: Vec<MergeClause>,
+    batches: Vec<arrow_array::RecordBatch>,
+    schema_ref: arrow_schema::SchemaRef,
+    options: &MergeOptions,
+) -> Result<FfiMergeResult, WriteError> {
+    let merge = resolve_clauses(clauses)?;
+    if options.return_affected && options.use_lsm_write == 1 {
+        return Err("Affected rows cannot be returned from the LSM write path".to_string().into());
+    }
+
+    if merge.is_update_all()
+        && !options.return_affected
+        && options.write.idempotency_key.is_none()
+        && options.commit_metadata.is_none()
+    {
+        let on_refs: Vec<&str> = on.iter().map(|s| s.as_str()).collect();
+        let mut builder = table.merge_insert(&on_refs);
+        if let Some(MatchedAction::Update { condition, .. }) = merge.matched {
+            builder.when_matched_update_all(condition);
+        }
+        if merge.insert {
+            builder.when_not_matched_insert_all();
+        }
+        if let Some(BySourceAction::Delete { condition }) = merge.by_source {
+            builder.when_not_matched_by_source_delete(condition);
+        }
+        apply_merge_options(&mut builder, options);
+        let reader = arrow_array::RecordBatchIterator::new(batches.into_iter().map(Ok), schema_ref);
+        let result = builder.execute(Box::new(reader)).await.map_err(|e| e.to_string())?;
+        return Ok(FfiMergeResult {
+            version: result.version,
+            num_inserted_rows: result.num_inserted_rows,
+            num_updated_rows: result.num_updated_rows,
+            num_deleted_rows: result.num_deleted_rows,
+            num_attempts: result.num_attempts,
+            num_rows: result.num_rows,
+            affected_rows: std::ptr::null_mut(),
+        });
+    }
+
+    if table.as_native().is_none() {
+        return Err("Deleting matched rows, setting columns, updating unmatched target rows, returning affected \
+             rows, idempotency keys and commit metadata in a merge are only supported for local tables"
+            .to_string()
+            .into());
+    }
+    if options.use_lsm_write == 1 {
+        return Err("Deleting matched rows, setting columns, updating unmatched target rows, idempotency keys and \
+             commit metadata are not supported on the LSM write path"
+            .to_string()
+            .into());
+    }
+    // Merges lance cannot express directly are resolved against the version each
+    // attempt is staged on, so a retried merge reads the rows it commits against.
+    let (resolve, rows, schema) = match &merge.matched {
+        _ if merge.needs_target() => (true, batches, schema_ref),
+        Some(MatchedAction::Update { set: Some(set), .. }) => {
+            match set_source_columns(table, &on, set, &batches, &schema_ref).await? {
+                Some((rows, schema)) => (false, rows, schema),
+                None => (true, batches, schema_ref),
             }
-            Err(e) => callback_error(completion, user_data, e),
+        }
+        _ => (false, batches, schema_ref),
+    };
+    // Matched rows the condition skips are left out of the resolved rows, where
+    // lance would then see them as unmatched and delete them.
+    if resolve
+        && matches!(merge.matched, Some(MatchedAction::Update { condition: Some(_), .. }))
+        && matches!(merge.by_source, Some(BySourceAction::Delete { .. }))
+    {
+        return Err("A conditional when_matched_update clause cannot be combined with \
+             when_not_matched_by_source_delete when its `set` reads target columns or rows are inserted"
+            .to_string()
+            .into());
+    }
+
+    let use_index = options.use_index;
+    let conditional = matches!(merge.matched, Some(MatchedAction::Update { condition: Some(_), .. }));
+    let execution = commit_staged(table, &options.write, options.commit_metadata.as_ref(), |dataset| {
+        let (on, merge, rows, schema) = (&on, &merge, &rows, &schema);
+        async move {
+            if resolve {
+                let (rows, schema) = resolve_against_target(&dataset, on, merge, rows, schema).await?;
+                return stage_merge(dataset, on, &merge.after_resolution(), use_index, &rows, &schema).await;
+            }
+            let staged = stage_merge(dataset, on, merge, use_index, rows, schema).await?;
+            // Rows updated in place are found by key afterwards, which cannot
+            // tell the rows a condition skipped.
+            if options.return_affected && conditional && rewrites_columns(&staged.transaction) {
+                return Err("Affected rows are not available for a conditional update that the key index runs \
+                     in place; set use_index to false"
+                    .to_string());
+            }
+            Ok(staged)
         }
     });
-}
+    let committed = match options.timeout() {
+        Some(timeout) => tokio::time::timeout(timeout, execution)
+            .await
+            .map_err(|_| "Merge insert timed out".to_string())?,
+        None => execution.await,
+    }?;
 
-/// Frees an FfiTableStats pointer returned by table_stats.
-#[unsafe(no_mangle)]
-pub extern "C" fn table_stats_free(ptr: *mut FfiTableStats) {
-    if !ptr.is_null() {
-        unsafe { drop(Box::from_raw(ptr)); }
-    }
+    let (committed, attempts, stats) = match committed {
+        Committed::Written { dataset, attempts, output } => (dataset, attempts, output),
+        Committed::Replayed { version } => {
+            let affected_rows = if options.return_affected {
+                let schema = table.schema().await.map_err(|e| e.to_string())?;
+                let key_fields = affected_key_fields(&schema, &on)?;
+                let batch = arrow_array::RecordBatch::new_empty(affected_rows_schema(key_fields));
+                ffi::export_record_batch(batch).map_err(|e| e.to_string())?
+            } else {
+                std::ptr::null_mut()
+            };
+            return Ok(FfiMergeResult {
+                version,
+                num_inserted_rows: 0,
+                num_updated_rows: 0,
+                num_deleted_rows: 0,
+                num_attempts: 0,
+                num_rows: 0,
+                affected_rows,
+            });
+        }
+    };
+    let affected_rows = if options.return_affected {
+        ffi::export_record_batch(affected_rows(&committed, &on, &rows).await?).map_err(|e| e.to_string())?
+    } else {
+        std::ptr::null_mut()
+    };
+    Ok(FfiMergeResult {
+        version: committed.manifest.version,
+        num_inserted_rows: stats.num_inserted_rows,
+        num_updated_rows: stats.num_updated_rows,
+        num_deleted_rows: stats.num_deleted_rows,
+        num_attempts: attempts,
+        num_rows: stats.num_inserted_rows + stats.num_updated_rows,
+        affected_rows,
+    })
 }
 
-/// Merge insert (upsert) operation on the table.
+/// Merge insert driven by a structured list of clauses, run as one lance merge insert.
 /// on_columns_json: JSON array of column names to match on, e.g. '["id"]'.
-/// when_matched_update_all: if true, update matched rows.
-/// when_matched_update_all_filter: optional SQL filter for matched update (null for none).
-/// when_not_matched_insert_all: if true, insert non-matching source rows.
-/// when_not_matched_by_source_delete: if true, delete target rows not in source.
-/// when_not_matched_by_source_delete_filter: optional SQL filter for source delete (null for none).
-/// arrays/schema/batch_count: Arrow C Data Interface arrays containing the new data.
-/// use_lsm_write: sentinel `-1` leaves the routing default unset; `0` opts out
-/// of the LSM write path; `1` requires the LSM path (and errors if no
-/// LsmWriteSpec is installed on the table).
+/// clauses_json: JSON array of clauses, each an object with a `kind` of
+/// `when_matched_update` (optional `condition`, optional `set`),
+/// `when_matched_delete`, `when_not_matched_insert`,
+/// `when_not_matched_by_source_delete` (optional `condition`) or
+/// `when_not_matched_by_source_update` (optional `condition`, required `set`). At
+/// most one matched clause, one insert clause and one by-source clause may be given.
+/// `set` is a JSON array of `[column, sql_expr]` pairs. For matched rows the
+/// expressions may use `source.` and `target.` columns; for unmatched target rows
+/// they are evaluated over the target row. Updated counts include the unmatched
+/// target rows a by-source update changes.
+/// Deleting matched rows, `set`, by-source updates, return_affected and commit
+/// metadata need a local table.
+/// arrays/schema/batch_count, use_index, timeout_ms and use_lsm_write are as for
+/// table_merge_insert.
+/// return_affected: if true, `affected_rows` of the result holds a batch with the
+/// `on` key columns, an `action` column (`inserted`, `updated` or `deleted`) and
+/// the `_rowid` of each written row (null for deletes), read from the fragments
+/// the commit changed. Not supported together with `use_lsm_write = 1`, nor for a
+/// conditional update that lance runs in place through the key's index (the merge
+/// fails before committing; pass use_index = false).
+/// cast_to_table_schema: if true, the source columns are cast to the table schema first
+/// (see `cast::cast_batches_to_schema`); columns absent from the source stay absent.
+/// write_options_json: optional JSON object of write options (null for defaults),
+/// see `WriteOptions`. They apply to the merges lance runs directly: an
+/// `idempotency_key` routes the merge there, and with a key that is already
+/// committed nothing is written and the result reports the original version with
+/// zero counts and no affected rows. A conflict that is not retried is reported
+/// with `ERROR_CODE_COMMIT_CONFLICT` (see ffi_error_details).
+/// Returns an FfiMergeResult (free with table_merge_result_free).
+/// commit_metadata_json: optional JSON object of string values recorded with the
+/// commit (null for none); see table_list_versions.
 #[unsafe(no_mangle)]
-pub extern "C" fn table_merge_insert(
+pub extern "C" fn table_merge_insert_clauses(
     table_ptr: *const Table,
     on_columns_json: *const c_char,
-    when_matched_update_all: bool,
-    when_matched_update_all_filter: *const c_char,
-    when_not_matched_insert_all: bool,
-    when_not_matched_by_source_delete: bool,
-    when_not_matched_by_source_delete_filter: *const c_char,
+    clauses_json: *const c_char,
     arrays: *mut arrow_data::ffi::FFI_ArrowArray,
     schema: *mut arrow_schema::ffi::FFI_ArrowSchema,
     batch_count: usize,
     use_index: bool,
     timeout_ms: i64,
     use_lsm_write: i32,
+    return_affected: bool,
+    write_options_json: *const c_char,
+    cast_to_table_schema: bool,
+    commit_metadata_json: *const c_char,
     completion: FfiCallback,
     user_data: *mut std::ffi::c_void,
 ) {
     let user_data = UserData(user_data);
     let table = ffi_clone_arc!(table_ptr, Table);
-    let on_columns_str = crate::ffi::to_string(on_columns_json);
-
-    let matched_filter = if when_matched_update_all_filter.is_null() {
-        None
-    } else {
-        Some(crate::ffi::to_string(when_matched_update_all_filter))
+    let commit_metadata = match parse_commit_metadata(commit_metadata_json) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
     };
-
-    let source_delete_filter = if when_not_matched_by_source_delete_filter.is_null() {
-        None
-    } else {
-        Some(crate::ffi::to_string(when_not_matched_by_source_delete_filter))
+    let write_options = match WriteOptions::parse(write_options_json) {
+        Ok(o) => o,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
     };
+    let on_columns_str = crate::ffi::to_string(on_columns_json);
+    let clauses_str = crate::ffi::to_string(clauses_json);
 
-    let (batches, _schema_ref) = match ffi::import_batches(arrays, schema, batch_count) {
+    let (batches, schema_ref) = match ffi::import_batches(arrays, schema, batch_count) {
         Ok(r) => r,
         Err(e) => {
             callback_error(completion, user_data, e);
@@ -1556,12 +3912,6 @@ pub extern "C" fn table_merge_insert(
         }
     };
 
-    let schema_ref = if batches.is_empty() {
-        arrow_schema::SchemaRef::new(arrow_schema::Schema::empty())
-    } else {
-        batches[0].schema()
-    };
-
     crate::spawn(async move {
         let on_columns: Vec<String> = match sonic_rs::from_str(&on_columns_str) {
             Ok(c) => c,
@@ -1570,62 +3920,43 @@ pub extern "C" fn table_merge_insert(
                 return;
             }
         };
+        let clauses: Vec<MergeClause> = match sonic_rs::from_str(&clauses_str) {
+            Ok(c) => c,
+            Err(e) => {
+                callback_error(completion, user_data, e);
+                return;
+            }
+        };
+        let (batches, schema_ref) = if cast_to_table_schema {
+            match crate::cast::cast_to_table(&table, batches, &schema_ref, false).await {
+                Ok(r) => r,
+                Err(e) => {
+                    callback_error(completion, user_data, e);
+                    return;
+                }
+            }
+        } else {
+            (batches, schema_ref)
+        };
 
-        let on_refs: Vec<&str> = on_columns.iter().map(|s| s.as_str()).collect();
-        let mut builder = table.merge_insert(&on_refs);
-
-        if when_matched_update_all {
-            builder.when_matched_update_all(matched_filter);
-        }
-        if when_not_matched_insert_all {
-            builder.when_not_matched_insert_all();
-        }
-        if when_not_matched_by_source_delete {
-            builder.when_not_matched_by_source_delete(source_delete_filter);
-        }
-
-        builder.use_index(use_index);
-        if timeout_ms >= 0 {
-            builder.timeout(std::time::Duration::from_millis(timeout_ms as u64));
-        }
-        // Only forward use_lsm_write when the caller explicitly sets it
-        // (sentinel `-1` means "leave the builder's default in place").
-        if use_lsm_write == 0 {
-            builder.use_lsm_write(false);
-        } else if use_lsm_write == 1 {
-            builder.use_lsm_write(true);
-        }
-
-        let reader = arrow_array::RecordBatchIterator::new(
-            batches.into_iter().map(Ok),
-            schema_ref,
-        );
-
-        match builder.execute(Box::new(reader)).await {
+        let options = MergeOptions {
+            use_index,
+            timeout_ms,
+            use_lsm_write,
+            return_affected,
+            write: write_options,
+            commit_metadata,
+        };
+        match merge_insert_clauses_impl(&table, on_columns, clauses, batches, schema_ref, &options).await {
             Ok(result) => {
-                let ffi = Box::new(FfiMergeResult {
-                    version: result.version,
-                    num_inserted_rows: result.num_inserted_rows,
-                    num_updated_rows: result.num_updated_rows,
-                    num_deleted_rows: result.num_deleted_rows,
-                    num_attempts: result.num_attempts,
-                    num_rows: result.num_rows,
-                });
+                let ffi = Box::new(result);
                 completion(Box::into_raw(ffi) as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
             }
-            Err(e) => callback_error(completion, user_data, e),
+            Err(e) => e.report(completion, user_data),
         }
     });
 }
 
-/// Frees an FfiMergeResult pointer returned by table_merge_insert.
-#[unsafe(no_mangle)]
-pub extern "C" fn table_merge_result_free(ptr: *mut FfiMergeResult) {
-    if !ptr.is_null() {
-        unsafe { drop(Box::from_raw(ptr)); }
-    }
-}
-
 /// Takes rows by offset positions and returns results via Arrow C Data Interface.
 /// offsets: pointer to array of u64 offset values.
 /// offsets_len: number of offsets.
@@ -1824,6 +4155,186 @@ pub extern "C" fn table_take_row_ids(
     });
 }
 
+/// Number of keys looked up per query by table_get_by_keys, bounding the size of
+/// the generated predicate.
+const KEY_LOOKUP_CHUNK: usize = 1024;
+
+/// Arranges lookup keys as a batch of the primary key columns, cast to the table's
+/// key types. `keys` is a plain array for a single-column key, or a struct array with
+/// one field per key column. The fields are nullable so null keys can be passed.
+fn key_batch(
+    keys: &arrow_array::ArrayRef,
+    primary_key: &[String],
+    table_schema: &arrow_schema::Schema,
+) -> Result<arrow_array::RecordBatch, String> {
+    use arrow_array::cast::AsArray;
+    use arrow_schema::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    let batch = match keys.data_type() {
+        DataType::Struct(_) => arrow_array::RecordBatch::from(keys.as_struct().clone()),
+        data_type if primary_key.len() == 1 => {
+            let schema = Schema::new(vec![Field::new(&primary_key[0], data_type.clone(), true)]);
+            arrow_array::RecordBatch::try_new(Arc::new(schema), vec![keys.clone()]).map_err(|e| e.to_string())?
+        }
+        _ => {
+            return Err(format!(
+                "The primary key has {} columns; pass the keys as a struct array with one field per key column",
+                primary_key.len()
+            ));
+        }
+    };
+    let fields = primary_key
+        .iter()
+        .map(|c| {
+            table_schema
+                .field_with_name(c)
+                .map(|f| f.clone().with_nullable(true))
+                .map_err(|e| e.to_string())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    cast_to_schema(&batch, &Arc::new(Schema::new(fields)))
+}
+
+async fn get_by_keys_impl(
+    table: &Table,
+    keys: arrow_array::ArrayRef,
+    columns: Option<Vec<String>>,
+) -> Result<arrow_array::RecordBatch, String> {
+    use arrow_array::{RecordBatch, UInt64Array};
+    use arrow_schema::Schema;
+    use futures::TryStreamExt;
+    use lancedb::query::{QueryBase, Select};
+    use std::sync::Arc;
+
+    let primary_key = primary_key_columns(table).await?;
+    let table_schema = table.schema().await.map_err(|e| e.to_string())?;
+    let keys = key_batch(&keys, &primary_key, &table_schema)?;
+    let output_columns = match columns {
+        Some(c) => c,
+        None => table_schema.fields().iter().map(|f| f.name().clone()).collect(),
+    };
+    // The key columns are fetched even when not requested, to match rows to keys.
+    let mut fetch = output_columns.clone();
+    for column in &primary_key {
+        if !fetch.contains(column) {
+            fetch.push(column.clone());
+        }
+    }
+    let fetch_schema = Arc::new(Schema::new(
+        fetch
+            .iter()
+            .map(|c| table_schema.field_with_name(c).cloned().map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, _>>()?,
+    ));
+
+    // An IN list on the key is answered from a BTree or bitmap index when one exists.
+    let mut found = Vec::new();
+    for start in (0..keys.num_rows()).step_by(KEY_LOOKUP_CHUNK) {
+        let chunk = keys.slice(start, KEY_LOOKUP_CHUNK.min(keys.num_rows() - start));
+        let Some(predicate) = key_predicate(&primary_key, &[chunk])? else {
+            continue;
+        };
+        let stream = table
+            .query()
+            .only_if(predicate)
+            .select(Select::Columns(fetch.clone()))
+            .execute()
+            .await
+            .map_err(|e| e.to_string())?;
+        let batches: Vec<RecordBatch> = stream.try_collect().await.map_err(|e| e.to_string())?;
+        found.extend(batches);
+    }
+    let schema = found.first().map(|b| b.schema()).unwrap_or(fetch_schema);
+    let found = ffi::concat_or_empty(schema, found).map_err(|e| e.to_string())?;
+
+    // The key is not enforced, so the first stored row wins when a key repeats.
+    let found_keys = primary_key
+        .iter()
+        .map(|c| found.column_by_name(c).cloned().ok_or_else(|| format!("Missing key column '{}'", c)))
+        .collect::<Result<Vec<_>, _>>()?;
+    // Both sides have the table's key types, so their encoded rows compare directly.
+    let found_rows = key_rows(&found_keys)?;
+    let mut positions = std::collections::HashMap::new();
+    for row in 0..found.num_rows() {
+        if !has_null_key(&found_keys, row) {
+            positions.entry(found_rows.row(row)).or_insert(row as u64);
+        }
+    }
+    let lookup_rows = key_rows(keys.columns())?;
+    let indices = (0..keys.num_rows())
+        .map(|row| {
+            if has_null_key(keys.columns(), row) {
+                return None;
+            }
+            positions.get(&lookup_rows.row(row)).copied()
+        })
+        .collect::<UInt64Array>();
+
+    let mut fields = Vec::with_capacity(output_columns.len());
+    let mut arrays = Vec::with_capacity(output_columns.len());
+    for column in &output_columns {
+        let index = found.schema().index_of(column).map_err(|e| e.to_string())?;
+        fields.push(found.schema().field(index).clone().with_nullable(true));
+        arrays.push(arrow_select::take::take(found.column(index), &indices, None).map_err(|e| e.to_string())?);
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays).map_err(|e| e.to_string())
+}
+
+/// Fetches rows by primary key value, using the key set by
+/// table_set_unenforced_primary_key.
+/// keys_array/keys_schema: an Arrow array of key values; for a composite key, a struct
+/// array with one field per key column. Values are cast to the key column types; a
+/// value the key type cannot hold is an error.
+/// columns_json: optional JSON array of column names to select (null for all columns).
+/// Returns one row per key, in input order, via Arrow C Data Interface (free with
+/// free_ffi_cdata). Keys that match no row, and null keys, yield a row of nulls.
+/// The lookup uses a BTree or bitmap index on a single-column key when present.
+#[unsafe(no_mangle)]
+pub extern "C" fn table_get_by_keys(
+    table_ptr: *const Table,
+    keys_array: *mut arrow_data::ffi::FFI_ArrowArray,
+    keys_schema: *mut arrow_schema::ffi::FFI_ArrowSchema,
+    columns_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let keys = match ffi::import_array(keys_array, keys_schema) {
+        Ok(k) => k,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let columns = if columns_json.is_null() {
+        None
+    } else {
+        match sonic_rs::from_str::<Vec<String>>(&ffi::to_string(columns_json)) {
+            Ok(c) => Some(c),
+            Err(e) => {
+                callback_error(completion, user_data, e);
+                return;
+            }
+        }
+    };
+
+    crate::spawn(async move {
+        let batch = match get_by_keys_impl(&table, keys, columns).await {
+            Ok(b) => b,
+            Err(e) => {
+                callback_error(completion, user_data, e);
+                return;
+            }
+        };
+        match ffi::export_record_batch(batch) {
+            Ok(ptr) => completion(ptr as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr()),
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
 /// Returns the initial storage options as a JSON string, or null if none.
 /// Caller must free the returned string with free_string() (if non-null).
 #[unsafe(no_mangle)]
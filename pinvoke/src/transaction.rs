@@ -0,0 +1,683 @@
+use arrow_array::RecordBatch;
+use lancedb::table::Table;
+use libc::c_char;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::ffi;
+use crate::ffi::{callback_error, FfiCallback, UserData};
+use crate::table::FfiMergeResult;
+use crate::write::{commit_staged, parse_commit_metadata, ConflictMode, Committed, StagedWrite, WriteError, WriteOptions};
+
+/// Marks where a row in the staged view came from.
+const ORIGIN_COLUMN: &str = "__lancedb_txn_origin";
+
+/// A write staged on a transaction, applied in the order it was staged.
+enum StagedOp {
+    Add(Vec<RecordBatch>),
+    Delete(String),
+    Update {
+        filter: Option<String>,
+        columns: Vec<(String, String)>,
+    },
+    /// New columns computed from SQL expressions, as `(name, expr)` pairs.
+    AddColumns(Vec<(String, String)>),
+}
+
+enum TransactionState {
+    Open(Vec<StagedOp>),
+    Committing,
+    Committed(u64),
+    Failed,
+    RolledBack,
+}
+
+impl TransactionState {
+    fn closed_error(&self) -> String {
+        match self {
+            TransactionState::Open(_) => "Transaction is open".to_string(),
+            TransactionState::Committing => "Transaction is being committed".to_string(),
+            TransactionState::Committed(version) => {
+                format!("Transaction was already committed as version {}", version)
+            }
+            TransactionState::Failed => "Transaction commit failed; begin a new transaction".to_string(),
+            TransactionState::RolledBack => "Transaction was rolled back".to_string(),
+        }
+    }
+}
+
+/// A set of staged writes against one table, committed as a single version.
+///
+/// Staged operations are replayed at commit time against the base version (the
+/// version the table was at when the transaction began) and committed as one
+/// lance transaction read at that version, so readers never see a partial change
+/// and a concurrent write to the touched rows fails the commit. When key columns
+/// are given, written rows must have unique keys: an added row may not reuse the
+/// key of a row the transaction leaves in place, and keys cannot be updated.
+pub struct Transaction {
+    table: Table,
+    on: Vec<String>,
+    base_version: u64,
+    state: Mutex<TransactionState>,
+}
+
+impl Transaction {
+    fn stage(&self, op: StagedOp) -> Result<(), String> {
+        match &mut *self.state.lock().unwrap() {
+            TransactionState::Open(ops) => {
+                ops.push(op);
+                Ok(())
+            }
+            closed => Err(closed.closed_error()),
+        }
+    }
+
+    /// Takes the staged operations, closing the transaction to new ones.
+    fn take_ops(&self) -> Result<Vec<StagedOp>, String> {
+        let mut state = self.state.lock().unwrap();
+        match std::mem::replace(&mut *state, TransactionState::Committing) {
+            TransactionState::Open(ops) => Ok(ops),
+            closed => {
+                let error = closed.closed_error();
+                *state = closed;
+                Err(error)
+            }
+        }
+    }
+
+    fn finish(&self, state: TransactionState) {
+        *self.state.lock().unwrap() = state;
+    }
+}
+
+fn empty_merge_result(version: u64) -> FfiMergeResult {
+    FfiMergeResult {
+        version,
+        num_inserted_rows: 0,
+        num_updated_rows: 0,
+        num_deleted_rows: 0,
+        num_attempts: 0,
+        num_rows: 0,
+        affected_rows: std::ptr::null_mut(),
+    }
+}
+
+/// Rows written by a transaction commit, by what happened to them.
+#[derive(Default)]
+struct CommitCounts {
+    inserted: u64,
+    updated: u64,
+    deleted: u64,
+}
+
+/// Replays the staged operations over the base-version rows they touch and
+/// commits the outcome as one lance transaction read at the base version: the
+/// touched rows are deleted and the surviving and added rows are streamed into new
+/// fragments. The commit checks the touched rows against every write since the
+/// base version, so a concurrent change to them fails the commit; it is never
+/// re-staged on a later version.
+async fn commit_impl(
+    txn: &Transaction,
+    ops: Vec<StagedOp>,
+    options: &WriteOptions,
+    commit_metadata: Option<&std::collections::HashMap<String, String>>,
+) -> Result<FfiMergeResult, WriteError> {
+    if ops.is_empty() {
+        return Ok(empty_merge_result(txn.base_version));
+    }
+    for op in &ops {
+        if let StagedOp::Update { columns, .. } = op {
+            for (column, _) in columns {
+                if txn.on.contains(column) {
+                    return Err(format!("Transactions cannot update the key column '{}'", column).into());
+                }
+            }
+        }
+    }
+
+    let table = &txn.table;
+    let latest = crate::write::open_dataset(table).await?;
+    let base = Arc::new(latest.checkout_version(txn.base_version).await.map_err(|e| e.to_string())?);
+    let Some(staged) = stage_ops(txn, &ops, base).await? else {
+        return Ok(empty_merge_result(txn.base_version));
+    };
+    // The replay is read at the base version, so staging it again on a later
+    // version would not resolve a conflict.
+    let options = WriteOptions {
+        conflict_mode: ConflictMode::FailFast,
+        ..options.clone()
+    };
+    let mut staged = Some(staged);
+    let committed = commit_staged(table, &options, commit_metadata, |_| {
+        let staged = staged.take();
+        async move { staged.ok_or_else(|| "Transaction was already staged".to_string()) }
+    })
+    .await?;
+    Ok(match committed {
+        Committed::Written { dataset, output, .. } => FfiMergeResult {
+            version: dataset.manifest.version,
+            num_inserted_rows: output.inserted,
+            num_updated_rows: output.updated,
+            num_deleted_rows: output.deleted,
+            num_attempts: 1,
+            num_rows: output.inserted + output.updated,
+            affected_rows: std::ptr::null_mut(),
+        },
+        Committed::Replayed { version } => empty_merge_result(version),
+    })
+}
+
+/// Stages the outcome of `ops` over `base`, the transaction's base version, or
+/// `None` when they change nothing.
+async fn stage_ops(
+    txn: &Transaction,
+    ops: &[StagedOp],
+    base: Arc<lance::Dataset>,
+) -> Result<Option<StagedWrite<CommitCounts>>, String> {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::UInt64Type;
+    use datafusion::common::{Column, JoinType};
+    use datafusion::datasource::MemTable;
+    use datafusion::error::DataFusionError;
+    use datafusion::functions_aggregate::expr_fn::count;
+    use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+    use datafusion::prelude::{col, lit, Expr, SessionContext};
+    use futures::{StreamExt, TryStreamExt};
+    use lance::datafusion::LanceTableProvider;
+    use lance::dataset::transaction::{Operation, Transaction as LanceTransaction};
+
+    let base_names: Vec<String> = base.schema().fields.iter().map(|f| f.name.clone()).collect();
+    let new_columns: Vec<(String, String)> = ops
+        .iter()
+        .filter_map(|op| match op {
+            StagedOp::AddColumns(columns) => Some(columns.iter().cloned()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
+    // Existing rows can only be changed by a delete or update whose predicate
+    // holds for their values at the base version, with the columns the
+    // transaction adds computed from those values, so those are the only rows to
+    // replay.
+    let touched = ops
+        .iter()
+        .filter_map(|op| match op {
+            StagedOp::Add(_) | StagedOp::AddColumns(_) => None,
+            StagedOp::Delete(predicate) => Some(format!("COALESCE(({}), FALSE)", predicate)),
+            StagedOp::Update { filter: None, .. } => Some("TRUE".to_string()),
+            StagedOp::Update { filter: Some(filter), .. } => Some(format!("COALESCE(({}), FALSE)", filter)),
+        })
+        .collect::<Vec<_>>();
+    let touched = if touched.is_empty() { "FALSE".to_string() } else { touched.join(" OR ") };
+
+    let ctx = SessionContext::new();
+    let provider = Arc::new(LanceTableProvider::new(base.clone(), false, true));
+    let mut base_rows = ctx.read_table(provider).map_err(|e| e.to_string())?;
+    for (name, expr) in &new_columns {
+        let expr = base_rows.parse_sql_expr(expr).map_err(|e| e.to_string())?;
+        base_rows = base_rows.with_column(name, expr).map_err(|e| e.to_string())?;
+    }
+    let touched_expr = base_rows.parse_sql_expr(&touched).map_err(|e| e.to_string())?;
+    let target = base_rows.clone().filter(touched_expr.clone()).map_err(|e| e.to_string())?;
+
+    let mut addresses = Vec::new();
+    let mut address_stream = target
+        .clone()
+        .select_columns(&["_rowaddr"])
+        .map_err(|e| e.to_string())?
+        .execute_stream()
+        .await
+        .map_err(|e| e.to_string())?;
+    while let Some(batch) = address_stream.try_next().await.map_err(|e| e.to_string())? {
+        addresses.extend(batch.column(0).as_primitive::<UInt64Type>().values().iter().copied());
+    }
+
+    let base_refs = base_names.iter().map(|n| n.as_str()).collect::<Vec<_>>();
+    let mut view = target
+        .select_columns(&base_refs)
+        .and_then(|df| df.with_column(ORIGIN_COLUMN, lit("target")))
+        .map_err(|e| e.to_string())?;
+    let mut names = base_names.clone();
+    let mut modified = Vec::new();
+    let mut has_new_rows = false;
+    for op in ops {
+        view = match op {
+            StagedOp::Add(batches) => {
+                let fields = names
+                    .iter()
+                    .map(|n| view.schema().as_arrow().field_with_name(n).cloned())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| e.to_string())?;
+                let schema = Arc::new(arrow_schema::Schema::new(fields));
+                let batches = batches
+                    .iter()
+                    .map(|b| conform_to_schema(b, &schema))
+                    .collect::<Result<Vec<_>, _>>()?;
+                has_new_rows |= batches.iter().any(|b| b.num_rows() > 0);
+                let mem = MemTable::try_new(schema, vec![batches]).map_err(|e| e.to_string())?;
+                let added = ctx
+                    .read_table(Arc::new(mem))
+                    .and_then(|df| df.with_column(ORIGIN_COLUMN, lit("new")))
+                    .map_err(|e| e.to_string())?;
+                view.union(added).map_err(|e| e.to_string())?
+            }
+            StagedOp::Delete(predicate) => {
+                let keep = view
+                    .parse_sql_expr(&format!("NOT COALESCE(({}), FALSE)", predicate))
+                    .map_err(|e| e.to_string())?;
+                view.filter(keep).map_err(|e| e.to_string())?
+            }
+            StagedOp::Update { filter, columns } => {
+                let condition = filter
+                    .as_ref()
+                    .map(|f| format!("COALESCE(({}), FALSE)", f))
+                    .unwrap_or_else(|| "TRUE".to_string());
+                let mut exprs = Vec::with_capacity(names.len() + 1);
+                for name in &names {
+                    let quoted = crate::query::quote_identifier(name);
+                    let sql = match columns.iter().find(|(c, _)| c == name) {
+                        Some((_, expr)) => format!("CASE WHEN {} THEN ({}) ELSE {} END", condition, expr, quoted),
+                        None => quoted,
+                    };
+                    let expr = view.parse_sql_expr(&sql).map_err(|e| e.to_string())?;
+                    exprs.push(expr.alias(name));
+                }
+                exprs.push(col(ORIGIN_COLUMN));
+                modified.extend(columns.iter().map(|(column, _)| column.clone()));
+                view.select(exprs).map_err(|e| e.to_string())?
+            }
+            StagedOp::AddColumns(columns) => {
+                for (name, expr) in columns {
+                    let expr = view.parse_sql_expr(expr).map_err(|e| e.to_string())?;
+                    view = view.with_column(name, expr).map_err(|e| e.to_string())?;
+                    names.push(name.clone());
+                }
+                // Keep the origin last so later adds line up in the union.
+                let order = names.iter().map(|n| n.as_str()).chain([ORIGIN_COLUMN]).collect::<Vec<_>>();
+                view.select_columns(&order).map_err(|e| e.to_string())?
+            }
+        };
+    }
+    if addresses.is_empty() && !has_new_rows && new_columns.is_empty() {
+        return Ok(None);
+    }
+
+    // Keys stay unique: written rows may not share a key, and an added row may
+    // not reuse the key of a base row the transaction leaves in place.
+    if !txn.on.is_empty() {
+        let keys = txn.on.iter().map(|k| Expr::Column(Column::from_name(k))).collect::<Vec<_>>();
+        let repeated = view
+            .clone()
+            .aggregate(keys, vec![count(lit(1)).alias("n")])
+            .and_then(|df| df.filter(col("n").gt(lit(1))))
+            .map_err(|e| e.to_string())?
+            .count()
+            .await
+            .map_err(|e| e.to_string())?;
+        if repeated > 0 {
+            return Err(format!("Transaction writes more than one row with the same {:?} key", txn.on));
+        }
+        let key_refs = txn.on.iter().map(|k| k.as_str()).collect::<Vec<_>>();
+        let new_keys = view
+            .clone()
+            .filter(col(ORIGIN_COLUMN).eq(lit("new")))
+            .and_then(|df| df.select_columns(&key_refs))
+            .and_then(|df| df.alias("new"))
+            .map_err(|e| e.to_string())?;
+        let base_keys = base_rows
+            .filter(!touched_expr)
+            .and_then(|df| df.select_columns(&key_refs))
+            .and_then(|df| df.alias("base"))
+            .map_err(|e| e.to_string())?;
+        let on = txn
+            .on
+            .iter()
+            .map(|k| Expr::Column(Column::new(Some("new"), k)).eq(Expr::Column(Column::new(Some("base"), k))))
+            .collect::<Vec<_>>();
+        let existing = new_keys
+            .join_on(base_keys, JoinType::LeftSemi, on)
+            .map_err(|e| e.to_string())?
+            .count()
+            .await
+            .map_err(|e| e.to_string())?;
+        if existing > 0 {
+            return Err(format!(
+                "Transaction adds {} row(s) whose {:?} key already exists in version {}",
+                existing, txn.on, txn.base_version
+            ));
+        }
+    }
+
+    // Adding columns changes the schema of every fragment, so the commit is a
+    // merge of the rewritten base fragments and the new ones.
+    let added = if new_columns.is_empty() {
+        None
+    } else {
+        Some(crate::write::add_columns_to_fragments(&base, &new_columns).await?)
+    };
+    let lance_schema = added.as_ref().map_or_else(|| base.schema().clone(), |(_, schema)| schema.clone());
+    let schema = Arc::new(arrow_schema::Schema::from(&lance_schema));
+
+    // The replayed rows are streamed into the new fragments, counted by origin
+    // on the way.
+    let inserted = Arc::new(AtomicU64::new(0));
+    let updated = Arc::new(AtomicU64::new(0));
+    let (inserted_rows, updated_rows, write_schema) = (inserted.clone(), updated.clone(), schema.clone());
+    let rows = view
+        .execute_stream()
+        .await
+        .map_err(|e| e.to_string())?
+        .map(move |batch| {
+            let batch = batch?;
+            let origin = batch
+                .column_by_name(ORIGIN_COLUMN)
+                .ok_or_else(|| DataFusionError::Execution("Staged rows are missing their origin".to_string()))?;
+            let origin = datafusion::arrow::compute::cast(origin, &arrow_schema::DataType::Utf8)?;
+            let new_rows = origin.as_string::<i32>().iter().filter(|v| *v == Some("new")).count() as u64;
+            inserted_rows.fetch_add(new_rows, Ordering::Relaxed);
+            updated_rows.fetch_add(batch.num_rows() as u64 - new_rows, Ordering::Relaxed);
+            crate::table::cast_to_schema(&batch, &write_schema).map_err(DataFusionError::Execution)
+        });
+    let rows = Box::pin(RecordBatchStreamAdapter::new(schema, rows));
+
+    let operation = match added {
+        None => {
+            let new_fragments = crate::write::write_fragments(&base, rows).await?;
+            crate::write::rewrite_rows(&base, &addresses, new_fragments, &modified).await?
+        }
+        Some((fragments, schema)) => {
+            let new_fragments =
+                crate::write::write_fragments_with_schema(&txn.table, &base, &schema, rows).await?;
+            let (with_deletions, removed) = crate::table::delete_addresses(&base, &addresses).await?;
+            let mut fragments = fragments
+                .into_iter()
+                .filter(|f| !removed.contains(&f.id))
+                .map(|mut fragment| {
+                    if let Some(deleted) = with_deletions.iter().find(|d| d.id == fragment.id) {
+                        fragment.deletion_file = deleted.deletion_file.clone();
+                    }
+                    fragment
+                })
+                .collect::<Vec<_>>();
+            fragments.extend(new_fragments);
+            Operation::Merge { fragments, schema }
+        }
+    };
+
+    let updated = updated.load(Ordering::Relaxed);
+    Ok(Some(StagedWrite {
+        transaction: LanceTransaction::new(txn.base_version, operation, None),
+        output: CommitCounts {
+            inserted: inserted.load(Ordering::Relaxed),
+            updated,
+            deleted: addresses.len() as u64 - updated,
+        },
+        affected_rows: addresses,
+    }))
+}
+
+/// `batch` with the columns of `schema`, cast to its types. Columns the batch
+/// lacks are filled with nulls when nullable, such as columns added earlier in
+/// the transaction.
+fn conform_to_schema(batch: &RecordBatch, schema: &arrow_schema::SchemaRef) -> Result<RecordBatch, String> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| match batch.column_by_name(field.name()) {
+            Some(column) => datafusion::arrow::compute::cast(column, field.data_type()).map_err(|e| e.to_string()),
+            None if field.is_nullable() => Ok(arrow_array::new_null_array(field.data_type(), batch.num_rows())),
+            None => Err(format!("Column '{}' is missing from the data", field.name())),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    RecordBatch::try_new(schema.clone(), columns).map_err(|e| e.to_string())
+}
+
+/// Begins a transaction on the table.
+/// on_columns_json: optional JSON array of key columns that identify rows, e.g.
+/// '["id"]' (null or empty for none). With keys, the commit checks that written
+/// rows have unique keys, and staged updates cannot change them.
+/// The callback receives an opaque Transaction pointer (free with transaction_close).
+#[unsafe(no_mangle)]
+pub extern "C" fn table_begin_transaction(
+    table_ptr: *const Table,
+    on_columns_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let table = ffi_clone_arc!(table_ptr, Table);
+    let on = if on_columns_json.is_null() {
+        Vec::new()
+    } else {
+        match sonic_rs::from_str::<Vec<String>>(&ffi::to_string(on_columns_json)) {
+            Ok(on) => on,
+            Err(e) => {
+                callback_error(completion, user_data, e);
+                return;
+            }
+        }
+    };
+    crate::spawn(async move {
+        if table.as_native().is_none() {
+            callback_error(completion, user_data, "Transactions are only supported for local tables");
+            return;
+        }
+        match table.version().await {
+            Ok(base_version) => {
+                let txn = Arc::new(Transaction {
+                    table: (*table).clone(),
+                    on,
+                    base_version,
+                    state: Mutex::new(TransactionState::Open(Vec::new())),
+                });
+                completion(Arc::into_raw(txn) as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => callback_error(completion, user_data, e),
+        }
+    });
+}
+
+/// Returns the version the transaction is based on.
+#[unsafe(no_mangle)]
+pub extern "C" fn transaction_base_version(txn_ptr: *const Transaction) -> u64 {
+    let txn = ffi_borrow!(txn_ptr, Transaction);
+    txn.base_version
+}
+
+/// Stages an append of the given Arrow C Data Interface batches.
+#[unsafe(no_mangle)]
+pub extern "C" fn transaction_add(
+    txn_ptr: *const Transaction,
+    arrays: *mut arrow_data::ffi::FFI_ArrowArray,
+    schema: *mut arrow_schema::ffi::FFI_ArrowSchema,
+    batch_count: usize,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let txn = ffi_borrow!(txn_ptr, Transaction);
+    let result = ffi::import_batches(arrays, schema, batch_count)
+        .and_then(|(batches, _)| txn.stage(StagedOp::Add(batches)));
+    match result {
+        Ok(()) => completion(std::ptr::null(), std::ptr::null(), user_data.as_ptr()),
+        Err(e) => callback_error(completion, user_data, e),
+    }
+}
+
+/// Stages a delete of the rows matching the SQL predicate.
+#[unsafe(no_mangle)]
+pub extern "C" fn transaction_delete(
+    txn_ptr: *const Transaction,
+    predicate: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let txn = ffi_borrow!(txn_ptr, Transaction);
+    let predicate = ffi::to_string(predicate);
+    match txn.stage(StagedOp::Delete(predicate)) {
+        Ok(()) => completion(std::ptr::null(), std::ptr::null(), user_data.as_ptr()),
+        Err(e) => callback_error(completion, user_data, e),
+    }
+}
+
+/// Stages an update. column_sqlexprs_json is a JSON array of [name, expr] pairs;
+/// filter is an optional SQL predicate (null for all rows).
+#[unsafe(no_mangle)]
+pub extern "C" fn transaction_update(
+    txn_ptr: *const Transaction,
+    filter: *const c_char,
+    column_sqlexprs_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let txn = ffi_borrow!(txn_ptr, Transaction);
+    let filter = if filter.is_null() {
+        None
+    } else {
+        Some(ffi::to_string(filter))
+    };
+    let columns: Vec<(String, String)> = match sonic_rs::from_str(&ffi::to_string(column_sqlexprs_json)) {
+        Ok(c) => c,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    match txn.stage(StagedOp::Update { filter, columns }) {
+        Ok(()) => completion(std::ptr::null(), std::ptr::null(), user_data.as_ptr()),
+        Err(e) => callback_error(completion, user_data, e),
+    }
+}
+
+/// Stages adding columns computed from SQL expressions over each row, as
+/// table_add_columns. transforms_json is a JSON array of [name, expression] pairs.
+/// Rows staged before are given the new columns; rows added after may omit them
+/// to have them filled with nulls. The commit rewrites every fragment of the
+/// table, so any write since the base version makes it conflict.
+#[unsafe(no_mangle)]
+pub extern "C" fn transaction_add_columns(
+    txn_ptr: *const Transaction,
+    transforms_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let txn = ffi_borrow!(txn_ptr, Transaction);
+    let columns: Vec<(String, String)> = match sonic_rs::from_str(&ffi::to_string(transforms_json)) {
+        Ok(c) => c,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    if columns.is_empty() {
+        callback_error(completion, user_data, "No columns provided");
+        return;
+    }
+    match txn.stage(StagedOp::AddColumns(columns)) {
+        Ok(()) => completion(std::ptr::null(), std::ptr::null(), user_data.as_ptr()),
+        Err(e) => callback_error(completion, user_data, e),
+    }
+}
+
+/// Commits the staged operations as a single new table version read at the base
+/// version. Fails with a conflict error if a write since the base version changed
+/// the rows the transaction deletes or updates.
+/// The transaction is closed afterwards whether or not the commit succeeds.
+/// Returns an FfiMergeResult (free with table_merge_result_free).
+#[unsafe(no_mangle)]
+pub extern "C" fn transaction_commit(
+    txn_ptr: *const Transaction,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    transaction_commit_with_options(txn_ptr, std::ptr::null(), std::ptr::null(), completion, user_data);
+}
+
+/// Commits the transaction, as transaction_commit, with commit metadata and an
+/// idempotency key.
+/// write_options_json: optional JSON object of write options (null for defaults),
+/// see `WriteOptions`. With an `idempotency_key` that is already committed, nothing
+/// is written and the original version is returned with zero row counts. Conflicts
+/// are never retried, as the transaction was read at its base version; they are
+/// reported with `ERROR_CODE_COMMIT_CONFLICT` (see ffi_error_details).
+/// commit_metadata_json: optional JSON object of string values recorded with the
+/// commit (null for none); see table_list_versions.
+#[unsafe(no_mangle)]
+pub extern "C" fn transaction_commit_with_options(
+    txn_ptr: *const Transaction,
+    write_options_json: *const c_char,
+    commit_metadata_json: *const c_char,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let txn = ffi_clone_arc!(txn_ptr, Transaction);
+    let write_options = match WriteOptions::parse(write_options_json) {
+        Ok(o) => o,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    let commit_metadata = match parse_commit_metadata(commit_metadata_json) {
+        Ok(m) => m,
+        Err(e) => {
+            callback_error(completion, user_data, e);
+            return;
+        }
+    };
+    crate::spawn(async move {
+        let ops = match txn.take_ops() {
+            Ok(ops) => ops,
+            Err(e) => {
+                callback_error(completion, user_data, e);
+                return;
+            }
+        };
+        match commit_impl(&txn, ops, &write_options, commit_metadata.as_ref()).await {
+            Ok(result) => {
+                txn.finish(TransactionState::Committed(result.version));
+                let ffi = Box::new(result);
+                completion(Box::into_raw(ffi) as *const std::ffi::c_void, std::ptr::null(), user_data.as_ptr());
+            }
+            Err(e) => {
+                txn.finish(TransactionState::Failed);
+                e.report(completion, user_data);
+            }
+        }
+    });
+}
+
+/// Discards the staged operations. Rolling back a committed transaction is an error.
+#[unsafe(no_mangle)]
+pub extern "C" fn transaction_rollback(
+    txn_ptr: *const Transaction,
+    completion: FfiCallback,
+    user_data: *mut std::ffi::c_void,
+) {
+    let user_data = UserData(user_data);
+    let txn = ffi_borrow!(txn_ptr, Transaction);
+    let mut state = txn.state.lock().unwrap();
+    match &*state {
+        TransactionState::Committing | TransactionState::Committed(_) => {
+            let error = state.closed_error();
+            drop(state);
+            callback_error(completion, user_data, error);
+        }
+        _ => {
+            *state = TransactionState::RolledBack;
+            drop(state);
+            completion(std::ptr::null(), std::ptr::null(), user_data.as_ptr());
+        }
+    }
+}
+
+/// Frees the transaction. Uncommitted operations are discarded.
+#[unsafe(no_mangle)]
+pub extern "C" fn transaction_close(txn_ptr: *const Transaction) {
+    ffi_free!(txn_ptr, Transaction);
+}
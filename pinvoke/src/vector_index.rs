@@ -0,0 +1,357 @@
+//! Vector indices built with lance's index parameters.
+//!
+//! Training the IVF centroids, and the PQ codebook, with kmeans is most of the cost
+//! of building a vector index. Tables of embeddings from the same model can share
+//! one trained model instead: `table_create_index_with_model` takes the centroids
+//! and codebook as Arrow arrays, or copies them from a vector index on another
+//! table, and builds the index on the table's lance dataset without training them.
+//!
+//! The same path builds IVF-SQ and IVF-HNSW-SQ indices that set `num_bits`, and
+//! IVF-RQ indices that set `rotation_type`, which lancedb's builders do not expose.
+
+use std::sync::Arc;
+
+use arrow_array::{Array, ArrayRef, FixedSizeListArray};
+use arrow_schema::DataType;
+use lance::index::vector::VectorIndexParams;
+use lance::index::{DatasetIndexExt, DatasetIndexInternalExt};
+use lance_index::metrics::NoOpMetricsCollector;
+use lance_index::vector::bq::{RQBuildParams, RQRotationType};
+use lance_index::vector::hnsw::builder::HnswBuildParams;
+use lance_index::vector::ivf::IvfBuildParams;
+use lance_index::vector::pq::PQBuildParams;
+use lance_index::vector::quantizer::Quantizer;
+use lance_index::vector::sq::builder::SQBuildParams;
+use lancedb::index::IndexType;
+use lancedb::table::Table;
+use lancedb::DistanceType;
+use sonic_rs::JsonValueTrait;
+
+use crate::ffi;
+
+/// Where a vector index gets its trained model from.
+pub(crate) enum ModelSource {
+    /// IVF centroids, one per partition, and optionally a PQ codebook.
+    Arrays {
+        centroids: ArrayRef,
+        codebook: Option<ArrayRef>,
+    },
+    /// The model of the vector index named `index_name` on `table`.
+    Index { table: Arc<Table>, index_name: String },
+}
+
+/// A trained IVF model: centroids and, for PQ indices, the codebook.
+struct IvfModel {
+    centroids: Arc<FixedSizeListArray>,
+    codebook: Option<ArrayRef>,
+}
+
+fn config_u64(config: &sonic_rs::Value, key: &str) -> Option<u64> {
+    config.get(key).and_then(|v| v.as_u64())
+}
+
+fn is_pq(index_type: &IndexType) -> bool {
+    matches!(index_type, IndexType::IvfPq | IndexType::IvfHnswPq)
+}
+
+fn is_sq(index_type: &IndexType) -> bool {
+    matches!(index_type, IndexType::IvfSq | IndexType::IvfHnswSq)
+}
+
+/// The number of PQ sub-vectors lancedb uses when none is given.
+fn default_num_sub_vectors(dimension: usize) -> usize {
+    if dimension % 16 == 0 {
+        dimension / 16
+    } else if dimension % 8 == 0 {
+        dimension / 8
+    } else {
+        1
+    }
+}
+
+/// A vector index to build with lance index parameters.
+pub(crate) struct VectorIndexBuild {
+    index_type: IndexType,
+    config: sonic_rs::Value,
+    model: Option<ModelSource>,
+}
+
+impl VectorIndexBuild {
+    /// `None` when `config` sets no option that needs lance index parameters;
+    /// lancedb builds those indices itself.
+    pub(crate) fn parse(index_type: &IndexType, config: &sonic_rs::Value) -> Result<Option<Self>, String> {
+        let native = match index_type {
+            IndexType::IvfRq => config.get("rotation_type").is_some(),
+            _ => is_sq(index_type) && config.get("num_bits").is_some(),
+        };
+        if !native {
+            return Ok(None);
+        }
+        Self::with_model(index_type, config, None).map(Some)
+    }
+
+    pub(crate) fn with_model(
+        index_type: &IndexType,
+        config: &sonic_rs::Value,
+        model: Option<ModelSource>,
+    ) -> Result<Self, String> {
+        if !matches!(
+            index_type,
+            IndexType::IvfFlat
+                | IndexType::IvfSq
+                | IndexType::IvfPq
+                | IndexType::IvfRq
+                | IndexType::IvfHnswPq
+                | IndexType::IvfHnswSq
+                | IndexType::IvfHnswFlat
+        ) {
+            return Err(format!("A trained model only applies to IVF vector indices, not {:?}", index_type));
+        }
+        if let Some(ModelSource::Arrays { codebook: Some(_), .. }) = &model {
+            if !is_pq(index_type) {
+                return Err("A PQ codebook only applies to IVF-PQ and IVF-HNSW-PQ indices".to_string());
+            }
+        }
+        Ok(Self {
+            index_type: index_type.clone(),
+            config: config.clone(),
+            model,
+        })
+    }
+
+    fn distance_type(&self) -> Result<DistanceType, String> {
+        match self.config.get("distance_type").and_then(|v| v.as_i64()) {
+            Some(v) => ffi::ffi_to_distance_type(v as i32),
+            None => Ok(DistanceType::L2),
+        }
+    }
+
+    async fn model(&self, distance_type: DistanceType) -> Result<Option<IvfModel>, String> {
+        let (centroids, codebook) = match &self.model {
+            None => return Ok(None),
+            Some(ModelSource::Arrays { centroids, codebook }) => (centroids.clone(), codebook.clone()),
+            Some(ModelSource::Index { table, index_name }) => {
+                let (centroids, codebook) = copy_model(table, index_name, distance_type).await?;
+                // Only PQ indices use the source's codebook; the others train
+                // their own quantizer on top of the shared centroids.
+                (centroids, codebook.filter(|_| is_pq(&self.index_type)))
+            }
+        };
+        let centroids = centroids
+            .as_any()
+            .downcast_ref::<FixedSizeListArray>()
+            .filter(|c| matches!(c.value_type(), DataType::Float16 | DataType::Float32 | DataType::Float64))
+            .ok_or_else(|| {
+                format!(
+                    "Centroids must be a fixed size list array of floats, found {}",
+                    centroids.data_type()
+                )
+            })?;
+        Ok(Some(IvfModel {
+            centroids: Arc::new(centroids.clone()),
+            codebook,
+        }))
+    }
+
+    fn ivf_params(&self, model: Option<&IvfModel>) -> Result<IvfBuildParams, String> {
+        let mut ivf = IvfBuildParams::default();
+        if let Some(v) = config_u64(&self.config, "num_partitions") {
+            ivf.num_partitions = Some(v as usize);
+        }
+        if let Some(v) = config_u64(&self.config, "max_iterations") {
+            ivf.max_iters = v as usize;
+        }
+        if let Some(v) = config_u64(&self.config, "sample_rate") {
+            ivf.sample_rate = v as usize;
+        }
+        if let Some(v) = config_u64(&self.config, "target_partition_size") {
+            ivf.target_partition_size = Some(v as usize);
+        }
+        if let Some(model) = model {
+            let num_partitions = model.centroids.len();
+            if ivf.num_partitions.is_some_and(|n| n != num_partitions) {
+                return Err(format!(
+                    "num_partitions is {}, but the model has {} centroids",
+                    ivf.num_partitions.unwrap(),
+                    num_partitions
+                ));
+            }
+            ivf.num_partitions = Some(num_partitions);
+            ivf.centroids = Some(model.centroids.clone());
+        }
+        Ok(ivf)
+    }
+
+    fn pq_params(&self, dimension: usize, model: Option<&IvfModel>) -> PQBuildParams {
+        let num_bits = config_u64(&self.config, "num_bits").unwrap_or(8) as usize;
+        let codebook = model.and_then(|m| m.codebook.clone());
+        let num_sub_vectors = match (config_u64(&self.config, "num_sub_vectors"), &codebook) {
+            (Some(v), _) => v as usize,
+            // The codebook holds 2^num_bits centroids per sub-vector.
+            (None, Some(codebook)) => codebook.len() >> num_bits,
+            (None, None) => default_num_sub_vectors(dimension),
+        };
+        let mut pq = PQBuildParams {
+            num_sub_vectors,
+            num_bits,
+            codebook,
+            ..Default::default()
+        };
+        if let Some(v) = config_u64(&self.config, "max_iterations") {
+            pq.max_iters = v as usize;
+        }
+        pq
+    }
+
+    fn sq_params(&self) -> SQBuildParams {
+        let mut sq = SQBuildParams::default();
+        if let Some(v) = config_u64(&self.config, "num_bits") {
+            sq.num_bits = v as u16;
+        }
+        sq
+    }
+
+    fn rq_params(&self) -> Result<RQBuildParams, String> {
+        let mut rq = RQBuildParams::default();
+        if let Some(v) = config_u64(&self.config, "num_bits") {
+            rq.num_bits = v as u8;
+        }
+        if let Some(v) = self.config.get("rotation_type") {
+            rq.rotation_type = match v.as_str() {
+                Some("fast") => RQRotationType::Fast,
+                Some("matrix") => RQRotationType::Matrix,
+                _ => return Err("rotation_type must be \"fast\" or \"matrix\"".to_string()),
+            };
+        }
+        Ok(rq)
+    }
+
+    fn hnsw_params(&self) -> HnswBuildParams {
+        let mut hnsw = HnswBuildParams::default();
+        if let Some(v) = config_u64(&self.config, "num_edges") {
+            hnsw = hnsw.num_edges(v as usize);
+        }
+        if let Some(v) = config_u64(&self.config, "ef_construction") {
+            hnsw = hnsw.ef_construction(v as usize);
+        }
+        hnsw
+    }
+
+    /// Builds the index on the table's dataset and reloads the table.
+    pub(crate) async fn create(
+        &self,
+        table: &Table,
+        columns: &[String],
+        name: Option<String>,
+        replace: bool,
+        train: bool,
+    ) -> Result<(), String> {
+        if table.as_native().is_none() {
+            return Err("Vector index build options are only supported for local tables".to_string());
+        }
+        let [column] = columns else {
+            return Err("A vector index is built on a single column".to_string());
+        };
+        let schema = table.schema().await.map_err(|e| e.to_string())?;
+        let field = schema.field_with_name(column).map_err(|e| e.to_string())?;
+        let DataType::FixedSizeList(_, dimension) = field.data_type() else {
+            return Err(format!(
+                "A vector index needs a fixed size list column, but '{}' is {}",
+                column,
+                field.data_type()
+            ));
+        };
+        let dimension = *dimension as usize;
+
+        let distance_type = self.distance_type()?;
+        let model = self.model(distance_type).await?;
+        if let Some(model) = &model {
+            let model_dimension = model.centroids.value_length() as usize;
+            if model_dimension != dimension {
+                return Err(format!(
+                    "The centroids have dimension {}, but column '{}' has dimension {}",
+                    model_dimension, column, dimension
+                ));
+            }
+        }
+
+        let ivf = self.ivf_params(model.as_ref())?;
+        let params = match self.index_type {
+            IndexType::IvfFlat => VectorIndexParams::with_ivf_flat_params(distance_type, ivf),
+            IndexType::IvfSq => VectorIndexParams::with_ivf_sq_params(distance_type, ivf, self.sq_params()),
+            IndexType::IvfPq => {
+                VectorIndexParams::with_ivf_pq_params(distance_type, ivf, self.pq_params(dimension, model.as_ref()))
+            }
+            IndexType::IvfRq => VectorIndexParams::with_ivf_rq_params(distance_type, ivf, self.rq_params()?),
+            IndexType::IvfHnswFlat => VectorIndexParams::ivf_hnsw(distance_type, ivf, self.hnsw_params()),
+            IndexType::IvfHnswSq => {
+                VectorIndexParams::with_ivf_hnsw_sq_params(distance_type, ivf, self.hnsw_params(), self.sq_params())
+            }
+            IndexType::IvfHnswPq => VectorIndexParams::with_ivf_hnsw_pq_params(
+                distance_type,
+                ivf,
+                self.hnsw_params(),
+                self.pq_params(dimension, model.as_ref()),
+            ),
+            _ => unreachable!("with_model only accepts IVF index types"),
+        };
+
+        let mut dataset = crate::write::open_dataset(table).await?;
+        let columns = [column.as_str()];
+        let mut builder = dataset
+            .create_index_builder(&columns, lance_index::IndexType::Vector, &params)
+            .replace(replace)
+            .train(train);
+        if let Some(name) = name {
+            builder = builder.name(name);
+        }
+        builder.await.map_err(|e| e.to_string())?;
+        // The index was committed on the dataset, so reload the table to see it.
+        table.checkout_latest().await.map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+/// The centroids and PQ codebook of the vector index `index_name` on `table`.
+async fn copy_model(
+    table: &Table,
+    index_name: &str,
+    distance_type: DistanceType,
+) -> Result<(ArrayRef, Option<ArrayRef>), String> {
+    if table.as_native().is_none() {
+        return Err("A model can only be copied from an index on a local table".to_string());
+    }
+    let dataset = crate::write::open_dataset(table).await?;
+    let indices = dataset.load_indices_by_name(index_name).await.map_err(|e| e.to_string())?;
+    let index = indices
+        .first()
+        .ok_or_else(|| format!("The source table has no index named '{}'", index_name))?;
+    let column = index
+        .fields
+        .first()
+        .and_then(|id| dataset.schema().field_by_id(*id))
+        .map(|field| field.name.clone())
+        .ok_or_else(|| format!("Index '{}' has no column", index_name))?;
+    let source = dataset
+        .open_vector_index(&column, &index.uuid.to_string(), &NoOpMetricsCollector)
+        .await
+        .map_err(|e| format!("Index '{}' is not a vector index: {}", index_name, e))?;
+    if source.metric_type() != distance_type {
+        return Err(format!(
+            "Index '{}' uses {} distance, but the new index uses {}",
+            index_name,
+            source.metric_type(),
+            distance_type
+        ));
+    }
+    let centroids = source
+        .ivf_model()
+        .centroids
+        .clone()
+        .ok_or_else(|| format!("Index '{}' has no IVF centroids", index_name))?;
+    let codebook = match source.quantizer() {
+        Quantizer::Product(pq) => Some(Arc::new(pq.codebook.clone()) as ArrayRef),
+        _ => None,
+    };
+    Ok((Arc::new(centroids), codebook))
+}
@@ -0,0 +1,1071 @@
+use arrow_array::RecordBatch;
+use futures::stream::BoxStream;
+use lance_table::format::{IndexMetadata, Manifest, Transaction as TableTransaction};
+use lance_table::io::commit::{
+    commit_handler_from_url, CommitError, CommitHandler, ManifestLocation, ManifestNamingScheme, ManifestWriter,
+};
+use lancedb::table::{AddDataMode, Table};
+use object_store::path::Path;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Transaction property under which a write's idempotency key is committed.
+pub const IDEMPOTENCY_KEY_PROPERTY: &str = "lancedb.idempotency_key";
+
+/// Row address column exposed by `LanceTableProvider` when asked for it.
+const ROW_ADDR: &str = "_rowaddr";
+
+/// How a write reacts when its commit conflicts with a concurrent writer.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictMode {
+    /// Re-run the write up to `max_retries` times, backing off between attempts.
+    #[default]
+    Retry,
+    /// Report the first conflict without retrying in this layer.
+    FailFast,
+}
+
+/// Options accepted by the write exports as an optional `write_options_json` object.
+/// Every field is optional; a null or empty object retries conflicts up to 10 times.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct WriteOptions {
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    #[serde(default)]
+    pub initial_backoff_ms: Option<u64>,
+    #[serde(default)]
+    pub max_backoff_ms: Option<u64>,
+    #[serde(default)]
+    pub conflict_mode: ConflictMode,
+    /// Client-supplied token stored in the commit's transaction properties. A write
+    /// whose key is already committed resolves to that version instead of re-applying.
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// How many of the latest versions are searched for an earlier commit of
+    /// `idempotency_key` before the write is staged; each one searched is a read
+    /// of that version's transaction file.
+    #[serde(default)]
+    pub idempotency_window: Option<u64>,
+}
+
+const DEFAULT_INITIAL_BACKOFF_MS: u64 = 50;
+const DEFAULT_MAX_BACKOFF_MS: u64 = 5_000;
+/// Retries when `max_retries` is not set, the same default lance uses for its own
+/// update and delete builders.
+const DEFAULT_CONFLICT_RETRIES: u32 = 10;
+/// Versions searched for an earlier commit of an idempotency key when
+/// `idempotency_window` is not set.
+pub const DEFAULT_IDEMPOTENCY_WINDOW: u64 = 100;
+
+impl WriteOptions {
+    /// Parses `write_options_json`; a null pointer yields the defaults.
+    pub fn parse(json: *const libc::c_char) -> Result<Self, String> {
+        if json.is_null() {
+            return Ok(Self::default());
+        }
+        let json = crate::ffi::to_string(json);
+        if json.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        sonic_rs::from_str(&json).map_err(|e| format!("Invalid write options: {}", e))
+    }
+
+    /// Errors when an idempotency key is given to a write that cannot record it.
+    pub fn reject_idempotency_key(&self, operation: &str) -> Result<(), String> {
+        match self.idempotency_key {
+            Some(_) => Err(format!("idempotency_key is not supported by {}", operation)),
+            None => Ok(()),
+        }
+    }
+
+    /// lancedb write options for an add in `mode`, carrying the idempotency key and
+    /// the commit metadata in the transaction properties. `None` when neither is set.
+    pub fn add_write_options(
+        &self,
+        mode: &AddDataMode,
+        commit_metadata: Option<&HashMap<String, String>>,
+    ) -> Option<lancedb::table::WriteOptions> {
+        let properties = self.transaction_properties(commit_metadata)?;
+        let params = lance::dataset::WriteParams {
+            mode: match mode {
+                AddDataMode::Overwrite => lance::dataset::WriteMode::Overwrite,
+                _ => lance::dataset::WriteMode::Append,
+            },
+            transaction_properties: Some(properties),
+            ..Default::default()
+        };
+        Some(lancedb::table::WriteOptions {
+            lance_write_params: Some(params),
+        })
+    }
+
+    /// Transaction properties carrying the idempotency key and the commit metadata,
+    /// or `None` when neither is set.
+    pub fn transaction_properties(
+        &self,
+        commit_metadata: Option<&HashMap<String, String>>,
+    ) -> Option<Arc<HashMap<String, String>>> {
+        if self.idempotency_key.is_none() && commit_metadata.is_none() {
+            return None;
+        }
+        let mut properties = commit_metadata.cloned().unwrap_or_default();
+        if let Some(key) = &self.idempotency_key {
+            properties.insert(IDEMPOTENCY_KEY_PROPERTY.to_string(), key.clone());
+        }
+        Some(Arc::new(properties))
+    }
+
+    /// How many times a conflicting write is re-run before the conflict is reported.
+    pub fn retries(&self) -> u32 {
+        match self.conflict_mode {
+            ConflictMode::Retry => self.max_retries.unwrap_or(DEFAULT_CONFLICT_RETRIES),
+            ConflictMode::FailFast => 0,
+        }
+    }
+
+    /// Exponential backoff before retry number `attempt` (1-based), capped at
+    /// `max_backoff_ms`.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let initial = self.initial_backoff_ms.unwrap_or(DEFAULT_INITIAL_BACKOFF_MS);
+        let max = self.max_backoff_ms.unwrap_or(DEFAULT_MAX_BACKOFF_MS);
+        let delay = initial.saturating_mul(1u64 << (attempt - 1).min(20));
+        Duration::from_millis(delay.min(max))
+    }
+}
+
+/// Returns `Some(version)` when the error is a commit conflict, where `version`
+/// is the concurrent version it conflicted with, when lance reports one.
+pub fn conflict_version(err: &lance::Error) -> Option<Option<u64>> {
+    match err {
+        lance::Error::CommitConflict { version, .. } | lance::Error::RetryableCommitConflict { version, .. } => {
+            Some(Some(*version))
+        }
+        lance::Error::IncompatibleTransaction { .. } | lance::Error::TooMuchWriteContention { .. } => Some(None),
+        _ => None,
+    }
+}
+
+/// A write that failed to commit, keeping conflicts apart from other errors so
+/// they can be reported with `ERROR_CODE_COMMIT_CONFLICT`.
+#[derive(Debug)]
+pub enum WriteError {
+    Conflict {
+        /// The concurrent version, when known.
+        version: Option<u64>,
+        attempts: u32,
+        message: String,
+    },
+    Other(String),
+}
+
+impl WriteError {
+    fn from_lance(err: lance::Error, attempts: u32) -> Self {
+        match conflict_version(&err) {
+            Some(version) => WriteError::Conflict {
+                version,
+                attempts,
+                message: err.to_string(),
+            },
+            None => WriteError::Other(err.to_string()),
+        }
+    }
+
+    fn from_lancedb(err: lancedb::Error, attempts: u32) -> Self {
+        match err {
+            lancedb::Error::Lance { source } => Self::from_lance(source, attempts),
+            other => WriteError::Other(other.to_string()),
+        }
+    }
+
+    /// Passes the error to `completion`, with error details for conflicts.
+    pub fn report(self, completion: crate::ffi::FfiCallback, user_data: crate::ffi::UserData) {
+        let details = match &self {
+            WriteError::Conflict { version, attempts, .. } => crate::ffi::FfiErrorDetails {
+                code: crate::ffi::ERROR_CODE_COMMIT_CONFLICT,
+                conflict_version: version.map_or(-1, |v| v as i64),
+                attempts: *attempts,
+            },
+            WriteError::Other(_) => crate::ffi::FfiErrorDetails::default(),
+        };
+        crate::ffi::callback_error_with_details(completion, user_data, details, self);
+    }
+}
+
+impl From<String> for WriteError {
+    fn from(err: String) -> Self {
+        WriteError::Other(err)
+    }
+}
+
+impl std::fmt::Display for WriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WriteError::Conflict { version, attempts, message } => {
+                let plural = if *attempts == 1 { "" } else { "s" };
+                match version {
+                    Some(v) => write!(
+                        f,
+                        "Commit conflict with version {} after {} attempt{}: {}",
+                        v, attempts, plural, message
+                    ),
+                    None => write!(f, "Commit conflict after {} attempt{}: {}", attempts, plural, message),
+                }
+            }
+            WriteError::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+/// Runs a lancedb write, re-running it on commit conflicts as `options` allow.
+pub async fn with_conflict_retries<T, F, Fut>(options: &WriteOptions, mut op: F) -> Result<T, WriteError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = lancedb::Result<T>>,
+{
+    let retries = options.retries();
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) => match WriteError::from_lancedb(e, attempt) {
+                WriteError::Conflict { .. } if attempt <= retries => {
+                    tokio::time::sleep(options.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                e => return Err(e),
+            },
+        }
+    }
+}
+
+/// A write prepared against one version of a dataset but not yet committed.
+pub(crate) struct StagedWrite<T> {
+    pub transaction: lance::dataset::transaction::Transaction,
+    /// Addresses of the existing rows the write changes, used to detect conflicts
+    /// with concurrent writes to the same rows.
+    pub affected_rows: Vec<u64>,
+    pub output: T,
+}
+
+/// Outcome of `commit_staged`.
+pub(crate) enum Committed<T> {
+    /// The write was committed as the version of `dataset`.
+    Written {
+        dataset: Arc<lance::Dataset>,
+        /// Commit attempts made, including the successful one.
+        attempts: u32,
+        output: T,
+    },
+    /// A write with the same idempotency key was already committed as `version`,
+    /// so nothing was written.
+    Replayed { version: u64 },
+}
+
+impl<T> Committed<T> {
+    pub fn version(&self) -> u64 {
+        match self {
+            Committed::Written { dataset, .. } => dataset.manifest.version,
+            Committed::Replayed { version } => *version,
+        }
+    }
+
+    /// The committed version and the staged output, which is `None` for a replay.
+    pub fn into_version_and_output(self) -> (u64, Option<T>) {
+        match self {
+            Committed::Written { dataset, output, .. } => (dataset.manifest.version, Some(output)),
+            Committed::Replayed { version } => (version, None),
+        }
+    }
+}
+
+/// Stages a write with `stage` against the latest version of a local table and
+/// commits it, staging it again on the new latest version after a conflict, as
+/// `options` allow.
+///
+/// With an idempotency key, the latest `idempotency_window` versions are searched
+/// for the key before the first attempt, and the commit itself refuses to land on
+/// top of any later version carrying the key (see `IdempotentCommitHandler`), so
+/// concurrent writes with the same key commit at most once.
+pub(crate) async fn commit_staged<T, F, Fut>(
+    table: &Table,
+    options: &WriteOptions,
+    commit_metadata: Option<&HashMap<String, String>>,
+    mut stage: F,
+) -> Result<Committed<T>, WriteError>
+where
+    F: FnMut(Arc<lance::Dataset>) -> Fut,
+    Fut: Future<Output = Result<StagedWrite<T>, String>>,
+{
+    if table.as_native().is_none() {
+        return Err("Write options and commit metadata are only supported for local tables".to_string().into());
+    }
+    let properties = options.transaction_properties(commit_metadata);
+    let retries = options.retries();
+    let mut dataset = Arc::new(open_dataset(table).await?);
+    let handler = match &options.idempotency_key {
+        Some(key) => {
+            let window = options.idempotency_window.unwrap_or(DEFAULT_IDEMPOTENCY_WINDOW);
+            if let Some(version) = find_recent_key(&dataset, key, window).await.map_err(|e| e.to_string())? {
+                return Ok(Committed::Replayed { version });
+            }
+            Some(Arc::new(IdempotentCommitHandler::try_new(table, &dataset, key).await?))
+        }
+        None => None,
+    };
+    let mut attempt = 1;
+    loop {
+        let staged = stage(dataset.clone()).await?;
+        let mut transaction = staged.transaction;
+        transaction.transaction_properties = properties.clone();
+        let mut builder = lance::dataset::CommitBuilder::new(dataset.clone());
+        if !staged.affected_rows.is_empty() {
+            builder = builder.with_affected_rows(staged.affected_rows.iter().copied().collect());
+        }
+        if let Some(handler) = &handler {
+            builder = builder.with_commit_handler(handler.clone());
+        }
+        match builder.execute(transaction).await {
+            Ok(committed) => {
+                table.checkout_latest().await.map_err(|e| e.to_string())?;
+                return Ok(Committed::Written {
+                    dataset: Arc::new(committed),
+                    attempts: attempt,
+                    output: staged.output,
+                });
+            }
+            Err(e) => {
+                if let Some(version) = handler.as_ref().and_then(|h| h.committed_version()) {
+                    return Ok(Committed::Replayed { version });
+                }
+                match WriteError::from_lance(e, attempt) {
+                    WriteError::Conflict { .. } if attempt <= retries => {
+                        tokio::time::sleep(options.backoff(attempt)).await;
+                        let mut latest = dataset.as_ref().clone();
+                        latest.checkout_latest().await.map_err(|e| e.to_string())?;
+                        dataset = Arc::new(latest);
+                        attempt += 1;
+                    }
+                    e => return Err(e),
+                }
+            }
+        }
+    }
+}
+
+/// The version among `versions` (newest first) whose commit recorded `key` as its
+/// idempotency key. Stops at the first version removed by cleanup.
+async fn find_key_in(
+    dataset: &lance::Dataset,
+    key: &str,
+    versions: std::ops::Range<u64>,
+) -> lance::Result<Option<u64>> {
+    for version in versions.rev() {
+        let transaction = match dataset.read_transaction_by_version(version).await {
+            Ok(transaction) => transaction,
+            Err(lance::Error::DatasetNotFound { .. } | lance::Error::NotFound { .. }) => break,
+            Err(e) => return Err(e),
+        };
+        let committed_key = transaction
+            .as_ref()
+            .and_then(|t| t.transaction_properties.as_ref())
+            .and_then(|p| p.get(IDEMPOTENCY_KEY_PROPERTY));
+        if committed_key.is_some_and(|k| k == key) {
+            return Ok(Some(version));
+        }
+    }
+    Ok(None)
+}
+
+/// Searches the latest `window` versions of `dataset` for a commit of `key`.
+/// Each version searched reads its transaction file, newest first, so a keyed
+/// write whose key is new costs up to `window` small reads before it is staged.
+/// Lower `idempotency_window` to bound that cost on tables with frequent commits.
+async fn find_recent_key(dataset: &lance::Dataset, key: &str, window: u64) -> lance::Result<Option<u64>> {
+    let latest = dataset.manifest.version;
+    find_key_in(dataset, key, latest.saturating_sub(window).max(1)..latest + 1).await
+}
+
+/// Returns the version whose commit recorded `key` as its idempotency key, searching
+/// the latest `window` versions of a local table.
+pub async fn find_idempotency_key(table: &Table, key: &str, window: u64) -> Result<Option<u64>, String> {
+    if table.as_native().is_none() {
+        return Err("Idempotency keys are only supported for local tables".to_string());
+    }
+    let dataset = open_dataset(table).await?;
+    find_recent_key(&dataset, key, window).await.map_err(|e| e.to_string())
+}
+
+/// Commit handler for writes with an idempotency key. Lance calls `commit` once it
+/// has rebased the write onto every version before the one it is writing, and the
+/// inner handler only writes that version if no other writer took it first, so
+/// checking the versions in between for the key here leaves no window for a
+/// concurrent write with the same key.
+struct IdempotentCommitHandler {
+    inner: Arc<dyn CommitHandler>,
+    dataset: lance::Dataset,
+    key: String,
+    /// Versions up to this one are known not to carry the key.
+    checked: AtomicU64,
+    /// The version found to carry the key, or 0.
+    committed: AtomicU64,
+}
+
+impl IdempotentCommitHandler {
+    /// Wraps the commit handler lance resolves for the table's uri. `dataset` has
+    /// already been searched for the key up to its version.
+    async fn try_new(table: &Table, dataset: &Arc<lance::Dataset>, key: &str) -> Result<Self, String> {
+        let uri = table.uri().await.map_err(|e| e.to_string())?;
+        let params = object_store_params(table).await?;
+        let inner = commit_handler_from_url(&uri, &params).await.map_err(|e| e.to_string())?;
+        Ok(Self {
+            inner,
+            dataset: dataset.as_ref().clone(),
+            key: key.to_string(),
+            checked: AtomicU64::new(dataset.manifest.version),
+            committed: AtomicU64::new(0),
+        })
+    }
+
+    fn committed_version(&self) -> Option<u64> {
+        match self.committed.load(Ordering::SeqCst) {
+            0 => None,
+            version => Some(version),
+        }
+    }
+}
+
+impl std::fmt::Debug for IdempotentCommitHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdempotentCommitHandler")
+            .field("inner", &self.inner)
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl CommitHandler for IdempotentCommitHandler {
+    async fn resolve_latest_location(
+        &self,
+        base_path: &Path,
+        object_store: &lance::io::ObjectStore,
+    ) -> lance::Result<ManifestLocation> {
+        self.inner.resolve_latest_location(base_path, object_store).await
+    }
+
+    async fn resolve_version_location(
+        &self,
+        base_path: &Path,
+        version: u64,
+        object_store: &dyn object_store::ObjectStore,
+    ) -> lance::Result<ManifestLocation> {
+        self.inner.resolve_version_location(base_path, version, object_store).await
+    }
+
+    async fn version_exists(
+        &self,
+        base_path: &Path,
+        version: u64,
+        object_store: &dyn object_store::ObjectStore,
+        naming_scheme: ManifestNamingScheme,
+    ) -> lance::Result<bool> {
+        self.inner.version_exists(base_path, version, object_store, naming_scheme).await
+    }
+
+    fn list_detached_manifest_locations<'a>(
+        &self,
+        base_path: &Path,
+        object_store: &'a lance::io::ObjectStore,
+    ) -> BoxStream<'a, lance::Result<ManifestLocation>> {
+        self.inner.list_detached_manifest_locations(base_path, object_store)
+    }
+
+    fn list_manifest_locations<'a>(
+        &self,
+        base_path: &Path,
+        object_store: &'a lance::io::ObjectStore,
+        sorted_descending: bool,
+    ) -> BoxStream<'a, lance::Result<ManifestLocation>> {
+        self.inner.list_manifest_locations(base_path, object_store, sorted_descending)
+    }
+
+    fn list_manifest_locations_since<'a>(
+        &self,
+        base_path: &Path,
+        object_store: &'a lance::io::ObjectStore,
+        since_version: u64,
+    ) -> BoxStream<'a, lance::Result<ManifestLocation>> {
+        self.inner.list_manifest_locations_since(base_path, object_store, since_version)
+    }
+
+    async fn commit(
+        &self,
+        manifest: &mut Manifest,
+        indices: Option<Vec<IndexMetadata>>,
+        base_path: &Path,
+        object_store: &lance::io::ObjectStore,
+        manifest_writer: ManifestWriter,
+        naming_scheme: ManifestNamingScheme,
+        transaction: Option<TableTransaction>,
+    ) -> Result<ManifestLocation, CommitError> {
+        let from = self.checked.load(Ordering::SeqCst) + 1;
+        if let Some(version) = find_key_in(&self.dataset, &self.key, from..manifest.version).await? {
+            self.committed.store(version, Ordering::SeqCst);
+            return Err(CommitError::OtherError(lance::Error::invalid_input(format!(
+                "Idempotency key '{}' was committed by version {}",
+                self.key, version
+            ))));
+        }
+        self.checked.fetch_max(manifest.version - 1, Ordering::SeqCst);
+        self.inner
+            .commit(manifest, indices, base_path, object_store, manifest_writer, naming_scheme, transaction)
+            .await
+    }
+
+    async fn delete(&self, base_path: &Path) -> lance::Result<()> {
+        self.inner.delete(base_path).await
+    }
+}
+
+/// Outcome of `add_batches`.
+pub(crate) struct Added {
+    pub version: u64,
+    /// Whether an add with the same idempotency key was already committed as
+    /// `version`, so nothing was written.
+    pub replayed: bool,
+}
+
+/// Adds `batches` through lancedb's add in `mode`, re-running it on commit conflicts
+/// as `options` allow. The idempotency key and `commit_metadata` are committed in
+/// the transaction properties; a key already committed in the idempotency window
+/// resolves to that version without writing, and a concurrent commit of the same
+/// key is caught at commit time by `IdempotentCommitHandler`.
+pub(crate) async fn add_batches(
+    table: &Table,
+    batches: Vec<RecordBatch>,
+    mode: AddDataMode,
+    options: &WriteOptions,
+    commit_metadata: Option<&HashMap<String, String>>,
+) -> Result<Added, WriteError> {
+    let mut write_options = options.add_write_options(&mode, commit_metadata);
+    let handler = match &options.idempotency_key {
+        Some(key) => {
+            if table.as_native().is_none() {
+                return Err("Idempotency keys are only supported for local tables".to_string().into());
+            }
+            let dataset = Arc::new(open_dataset(table).await?);
+            let window = options.idempotency_window.unwrap_or(DEFAULT_IDEMPOTENCY_WINDOW);
+            if let Some(version) = find_recent_key(&dataset, key, window).await.map_err(|e| e.to_string())? {
+                return Ok(Added { version, replayed: true });
+            }
+            let handler = Arc::new(IdempotentCommitHandler::try_new(table, &dataset, key).await?);
+            if let Some(params) = write_options.as_mut().and_then(|o| o.lance_write_params.as_mut()) {
+                params.commit_handler = Some(handler.clone());
+            }
+            Some(handler)
+        }
+        None => None,
+    };
+    let result = with_conflict_retries(options, || {
+        let mut builder = table.add(batches.clone()).mode(mode.clone());
+        if let Some(write_options) = &write_options {
+            builder = builder.write_options(write_options.clone());
+        }
+        builder.execute()
+    })
+    .await;
+    match result {
+        Ok(result) => Ok(Added { version: result.version, replayed: false }),
+        Err(e) => match handler.as_ref().and_then(|h| h.committed_version()) {
+            Some(version) => Ok(Added { version, replayed: true }),
+            None => Err(e),
+        },
+    }
+}
+
+/// The rows of `dataset` matching `filter` (all rows when `None`), with their
+/// `_rowaddr` column.
+async fn matching_rows(
+    dataset: &Arc<lance::Dataset>,
+    filter: Option<&str>,
+) -> Result<datafusion::prelude::DataFrame, String> {
+    let ctx = datafusion::prelude::SessionContext::new();
+    let provider = Arc::new(lance::datafusion::LanceTableProvider::new(dataset.clone(), false, true));
+    let df = ctx.read_table(provider).map_err(|e| e.to_string())?;
+    match filter {
+        Some(filter) => {
+            let predicate = df
+                .parse_sql_expr(&format!("COALESCE(({}), FALSE)", filter))
+                .map_err(|e| e.to_string())?;
+            df.filter(predicate).map_err(|e| e.to_string())
+        }
+        None => Ok(df),
+    }
+}
+
+fn row_addresses(batches: &[RecordBatch]) -> Result<Vec<u64>, String> {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::UInt64Type;
+
+    let mut addresses = Vec::new();
+    for batch in batches {
+        let column = batch
+            .column_by_name(ROW_ADDR)
+            .ok_or_else(|| "Scanned rows are missing their row addresses".to_string())?;
+        addresses.extend(column.as_primitive::<UInt64Type>().values().iter().copied());
+    }
+    Ok(addresses)
+}
+
+/// Stages deleting the rows matching `predicate`. The output is the number of
+/// rows deleted.
+pub(crate) async fn stage_delete(
+    dataset: Arc<lance::Dataset>,
+    predicate: &str,
+) -> Result<StagedWrite<u64>, String> {
+    let batches = matching_rows(&dataset, Some(predicate))
+        .await?
+        .select_columns(&[ROW_ADDR])
+        .map_err(|e| e.to_string())?
+        .collect()
+        .await
+        .map_err(|e| e.to_string())?;
+    let addresses = row_addresses(&batches)?;
+    let (updated_fragments, deleted_fragment_ids) = crate::table::delete_addresses(&dataset, &addresses).await?;
+    let operation = lance::dataset::transaction::Operation::Delete {
+        updated_fragments,
+        deleted_fragment_ids,
+        predicate: predicate.to_string(),
+    };
+    Ok(StagedWrite {
+        transaction: lance::dataset::transaction::Transaction::new(dataset.manifest.version, operation, None),
+        output: addresses.len() as u64,
+        affected_rows: addresses,
+    })
+}
+
+/// Stages setting each `(column, expr)` of `columns` on the rows matching `filter`
+/// (all rows when `None`), with the expressions evaluated against the old values.
+/// The matching rows are streamed from a lance scan into new fragments, batch by
+/// batch, as lance's own update does. The output is the number of rows updated.
+pub(crate) async fn stage_update(
+    dataset: Arc<lance::Dataset>,
+    filter: Option<&str>,
+    columns: &[(String, String)],
+) -> Result<StagedWrite<u64>, String> {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::UInt64Type;
+    use datafusion::error::DataFusionError;
+    use futures::TryStreamExt;
+
+    let schema = Arc::new(arrow_schema::Schema::from(dataset.schema()));
+    if columns.is_empty() {
+        return Err("No updates provided".to_string());
+    }
+    for (column, _) in columns {
+        if schema.field_with_name(column).is_err() {
+            return Err(format!("Column '{}' does not exist in the table schema", column));
+        }
+    }
+    let exprs: Vec<(String, String)> = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let sql = match columns.iter().find(|(c, _)| c == field.name()) {
+                Some((_, expr)) => format!("({})", expr),
+                None => crate::query::quote_identifier(field.name()),
+            };
+            (field.name().clone(), sql)
+        })
+        .collect();
+
+    let mut scanner = dataset.scan();
+    if let Some(filter) = filter {
+        scanner.filter(filter).map_err(|e| e.to_string())?;
+    }
+    scanner.with_row_address();
+    let scanned = scanner.try_into_stream().await.map_err(|e| e.to_string())?;
+
+    let addresses = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let collected = addresses.clone();
+    let ctx = datafusion::prelude::SessionContext::new();
+    let target = schema.clone();
+    let updated = scanned.map_err(|e| DataFusionError::External(Box::new(e))).and_then(move |mut batch| {
+        let ctx = ctx.clone();
+        let exprs = exprs.clone();
+        let target = target.clone();
+        let collected = collected.clone();
+        async move {
+            let index = batch.schema().index_of(ROW_ADDR)?;
+            let column = batch.remove_column(index);
+            collected.lock().unwrap().extend(column.as_primitive::<UInt64Type>().values().iter().copied());
+            let values = select_expressions(&ctx, batch, &exprs)
+                .map_err(DataFusionError::Execution)?
+                .collect()
+                .await?;
+            let values = arrow_select::concat::concat_batches(&target, &values)?;
+            crate::table::cast_to_schema(&values, &target).map_err(DataFusionError::Execution)
+        }
+    });
+    let rows = Box::pin(datafusion::physical_plan::stream::RecordBatchStreamAdapter::new(schema, updated));
+    let new_fragments = write_fragments(&dataset, rows).await?;
+
+    let addresses = std::mem::take(&mut *addresses.lock().unwrap());
+    let modified: Vec<String> = columns.iter().map(|(column, _)| column.clone()).collect();
+    let operation = rewrite_rows(&dataset, &addresses, new_fragments, &modified).await?;
+    Ok(StagedWrite {
+        transaction: lance::dataset::transaction::Transaction::new(dataset.manifest.version, operation, None),
+        output: addresses.len() as u64,
+        affected_rows: addresses,
+    })
+}
+
+/// Writes `rows` to new fragments of `dataset` without committing them.
+pub(crate) async fn write_fragments(
+    dataset: &Arc<lance::Dataset>,
+    rows: datafusion::execution::SendableRecordBatchStream,
+) -> Result<Vec<lance::table::format::Fragment>, String> {
+    let params = lance::dataset::WriteParams {
+        mode: lance::dataset::WriteMode::Append,
+        ..Default::default()
+    };
+    let written = lance::dataset::InsertBuilder::new(dataset.clone())
+        .with_params(&params)
+        .execute_uncommitted_stream(rows)
+        .await
+        .map_err(|e| e.to_string())?;
+    match written.operation {
+        lance::dataset::transaction::Operation::Append { fragments } => Ok(fragments),
+        _ => Err("Writing the new rows did not produce an append".to_string()),
+    }
+}
+
+/// Writes `rows` to new fragments of the table `dataset` was read from, with the
+/// field ids of `schema` rather than those of the dataset, for a commit that also
+/// changes the schema. The fragments are numbered after the dataset's last one.
+pub(crate) async fn write_fragments_with_schema(
+    table: &Table,
+    dataset: &lance::Dataset,
+    schema: &lance::datatypes::Schema,
+    rows: datafusion::execution::SendableRecordBatchStream,
+) -> Result<Vec<lance::table::format::Fragment>, String> {
+    let params = lance::dataset::WriteParams {
+        store_params: object_store_params(table).await?,
+        ..Default::default()
+    };
+    let mut fragments = lance::dataset::fragment::write::FragmentCreateBuilder::new(dataset.uri())
+        .schema(schema)
+        .write_params(&params)
+        .write_fragments(rows)
+        .await
+        .map_err(|e| e.to_string())?;
+    let first_id = dataset.manifest.max_fragment_id().map_or(0, |id| id as u64 + 1);
+    for (i, fragment) in fragments.iter_mut().enumerate() {
+        fragment.id = first_id + i as u64;
+    }
+    Ok(fragments)
+}
+
+/// Builds the operation that replaces the rows at `addresses` of `dataset` with
+/// the rows written to `new_fragments` (see `write_fragments`). `modified_columns`
+/// are the columns whose values the rewrite may change; indices on the other
+/// columns keep covering the new fragments.
+pub(crate) async fn rewrite_rows(
+    dataset: &Arc<lance::Dataset>,
+    addresses: &[u64],
+    new_fragments: Vec<lance::table::format::Fragment>,
+    modified_columns: &[String],
+) -> Result<lance::dataset::transaction::Operation, String> {
+    use lance::dataset::transaction::{Operation, UpdateMode};
+
+    let fields_for_preserving_frag_bitmap = dataset
+        .schema()
+        .fields
+        .iter()
+        .filter(|f| !modified_columns.contains(&f.name))
+        .map(|f| f.id as u32)
+        .collect();
+    let (updated_fragments, removed_fragment_ids) = crate::table::delete_addresses(dataset, addresses).await?;
+    Ok(Operation::Update {
+        removed_fragment_ids,
+        updated_fragments,
+        new_fragments,
+        fields_modified: Vec::new(),
+        merged_generations: Vec::new(),
+        fields_for_preserving_frag_bitmap,
+        update_mode: Some(UpdateMode::RewriteRows),
+        inserted_rows_filter: None,
+        updated_fragment_offsets: None,
+    })
+}
+
+/// Stages adding the columns of `columns` (all nullable) without writing any data,
+/// as lance does for all-null columns.
+pub(crate) async fn stage_add_null_columns(
+    dataset: Arc<lance::Dataset>,
+    columns: &arrow_schema::Schema,
+) -> Result<StagedWrite<()>, String> {
+    if let Some(field) = columns.fields().iter().find(|f| !f.is_nullable()) {
+        return Err(format!("All-null column '{}' must be nullable", field.name()));
+    }
+    let schema = merged_schema(&dataset, columns)?;
+    let operation = lance::dataset::transaction::Operation::Merge {
+        fragments: dataset.fragments().as_ref().clone(),
+        schema,
+    };
+    Ok(StagedWrite {
+        transaction: lance::dataset::transaction::Transaction::new(dataset.manifest.version, operation, None),
+        affected_rows: Vec::new(),
+        output: (),
+    })
+}
+
+/// Stages adding a column for each `(name, expr)` of `columns`, with the SQL
+/// expressions evaluated over the existing columns of every fragment.
+pub(crate) async fn stage_add_columns(
+    dataset: Arc<lance::Dataset>,
+    columns: &[(String, String)],
+) -> Result<StagedWrite<()>, String> {
+    let (fragments, schema) = add_columns_to_fragments(&dataset, columns).await?;
+    let operation = lance::dataset::transaction::Operation::Merge { fragments, schema };
+    Ok(StagedWrite {
+        transaction: lance::dataset::transaction::Transaction::new(dataset.manifest.version, operation, None),
+        affected_rows: Vec::new(),
+        output: (),
+    })
+}
+
+/// Writes a column for each `(name, expr)` of `columns` to every fragment of
+/// `dataset`, returning the updated fragments and the schema with the new columns.
+/// An expression may refer to the columns added before it.
+pub(crate) async fn add_columns_to_fragments(
+    dataset: &lance::Dataset,
+    columns: &[(String, String)],
+) -> Result<(Vec<lance::table::format::Fragment>, lance::datatypes::Schema), String> {
+    if columns.is_empty() {
+        return Err("No columns provided".to_string());
+    }
+    let table_schema = Arc::new(arrow_schema::Schema::from(dataset.schema()));
+    let ctx = datafusion::prelude::SessionContext::new();
+    // Planning over an empty batch gives the types of the new columns.
+    let planned = add_expressions(&ctx, RecordBatch::new_empty(table_schema.clone()), columns)?;
+    let new_columns = Arc::new(planned.schema().as_arrow().clone());
+    let schema = merged_schema(dataset, &new_columns)?;
+    let names: Vec<&str> = columns.iter().map(|(name, _)| name.as_str()).collect();
+    let write_schema = schema.project(&names).map_err(|e| e.to_string())?;
+    let read_columns: Vec<String> = table_schema.fields().iter().map(|f| f.name().clone()).collect();
+
+    let mut fragments = Vec::new();
+    for fragment in dataset.get_fragments() {
+        let mut updater = fragment
+            .updater(Some(&read_columns), Some((write_schema.clone(), schema.clone())), None)
+            .await
+            .map_err(|e| e.to_string())?;
+        while let Some(batch) = updater.next().await.map_err(|e| e.to_string())? {
+            let values = add_expressions(&ctx, batch.clone(), columns)?
+                .collect()
+                .await
+                .map_err(|e| e.to_string())?;
+            let values = arrow_select::concat::concat_batches(&new_columns, &values).map_err(|e| e.to_string())?;
+            updater.update(values).await.map_err(|e| e.to_string())?;
+        }
+        fragments.push(updater.finish().await.map_err(|e| e.to_string())?);
+    }
+    Ok((fragments, schema))
+}
+
+/// Selects each `(name, expr)` of `columns` over the rows of `batch`, with every
+/// expression evaluated against the values in `batch`.
+fn select_expressions(
+    ctx: &datafusion::prelude::SessionContext,
+    batch: RecordBatch,
+    columns: &[(String, String)],
+) -> Result<datafusion::prelude::DataFrame, String> {
+    let df = ctx.read_batch(batch).map_err(|e| e.to_string())?;
+    let exprs = columns
+        .iter()
+        .map(|(name, expr)| Ok(df.parse_sql_expr(expr).map_err(|e| e.to_string())?.alias(name)))
+        .collect::<Result<Vec<_>, String>>()?;
+    df.select(exprs).map_err(|e| e.to_string())
+}
+
+/// Selects each `(name, expr)` of `columns` over the rows of `batch`, adding the
+/// columns in order so that an expression can refer to the ones before it.
+fn add_expressions(
+    ctx: &datafusion::prelude::SessionContext,
+    batch: RecordBatch,
+    columns: &[(String, String)],
+) -> Result<datafusion::prelude::DataFrame, String> {
+    let mut df = ctx.read_batch(batch).map_err(|e| e.to_string())?;
+    for (name, expr) in columns {
+        let expr = df.parse_sql_expr(expr).map_err(|e| e.to_string())?;
+        df = df.with_column(name, expr).map_err(|e| e.to_string())?;
+    }
+    let names: Vec<&str> = columns.iter().map(|(name, _)| name.as_str()).collect();
+    df.select_columns(&names).map_err(|e| e.to_string())
+}
+
+/// The schema of `dataset` with `columns` appended, given fresh field ids.
+pub(crate) fn merged_schema(
+    dataset: &lance::Dataset,
+    columns: &arrow_schema::Schema,
+) -> Result<lance::datatypes::Schema, String> {
+    let mut schema = dataset.schema().merge(columns).map_err(|e| e.to_string())?;
+    schema.set_field_id(Some(dataset.manifest.max_field_id()));
+    Ok(schema)
+}
+
+/// Stages renaming columns and relaxing their nullability, which only changes
+/// the schema. The FFI alterations never carry a type change.
+pub(crate) async fn stage_alter_columns(
+    dataset: Arc<lance::Dataset>,
+    alterations: &[lancedb::table::ColumnAlteration],
+) -> Result<StagedWrite<()>, String> {
+    let mut schema = dataset.schema().clone();
+    for alteration in alterations {
+        let field = dataset
+            .schema()
+            .field(&alteration.path)
+            .ok_or_else(|| format!("Column '{}' does not exist in the table schema", alteration.path))?;
+        if alteration.data_type.is_some() {
+            return Err("Changing a column's type is not supported".to_string());
+        }
+        let altered = schema
+            .mut_field_by_id(field.id)
+            .ok_or_else(|| format!("Column '{}' does not exist in the table schema", alteration.path))?;
+        if let Some(name) = &alteration.rename {
+            altered.name = name.clone();
+        }
+        if let Some(nullable) = alteration.nullable {
+            if field.nullable && !nullable {
+                return Err(format!("Column '{}' cannot be made non-nullable", alteration.path));
+            }
+            altered.nullable = nullable;
+        }
+    }
+    schema.validate().map_err(|e| e.to_string())?;
+    Ok(project_schema(&dataset, schema))
+}
+
+/// Stages dropping `columns`, which only changes the schema.
+pub(crate) async fn stage_drop_columns(
+    dataset: Arc<lance::Dataset>,
+    columns: &[String],
+) -> Result<StagedWrite<()>, String> {
+    if let Some(missing) = columns.iter().find(|c| dataset.schema().field(c).is_none()) {
+        return Err(format!("Column '{}' does not exist in the table schema", missing));
+    }
+    let dropped = dataset.schema().project(columns).map_err(|e| e.to_string())?;
+    let schema = dataset.schema().exclude(dropped).map_err(|e| e.to_string())?;
+    if schema.fields.is_empty() {
+        return Err("Cannot drop all columns from a table".to_string());
+    }
+    Ok(project_schema(&dataset, schema))
+}
+
+fn project_schema(dataset: &lance::Dataset, schema: lance::datatypes::Schema) -> StagedWrite<()> {
+    let operation = lance::dataset::transaction::Operation::Project { schema };
+    StagedWrite {
+        transaction: lance::dataset::transaction::Transaction::new(dataset.manifest.version, operation, None),
+        affected_rows: Vec::new(),
+        output: (),
+    }
+}
+
+/// Parses `commit_metadata_json`, a JSON object with string values; null yields `None`.
+pub fn parse_commit_metadata(json: *const libc::c_char) -> Result<Option<HashMap<String, String>>, String> {
+    if json.is_null() {
+        return Ok(None);
+    }
+    match crate::ffi::parse_optional_json_map(json) {
+        Some(map) => Ok(Some(map)),
+        None => Err("commit_metadata_json must be a JSON object with string values".to_string()),
+    }
+}
+
+pub(crate) async fn storage_options(table: &Table) -> Result<Option<HashMap<String, String>>, String> {
+    table.latest_storage_options().await.map_err(|e| e.to_string())
+}
+
+/// Object store parameters carrying the table's storage options, if it has any.
+async fn object_store_params(table: &Table) -> Result<Option<lance::io::ObjectStoreParams>, String> {
+    Ok(storage_options(table).await?.map(|options| lance::io::ObjectStoreParams {
+        storage_options_accessor: Some(Arc::new(lance::io::StorageOptionsAccessor::with_static_options(options))),
+        ..Default::default()
+    }))
+}
+
+/// Opens the lance dataset behind a local table, at its latest version.
+pub(crate) async fn open_dataset(table: &Table) -> Result<lance::Dataset, String> {
+    let uri = table.uri().await.map_err(|e| e.to_string())?;
+    let mut builder = lance::dataset::builder::DatasetBuilder::from_uri(&uri);
+    if let Some(options) = storage_options(table).await? {
+        builder = builder.with_storage_options(options);
+    }
+    builder.load().await.map_err(|e| e.to_string())
+}
+
+/// Audit details of one table version, reported by `table_list_versions`.
+pub struct VersionDetails {
+    /// Name of the operation that created the version, e.g. "Append" or "Delete".
+    pub operation: Option<String>,
+    pub num_rows: u64,
+    /// Change in row count from the previous listed version.
+    pub num_rows_delta: i64,
+    /// Metadata attached by the writer in the commit's transaction properties.
+    pub commit_metadata: HashMap<String, String>,
+}
+
+/// Collects `VersionDetails` for `versions` of a local table; remote tables yield an
+/// empty map. Each version costs a manifest and a transaction read (row counts come
+/// from the fragment metadata), so the cost grows with the number of versions listed.
+pub async fn version_details(table: &Table, versions: &[u64]) -> Result<HashMap<u64, VersionDetails>, String> {
+    use futures::{StreamExt, TryStreamExt};
+
+    if table.as_native().is_none() {
+        return Ok(HashMap::new());
+    }
+    let dataset = open_dataset(table).await?;
+    let mut versions = versions.to_vec();
+    versions.sort_unstable();
+    let read = |version: u64| {
+        let dataset = &dataset;
+        async move {
+            let at_version = dataset.checkout_version(version).await?;
+            let num_rows = at_version.count_rows(None).await? as u64;
+            let transaction = at_version.read_transaction().await?;
+            lance::Result::Ok((version, num_rows, transaction))
+        }
+    };
+    let read: Vec<_> = futures::stream::iter(versions.iter().copied().map(read))
+        .buffered(num_cpus::get().max(1))
+        .try_collect()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut details = HashMap::new();
+    let mut previous_rows: Option<u64> = None;
+    for (version, num_rows, transaction) in read {
+        let commit_metadata = transaction
+            .as_ref()
+            .and_then(|t| t.transaction_properties.as_ref())
+            .map(|p| p.as_ref().clone())
+            .unwrap_or_default();
+        details.insert(
+            version,
+            VersionDetails {
+                operation: transaction.map(|t| t.operation.name().to_string()),
+                num_rows,
+                num_rows_delta: num_rows as i64 - previous_rows.unwrap_or(0) as i64,
+                commit_metadata,
+            },
+        );
+        previous_rows = Some(num_rows);
+    }
+    Ok(details)
+}
@@ -196,3 +196,332 @@ fn test_connection_create_table_with_data_returns_table() {
     table_close(table_ptr);
     connection_close(conn_ptr);
 }
+
+fn create_table_raw(
+    conn_ptr: *const lancedb::connection::Connection,
+    name: &str,
+    batch: RecordBatch,
+    target_schema: Option<&Schema>,
+    create_options_json: &str,
+) -> (*const std::ffi::c_void, *const libc::c_char) {
+    use arrow_array::Array;
+    let struct_array: arrow_array::StructArray = batch.into();
+    let data = struct_array.to_data();
+    let mut ffi_array = arrow_data::ffi::FFI_ArrowArray::new(&data);
+    let mut ffi_schema = arrow_schema::ffi::FFI_ArrowSchema::try_from(data.data_type()).unwrap();
+    let mut ffi_target = target_schema.map(|s| arrow_schema::ffi::FFI_ArrowSchema::try_from(s).unwrap());
+    let target_ptr = ffi_target.as_mut().map_or(std::ptr::null_mut(), |s| s as *mut _);
+    let table_name = std::ffi::CString::new(name).unwrap();
+    let create_options = std::ffi::CString::new(create_options_json).unwrap();
+
+    let ctx = common::FfiTestContext::new();
+    connection_create_table_with_options(
+        conn_ptr,
+        table_name.as_ptr(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        std::ptr::null(),
+        std::ptr::null(),
+        std::ptr::null(),
+        std::ptr::null(),
+        false,
+        target_ptr,
+        create_options.as_ptr(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    ctx.wait_raw()
+}
+
+fn vector_batch(vectors: Vec<Option<Vec<Option<f64>>>>) -> RecordBatch {
+    use arrow_array::Array;
+    let ids: Vec<i32> = (0..vectors.len() as i32).collect();
+    let vectors = arrow_array::ListArray::from_iter_primitive::<arrow_array::types::Float64Type, _, _>(vectors);
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("vector", vectors.data_type().clone(), true),
+    ]));
+    RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(ids)), Arc::new(vectors)]).unwrap()
+}
+
+#[test]
+fn test_connection_create_table_casts_into_target_schema_with_metadata() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = vector_batch(vec![Some(vec![Some(1.0), Some(2.0)]), Some(vec![Some(3.0), Some(4.0)])]);
+    let target = Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new(
+            "vector",
+            DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float32, true)), 2),
+            true,
+        ),
+        Field::new("label", DataType::Utf8, true),
+    ]);
+    let (result, error) = create_table_raw(
+        conn_ptr,
+        "create_target_ffi",
+        batch,
+        Some(&target),
+        r#"{"vector_columns":{"vector":2},"metadata":{"owner":"search"}}"#,
+    );
+    assert!(error.is_null());
+    let table_ptr = result as *const lancedb::table::Table;
+
+    unsafe { Arc::increment_strong_count(table_ptr) };
+    let table = unsafe { Arc::from_raw(table_ptr) };
+    let schema = tokio::runtime::Runtime::new().unwrap().block_on(table.schema()).unwrap();
+    assert_eq!(schema.field_with_name("id").unwrap().data_type(), &DataType::Int64);
+    assert_eq!(schema.field_with_name("vector").unwrap().data_type(), target.field(1).data_type());
+    assert!(schema.field_with_name("label").unwrap().is_nullable());
+    assert_eq!(schema.metadata().get("owner").map(String::as_str), Some("search"));
+    assert_eq!(common::count_rows_sync(table_ptr, Some("label IS NULL".into())), 2);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_connection_create_table_rejects_wrong_vector_dimension() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = vector_batch(vec![Some(vec![Some(1.0), Some(2.0)]), Some(vec![Some(3.0)])]);
+    let (result, error) =
+        create_table_raw(conn_ptr, "create_bad_dim_ffi", batch, None, r#"{"vector_columns":{"vector":2}}"#);
+    assert!(result.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+    assert!(message.contains("'vector' row 1 has 1 values"), "{}", message);
+    free_string(error as *mut libc::c_char);
+    assert!(!common::table_names_sync(conn_ptr).contains(&"create_bad_dim_ffi".to_string()));
+
+    connection_close(conn_ptr);
+}
+
+fn create_table_from_files_raw(
+    conn_ptr: *const lancedb::connection::Connection,
+    name: &str,
+    path: &std::path::Path,
+    format: &str,
+    mode: &str,
+    create_options_json: &str,
+    exist_ok: bool,
+) -> (*const std::ffi::c_void, *const libc::c_char) {
+    let table_name = std::ffi::CString::new(name).unwrap();
+    let paths_json = std::ffi::CString::new(format!("[{:?}]", path.to_str().unwrap())).unwrap();
+    let format = std::ffi::CString::new(format).unwrap();
+    let mode = std::ffi::CString::new(mode).unwrap();
+    let create_options = std::ffi::CString::new(create_options_json).unwrap();
+
+    let ctx = common::FfiTestContext::new();
+    connection_create_table_from_files(
+        conn_ptr,
+        table_name.as_ptr(),
+        paths_json.as_ptr(),
+        format.as_ptr(),
+        std::ptr::null(),
+        std::ptr::null_mut(),
+        mode.as_ptr(),
+        std::ptr::null(),
+        std::ptr::null(),
+        std::ptr::null(),
+        create_options.as_ptr(),
+        exist_ok,
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    ctx.wait_raw()
+}
+
+#[test]
+fn test_connection_create_table_from_json_files_infers_schema() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().join("db").to_str().unwrap());
+    let json = tmp.path().join("rows.json");
+    std::fs::write(
+        &json,
+        "{\"id\": 1, \"vec\": [1.0, 2.0]}\n{\"id\": 2, \"vec\": [3.0, 4.0]}\n",
+    )
+    .unwrap();
+
+    let (table_ptr, error) =
+        create_table_from_files_raw(conn_ptr, "from_files", &json, "json", "create", r#"{"vector_columns": {"vec": 2}}"#, false);
+    assert!(error.is_null());
+    let table_ptr = table_ptr as *const lancedb::table::Table;
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 2".into())), 1);
+    table_close(table_ptr);
+
+    // An existing table is returned as is, without reading the files again.
+    let (table_ptr, error) = create_table_from_files_raw(conn_ptr, "from_files", &json, "json", "create", "{}", true);
+    assert!(error.is_null());
+    let table_ptr = table_ptr as *const lancedb::table::Table;
+    assert_eq!(common::count_rows_sync(table_ptr, None), 2);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_connection_create_table_from_files_leaves_nothing_on_failure() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().join("db").to_str().unwrap());
+    let good = tmp.path().join("good.json");
+    std::fs::write(&good, "{\"id\": 1, \"vec\": [1.0, 2.0]}\n").unwrap();
+    // The second row has the wrong dimension, so the import fails while writing.
+    let bad = tmp.path().join("bad.json");
+    std::fs::write(&bad, "{\"id\": 2, \"vec\": [3.0, 4.0]}\n{\"id\": 3, \"vec\": [5.0]}\n").unwrap();
+    let vectors = r#"{"vector_columns": {"vec": 2}}"#;
+
+    let (result, error) = create_table_from_files_raw(conn_ptr, "failed", &bad, "json", "create", vectors, false);
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    free_string(error as *mut libc::c_char);
+    assert!(!common::table_names_sync(conn_ptr).contains(&"failed".to_string()));
+
+    let (table_ptr, error) = create_table_from_files_raw(conn_ptr, "kept", &good, "json", "create", vectors, false);
+    assert!(error.is_null());
+    table_close(table_ptr as *const lancedb::table::Table);
+
+    // A failed overwrite keeps the existing data; a successful one replaces it.
+    let (result, error) = create_table_from_files_raw(conn_ptr, "kept", &bad, "json", "overwrite", vectors, false);
+    assert!(result.is_null());
+    free_string(error as *mut libc::c_char);
+    let (table_ptr, error) = create_table_from_files_raw(conn_ptr, "kept", &good, "json", "create", "{}", true);
+    assert!(error.is_null());
+    let table_ptr = table_ptr as *const lancedb::table::Table;
+    assert_eq!(common::count_rows_sync(table_ptr, None), 1);
+    table_close(table_ptr);
+
+    let replacement = tmp.path().join("replacement.json");
+    std::fs::write(&replacement, "{\"id\": 7, \"vec\": [7.0, 7.0]}\n{\"id\": 8, \"vec\": [8.0, 8.0]}\n").unwrap();
+    let (table_ptr, error) =
+        create_table_from_files_raw(conn_ptr, "kept", &replacement, "json", "overwrite", vectors, false);
+    assert!(error.is_null());
+    let table_ptr = table_ptr as *const lancedb::table::Table;
+    assert_eq!(common::count_rows_sync(table_ptr, None), 2);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 1".into())), 0);
+    table_close(table_ptr);
+
+    let (result, error) = create_table_from_files_raw(conn_ptr, "kept", &good, "json", "append", vectors, false);
+    assert!(result.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+    assert!(message.contains("Unknown mode 'append'"), "{}", message);
+    free_string(error as *mut libc::c_char);
+
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_connection_sql_group_by_returns_stream() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let schema = Arc::new(Schema::new(vec![Field::new("tenant", DataType::Int32, false)]));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![Arc::new(Int32Array::from(vec![1, 1, 2, 1, 2]))],
+    )
+    .unwrap();
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "docs", vec![batch]);
+
+    let sql = std::ffi::CString::new(
+        "SELECT tenant, COUNT(*) AS n FROM docs GROUP BY tenant ORDER BY tenant",
+    )
+    .unwrap();
+    let ctx = common::FfiTestContext::new();
+    connection_sql(
+        conn_ptr,
+        sql.as_ptr(),
+        std::ptr::null(), // namespace_json
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let stream_ptr = ctx.wait_success();
+    assert!(!stream_ptr.is_null());
+
+    use arrow_array::Array;
+    let mut counts = Vec::new();
+    loop {
+        let ctx = common::FfiTestContext::new();
+        stream_next(stream_ptr as *const _, common::ffi_callback, ctx.user_data());
+        let result = ctx.wait_success();
+        if result.is_null() {
+            break;
+        }
+        let cdata = result as *mut FfiCData;
+        let (array_ptr, schema_ptr) = unsafe { ((*cdata).array, (*cdata).schema) };
+        let schema = unsafe { arrow_schema::ffi::FFI_ArrowSchema::from_raw(schema_ptr) };
+        let array = unsafe { arrow_data::ffi::FFI_ArrowArray::from_raw(array_ptr) };
+        let data = unsafe { arrow_array::ffi::from_ffi(array, &schema).unwrap() };
+        let batch = RecordBatch::from(arrow_array::StructArray::from(data));
+        let n = batch
+            .column_by_name("n")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow_array::Int64Array>()
+            .unwrap();
+        counts.extend(n.values().iter().copied());
+        unsafe { drop(Box::from_raw(cdata)) };
+    }
+    assert_eq!(counts, vec![3, 2]);
+
+    stream_close(stream_ptr as *const _);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_connection_sql_unknown_table_returns_error() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let sql = std::ffi::CString::new("SELECT * FROM missing").unwrap();
+    let ctx = common::FfiTestContext::new();
+    connection_sql(
+        conn_ptr,
+        sql.as_ptr(),
+        std::ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    free_string(error as *mut libc::c_char);
+
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_connection_sql_opens_only_referenced_tables() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+    let batch = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1, 2, 3]))]).unwrap();
+    let docs_ptr = common::create_table_with_data_sync(conn_ptr, "docs", vec![batch.clone()]);
+    let broken_ptr = common::create_table_with_data_sync(conn_ptr, "broken", vec![batch]);
+    table_close(broken_ptr);
+    // "broken" is still listed but can no longer be opened.
+    std::fs::remove_dir_all(tmp.path().join("broken.lance").join("_versions")).unwrap();
+
+    let sql = std::ffi::CString::new("SELECT COUNT(*) AS n FROM docs").unwrap();
+    let ctx = common::FfiTestContext::new();
+    connection_sql(conn_ptr, sql.as_ptr(), std::ptr::null(), common::ffi_callback, ctx.user_data());
+    let stream_ptr = ctx.wait_success();
+    assert!(!stream_ptr.is_null());
+    stream_close(stream_ptr as *const _);
+
+    let sql = std::ffi::CString::new("SELECT COUNT(*) AS n FROM broken").unwrap();
+    let ctx = common::FfiTestContext::new();
+    connection_sql(conn_ptr, sql.as_ptr(), std::ptr::null(), common::ffi_callback, ctx.user_data());
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    free_string(error as *mut libc::c_char);
+
+    table_close(docs_ptr);
+    connection_close(conn_ptr);
+}
@@ -2,7 +2,7 @@
 
 mod common;
 
-use arrow_array::{Int32Array, RecordBatch, FixedSizeListArray, Float32Array};
+use arrow_array::{Array, Int32Array, RecordBatch, FixedSizeListArray, Float32Array};
 use arrow_schema::{DataType, Field, Schema};
 use lancedb_ffi::*;
 use std::ffi::CString;
@@ -47,6 +47,28 @@ fn create_vector_batch(num_rows: usize, dim: usize) -> RecordBatch {
     batch
 }
 
+/// Imports an FfiCData result into a RecordBatch and frees the outer box.
+fn cdata_to_batch(result: *const std::ffi::c_void) -> RecordBatch {
+    let cdata = result as *mut FfiCData;
+    let (array_ptr, schema_ptr) = unsafe { ((*cdata).array, (*cdata).schema) };
+    let schema = unsafe { arrow_schema::ffi::FFI_ArrowSchema::from_raw(schema_ptr) };
+    let array = unsafe { arrow_data::ffi::FFI_ArrowArray::from_raw(array_ptr) };
+    let data = unsafe { arrow_array::ffi::from_ffi(array, &schema).unwrap() };
+    unsafe { drop(Box::from_raw(cdata)) };
+    RecordBatch::from(arrow_array::StructArray::from(data))
+}
+
+fn int32_column(batch: &RecordBatch, name: &str) -> Vec<i32> {
+    batch
+        .column_by_name(name)
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap()
+        .values()
+        .to_vec()
+}
+
 #[test]
 fn test_free_ffi_cdata_null_is_safe() {
     free_ffi_cdata(std::ptr::null_mut());
@@ -119,6 +141,365 @@ fn test_query_execute_with_limit_and_select_returns_subset() {
     connection_close(conn_ptr);
 }
 
+#[test]
+fn test_query_export_splits_filtered_rows_into_csv_files() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().join("db").to_str().unwrap());
+    let table_ptr = common::create_table_sync(conn_ptr, "query_export");
+    common::add_sync(table_ptr, vec![create_test_batch(10)]);
+
+    let out = tmp.path().join("export");
+    let params = CString::new(r#"{"where":"id >= 4"}"#).unwrap();
+    let uri = CString::new(out.to_str().unwrap()).unwrap();
+    let format = CString::new("csv").unwrap();
+    let options = CString::new(r#"{"max_rows_per_file":4}"#).unwrap();
+    let ctx = common::FfiTestContext::new();
+    query_export(
+        table_ptr,
+        ptr::null(),
+        0,
+        params.as_ptr(),
+        uri.as_ptr(),
+        format.as_ptr(),
+        options.as_ptr(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success() as *mut libc::c_char;
+    let json = unsafe { std::ffi::CStr::from_ptr(result) }.to_str().unwrap().to_string();
+    free_string(result);
+
+    use sonic_rs::{JsonContainerTrait, JsonValueTrait};
+    let summary: sonic_rs::Value = sonic_rs::from_str(&json).unwrap();
+    assert_eq!(summary["num_rows"].as_u64(), Some(6));
+    assert_eq!(summary["files"].as_array().unwrap().len(), 2);
+    // Each file has its own header row.
+    let first = std::fs::read_to_string(out.join("part-00000.csv")).unwrap();
+    let second = std::fs::read_to_string(out.join("part-00001.csv")).unwrap();
+    assert_eq!(first.lines().count(), 5);
+    assert_eq!(second.lines().collect::<Vec<_>>(), vec!["id", "8", "9"]);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_query_export_failing_part_way_deletes_written_files() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().join("db").to_str().unwrap());
+    let table_ptr = common::create_table_sync(conn_ptr, "query_export_failure");
+    common::add_sync(table_ptr, vec![create_test_batch(10)]);
+    let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+    let later = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from((10..20).collect::<Vec<i32>>()))]).unwrap();
+    common::add_sync(table_ptr, vec![later]);
+
+    // Dividing by zero at id 15 fails after the first fragment's files are written.
+    let out = tmp.path().join("export");
+    let params = CString::new(r#"{"select":{"id":"id","ratio":"10 / (id - 15)"}}"#).unwrap();
+    let uri = CString::new(out.to_str().unwrap()).unwrap();
+    let format = CString::new("csv").unwrap();
+    let options = CString::new(r#"{"max_rows_per_file":4}"#).unwrap();
+    let ctx = common::FfiTestContext::new();
+    query_export(
+        table_ptr,
+        ptr::null(),
+        0,
+        params.as_ptr(),
+        uri.as_ptr(),
+        format.as_ptr(),
+        options.as_ptr(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    free_string(error as *mut libc::c_char);
+
+    let remaining: Vec<_> = std::fs::read_dir(&out)
+        .map(|entries| entries.map(|e| e.unwrap().file_name()).collect())
+        .unwrap_or_default();
+    assert!(remaining.is_empty(), "{:?}", remaining);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+/// Creates a table with `id` and `text` columns and an FTS index on `text`
+/// built with `fts_config` (the `table_create_index` config JSON).
+fn create_text_table(
+    conn_ptr: *const lancedb::connection::Connection,
+    name: &str,
+    texts: &[&str],
+    fts_config: &str,
+) -> *const lancedb::table::Table {
+    use arrow_array::StringArray;
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("text", DataType::Utf8, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(Int32Array::from_iter_values(0..texts.len() as i32)),
+            Arc::new(StringArray::from(texts.to_vec())),
+        ],
+    )
+    .unwrap();
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, name, vec![batch]);
+
+    let columns = CString::new(r#"["text"]"#).unwrap();
+    let config = CString::new(fts_config).unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_create_index(
+        table_ptr,
+        columns.as_ptr(),
+        10, // FTS
+        config.as_ptr(),
+        true,
+        ptr::null(),
+        true,
+        -1,
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    ctx.wait_success();
+    table_ptr
+}
+
+#[test]
+fn test_query_execute_highlights_stemmed_and_folded_matches() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = create_text_table(
+        conn_ptr,
+        "highlight",
+        &["Running a Café downtown", "nothing to see here"],
+        r#"{"stem":true,"ascii_folding":true}"#,
+    );
+
+    let params = CString::new(
+        r#"{"full_text_search":"runs cafe","highlight":{"pre_tag":"[","post_tag":"]"}}"#,
+    )
+    .unwrap();
+    let ctx = common::FfiTestContext::new();
+    query_execute(table_ptr, params.as_ptr(), -1, 0, common::ffi_callback, ctx.user_data());
+    let batch = cdata_to_batch(ctx.wait_success());
+
+    assert_eq!(batch.num_rows(), 1);
+    let highlights = batch
+        .column_by_name("_highlight_text")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<arrow_array::ListArray>()
+        .unwrap()
+        .value(0);
+    let fragments = highlights.as_any().downcast_ref::<arrow_array::StringArray>().unwrap();
+    assert_eq!(fragments.len(), 1);
+    assert_eq!(fragments.value(0), "[Running] a [Café] downtown");
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+/// Runs a plain query with a structured full-text query and returns the ids of
+/// the matching rows, sorted.
+fn full_text_query_ids(table_ptr: *const lancedb::table::Table, query: &str) -> Vec<i32> {
+    let params = CString::new(format!(
+        r#"{{"full_text_query":{}}}"#,
+        sonic_rs::to_string(query).unwrap()
+    ))
+    .unwrap();
+    let ctx = common::FfiTestContext::new();
+    query_execute(table_ptr, params.as_ptr(), -1, 0, common::ffi_callback, ctx.user_data());
+    let batch = cdata_to_batch(ctx.wait_success());
+    let ids = batch.column_by_name("id").unwrap().as_any().downcast_ref::<Int32Array>().unwrap();
+    let mut ids: Vec<i32> = ids.values().to_vec();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn test_query_execute_expands_multi_term_queries() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = create_text_table(
+        conn_ptr,
+        "multi_term",
+        &["contract breach", "contractor agreement", "tort claim"],
+        r#"{"stem":false}"#,
+    );
+
+    let cases = [
+        (r#"{"prefix":{"column":"text","prefix":"contract"}}"#, vec![0, 1]),
+        (r#"{"wildcard":{"column":"text","pattern":"t?r*"}}"#, vec![2]),
+        (r#"{"regex":{"column":"text","pattern":"claim|breach"}}"#, vec![0, 2]),
+        (r#"{"term_range":{"column":"text","gte":"a","lt":"b"}}"#, vec![1]),
+        (r#"{"exists":{"column":"text"}}"#, vec![0, 1, 2]),
+        (
+            r#"{"boolean":{"must":[{"exists":{"column":"text"}},{"prefix":{"column":"text","prefix":"contract"}}]}}"#,
+            vec![0, 1],
+        ),
+        (r#"{"boolean":{"must":[{"prefix":{"column":"text","prefix":"t"}}],"must_not":[{"exists":{"column":"text"}}]}}"#, vec![]),
+        (r#"{"prefix":{"column":"text","prefix":"zzz"}}"#, vec![]),
+        (
+            r#"{"boolean":{"must":[{"prefix":{"column":"text","prefix":"contract"}}],"must_not":[{"match":{"column":"text","terms":"breach","fuzziness":0}}]}}"#,
+            vec![1],
+        ),
+    ];
+    for (query, expected) in cases {
+        assert_eq!(full_text_query_ids(table_ptr, query), expected, "{}", query);
+    }
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_query_execute_rejects_truncated_expansions_and_misplaced_exists() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = create_text_table(
+        conn_ptr,
+        "multi_term_errors",
+        &["contract breach", "contractor agreement", "tort claim"],
+        r#"{"stem":false}"#,
+    );
+
+    let cases = [
+        (
+            r#"{"prefix":{"column":"text","prefix":"contract","max_expansions":1}}"#,
+            "matches 2 terms, more than max_expansions (1)",
+        ),
+        (
+            r#"{"boolean":{"should":[{"exists":{"column":"text"}},{"match":{"column":"text","terms":"tort"}}]}}"#,
+            "exists can only be",
+        ),
+        (
+            r#"{"boolean":{"must":[{"exists":{"column":"text"}}],"should":[{"match":{"column":"text","terms":"tort"}}]}}"#,
+            "needs minimum_should_match",
+        ),
+    ];
+    for (query, expected) in cases {
+        let params = CString::new(format!(r#"{{"full_text_query":{}}}"#, sonic_rs::to_string(query).unwrap())).unwrap();
+        let ctx = common::FfiTestContext::new();
+        query_execute(table_ptr, params.as_ptr(), -1, 0, common::ffi_callback, ctx.user_data());
+        let (result, error) = ctx.wait_raw();
+        assert!(result.is_null(), "{}", query);
+        let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+        assert!(message.contains(expected), "{}: {}", query, message);
+        free_string(error as *mut libc::c_char);
+    }
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_query_execute_applies_minimum_should_match() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = create_text_table(
+        conn_ptr,
+        "minimum_should_match",
+        &["alpha beta gamma", "alpha beta", "alpha", "delta"],
+        r#"{"stem":false}"#,
+    );
+
+    let clauses = r#"[{"match":{"column":"text","terms":"alpha","fuzziness":0}},{"match":{"column":"text","terms":"beta","fuzziness":0}},{"match":{"column":"text","terms":"gamma","fuzziness":0}}]"#;
+    let query = |minimum: &str| format!(r#"{{"boolean":{{"should":{},"minimum_should_match":{}}}}}"#, clauses, minimum);
+    assert_eq!(full_text_query_ids(table_ptr, &query("2")), vec![0, 1]);
+    assert_eq!(full_text_query_ids(table_ptr, &query("-1")), vec![0, 1]);
+    assert_eq!(full_text_query_ids(table_ptr, &query(r#""100%""#)), vec![0]);
+    assert_eq!(full_text_query_ids(table_ptr, &query("1")), vec![0, 1, 2]);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_fts_analyze_reports_tokens_with_positions_and_offsets() {
+    let config = CString::new(r#"{"stem":true,"ascii_folding":true,"remove_stop_words":true}"#).unwrap();
+    let text = CString::new("Running the Cafés").unwrap();
+    let result = fts_analyze(config.as_ptr(), text.as_ptr());
+    assert!(!result.is_null());
+    let json = unsafe { std::ffi::CStr::from_ptr(result) }.to_str().unwrap().to_string();
+    free_string(result);
+
+    use sonic_rs::JsonValueTrait;
+    let tokens: sonic_rs::Value = sonic_rs::from_str(&json).unwrap();
+    assert_eq!(tokens[0]["text"].as_str(), Some("run"));
+    assert_eq!((tokens[0]["start"].as_u64(), tokens[0]["end"].as_u64()), (Some(0), Some(7)));
+    // "the" is a stop word, so "cafe" keeps its original position.
+    assert_eq!(tokens[1]["text"].as_str(), Some("cafe"));
+    assert_eq!(tokens[1]["position"].as_u64(), Some(2));
+    assert_eq!((tokens[1]["start"].as_u64(), tokens[1]["end"].as_u64()), (Some(12), Some(18)));
+
+    let config = CString::new(r#"{"language":"Klingon"}"#).unwrap();
+    assert!(fts_analyze(config.as_ptr(), text.as_ptr()).is_null());
+}
+
+#[test]
+fn test_table_fts_analyze_uses_index_configuration() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = create_text_table(conn_ptr, "analyze", &["x"], r#"{"stem":false,"lower_case":false}"#);
+
+    let name = CString::new("text_idx").unwrap();
+    let text = CString::new("Running Dogs").unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_fts_analyze(table_ptr, name.as_ptr(), text.as_ptr(), common::ffi_callback, ctx.user_data());
+    let result = ctx.wait_success() as *mut libc::c_char;
+    let json = unsafe { std::ffi::CStr::from_ptr(result) }.to_str().unwrap().to_string();
+    free_string(result);
+
+    use sonic_rs::JsonValueTrait;
+    let tokens: sonic_rs::Value = sonic_rs::from_str(&json).unwrap();
+    assert_eq!(tokens[0]["text"].as_str(), Some("Running"));
+    assert_eq!(tokens[1]["text"].as_str(), Some("Dogs"));
+
+    let missing = CString::new("no_such_idx").unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_fts_analyze(table_ptr, missing.as_ptr(), text.as_ptr(), common::ffi_callback, ctx.user_data());
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_str().unwrap().to_string();
+    free_string(error as *mut libc::c_char);
+    assert!(message.contains("Index 'no_such_idx' does not exist"));
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_vector_query_rejects_highlight() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "highlight_vector", vec![create_vector_batch(4, 2)]);
+
+    let vector = [0.0f32, 0.0];
+    let params = CString::new(r#"{"full_text_search":"x","highlight":{}}"#).unwrap();
+    let ctx = common::FfiTestContext::new();
+    vector_query_execute(
+        table_ptr,
+        vector.as_ptr(),
+        vector.len(),
+        params.as_ptr(),
+        -1,
+        0,
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_str().unwrap().to_string();
+    free_string(error as *mut libc::c_char);
+    assert!(message.contains("highlight is only supported for plain queries"));
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
 // ---------------------------------------------------------------------------
 // build_full_text_search helper tests
 // ---------------------------------------------------------------------------
@@ -131,6 +512,281 @@ fn params_with(full_text_query: Option<&str>, full_text_search: Option<&str>) ->
     }
 }
 
+#[test]
+fn test_query_execute_order_by_desc_with_limit_offset_returns_page() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_sync(conn_ptr, "query_order_by");
+    common::add_sync(table_ptr, vec![create_test_batch(10)]);
+    common::add_sync(table_ptr, vec![create_test_batch(10)]);
+
+    let params = CString::new(
+        r#"{"order_by":[{"column":"id","ascending":false}],"limit":3,"offset":1}"#,
+    )
+    .unwrap();
+    query_execute(table_ptr, params.as_ptr(), -1, 0, common::ffi_callback, ctx.user_data());
+    let batch = cdata_to_batch(ctx.wait_success());
+    assert_eq!(int32_column(&batch, "id"), vec![9, 8, 8]);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_query_execute_order_by_unselected_column_sorts_and_drops_it() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_with_data_sync(
+        conn_ptr,
+        "query_order_by_unselected",
+        vec![create_vector_batch(10, 2)],
+    );
+
+    let params = CString::new(
+        r#"{"select":["vector"],"order_by":[{"column":"id","ascending":false}],"limit":2}"#,
+    )
+    .unwrap();
+    query_execute(table_ptr, params.as_ptr(), -1, 0, common::ffi_callback, ctx.user_data());
+    let batch = cdata_to_batch(ctx.wait_success());
+    assert!(batch.column_by_name("id").is_none());
+    let vectors = batch
+        .column_by_name("vector")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<FixedSizeListArray>()
+        .unwrap();
+    let first: Vec<f32> = (0..vectors.len())
+        .map(|i| vectors.value(i).as_any().downcast_ref::<Float32Array>().unwrap().value(0))
+        .collect();
+    assert_eq!(first, vec![18.0, 16.0]);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_query_explain_plan_order_by_includes_sort() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_sync(conn_ptr, "query_order_by_plan");
+    common::add_sync(table_ptr, vec![create_test_batch(5)]);
+
+    let params = CString::new(r#"{"order_by":[{"column":"id"}],"limit":2}"#).unwrap();
+    query_explain_plan(table_ptr, params.as_ptr(), false, common::ffi_callback, ctx.user_data());
+    let result = ctx.wait_success();
+    let plan = unsafe { std::ffi::CStr::from_ptr(result as *const libc::c_char) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(plan.contains("SortExec"), "expected SortExec in plan: {}", plan);
+
+    free_string(result as *mut libc::c_char);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_query_execute_order_by_unknown_column_returns_error() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_sync(conn_ptr, "query_order_by_bad");
+    common::add_sync(table_ptr, vec![create_test_batch(5)]);
+
+    let params = CString::new(r#"{"order_by":[{"column":"missing"}]}"#).unwrap();
+    query_execute(table_ptr, params.as_ptr(), -1, 0, common::ffi_callback, ctx.user_data());
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    free_string(error as *mut libc::c_char);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+/// Runs query_execute_page and returns the page batch and next token.
+fn execute_page_sync(
+    table_ptr: *const lancedb::table::Table,
+    params: &str,
+    token: Option<&str>,
+) -> (RecordBatch, Option<String>) {
+    let ctx = common::FfiTestContext::new();
+    let params = CString::new(params).unwrap();
+    let token = token.map(|t| CString::new(t).unwrap());
+    query_execute_page(
+        table_ptr,
+        params.as_ptr(),
+        token.as_ref().map_or(ptr::null(), |t| t.as_ptr()),
+        -1,
+        0,
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let page = ctx.wait_success() as *mut FfiQueryPage;
+    let (data, token_ptr) = unsafe { ((*page).data, (*page).next_page_token) };
+    let next = if token_ptr.is_null() {
+        None
+    } else {
+        Some(unsafe { std::ffi::CStr::from_ptr(token_ptr) }.to_str().unwrap().to_string())
+    };
+    let batch = cdata_to_batch(data as *const std::ffi::c_void);
+    unsafe {
+        (*page).data = ptr::null_mut();
+    }
+    query_page_free(page);
+    (batch, next)
+}
+
+#[test]
+fn test_query_execute_page_walks_all_rows_in_order() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_sync(conn_ptr, "query_page_walk");
+    common::add_sync(table_ptr, vec![create_test_batch(7)]);
+
+    let params = r#"{"order_by":[{"column":"id","ascending":false}],"limit":3}"#;
+    let mut ids = Vec::new();
+    let mut token: Option<String> = None;
+    let mut pages = 0;
+    loop {
+        let (batch, next) = execute_page_sync(table_ptr, params, token.as_deref());
+        assert!(batch.column_by_name("_rowid").is_none());
+        ids.extend(int32_column(&batch, "id"));
+        pages += 1;
+        match next {
+            Some(t) => token = Some(t),
+            None => break,
+        }
+    }
+    assert_eq!(ids, vec![6, 5, 4, 3, 2, 1, 0]);
+    assert_eq!(pages, 3);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_query_execute_page_token_reads_its_version_after_writes() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_sync(conn_ptr, "query_page_stale");
+    common::add_sync(table_ptr, vec![create_test_batch(4)]);
+
+    let params = r#"{"order_by":[{"column":"id"}],"limit":2}"#;
+    let (first, token) = execute_page_sync(table_ptr, params, None);
+    common::add_sync(table_ptr, vec![create_test_batch(4)]);
+    let (second, token) = execute_page_sync(table_ptr, params, token.as_deref());
+
+    assert_eq!(int32_column(&first, "id"), vec![0, 1]);
+    assert_eq!(int32_column(&second, "id"), vec![2, 3]);
+    let (third, _) = execute_page_sync(table_ptr, params, token.as_deref());
+    assert_eq!(third.num_rows(), 0);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_query_execute_page_walks_null_and_nan_keys_unselected() {
+    use arrow_array::Float64Array;
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("score", DataType::Float64, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(Int32Array::from(vec![0, 1, 2, 3, 4, 5])),
+            Arc::new(Float64Array::from(vec![
+                Some(2.0),
+                None,
+                Some(f64::NAN),
+                Some(1.0),
+                None,
+                Some(f64::NAN),
+            ])),
+        ],
+    )
+    .unwrap();
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "query_page_nulls", vec![batch]);
+
+    let params = r#"{"select":["id"],"order_by":[{"column":"score"}],"limit":1}"#;
+    let mut ids = Vec::new();
+    let mut token: Option<String> = None;
+    loop {
+        let (batch, next) = execute_page_sync(table_ptr, params, token.as_deref());
+        assert!(batch.column_by_name("score").is_none());
+        ids.extend(int32_column(&batch, "id"));
+        match next {
+            Some(t) => token = Some(t),
+            None => break,
+        }
+    }
+    assert_eq!(ids, vec![3, 0, 2, 5, 1, 4]);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_query_execute_page_tampered_token_returns_error() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_sync(conn_ptr, "query_page_tampered");
+    common::add_sync(table_ptr, vec![create_test_batch(4)]);
+
+    let ctx = common::FfiTestContext::new();
+    let params = CString::new(r#"{"limit":2}"#).unwrap();
+    let token = CString::new(r#"{"version":1,"keys":["1) OR (1 = 1"],"row_id":0}"#).unwrap();
+    query_execute_page(
+        table_ptr,
+        params.as_ptr(),
+        token.as_ptr(),
+        -1,
+        0,
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    free_string(error as *mut libc::c_char);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_query_execute_page_without_limit_returns_error() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_sync(conn_ptr, "query_page_no_limit");
+
+    let params = CString::new("{}").unwrap();
+    query_execute_page(
+        table_ptr,
+        params.as_ptr(),
+        ptr::null(),
+        -1,
+        0,
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    free_string(error as *mut libc::c_char);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
 #[test]
 fn test_build_full_text_search_none_when_unset() {
     let params = QueryParams::default();
@@ -247,6 +903,135 @@ fn test_parse_fts_query_json_rejects_invalid_operator() {
     assert!(parse_fts_query_json(json).is_err());
 }
 
+#[test]
+fn test_parse_fts_query_json_rewrites_minimum_should_match() {
+    let json = r#"{"boolean":{"should":[{"match":{"column":"t","terms":"a"}},{"match":{"column":"t","terms":"b"}},{"match":{"column":"t","terms":"c"}}],"minimum_should_match":"60%"}}"#;
+    match parse_fts_query_json(json).unwrap() {
+        lancedb::index::scalar::FtsQuery::Boolean(b) => {
+            // 60% of 3 rounds down to 1, and without must clauses one should
+            // clause is already required.
+            assert_eq!(b.should.len(), 3);
+            assert!(b.must.is_empty());
+        }
+        other => panic!("expected Boolean, got {:?}", other),
+    }
+
+    let json = r#"{"boolean":{"should":[{"match":{"column":"t","terms":"a"}},{"match":{"column":"t","terms":"b"}},{"match":{"column":"t","terms":"c"}}],"minimum_should_match":2}}"#;
+    match parse_fts_query_json(json).unwrap() {
+        lancedb::index::scalar::FtsQuery::Boolean(b) => {
+            assert!(b.should.is_empty());
+            assert_eq!(b.must.len(), 1);
+            match &b.must[0] {
+                lancedb::index::scalar::FtsQuery::Boolean(pairs) => {
+                    assert_eq!(pairs.should.len(), 3);
+                }
+                other => panic!("expected nested Boolean, got {:?}", other),
+            }
+        }
+        other => panic!("expected Boolean, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_fts_query_json_validates_new_nodes() {
+    let cases = [
+        (
+            r#"{"boolean":{"should":[{"match":{"column":"t","terms":"a"}}],"minimum_should_match":2}}"#,
+            "requires 2 should clauses",
+        ),
+        (
+            r#"{"boolean":{"should":[{"match":{"column":"t","terms":"a"}}],"minimum_should_match":"most"}}"#,
+            "Invalid minimum_should_match 'most'",
+        ),
+        (r#"{"phrase":{"column":"t","terms":"a b","boost":2.0}}"#, "phrase queries do not support a boost"),
+        (r#"{"prefix":{"column":"t","prefix":""}}"#, "non-empty prefix"),
+        (r#"{"prefix":{"column":"t","prefix":"a","max_expansions":0}}"#, "max_expansions must be positive"),
+        (r#"{"wildcard":{"column":"t","pattern":"ab\"}}"#, "unescaped"),
+        (r#"{"regex":{"column":"t","pattern":"(ab"}}"#, "Invalid regex pattern '(ab'"),
+        (r#"{"term_range":{"column":"t"}}"#, "at least one of gt, gte, lt and lte"),
+        (r#"{"term_range":{"column":"t","gt":"a","gte":"a"}}"#, "gt or gte, not both"),
+        (r#"{"term_range":{"column":"t","gte":"b","lte":"a"}}"#, "is above upper bound"),
+        (r#"{"exists":{"column":""}}"#, "exists query needs a column"),
+        // Valid, but needs the table's terms.
+        (r#"{"prefix":{"column":"t","prefix":"a"}}"#, "only supported by plain queries"),
+    ];
+    for (json, expected) in cases {
+        let err = parse_fts_query_json(json).unwrap_err();
+        assert!(err.contains(expected), "{}: {}", json, err);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// parse_fts_query_string (Lucene-style query string) tests
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_parse_fts_query_string_builds_boolean_tree() {
+    let json = parse_fts_query_string(
+        r#"title:"vector db" AND (rust OR csharp) -java~2"#,
+        &["body".to_string()],
+    )
+    .unwrap();
+    use sonic_rs::JsonValueTrait;
+    let value: sonic_rs::Value = sonic_rs::from_str(&json).unwrap();
+
+    let and = &value["boolean"]["should"][0]["boolean"]["must"];
+    assert_eq!(and[0]["phrase"]["column"].as_str(), Some("title"));
+    assert_eq!(and[0]["phrase"]["terms"].as_str(), Some("vector db"));
+    assert_eq!(and[1]["boolean"]["should"][0]["match"]["terms"].as_str(), Some("rust"));
+    assert_eq!(and[1]["boolean"]["should"][1]["match"]["column"].as_str(), Some("body"));
+    let java = &value["boolean"]["must_not"][0]["match"];
+    assert_eq!(java["terms"].as_str(), Some("java"));
+    assert_eq!(java["fuzziness"].as_u64(), Some(2));
+
+    assert!(parse_fts_query_json(&json).is_ok());
+}
+
+#[test]
+fn test_parse_fts_query_string_searches_every_default_column() {
+    let json = parse_fts_query_string("rust^2", &["title".to_string(), "body".to_string()]).unwrap();
+    use sonic_rs::JsonValueTrait;
+    let value: sonic_rs::Value = sonic_rs::from_str(&json).unwrap();
+    let should = &value["boolean"]["should"];
+    assert_eq!(should[0]["match"]["column"].as_str(), Some("title"));
+    assert_eq!(should[1]["match"]["column"].as_str(), Some("body"));
+    assert_eq!(should[1]["match"]["boost"].as_f64(), Some(2.0));
+}
+
+#[test]
+fn test_parse_fts_query_string_reports_error_positions() {
+    let columns = ["body".to_string()];
+    let err = parse_fts_query_string(r#"rust "vector db"#, &columns).unwrap_err();
+    assert_eq!((err.position, err.message.as_str()), (5, "unterminated phrase"));
+
+    let err = parse_fts_query_string("(rust)) csharp", &columns).unwrap_err();
+    assert_eq!(err.position, 6);
+
+    let err = parse_fts_query_string(r#""vector db"~2^2"#, &columns).unwrap_err();
+    assert_eq!((err.position, err.message.as_str()), (13, "'^' is not supported on a phrase"));
+
+    let err = parse_fts_query_string("rust", &[]).unwrap_err();
+    assert_eq!(err.position, 0);
+    assert!(err.message.contains("no default columns"));
+}
+
+#[test]
+fn test_fts_parse_query_string_sets_last_error() {
+    let query = CString::new("body:rust AND").unwrap();
+    let result = fts_parse_query_string(query.as_ptr(), ptr::null());
+    assert!(result.is_null());
+    let error = lancedb_ffi::ffi::ffi_get_last_error();
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_str().unwrap().to_string();
+    free_string(error);
+    assert_eq!(message, "Query string parse error at position 13: unexpected end of query");
+
+    let columns = CString::new(r#"["body"]"#).unwrap();
+    let query = CString::new("rust && csharp").unwrap();
+    let result = fts_parse_query_string(query.as_ptr(), columns.as_ptr());
+    assert!(!result.is_null());
+    free_string(result);
+}
+
 #[test]
 fn test_query_explain_plan_returns_plan_text() {
     let ctx = common::FfiTestContext::new();
@@ -152,6 +152,303 @@ fn test_table_create_index_train_false_succeeds() {
     connection_close(conn_ptr);
 }
 
+/// Calls `table_create_index` and returns the raw `(result, error)` pair.
+fn create_index_raw(
+    table_ptr: *const lancedb::table::Table,
+    column: &str,
+    index_type: i32,
+    config: &str,
+    name: &str,
+) -> (*const std::ffi::c_void, *const libc::c_char) {
+    let ctx = common::FfiTestContext::new();
+    let columns_json = std::ffi::CString::new(format!(r#"["{}"]"#, column)).unwrap();
+    let config_json = std::ffi::CString::new(config).unwrap();
+    let name = std::ffi::CString::new(name).unwrap();
+    table_create_index(
+        table_ptr,
+        columns_json.as_ptr(),
+        index_type,
+        config_json.as_ptr(),
+        true,
+        name.as_ptr(),
+        true,
+        -1,
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    ctx.wait_raw()
+}
+
+#[test]
+fn test_table_create_index_build_options_index_listed_fragments() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_sync(conn_ptr, "idx_build_options_ffi");
+
+    common::add_sync(table_ptr, vec![create_test_batch(100)]);
+    common::add_sync(table_ptr, vec![create_test_batch(50)]);
+
+    let (result, error) = create_index_raw(
+        table_ptr,
+        "id",
+        7, // BTree
+        r#"{"zone_size": 16, "memory_limit_mb": 64, "partial_build_fragments": [0]}"#,
+        "id_idx",
+    );
+    assert!(error.is_null());
+    assert!(!result.is_null());
+
+    let ctx = common::FfiTestContext::new();
+    let name = std::ffi::CString::new("id_idx").unwrap();
+    table_index_stats(table_ptr, name.as_ptr(), common::ffi_callback, ctx.user_data());
+    let stats = ctx.wait_success() as *mut FfiIndexStats;
+    assert!(!stats.is_null());
+    assert_eq!(unsafe { &*stats }.num_indexed_rows, 100);
+    assert_eq!(unsafe { &*stats }.num_unindexed_rows, 50);
+    table_index_stats_free(stats);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_create_index_string_variants() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_with_data_sync(
+        conn_ptr,
+        "idx_variants_ffi",
+        vec![create_id_value_batch(&[1, 2, 3], &["apple pie", "banana split", "cherry tart"])],
+    );
+
+    let (_, error) = create_index_raw(table_ptr, "value", 7, r#"{"variant": "ngram"}"#, "value_ngram");
+    assert!(error.is_null());
+    let (_, error) = create_index_raw(
+        table_ptr,
+        "value",
+        7,
+        r#"{"variant": "inverted", "lower_case": true}"#,
+        "value_inverted",
+    );
+    assert!(error.is_null());
+    let indices = common::list_indices_sync(table_ptr);
+    assert!(indices.iter().any(|i| i.name == "value_ngram"));
+    assert!(indices.iter().any(|i| i.name == "value_inverted"));
+
+    let (result, error) = create_index_raw(table_ptr, "id", 7, r#"{"variant": "ngram"}"#, "id_ngram");
+    assert!(result.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+    assert!(message.contains("needs a string column"));
+    free_string(error as *mut libc::c_char);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_create_index_invalid_build_options_return_error() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_sync(conn_ptr, "idx_bad_options_ffi");
+    common::add_sync(table_ptr, vec![create_test_batch(10)]);
+
+    for (index_type, config, expected) in [
+        (8, r#"{"zone_size": 16}"#, "only supported by BTree indices"),
+        (7, r#"{"zone_size": 0}"#, "zone_size must be a positive integer"),
+        (7, r#"{"variant": "trigram"}"#, "variant must be"),
+        (7, r#"{"partial_build_fragments": []}"#, "partial_build_fragments must be a non-empty array"),
+    ] {
+        let (result, error) = create_index_raw(table_ptr, "id", index_type, config, "bad_idx");
+        assert!(result.is_null());
+        assert!(!error.is_null());
+        let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+        assert!(message.contains(expected), "{}: {}", config, message);
+        free_string(error as *mut libc::c_char);
+    }
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+fn create_vector_batch(num_rows: usize, dim: usize) -> RecordBatch {
+    let item = Arc::new(Field::new("item", DataType::Float32, true));
+    let values: Vec<f32> = (0..num_rows * dim).map(|i| ((i * 7919) % 1000) as f32 / 1000.0).collect();
+    let vectors = arrow_array::FixedSizeListArray::try_new(
+        item.clone(),
+        dim as i32,
+        Arc::new(arrow_array::Float32Array::from(values)),
+        None,
+    )
+    .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("vector", DataType::FixedSizeList(item, dim as i32), true),
+    ]));
+    let ids: Vec<i32> = (0..num_rows as i32).collect();
+    RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(ids)), Arc::new(vectors)]).unwrap()
+}
+
+/// `num` centroids of dimension `dim`, exported over the C Data interface.
+fn export_centroids(
+    num: usize,
+    dim: usize,
+) -> (arrow_data::ffi::FFI_ArrowArray, arrow_schema::ffi::FFI_ArrowSchema) {
+    let values: Vec<f32> = (0..num * dim).map(|i| (i / dim) as f32 / num as f32).collect();
+    let centroids = arrow_array::FixedSizeListArray::try_new(
+        Arc::new(Field::new("item", DataType::Float32, true)),
+        dim as i32,
+        Arc::new(arrow_array::Float32Array::from(values)),
+        None,
+    )
+    .unwrap();
+    let data = centroids.to_data();
+    (
+        arrow_data::ffi::FFI_ArrowArray::new(&data),
+        arrow_schema::ffi::FFI_ArrowSchema::try_from(data.data_type()).unwrap(),
+    )
+}
+
+/// Calls `table_create_index_with_model` on the `vector` column and returns the
+/// raw `(result, error)` pair.
+#[allow(clippy::too_many_arguments)]
+fn create_index_with_model_raw(
+    table_ptr: *const lancedb::table::Table,
+    index_type: i32,
+    config: &str,
+    name: &str,
+    centroids: Option<&mut (arrow_data::ffi::FFI_ArrowArray, arrow_schema::ffi::FFI_ArrowSchema)>,
+    codebook: Option<&mut (arrow_data::ffi::FFI_ArrowArray, arrow_schema::ffi::FFI_ArrowSchema)>,
+    source: Option<(*const lancedb::table::Table, &str)>,
+) -> (*const std::ffi::c_void, *const libc::c_char) {
+    let ctx = common::FfiTestContext::new();
+    let columns_json = std::ffi::CString::new(r#"["vector"]"#).unwrap();
+    let config_json = std::ffi::CString::new(config).unwrap();
+    let name = std::ffi::CString::new(name).unwrap();
+    let source_index = source.map(|(_, index)| std::ffi::CString::new(index).unwrap());
+    let (centroids_array, centroids_schema) = match centroids {
+        Some((array, schema)) => (array as *mut _, schema as *mut _),
+        None => (ptr::null_mut(), ptr::null_mut()),
+    };
+    let (codebook_array, codebook_schema) = match codebook {
+        Some((array, schema)) => (array as *mut _, schema as *mut _),
+        None => (ptr::null_mut(), ptr::null_mut()),
+    };
+    table_create_index_with_model(
+        table_ptr,
+        columns_json.as_ptr(),
+        index_type,
+        config_json.as_ptr(),
+        true,
+        name.as_ptr(),
+        true,
+        centroids_array,
+        centroids_schema,
+        codebook_array,
+        codebook_schema,
+        source.map_or(ptr::null(), |(table, _)| table),
+        source_index.as_ref().map_or(ptr::null(), |index| index.as_ptr()),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    ctx.wait_raw()
+}
+
+#[test]
+fn test_table_create_index_with_model_copies_source_index() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let source_ptr = common::create_table_with_data_sync(conn_ptr, "model_source", vec![create_vector_batch(512, 8)]);
+    let target_ptr = common::create_table_with_data_sync(conn_ptr, "model_target", vec![create_vector_batch(300, 8)]);
+
+    let (_, error) = create_index_raw(
+        source_ptr,
+        "vector",
+        2, // IvfPq
+        r#"{"num_partitions": 4, "num_sub_vectors": 2}"#,
+        "vector_idx",
+    );
+    assert!(error.is_null());
+
+    let (result, error) = create_index_with_model_raw(
+        target_ptr,
+        2, // IvfPq
+        r#"{"num_sub_vectors": 2}"#,
+        "vector_idx",
+        None,
+        None,
+        Some((source_ptr, "vector_idx")),
+    );
+    assert!(error.is_null());
+    assert!(!result.is_null());
+    let indices = common::list_indices_sync(target_ptr);
+    assert!(indices.iter().any(|i| i.name == "vector_idx"));
+
+    // A distance type that differs from the source index's is rejected.
+    let (result, error) = create_index_with_model_raw(
+        target_ptr,
+        2,
+        r#"{"distance_type": 1}"#,
+        "cosine_idx",
+        None,
+        None,
+        Some((source_ptr, "vector_idx")),
+    );
+    assert!(result.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+    assert!(message.contains("distance"), "{}", message);
+    free_string(error as *mut libc::c_char);
+
+    table_close(source_ptr);
+    table_close(target_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_create_index_with_model_uses_given_centroids() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "model_arrays", vec![create_vector_batch(300, 8)]);
+
+    let mut centroids = export_centroids(3, 8);
+    let (result, error) =
+        create_index_with_model_raw(table_ptr, 0, "{}", "flat_idx", Some(&mut centroids), None, None);
+    assert!(error.is_null());
+    assert!(!result.is_null());
+
+    let ctx = common::FfiTestContext::new();
+    let name = std::ffi::CString::new("flat_idx").unwrap();
+    table_index_stats(table_ptr, name.as_ptr(), common::ffi_callback, ctx.user_data());
+    let stats = ctx.wait_success() as *mut FfiIndexStats;
+    assert_eq!(unsafe { &*stats }.num_indexed_rows, 300);
+    table_index_stats_free(stats);
+
+    for (index_type, config, centroids, codebook, expected) in [
+        (0, "{}", export_centroids(3, 4), Some(export_centroids(16, 2)), "only applies to IVF-PQ"),
+        (0, "{}", export_centroids(3, 4), None, "dimension 4"),
+        (0, r#"{"num_partitions": 5}"#, export_centroids(3, 8), None, "3 centroids"),
+        (7, "{}", export_centroids(3, 8), None, "only applies to IVF vector indices"),
+    ] {
+        let mut centroids = centroids;
+        let mut codebook = codebook;
+        let (result, error) = create_index_with_model_raw(
+            table_ptr,
+            index_type,
+            config,
+            "bad_idx",
+            Some(&mut centroids),
+            codebook.as_mut(),
+            None,
+        );
+        assert!(result.is_null());
+        let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+        assert!(message.contains(expected), "{}", message);
+        free_string(error as *mut libc::c_char);
+    }
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
 #[test]
 fn test_table_optimize_with_params_succeeds() {
     let ctx = common::FfiTestContext::new();
@@ -256,139 +553,550 @@ fn test_table_add_appends_batch() {
 }
 
 #[test]
-fn test_table_list_indices_returns_json() {
+fn test_table_add_appends_for_unknown_modes_and_add_with_options_rejects_them() {
     let tmp = TempDir::new().unwrap();
     let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
-    let table_ptr = common::create_table_with_data_sync(
-        conn_ptr,
-        "list_idx_ffi",
-        vec![create_test_batch(10)],
-    );
-    common::create_btree_index_sync(table_ptr, "id");
+    let table_ptr =
+        common::create_table_with_data_sync(conn_ptr, "add_mode_ffi", vec![create_test_batch(2)]);
+    let mode = std::ffi::CString::new("upsert").unwrap();
 
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_test_batch(3));
     let ctx = common::FfiTestContext::new();
-    table_list_indices(table_ptr, common::ffi_callback, ctx.user_data());
-    let result = ctx.wait_success();
-    assert!(!result.is_null());
+    table_add(table_ptr, &mut ffi_array, &mut ffi_schema, 1, mode.as_ptr(), common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+    assert_eq!(common::count_rows_sync(table_ptr, None), 5);
 
-    let json = unsafe { std::ffi::CStr::from_ptr(result as *const libc::c_char) }
-        .to_str()
-        .unwrap()
-        .to_string();
-    assert!(
-        json.contains("\"columns\""),
-        "expected index JSON to include 'columns' field, got {}",
-        json
-    );
-    assert!(
-        json.contains("\"id\""),
-        "expected index JSON to reference 'id' column, got {}",
-        json
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_test_batch(3));
+    let ctx = common::FfiTestContext::new();
+    table_add_with_options(
+        table_ptr,
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        mode.as_ptr(),
+        ptr::null(),
+        false,
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
     );
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+    assert!(message.contains("Unknown add mode 'upsert'"), "{}", message);
+    free_string(error as *mut libc::c_char);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 5);
 
-    free_string(result as *mut libc::c_char);
     table_close(table_ptr);
     connection_close(conn_ptr);
 }
 
-/// Converts a RecordBatch to C Data Interface structs for FFI ingestion tests.
-fn batch_to_cdata(
+/// Adds `batch` with `options`, returning the version and the number of inserted rows.
+fn add_with_options_sync(
+    table_ptr: *const lancedb::table::Table,
     batch: &RecordBatch,
-) -> (arrow_data::ffi::FFI_ArrowArray, arrow_schema::ffi::FFI_ArrowSchema) {
-    use arrow_array::Array;
-    let struct_array: arrow_array::StructArray = batch.clone().into();
-    let data = struct_array.to_data();
-    let ffi_array = arrow_data::ffi::FFI_ArrowArray::new(&data);
-    let ffi_schema =
-        arrow_schema::ffi::FFI_ArrowSchema::try_from(data.data_type()).unwrap();
-    (ffi_array, ffi_schema)
+    options: &str,
+) -> (u64, u64) {
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(batch);
+    let options = std::ffi::CString::new(options).unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_add_with_options(
+        table_ptr,
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        ptr::null(),
+        options.as_ptr(),
+        false,
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success() as *mut FfiAddResult;
+    let added = unsafe { &*result };
+    let outcome = (added.version, added.num_inserted_rows);
+    table_add_result_free(result);
+    outcome
 }
 
-#[test]
-fn test_table_merge_insert_upsert_updates_and_inserts() {
+fn add_cast_raw(
+    table_ptr: *const lancedb::table::Table,
+    batch: &RecordBatch,
+) -> (*const std::ffi::c_void, *const libc::c_char) {
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(batch);
     let ctx = common::FfiTestContext::new();
-    let tmp = TempDir::new().unwrap();
-    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
-
-    let initial = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
-    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_upsert_ffi", vec![initial]);
-
-    let (mut ffi_array, mut ffi_schema) =
-        batch_to_cdata(&create_id_value_batch(&[2, 3, 4], &["B", "C", "D"]));
-    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
-
-    table_merge_insert(
+    table_add_with_options(
         table_ptr,
-        on_columns.as_ptr(),
-        true,          // when_matched_update_all
-        ptr::null(),   // no matched filter
-        true,          // when_not_matched_insert_all
-        false,         // when_not_matched_by_source_delete
-        ptr::null(),   // no delete filter
         &mut ffi_array,
         &mut ffi_schema,
-        1,             // batch_count
-        true,          // use_index
-        -1,            // timeout_ms (no timeout)
-        -1,            // use_lsm_write (sentinel: leave default)
+        1,
+        ptr::null(),
+        ptr::null(),
+        true, // cast_to_table_schema
+        ptr::null(),
         common::ffi_callback,
         ctx.user_data(),
     );
-    let result = ctx.wait_success();
-    assert!(!result.is_null());
-
-    assert_eq!(common::count_rows_sync(table_ptr, None), 4);
-
-    table_close(table_ptr);
-    connection_close(conn_ptr);
+    ctx.wait_raw()
 }
 
 #[test]
-fn test_table_merge_insert_insert_only_appends_new_rows() {
-    let ctx = common::FfiTestContext::new();
+fn test_table_add_cast_to_table_schema_widens_reorders_and_fills() {
     let tmp = TempDir::new().unwrap();
     let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("value", DataType::Utf8, true),
+    ]));
+    let initial = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(arrow_array::Int64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["a"])),
+        ],
+    )
+    .unwrap();
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "add_cast_ffi", vec![initial]);
+
+    // Reordered columns with Int32 ids and large_utf8 values.
+    let reordered = RecordBatch::try_new(
+        Arc::new(Schema::new(vec![
+            Field::new("value", DataType::LargeUtf8, true),
+            Field::new("id", DataType::Int32, false),
+        ])),
+        vec![
+            Arc::new(arrow_array::LargeStringArray::from(vec!["b"])),
+            Arc::new(Int32Array::from(vec![2])),
+        ],
+    )
+    .unwrap();
+    let (result, error) = add_cast_raw(table_ptr, &reordered);
+    assert!(error.is_null());
+    table_add_result_free(result as *mut FfiAddResult);
+
+    // The nullable value column is missing and filled with nulls.
+    let ids_only = RecordBatch::try_new(
+        Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)])),
+        vec![Arc::new(Int32Array::from(vec![3]))],
+    )
+    .unwrap();
+    let (result, error) = add_cast_raw(table_ptr, &ids_only);
+    assert!(error.is_null());
+    table_add_result_free(result as *mut FfiAddResult);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 2 AND value = 'b'".into())), 1);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 3 AND value IS NULL".into())), 1);
+
+    // Strings do not cast to the Int64 key, and the error names the column.
+    let bad = RecordBatch::try_new(
+        Arc::new(Schema::new(vec![Field::new("id", DataType::Utf8, false)])),
+        vec![Arc::new(StringArray::from(vec!["4"]))],
+    )
+    .unwrap();
+    let (result, error) = add_cast_raw(table_ptr, &bad);
+    assert!(result.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+    assert!(message.contains("Cannot cast column 'id'"));
+    free_string(error as *mut libc::c_char);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
 
-    let initial = create_id_value_batch(&[1, 2], &["a", "b"]);
-    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_ins_only_ffi", vec![initial]);
-
-    let (mut ffi_array, mut ffi_schema) =
-        batch_to_cdata(&create_id_value_batch(&[2, 3], &["B", "C"]));
-    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
 
-    table_merge_insert(
+fn add_from_files_raw(
+    table_ptr: *const lancedb::table::Table,
+    paths: &[&std::path::Path],
+    format: &str,
+) -> (*const std::ffi::c_void, *const libc::c_char) {
+    let paths: Vec<_> = paths.iter().map(|p| p.to_str().unwrap()).collect();
+    let paths_json = std::ffi::CString::new(sonic_rs::to_string(&paths).unwrap()).unwrap();
+    let format = std::ffi::CString::new(format).unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_add_from_files(
         table_ptr,
-        on_columns.as_ptr(),
-        false,         // when_matched_update_all
+        paths_json.as_ptr(),
+        format.as_ptr(),
+        ptr::null(),
         ptr::null(),
-        true,          // when_not_matched_insert_all
         false,
         ptr::null(),
-        &mut ffi_array,
-        &mut ffi_schema,
-        1,             // batch_count
-        true,          // use_index
-        -1,            // timeout_ms
-        -1,            // use_lsm_write (sentinel: leave default)
         common::ffi_callback,
         ctx.user_data(),
     );
-    ctx.wait_success();
-
-    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
-
-    table_close(table_ptr);
-    connection_close(conn_ptr);
+    ctx.wait_raw()
 }
 
 #[test]
-fn test_table_merge_insert_delete_not_in_source_removes_rows() {
-    let ctx = common::FfiTestContext::new();
+fn test_table_add_from_files_reads_csv_and_json_with_table_schema() {
     let tmp = TempDir::new().unwrap();
-    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
-
-    let initial = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
-    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_del_ffi", vec![initial]);
+    let conn_ptr = common::connect_sync(tmp.path().join("db").to_str().unwrap());
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("value", DataType::Utf8, true),
+    ]));
+    let initial = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(arrow_array::Int64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["a"])),
+        ],
+    )
+    .unwrap();
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "add_files_ffi", vec![initial]);
+
+    let csv = tmp.path().join("rows.csv");
+    std::fs::write(&csv, "id,value\n2,b\n3,c\n").unwrap();
+    let (version, error) = add_from_files_raw(table_ptr, &[&csv], "csv");
+    assert!(error.is_null());
+    assert_eq!(version as u64, 2);
+
+    // JSON fields are matched by name, whatever their order.
+    let json = tmp.path().join("rows.ndjson");
+    std::fs::write(&json, "{\"value\": \"d\", \"id\": 4}\n{\"id\": 5}\n").unwrap();
+    let (_, error) = add_from_files_raw(table_ptr, &[&json], "json");
+    assert!(error.is_null());
+    assert_eq!(common::count_rows_sync(table_ptr, None), 5);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 3 AND value = 'c'".into())), 1);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 5 AND value IS NULL".into())), 1);
+
+    let (result, error) = add_from_files_raw(table_ptr, &[&csv], "orc");
+    assert!(result.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+    assert!(message.contains("Unknown file format 'orc'"));
+    free_string(error as *mut libc::c_char);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_export_past_version_to_parquet_round_trips() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().join("db").to_str().unwrap());
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "export_ffi", vec![create_test_batch(3)]);
+    common::add_sync(table_ptr, vec![create_test_batch(2)]);
+
+    let out = tmp.path().join("snapshot");
+    let uri = std::ffi::CString::new(out.to_str().unwrap()).unwrap();
+    let format = std::ffi::CString::new("parquet").unwrap();
+    let options = std::ffi::CString::new(r#"{"compression":"zstd(3)","row_group_size":2}"#).unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_export(
+        table_ptr,
+        1,
+        uri.as_ptr(),
+        format.as_ptr(),
+        options.as_ptr(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success() as *mut libc::c_char;
+    let json = unsafe { std::ffi::CStr::from_ptr(result) }.to_str().unwrap().to_string();
+    free_string(result);
+    assert!(json.contains("\"num_rows\":3"), "{}", json);
+
+    // The snapshot reads back through the file import.
+    let copy_ptr = common::create_table_with_data_sync(conn_ptr, "export_copy_ffi", vec![create_test_batch(1)]);
+    let (_, error) = add_from_files_raw(copy_ptr, &[&out.join("part-00000.parquet")], "parquet");
+    assert!(error.is_null());
+    assert_eq!(common::count_rows_sync(copy_ptr, None), 4);
+
+    table_close(copy_ptr);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+fn find_idempotency_key_sync(table_ptr: *const lancedb::table::Table, key: &str) -> u64 {
+    let key = std::ffi::CString::new(key).unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_find_idempotency_key(table_ptr, key.as_ptr(), 0, common::ffi_callback, ctx.user_data());
+    ctx.wait_success() as u64
+}
+
+#[test]
+fn test_table_add_with_idempotency_key_applies_once() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr =
+        common::create_table_with_data_sync(conn_ptr, "add_idempotent_ffi", vec![create_test_batch(2)]);
+
+    assert_eq!(find_idempotency_key_sync(table_ptr, "load-42"), 0);
+
+    let options = r#"{"idempotency_key":"load-42"}"#;
+    let (first, inserted) = add_with_options_sync(table_ptr, &create_test_batch(5), options);
+    let (retried, replayed) = add_with_options_sync(table_ptr, &create_test_batch(5), options);
+
+    assert_eq!(retried, first);
+    assert_eq!(inserted, 5);
+    assert_eq!(replayed, 0);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 7);
+    assert_eq!(find_idempotency_key_sync(table_ptr, "load-42"), first);
+    assert_eq!(find_idempotency_key_sync(table_ptr, "load-43"), 0);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_list_versions_reports_operation_rows_and_commit_metadata() {
+    use sonic_rs::JsonValueTrait;
+
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "list_versions_audit_ffi", vec![batch]);
+
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[4, 5], &["d", "e"]));
+    let add_metadata = std::ffi::CString::new(r#"{"job_id":"ingest-7"}"#).unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_add_with_options(
+        table_ptr,
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        ptr::null(),
+        ptr::null(),
+        false,
+        add_metadata.as_ptr(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success() as *mut FfiAddResult;
+    let add_version = unsafe { &*result }.version;
+    table_add_result_free(result);
+
+    let predicate = std::ffi::CString::new("id = 1").unwrap();
+    let delete_metadata = std::ffi::CString::new(r#"{"user":"alice"}"#).unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_delete_with_options(
+        table_ptr,
+        predicate.as_ptr(),
+        ptr::null(),
+        delete_metadata.as_ptr(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success() as *mut FfiDeleteResult;
+    let delete_version = unsafe { &*result }.version;
+    table_delete_result_free(result);
+
+    let ctx = common::FfiTestContext::new();
+    table_list_versions(table_ptr, common::ffi_callback, ctx.user_data());
+    let json_ptr = ctx.wait_success() as *mut libc::c_char;
+    let json = unsafe { std::ffi::CStr::from_ptr(json_ptr) }.to_string_lossy().into_owned();
+    free_string(json_ptr);
+
+    let versions: Vec<sonic_rs::Value> = sonic_rs::from_str(&json).unwrap();
+    let find = |version: u64| {
+        versions
+            .iter()
+            .find(|v| v["version"].as_u64() == Some(version))
+            .unwrap()
+    };
+    let added = find(add_version);
+    assert_eq!(added["operation"].as_str(), Some("Append"));
+    assert_eq!(added["num_rows"].as_u64(), Some(5));
+    assert_eq!(added["num_rows_delta"].as_i64(), Some(2));
+    assert_eq!(added["commit_metadata"]["job_id"].as_str(), Some("ingest-7"));
+
+    let deleted = find(delete_version);
+    assert_eq!(deleted["operation"].as_str(), Some("Delete"));
+    assert_eq!(deleted["num_rows"].as_u64(), Some(4));
+    assert_eq!(deleted["num_rows_delta"].as_i64(), Some(-1));
+    assert_eq!(deleted["commit_metadata"]["user"].as_str(), Some("alice"));
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_add_columns_with_options_records_commit_metadata() {
+    use sonic_rs::JsonValueTrait;
+
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let batch = create_id_value_batch(&[1, 2], &["a", "b"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "add_columns_metadata_ffi", vec![batch]);
+
+    let transforms = std::ffi::CString::new(r#"[["doubled","id * 2"]]"#).unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_add_columns(table_ptr, transforms.as_ptr(), common::ffi_callback, ctx.user_data());
+    let plain_version = ctx.wait_success() as u64;
+
+    let columns = std::ffi::CString::new(r#"["doubled"]"#).unwrap();
+    let metadata = std::ffi::CString::new(r#"{"migration":"drop-doubled"}"#).unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_drop_columns_with_options(
+        table_ptr,
+        columns.as_ptr(),
+        metadata.as_ptr(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let dropped_version = ctx.wait_success() as u64;
+    assert_eq!(dropped_version, plain_version + 1);
+
+    let ctx = common::FfiTestContext::new();
+    table_list_versions(table_ptr, common::ffi_callback, ctx.user_data());
+    let json_ptr = ctx.wait_success() as *mut libc::c_char;
+    let json = unsafe { std::ffi::CStr::from_ptr(json_ptr) }.to_string_lossy().into_owned();
+    free_string(json_ptr);
+
+    let versions: Vec<sonic_rs::Value> = sonic_rs::from_str(&json).unwrap();
+    let find = |version: u64| versions.iter().find(|v| v["version"].as_u64() == Some(version)).unwrap();
+    assert!(find(plain_version)["commit_metadata"]["migration"].as_str().is_none());
+    assert_eq!(find(dropped_version)["commit_metadata"]["migration"].as_str(), Some("drop-doubled"));
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_list_indices_returns_json() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_with_data_sync(
+        conn_ptr,
+        "list_idx_ffi",
+        vec![create_test_batch(10)],
+    );
+    common::create_btree_index_sync(table_ptr, "id");
+
+    let ctx = common::FfiTestContext::new();
+    table_list_indices(table_ptr, common::ffi_callback, ctx.user_data());
+    let result = ctx.wait_success();
+    assert!(!result.is_null());
+
+    let json = unsafe { std::ffi::CStr::from_ptr(result as *const libc::c_char) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(
+        json.contains("\"columns\""),
+        "expected index JSON to include 'columns' field, got {}",
+        json
+    );
+    assert!(
+        json.contains("\"id\""),
+        "expected index JSON to reference 'id' column, got {}",
+        json
+    );
+
+    free_string(result as *mut libc::c_char);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+/// Converts a RecordBatch to C Data Interface structs for FFI ingestion tests.
+fn batch_to_cdata(
+    batch: &RecordBatch,
+) -> (arrow_data::ffi::FFI_ArrowArray, arrow_schema::ffi::FFI_ArrowSchema) {
+    use arrow_array::Array;
+    let struct_array: arrow_array::StructArray = batch.clone().into();
+    let data = struct_array.to_data();
+    let ffi_array = arrow_data::ffi::FFI_ArrowArray::new(&data);
+    let ffi_schema =
+        arrow_schema::ffi::FFI_ArrowSchema::try_from(data.data_type()).unwrap();
+    (ffi_array, ffi_schema)
+}
+
+/// Imports an FfiCData result into a RecordBatch and frees the outer box.
+fn cdata_to_batch(result: *const std::ffi::c_void) -> RecordBatch {
+    let cdata = result as *mut FfiCData;
+    let (array_ptr, schema_ptr) = unsafe { ((*cdata).array, (*cdata).schema) };
+    let schema = unsafe { arrow_schema::ffi::FFI_ArrowSchema::from_raw(schema_ptr) };
+    let array = unsafe { arrow_data::ffi::FFI_ArrowArray::from_raw(array_ptr) };
+    let data = unsafe { arrow_array::ffi::from_ffi(array, &schema).unwrap() };
+    unsafe { drop(Box::from_raw(cdata)) };
+    RecordBatch::from(arrow_array::StructArray::from(data))
+}
+
+#[test]
+fn test_table_merge_insert_upsert_updates_and_inserts() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let initial = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_upsert_ffi", vec![initial]);
+
+    let (mut ffi_array, mut ffi_schema) =
+        batch_to_cdata(&create_id_value_batch(&[2, 3, 4], &["B", "C", "D"]));
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+
+    table_merge_insert(
+        table_ptr,
+        on_columns.as_ptr(),
+        true,          // when_matched_update_all
+        ptr::null(),   // no matched filter
+        true,          // when_not_matched_insert_all
+        false,         // when_not_matched_by_source_delete
+        ptr::null(),   // no delete filter
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,             // batch_count
+        true,          // use_index
+        -1,            // timeout_ms (no timeout)
+        -1,            // use_lsm_write (sentinel: leave default)
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success();
+    assert!(!result.is_null());
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 4);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_merge_insert_insert_only_appends_new_rows() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let initial = create_id_value_batch(&[1, 2], &["a", "b"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_ins_only_ffi", vec![initial]);
+
+    let (mut ffi_array, mut ffi_schema) =
+        batch_to_cdata(&create_id_value_batch(&[2, 3], &["B", "C"]));
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+
+    table_merge_insert(
+        table_ptr,
+        on_columns.as_ptr(),
+        false,         // when_matched_update_all
+        ptr::null(),
+        true,          // when_not_matched_insert_all
+        false,
+        ptr::null(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,             // batch_count
+        true,          // use_index
+        -1,            // timeout_ms
+        -1,            // use_lsm_write (sentinel: leave default)
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    ctx.wait_success();
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_merge_insert_delete_not_in_source_removes_rows() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let initial = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_del_ffi", vec![initial]);
 
     let (mut ffi_array, mut ffi_schema) =
         batch_to_cdata(&create_id_value_batch(&[2], &["B"]));
@@ -602,113 +1310,1156 @@ fn test_table_delete_returns_delete_result() {
     assert_eq!(delete_result.num_deleted_rows, 2);
     assert!(delete_result.version > 0);
 
-    table_delete_result_free(ffi_result);
+    table_delete_result_free(ffi_result);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_delete_no_matching_rows_returns_zero() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "delete_none_ffi", vec![batch]);
+
+    let predicate = std::ffi::CString::new("id > 100").unwrap();
+    table_delete(table_ptr, predicate.as_ptr(), common::ffi_callback, ctx.user_data());
+    let result = ctx.wait_success();
+    assert!(!result.is_null());
+
+    let ffi_result = result as *mut FfiDeleteResult;
+    let delete_result = unsafe { &*ffi_result };
+    assert_eq!(delete_result.num_deleted_rows, 0);
+
+    table_delete_result_free(ffi_result);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_delete_with_write_options() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "delete_write_options_ffi", vec![batch]);
+
+    let predicate = std::ffi::CString::new("id = 1").unwrap();
+    let options = std::ffi::CString::new(
+        r#"{"max_retries":3,"initial_backoff_ms":10,"max_backoff_ms":100,"conflict_mode":"retry"}"#,
+    )
+    .unwrap();
+    table_delete_with_options(table_ptr, predicate.as_ptr(), options.as_ptr(), ptr::null(), common::ffi_callback, ctx.user_data());
+    let result = ctx.wait_success() as *mut FfiDeleteResult;
+    assert_eq!(unsafe { &*result }.num_deleted_rows, 1);
+    table_delete_result_free(result);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 2);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_delete_with_idempotency_key_applies_once() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "delete_idempotent_ffi", vec![batch.clone()]);
+
+    let predicate = std::ffi::CString::new("id = 1").unwrap();
+    let options = std::ffi::CString::new(r#"{"idempotency_key":"purge-1"}"#).unwrap();
+    let delete = || {
+        let ctx = common::FfiTestContext::new();
+        table_delete_with_options(table_ptr, predicate.as_ptr(), options.as_ptr(), ptr::null(), common::ffi_callback, ctx.user_data());
+        let result = ctx.wait_success() as *mut FfiDeleteResult;
+        let (version, deleted) = (unsafe { &*result }.version, unsafe { &*result }.num_deleted_rows);
+        table_delete_result_free(result);
+        (version, deleted)
+    };
+
+    let (first, deleted) = delete();
+    assert_eq!(deleted, 1);
+    // A row matching the predicate again must survive the retried delete.
+    add_with_options_sync(table_ptr, &create_id_value_batch(&[1], &["again"]), "{}");
+    let (retried, deleted) = delete();
+    assert_eq!(retried, first);
+    assert_eq!(deleted, 0);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
+    assert_eq!(find_idempotency_key_sync(table_ptr, "purge-1"), first);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_delete_invalid_write_options_returns_error() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "delete_bad_options_ffi", vec![batch]);
+
+    let predicate = std::ffi::CString::new("id = 1").unwrap();
+    let options = std::ffi::CString::new(r#"{"conflict_mode":"sometimes"}"#).unwrap();
+    table_delete_with_options(table_ptr, predicate.as_ptr(), options.as_ptr(), ptr::null(), common::ffi_callback, ctx.user_data());
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+    assert!(message.contains("Invalid write options"));
+    free_string(error as *mut libc::c_char);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+// ===== table_delete_row_ids FFI: returns FfiDeleteRowIdsResult =====
+
+/// Reads the `_rowid` values of the rows matching `filter`.
+fn row_ids_where(table_ptr: *const lancedb::table::Table, filter: &str) -> Vec<u64> {
+    use futures::TryStreamExt;
+    use lancedb::query::{ExecutableQuery, QueryBase};
+
+    let table = unsafe { &*table_ptr };
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let batches: Vec<RecordBatch> = table
+            .query()
+            .only_if(filter)
+            .with_row_id()
+            .execute()
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        batches
+            .iter()
+            .flat_map(|b| {
+                b.column_by_name("_rowid")
+                    .unwrap()
+                    .as_any()
+                    .downcast_ref::<arrow_array::UInt64Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn test_table_delete_row_ids_returns_deleted_rows() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = create_id_value_batch(&[1, 2, 3, 4, 5], &["a", "b", "c", "d", "e"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "delete_row_ids_ffi", vec![batch]);
+    let ids = row_ids_where(table_ptr, "id IN (2, 4)");
+    assert_eq!(ids.len(), 2);
+
+    let columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    table_delete_row_ids(
+        table_ptr,
+        ids.as_ptr(),
+        ids.len(),
+        true,
+        columns.as_ptr(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success() as *mut FfiDeleteRowIdsResult;
+    let delete_result = unsafe { &mut *result };
+    assert_eq!(delete_result.num_deleted_rows, 2);
+    assert!(delete_result.version > 0);
+
+    let deleted = cdata_to_batch(delete_result.deleted_rows as *const std::ffi::c_void);
+    delete_result.deleted_rows = ptr::null_mut();
+    let mut deleted_ids = deleted
+        .column_by_name("id")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap()
+        .values()
+        .to_vec();
+    deleted_ids.sort();
+    assert_eq!(deleted_ids, vec![2, 4]);
+
+    table_delete_row_ids_result_free(result);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_delete_row_ids_without_rows_returns_null_data() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let table_ptr =
+        common::create_table_with_data_sync(conn_ptr, "delete_row_ids_plain_ffi", vec![create_test_batch(4)]);
+    let ids = row_ids_where(table_ptr, "id = 0");
+
+    table_delete_row_ids(
+        table_ptr,
+        ids.as_ptr(),
+        ids.len(),
+        false,
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success() as *mut FfiDeleteRowIdsResult;
+    let delete_result = unsafe { &*result };
+    assert_eq!(delete_result.num_deleted_rows, 1);
+    assert!(delete_result.deleted_rows.is_null());
+
+    table_delete_row_ids_result_free(result);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+/// Deletes `ids` from `table_ptr` returning the rows with `columns_json`.
+fn delete_row_ids_returning(
+    table_ptr: *const lancedb::table::Table,
+    ids: &[u64],
+    columns_json: Option<&str>,
+) -> RecordBatch {
+    let ctx = common::FfiTestContext::new();
+    let columns = columns_json.map(|c| std::ffi::CString::new(c).unwrap());
+    table_delete_row_ids(
+        table_ptr,
+        ids.as_ptr(),
+        ids.len(),
+        true,
+        columns.as_ref().map_or(ptr::null(), |c| c.as_ptr()),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success() as *mut FfiDeleteRowIdsResult;
+    let delete_result = unsafe { &mut *result };
+    let deleted = cdata_to_batch(delete_result.deleted_rows as *const std::ffi::c_void);
+    delete_result.deleted_rows = ptr::null_mut();
+    table_delete_row_ids_result_free(result);
+    deleted
+}
+
+#[test]
+fn test_table_delete_row_ids_returns_table_columns_without_row_id() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "delete_row_ids_all_columns_ffi", vec![batch]);
+    let ids = row_ids_where(table_ptr, "id = 2");
+
+    let deleted = delete_row_ids_returning(table_ptr, &ids, None);
+    let names: Vec<&str> = deleted.schema().fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, vec!["id", "value"]);
+    assert_eq!(deleted.num_rows(), 1);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_delete_row_ids_returns_row_id_when_requested() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "delete_row_ids_with_row_id_ffi", vec![batch]);
+    let ids = row_ids_where(table_ptr, "id = 3");
+
+    let deleted = delete_row_ids_returning(table_ptr, &ids, Some(r#"["id", "_rowid"]"#));
+    let row_ids = deleted
+        .column_by_name("_rowid")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<arrow_array::UInt64Array>()
+        .unwrap()
+        .values()
+        .to_vec();
+    assert_eq!(row_ids, ids);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 2);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_delete_row_ids_across_fragments_removes_emptied_fragment() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let table_ptr =
+        common::create_table_with_data_sync(conn_ptr, "delete_row_ids_fragments_ffi", vec![create_test_batch(3)]);
+    common::add_sync(table_ptr, vec![create_test_batch(3)]);
+    let mut ids = row_ids_where(table_ptr, "id = 1");
+    assert_eq!(ids.len(), 2);
+    let first_fragment = ids.iter().copied().min().unwrap() >> 32;
+    ids.extend(row_ids_where(table_ptr, "id <> 1").into_iter().filter(|id| id >> 32 == first_fragment));
+
+    table_delete_row_ids(
+        table_ptr,
+        ids.as_ptr(),
+        ids.len(),
+        false,
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success() as *mut FfiDeleteRowIdsResult;
+    assert_eq!(unsafe { (*result).num_deleted_rows }, 4);
+    table_delete_row_ids_result_free(result);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 2);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 1".to_string())), 0);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+// ===== table_update FFI: returns FfiUpdateResult =====
+
+#[test]
+fn test_table_update_returns_update_result() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "update_result_ffi", vec![batch]);
+
+    let filter = std::ffi::CString::new("id >= 2").unwrap();
+    let columns_json = std::ffi::CString::new(r#"[["value","'updated'"]]"#).unwrap();
+    table_update(
+        table_ptr,
+        filter.as_ptr(),
+        columns_json.as_ptr(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success();
+    assert!(!result.is_null());
+
+    let ffi_result = result as *mut FfiUpdateResult;
+    let update_result = unsafe { &*ffi_result };
+    assert_eq!(update_result.rows_updated, 2);
+    assert!(update_result.version > 0);
+
+    table_update_result_free(ffi_result);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_update_with_options_rewrites_matching_rows() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "update_with_options_ffi", vec![batch]);
+
+    let filter = std::ffi::CString::new("id >= 2").unwrap();
+    let columns_json = std::ffi::CString::new(r#"[["value","concat(value, '!')"]]"#).unwrap();
+    let options = std::ffi::CString::new(r#"{"conflict_mode":"fail_fast"}"#).unwrap();
+    table_update_with_options(
+        table_ptr,
+        filter.as_ptr(),
+        columns_json.as_ptr(),
+        options.as_ptr(),
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success() as *mut FfiUpdateResult;
+    assert_eq!(unsafe { &*result }.rows_updated, 2);
+    table_update_result_free(result);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("value IN ('a', 'b!', 'c!')".to_string())), 3);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_update_with_commit_metadata_rewrites_rows_across_fragments() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "update_staged_fragments_ffi", vec![batch]);
+    common::add_sync(table_ptr, vec![create_id_value_batch(&[4, 5], &["d", "e"])]);
+
+    let filter = std::ffi::CString::new("id % 2 = 0").unwrap();
+    let columns_json = std::ffi::CString::new(r#"[["value","upper(value)"]]"#).unwrap();
+    let metadata = std::ffi::CString::new(r#"{"job_id":"fix-7"}"#).unwrap();
+    table_update_with_options(
+        table_ptr,
+        filter.as_ptr(),
+        columns_json.as_ptr(),
+        ptr::null(),
+        metadata.as_ptr(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success() as *mut FfiUpdateResult;
+    assert_eq!(unsafe { &*result }.rows_updated, 2);
+    table_update_result_free(result);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 5);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("value IN ('a', 'B', 'c', 'D', 'e')".to_string())), 5);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_update_from_batch_updates_matched_rows_only() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "update_from_batch_ffi", vec![batch]);
+
+    // id 4 has no match and must not be inserted.
+    let (mut ffi_array, mut ffi_schema) =
+        batch_to_cdata(&create_id_value_batch(&[2, 4], &["B", "D"]));
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    table_update_from_batch(
+        table_ptr,
+        on_columns.as_ptr(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        ptr::null(),
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success();
+    assert!(!result.is_null());
+
+    let ffi_result = result as *mut FfiUpdateResult;
+    let update_result = unsafe { &*ffi_result };
+    assert_eq!(update_result.rows_updated, 1);
+    assert!(update_result.version > 0);
+    table_update_result_free(ffi_result);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_update_from_batch_with_idempotency_key_applies_once() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "update_from_batch_key_ffi", vec![batch]);
+
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    let write_options = std::ffi::CString::new(r#"{"idempotency_key": "job-7"}"#).unwrap();
+    let mut results = Vec::new();
+    for _ in 0..2 {
+        let ctx = common::FfiTestContext::new();
+        let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[2], &["B"]));
+        table_update_from_batch(
+            table_ptr,
+            on_columns.as_ptr(),
+            &mut ffi_array,
+            &mut ffi_schema,
+            1,
+            write_options.as_ptr(),
+            ptr::null(),
+            common::ffi_callback,
+            ctx.user_data(),
+        );
+        let result = ctx.wait_success() as *mut FfiUpdateResult;
+        let update_result = unsafe { &*result };
+        results.push((update_result.version, update_result.rows_updated));
+        table_update_result_free(result);
+    }
+
+    // The replay reports the original version and updates nothing.
+    assert_eq!(results[0].1, 1);
+    assert_eq!(results[1], (results[0].0, 0));
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_update_from_batch_missing_key_column_errors() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = create_id_value_batch(&[1, 2], &["a", "b"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "update_from_batch_err_ffi", vec![batch]);
+
+    let (mut ffi_array, mut ffi_schema) =
+        batch_to_cdata(&create_id_value_batch(&[1], &["A"]));
+    let on_columns = std::ffi::CString::new(r#"["missing"]"#).unwrap();
+    table_update_from_batch(
+        table_ptr,
+        on_columns.as_ptr(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        ptr::null(),
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+    assert!(message.contains("missing"));
+    free_string(error as *mut libc::c_char);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+// ===== table_merge_insert FFI: returns FfiMergeResult =====
+
+#[test]
+fn test_table_merge_insert_returns_merge_result() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let initial = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_result_ffi", vec![initial]);
+
+    let (mut ffi_array, mut ffi_schema) =
+        batch_to_cdata(&create_id_value_batch(&[2, 3, 4, 5], &["B", "C", "D", "E"]));
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+
+    table_merge_insert(
+        table_ptr,
+        on_columns.as_ptr(),
+        true,          // when_matched_update_all
+        ptr::null(),
+        true,          // when_not_matched_insert_all
+        false,
+        ptr::null(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        true,
+        -1,
+        -1,            // use_lsm_write (sentinel: leave default)
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success();
+    assert!(!result.is_null());
+
+    let ffi_result = result as *mut FfiMergeResult;
+    let merge_result = unsafe { &*ffi_result };
+    assert!(merge_result.version > 0);
+    assert_eq!(merge_result.num_inserted_rows, 2);
+    assert_eq!(merge_result.num_updated_rows, 2);
+    assert_eq!(merge_result.num_deleted_rows, 0);
+
+    table_merge_result_free(ffi_result);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 5);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+// ===== table_merge_insert_clauses FFI =====
+
+#[test]
+fn test_table_merge_insert_clauses_conditional_set_and_source_delete() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let initial = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_clauses_ffi", vec![initial]);
+
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[1, 2], &["x", "skip"]));
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    let clauses = std::ffi::CString::new(
+        r#"[
+            {"kind":"when_matched_update","condition":"source.value <> 'skip'","set":[["value","upper(source.value)"]]},
+            {"kind":"when_not_matched_by_source_delete","condition":"target.id = 3"}
+        ]"#,
+    )
+    .unwrap();
+
+    table_merge_insert_clauses(
+        table_ptr,
+        on_columns.as_ptr(),
+        clauses.as_ptr(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        true,
+        -1,
+        -1,
+        false,
+        ptr::null(),
+        false,
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success();
+    assert!(!result.is_null());
+
+    let merge_result = unsafe { &*(result as *mut FfiMergeResult) };
+    assert_eq!(merge_result.num_inserted_rows, 0);
+    assert_eq!(merge_result.num_updated_rows, 1);
+    assert_eq!(merge_result.num_deleted_rows, 1);
+    table_merge_result_free(result as *mut FfiMergeResult);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 2);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 1 AND value = 'X'".into())), 1);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 2 AND value = 'b'".into())), 1);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_merge_insert_clauses_by_source_update() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let initial = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_by_source_update_ffi", vec![initial]);
+
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[1], &["x"]));
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    let clauses = std::ffi::CString::new(
+        r#"[
+            {"kind":"when_matched_update"},
+            {"kind":"when_not_matched_by_source_update","condition":"id > 2","set":[["value","concat(value, '-gone')"]]}
+        ]"#,
+    )
+    .unwrap();
+
+    table_merge_insert_clauses(
+        table_ptr,
+        on_columns.as_ptr(),
+        clauses.as_ptr(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        true,
+        -1,
+        -1,
+        false,
+        ptr::null(),
+        false,
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success();
+    assert!(!result.is_null());
+
+    let merge_result = unsafe { &*(result as *mut FfiMergeResult) };
+    assert_eq!(merge_result.num_inserted_rows, 0);
+    assert_eq!(merge_result.num_updated_rows, 2);
+    assert_eq!(merge_result.num_deleted_rows, 0);
+    table_merge_result_free(result as *mut FfiMergeResult);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 1 AND value = 'x'".into())), 1);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 2 AND value = 'b'".into())), 1);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 3 AND value = 'c-gone'".into())), 1);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_merge_insert_clauses_set_reads_target_with_insert() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let initial = create_id_value_batch(&[1, 2], &["a", "b"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_set_target_ffi", vec![initial]);
+
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[1, 3], &["x", "c"]));
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    let clauses = std::ffi::CString::new(
+        r#"[
+            {"kind":"when_matched_update","set":[["value","concat(target.value, source.value)"]]},
+            {"kind":"when_not_matched_insert"}
+        ]"#,
+    )
+    .unwrap();
+
+    table_merge_insert_clauses(
+        table_ptr,
+        on_columns.as_ptr(),
+        clauses.as_ptr(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        true,
+        -1,
+        -1,
+        false,
+        ptr::null(),
+        false,
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success();
+    assert!(!result.is_null());
+
+    let merge_result = unsafe { &*(result as *mut FfiMergeResult) };
+    assert_eq!(merge_result.num_inserted_rows, 1);
+    assert_eq!(merge_result.num_updated_rows, 1);
+    table_merge_result_free(result as *mut FfiMergeResult);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 1 AND value = 'ax'".into())), 1);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 2 AND value = 'b'".into())), 1);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 3 AND value = 'c'".into())), 1);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_merge_insert_clauses_matched_delete_with_by_source_update_errors() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let initial = create_id_value_batch(&[1, 2], &["a", "b"]);
+    let table_ptr =
+        common::create_table_with_data_sync(conn_ptr, "merge_delete_by_source_update_ffi", vec![initial]);
+
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[1], &["x"]));
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    let clauses = std::ffi::CString::new(
+        r#"[
+            {"kind":"when_matched_delete"},
+            {"kind":"when_not_matched_by_source_update","set":[["value","'gone'"]]}
+        ]"#,
+    )
+    .unwrap();
+
+    table_merge_insert_clauses(
+        table_ptr,
+        on_columns.as_ptr(),
+        clauses.as_ptr(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        true,
+        -1,
+        -1,
+        false,
+        ptr::null(),
+        false,
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+    assert!(message.contains("when_not_matched_by_source_update"), "{}", message);
+    free_string(error as *mut libc::c_char);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("value = 'gone'".into())), 0);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_merge_insert_clauses_matched_delete_and_insert() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let initial = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_delete_ffi", vec![initial]);
+
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[1, 4], &["x", "d"]));
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    let clauses = std::ffi::CString::new(
+        r#"[{"kind":"when_matched_delete"},{"kind":"when_not_matched_insert"}]"#,
+    )
+    .unwrap();
+
+    table_merge_insert_clauses(
+        table_ptr,
+        on_columns.as_ptr(),
+        clauses.as_ptr(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        true,
+        -1,
+        -1,
+        false,
+        ptr::null(),
+        false,
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let result = ctx.wait_success();
+    assert!(!result.is_null());
+
+    let merge_result = unsafe { &*(result as *mut FfiMergeResult) };
+    assert_eq!(merge_result.num_inserted_rows, 1);
+    assert_eq!(merge_result.num_deleted_rows, 1);
+    table_merge_result_free(result as *mut FfiMergeResult);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 1".into())), 0);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 4 AND value = 'd'".into())), 1);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_merge_insert_clauses_with_idempotency_key_applies_once() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let initial = create_id_value_batch(&[1, 2], &["a", "b"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_idempotent_ffi", vec![initial]);
+
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    let clauses = std::ffi::CString::new(r#"[{"kind":"when_not_matched_insert"}]"#).unwrap();
+    let options = std::ffi::CString::new(r#"{"idempotency_key":"upsert-7"}"#).unwrap();
+    let merge = || {
+        let ctx = common::FfiTestContext::new();
+        let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[3], &["c"]));
+        table_merge_insert_clauses(
+            table_ptr,
+            on_columns.as_ptr(),
+            clauses.as_ptr(),
+            &mut ffi_array,
+            &mut ffi_schema,
+            1,
+            true,
+            -1,
+            -1,
+            false,
+            options.as_ptr(),
+            false,
+            ptr::null(),
+            common::ffi_callback,
+            ctx.user_data(),
+        );
+        let result = ctx.wait_success() as *mut FfiMergeResult;
+        let (version, inserted) = (unsafe { &*result }.version, unsafe { &*result }.num_inserted_rows);
+        table_merge_result_free(result);
+        (version, inserted)
+    };
+
+    let (first, inserted) = merge();
+    assert_eq!(inserted, 1);
+    // Deleting the merged row shows whether the retried merge would insert it again.
+    let predicate = std::ffi::CString::new("id = 3").unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_delete(table_ptr, predicate.as_ptr(), common::ffi_callback, ctx.user_data());
+    table_delete_result_free(ctx.wait_success() as *mut FfiDeleteResult);
+    let (retried, inserted) = merge();
+    assert_eq!(retried, first);
+    assert_eq!(inserted, 0);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 2);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_merge_insert_clauses_two_matched_clauses_error() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let initial = create_id_value_batch(&[1], &["a"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_two_matched_ffi", vec![initial]);
+
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[1], &["b"]));
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    let clauses = std::ffi::CString::new(
+        r#"[{"kind":"when_matched_delete"},{"kind":"when_matched_update"}]"#,
+    )
+    .unwrap();
+
+    table_merge_insert_clauses(
+        table_ptr,
+        on_columns.as_ptr(),
+        clauses.as_ptr(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        true,
+        -1,
+        -1,
+        false,
+        ptr::null(),
+        false,
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    free_string(error as *mut libc::c_char);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 1);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_merge_insert_clauses_setting_key_column_errors() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let initial = create_id_value_batch(&[1], &["a"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_clauses_key_ffi", vec![initial]);
+
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[1], &["b"]));
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    let clauses =
+        std::ffi::CString::new(r#"[{"kind":"when_matched_update","set":[["id","source.id + 1"]]}]"#).unwrap();
+
+    table_merge_insert_clauses(
+        table_ptr,
+        on_columns.as_ptr(),
+        clauses.as_ptr(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        true,
+        -1,
+        -1,
+        false,
+        ptr::null(),
+        false,
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    free_string(error as *mut libc::c_char);
+
     table_close(table_ptr);
     connection_close(conn_ptr);
 }
 
 #[test]
-fn test_table_delete_no_matching_rows_returns_zero() {
+fn test_table_merge_insert_clauses_returns_affected_rows() {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Int32Type, UInt64Type};
+
     let ctx = common::FfiTestContext::new();
     let tmp = TempDir::new().unwrap();
     let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
 
-    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
-    let table_ptr = common::create_table_with_data_sync(conn_ptr, "delete_none_ffi", vec![batch]);
+    let initial = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_affected_ffi", vec![initial]);
 
-    let predicate = std::ffi::CString::new("id > 100").unwrap();
-    table_delete(table_ptr, predicate.as_ptr(), common::ffi_callback, ctx.user_data());
+    let (mut ffi_array, mut ffi_schema) =
+        batch_to_cdata(&create_id_value_batch(&[2, 4], &["B", "D"]));
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    let clauses = std::ffi::CString::new(
+        r#"[
+            {"kind":"when_matched_update"},
+            {"kind":"when_not_matched_insert"},
+            {"kind":"when_not_matched_by_source_delete","condition":"target.id = 1"}
+        ]"#,
+    )
+    .unwrap();
+
+    table_merge_insert_clauses(
+        table_ptr,
+        on_columns.as_ptr(),
+        clauses.as_ptr(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        true,
+        -1,
+        -1,
+        true, // return_affected
+        ptr::null(),
+        false, // cast_to_table_schema
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
     let result = ctx.wait_success();
     assert!(!result.is_null());
 
-    let ffi_result = result as *mut FfiDeleteResult;
-    let delete_result = unsafe { &*ffi_result };
-    assert_eq!(delete_result.num_deleted_rows, 0);
+    let ffi_result = result as *mut FfiMergeResult;
+    let affected_ptr = unsafe { (*ffi_result).affected_rows };
+    assert!(!affected_ptr.is_null());
+    unsafe { (*ffi_result).affected_rows = ptr::null_mut() };
+    table_merge_result_free(ffi_result);
 
-    table_delete_result_free(ffi_result);
-    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
+    let affected = cdata_to_batch(affected_ptr as *const std::ffi::c_void);
+    assert_eq!(affected.num_rows(), 3);
+    let ids = affected.column_by_name("id").unwrap().as_primitive::<Int32Type>();
+    let actions = affected.column_by_name("action").unwrap().as_string::<i32>();
+    let row_ids = affected.column_by_name("_rowid").unwrap().as_primitive::<UInt64Type>();
+    let mut rows: Vec<(i32, String, bool)> = (0..affected.num_rows())
+        .map(|i| (ids.value(i), actions.value(i).to_string(), row_ids.is_valid(i)))
+        .collect();
+    rows.sort();
+    assert_eq!(
+        rows,
+        vec![
+            (1, "deleted".to_string(), false),
+            (2, "updated".to_string(), true),
+            (4, "inserted".to_string(), true),
+        ]
+    );
 
     table_close(table_ptr);
     connection_close(conn_ptr);
 }
 
-// ===== table_update FFI: returns FfiUpdateResult =====
-
 #[test]
-fn test_table_update_returns_update_result() {
+fn test_table_merge_insert_clauses_returns_affected_rows_updated_in_place() {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Int32Type, UInt64Type};
+
     let ctx = common::FfiTestContext::new();
     let tmp = TempDir::new().unwrap();
     let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
 
-    let batch = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
-    let table_ptr = common::create_table_with_data_sync(conn_ptr, "update_result_ffi", vec![batch]);
+    let initial = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_affected_in_place_ffi", vec![initial]);
+    // With an index on the key, lance updates a subset of columns in place.
+    common::create_btree_index_sync(table_ptr, "id");
 
-    let filter = std::ffi::CString::new("id >= 2").unwrap();
-    let columns_json = std::ffi::CString::new(r#"[["value","'updated'"]]"#).unwrap();
-    table_update(
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[2, 4], &["x", "y"]));
+    let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    let clauses =
+        std::ffi::CString::new(r#"[{"kind":"when_matched_update","set":[["value","upper(source.value)"]]}]"#)
+            .unwrap();
+
+    table_merge_insert_clauses(
         table_ptr,
-        filter.as_ptr(),
-        columns_json.as_ptr(),
+        on_columns.as_ptr(),
+        clauses.as_ptr(),
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        true,
+        -1,
+        -1,
+        true, // return_affected
+        ptr::null(),
+        false,
+        ptr::null(),
         common::ffi_callback,
         ctx.user_data(),
     );
     let result = ctx.wait_success();
     assert!(!result.is_null());
 
-    let ffi_result = result as *mut FfiUpdateResult;
-    let update_result = unsafe { &*ffi_result };
-    assert_eq!(update_result.rows_updated, 2);
-    assert!(update_result.version > 0);
+    let ffi_result = result as *mut FfiMergeResult;
+    let affected_ptr = unsafe { (*ffi_result).affected_rows };
+    assert!(!affected_ptr.is_null());
+    unsafe { (*ffi_result).affected_rows = ptr::null_mut() };
+    table_merge_result_free(ffi_result);
+
+    let affected = cdata_to_batch(affected_ptr as *const std::ffi::c_void);
+    assert_eq!(affected.num_rows(), 1);
+    assert_eq!(affected.column_by_name("id").unwrap().as_primitive::<Int32Type>().value(0), 2);
+    assert_eq!(affected.column_by_name("action").unwrap().as_string::<i32>().value(0), "updated");
+    assert!(affected.column_by_name("_rowid").unwrap().as_primitive::<UInt64Type>().is_valid(0));
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 2 AND value = 'X'".into())), 1);
 
-    table_update_result_free(ffi_result);
     table_close(table_ptr);
     connection_close(conn_ptr);
 }
 
-// ===== table_merge_insert FFI: returns FfiMergeResult =====
-
 #[test]
-fn test_table_merge_insert_returns_merge_result() {
+fn test_table_merge_insert_clauses_affected_rows_of_conditional_in_place_update_errors() {
     let ctx = common::FfiTestContext::new();
     let tmp = TempDir::new().unwrap();
     let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
 
-    let initial = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
-    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_result_ffi", vec![initial]);
+    let initial = create_id_value_batch(&[1, 2], &["a", "b"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "merge_affected_conditional_ffi", vec![initial]);
+    common::create_btree_index_sync(table_ptr, "id");
 
-    let (mut ffi_array, mut ffi_schema) =
-        batch_to_cdata(&create_id_value_batch(&[2, 3, 4, 5], &["B", "C", "D", "E"]));
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[1, 2], &["x", "skip"]));
     let on_columns = std::ffi::CString::new(r#"["id"]"#).unwrap();
+    let clauses = std::ffi::CString::new(
+        r#"[{"kind":"when_matched_update","condition":"source.value <> 'skip'","set":[["value","source.value"]]}]"#,
+    )
+    .unwrap();
 
-    table_merge_insert(
+    table_merge_insert_clauses(
         table_ptr,
         on_columns.as_ptr(),
-        true,          // when_matched_update_all
-        ptr::null(),
-        true,          // when_not_matched_insert_all
-        false,
-        ptr::null(),
+        clauses.as_ptr(),
         &mut ffi_array,
         &mut ffi_schema,
         1,
-        true,
+        true, // use_index
         -1,
-        -1,            // use_lsm_write (sentinel: leave default)
+        -1,
+        true, // return_affected
+        ptr::null(),
+        false,
+        ptr::null(),
         common::ffi_callback,
         ctx.user_data(),
     );
-    let result = ctx.wait_success();
-    assert!(!result.is_null());
-
-    let ffi_result = result as *mut FfiMergeResult;
-    let merge_result = unsafe { &*ffi_result };
-    assert!(merge_result.version > 0);
-    assert_eq!(merge_result.num_inserted_rows, 2);
-    assert_eq!(merge_result.num_updated_rows, 2);
-    assert_eq!(merge_result.num_deleted_rows, 0);
-
-    table_merge_result_free(ffi_result);
-    assert_eq!(common::count_rows_sync(table_ptr, None), 5);
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+    assert!(message.contains("use_index"), "{}", message);
+    free_string(error as *mut libc::c_char);
+    // The merge failed before committing.
+    assert_eq!(common::count_rows_sync(table_ptr, Some("value = 'x'".into())), 0);
 
     table_close(table_ptr);
     connection_close(conn_ptr);
@@ -840,6 +2591,220 @@ fn test_table_set_unenforced_primary_key_succeeds() {
     connection_close(conn_ptr);
 }
 
+fn set_primary_key_sync(table_ptr: *const lancedb::table::Table, columns_json: &str) {
+    let columns_json = std::ffi::CString::new(columns_json).unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_set_unenforced_primary_key(table_ptr, columns_json.as_ptr(), common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+}
+
+fn add_deduplicated_raw(
+    table_ptr: *const lancedb::table::Table,
+    batch: &RecordBatch,
+    on_duplicate: &str,
+) -> (*const std::ffi::c_void, *const libc::c_char) {
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(batch);
+    let on_duplicate = std::ffi::CString::new(on_duplicate).unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_add_deduplicated(
+        table_ptr,
+        &mut ffi_array,
+        &mut ffi_schema,
+        1,
+        on_duplicate.as_ptr(),
+        ptr::null(),
+        false,
+        ptr::null(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    ctx.wait_raw()
+}
+
+#[test]
+fn test_table_add_deduplicated_skip_and_overwrite() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let initial = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "add_dedup_ffi", vec![initial]);
+    set_primary_key_sync(table_ptr, r#"["id"]"#);
+
+    // 3 exists and 4 repeats within the data: one insert, two skipped.
+    let (result, error) =
+        add_deduplicated_raw(table_ptr, &create_id_value_batch(&[3, 4, 4], &["x", "d", "y"]), "skip");
+    assert!(error.is_null());
+    let skip = result as *mut FfiAddResult;
+    assert_eq!(unsafe { &*skip }.num_inserted_rows, 1);
+    assert_eq!(unsafe { &*skip }.num_skipped_rows, 2);
+    table_add_result_free(skip);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 4);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 3 AND value = 'c'".into())), 1);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 4 AND value = 'd'".into())), 1);
+
+    let (result, error) =
+        add_deduplicated_raw(table_ptr, &create_id_value_batch(&[1, 5], &["A", "e"]), "overwrite");
+    assert!(error.is_null());
+    let overwrite = result as *mut FfiAddResult;
+    assert_eq!(unsafe { &*overwrite }.num_inserted_rows, 1);
+    assert_eq!(unsafe { &*overwrite }.num_updated_rows, 1);
+    table_add_result_free(overwrite);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 5);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 1 AND value = 'A'".into())), 1);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+fn version_sync(table_ptr: *const lancedb::table::Table) -> u64 {
+    let ctx = common::FfiTestContext::new();
+    table_version(table_ptr, common::ffi_callback, ctx.user_data());
+    ctx.wait_success() as u64
+}
+
+#[test]
+fn test_table_add_deduplicated_error_writes_nothing() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let initial = create_id_value_batch(&[1, 2], &["a", "b"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "add_dedup_error_ffi", vec![initial]);
+    set_primary_key_sync(table_ptr, r#"["id"]"#);
+
+    let (result, error) =
+        add_deduplicated_raw(table_ptr, &create_id_value_batch(&[3, 4], &["c", "d"]), "error");
+    assert!(error.is_null());
+    let added = result as *mut FfiAddResult;
+    assert_eq!(unsafe { &*added }.num_inserted_rows, 2);
+    let version = unsafe { &*added }.version;
+    table_add_result_free(added);
+
+    // Only key 2 exists, yet the new key 5 is not written either.
+    let (result, error) =
+        add_deduplicated_raw(table_ptr, &create_id_value_batch(&[2, 5], &["x", "e"]), "error");
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+    assert!(message.contains("1 rows have primary keys that already exist"));
+    free_string(error as *mut libc::c_char);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 4);
+    assert_eq!(version_sync(table_ptr), version);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_get_by_keys_returns_rows_in_key_order_with_nulls_for_misses() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let initial = create_id_value_batch(&[1, 2, 3], &["a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "get_by_keys_ffi", vec![initial]);
+    set_primary_key_sync(table_ptr, r#"["id"]"#);
+
+    // Int64 keys are cast to the Int32 key column.
+    let keys = arrow_array::Int64Array::from(vec![Some(3), Some(9), None, Some(1)]);
+    let data = keys.to_data();
+    let mut ffi_array = arrow_data::ffi::FFI_ArrowArray::new(&data);
+    let mut ffi_schema = arrow_schema::ffi::FFI_ArrowSchema::try_from(data.data_type()).unwrap();
+    let columns = std::ffi::CString::new(r#"["value"]"#).unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_get_by_keys(
+        table_ptr,
+        &mut ffi_array,
+        &mut ffi_schema,
+        columns.as_ptr(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    let cdata = ctx.wait_success() as *mut FfiCData;
+    let (array_ptr, schema_ptr) = unsafe { ((*cdata).array, (*cdata).schema) };
+    let schema = unsafe { arrow_schema::ffi::FFI_ArrowSchema::from_raw(schema_ptr) };
+    let array = unsafe { arrow_data::ffi::FFI_ArrowArray::from_raw(array_ptr) };
+    let data = unsafe { arrow_array::ffi::from_ffi(array, &schema).unwrap() };
+    unsafe { drop(Box::from_raw(cdata)) };
+    let batch = RecordBatch::from(arrow_array::StructArray::from(data));
+
+    assert_eq!(batch.num_columns(), 1);
+    let values = batch.column_by_name("value").unwrap().as_any().downcast_ref::<StringArray>().unwrap();
+    let values: Vec<Option<&str>> = values.iter().collect();
+    assert_eq!(values, vec![Some("c"), None, None, Some("a")]);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+/// Looks up `keys` with table_get_by_keys, returning the rows or the error message.
+fn get_by_keys_sync(table_ptr: *const lancedb::table::Table, keys: &dyn Array) -> Result<RecordBatch, String> {
+    let data = keys.to_data();
+    let mut ffi_array = arrow_data::ffi::FFI_ArrowArray::new(&data);
+    let mut ffi_schema = arrow_schema::ffi::FFI_ArrowSchema::try_from(data.data_type()).unwrap();
+    let ctx = common::FfiTestContext::new();
+    table_get_by_keys(table_ptr, &mut ffi_array, &mut ffi_schema, ptr::null(), common::ffi_callback, ctx.user_data());
+    let (result, error) = ctx.wait_raw();
+    if !error.is_null() {
+        let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().into_owned();
+        free_string(error as *mut libc::c_char);
+        return Err(message);
+    }
+    let cdata = result as *mut FfiCData;
+    let (array_ptr, schema_ptr) = unsafe { ((*cdata).array, (*cdata).schema) };
+    let schema = unsafe { arrow_schema::ffi::FFI_ArrowSchema::from_raw(schema_ptr) };
+    let array = unsafe { arrow_data::ffi::FFI_ArrowArray::from_raw(array_ptr) };
+    let data = unsafe { arrow_array::ffi::from_ffi(array, &schema).unwrap() };
+    unsafe { drop(Box::from_raw(cdata)) };
+    Ok(RecordBatch::from(arrow_array::StructArray::from(data)))
+}
+
+#[test]
+fn test_table_get_by_keys_matches_fixed_size_binary_keys() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::FixedSizeBinary(4), false),
+        Field::new("value", DataType::Utf8, true),
+    ]));
+    let ids = arrow_array::FixedSizeBinaryArray::try_from_iter(
+        vec![b"\x00\x01'a".to_vec(), b"bbbb".to_vec()].into_iter(),
+    )
+    .unwrap();
+    let initial = RecordBatch::try_new(
+        schema,
+        vec![Arc::new(ids), Arc::new(StringArray::from(vec!["first", "second"]))],
+    )
+    .unwrap();
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "get_by_binary_keys_ffi", vec![initial]);
+    set_primary_key_sync(table_ptr, r#"["id"]"#);
+
+    let keys = arrow_array::FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+        vec![Some(b"bbbb".to_vec()), Some(b"zzzz".to_vec()), Some(b"\x00\x01'a".to_vec())].into_iter(),
+        4,
+    )
+    .unwrap();
+    let batch = get_by_keys_sync(table_ptr, &keys).unwrap();
+    let values = batch.column_by_name("value").unwrap().as_any().downcast_ref::<StringArray>().unwrap();
+    let values: Vec<Option<&str>> = values.iter().collect();
+    assert_eq!(values, vec![Some("second"), None, Some("first")]);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_get_by_keys_rejects_keys_out_of_the_key_type_range() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let initial = create_id_value_batch(&[1], &["a"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "get_by_keys_range_ffi", vec![initial]);
+    set_primary_key_sync(table_ptr, r#"["id"]"#);
+
+    // The Int32 key column cannot hold this key, which must not turn into a null.
+    let keys = arrow_array::Int64Array::from(vec![1, i64::from(i32::MAX) + 1]);
+    let message = get_by_keys_sync(table_ptr, &keys).unwrap_err();
+    assert!(message.contains("Int32"), "{}", message);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
 #[test]
 fn test_table_set_unset_lsm_write_spec_unsharded_round_trips() {
     let tmp = TempDir::new().unwrap();
@@ -1168,3 +3133,66 @@ fn test_table_merge_insert_lsm_path_then_close_lsm_writers() {
     table_close(table_ptr);
     connection_close(conn_ptr);
 }
+
+// ===== table_aggregate FFI =====
+
+#[test]
+fn test_table_aggregate_group_by_with_filter_returns_rows() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+
+    let batch = create_id_value_batch(&[1, 2, 3, 4, 5, 6], &["a", "b", "a", "a", "b", "c"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "aggregate_ffi", vec![batch]);
+
+    let spec = std::ffi::CString::new(
+        r#"{"filter":"id > 1","group_by":["value"],"aggregates":[{"function":"count","alias":"n"},{"function":"max","column":"id"}]}"#,
+    )
+    .unwrap();
+    table_aggregate(table_ptr, spec.as_ptr(), common::ffi_callback, ctx.user_data());
+    let batch = cdata_to_batch(ctx.wait_success());
+
+    assert_eq!(batch.num_rows(), 3);
+    let names: Vec<&str> = batch.schema().fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, vec!["value", "n", "max_id"]);
+
+    let values = batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+    let counts = batch.column(1).as_any().downcast_ref::<arrow_array::Int64Array>().unwrap();
+    let maxes = batch.column(2).as_any().downcast_ref::<Int32Array>().unwrap();
+    for i in 0..batch.num_rows() {
+        let (n, max) = match values.value(i) {
+            "a" => (2, 4),
+            "b" => (2, 5),
+            "c" => (1, 6),
+            other => panic!("unexpected group {}", other),
+        };
+        assert_eq!(counts.value(i), n);
+        assert_eq!(maxes.value(i), max);
+    }
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_table_aggregate_unknown_function_returns_error() {
+    let ctx = common::FfiTestContext::new();
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let table_ptr = common::create_table_with_data_sync(
+        conn_ptr,
+        "aggregate_bad_ffi",
+        vec![create_test_batch(3)],
+    );
+
+    let spec = std::ffi::CString::new(r#"{"aggregates":[{"function":"median","column":"id"}]}"#)
+        .unwrap();
+    table_aggregate(table_ptr, spec.as_ptr(), common::ffi_callback, ctx.user_data());
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    free_string(error as *mut libc::c_char);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
@@ -0,0 +1,350 @@
+//! Tests for transaction FFI functions.
+
+mod common;
+
+use arrow_array::{Int32Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use lancedb_ffi::*;
+use std::ffi::CString;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn create_id_value_batch(ids: &[i32], values: &[&str]) -> RecordBatch {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("value", DataType::Utf8, true),
+    ]));
+    RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(Int32Array::from(ids.to_vec())),
+            Arc::new(StringArray::from(values.to_vec())),
+        ],
+    )
+    .unwrap()
+}
+
+fn batch_to_cdata(
+    batch: &RecordBatch,
+) -> (arrow_data::ffi::FFI_ArrowArray, arrow_schema::ffi::FFI_ArrowSchema) {
+    use arrow_array::Array;
+    let struct_array: arrow_array::StructArray = batch.clone().into();
+    let data = struct_array.to_data();
+    let ffi_array = arrow_data::ffi::FFI_ArrowArray::new(&data);
+    let ffi_schema =
+        arrow_schema::ffi::FFI_ArrowSchema::try_from(data.data_type()).unwrap();
+    (ffi_array, ffi_schema)
+}
+
+fn begin_sync(table_ptr: *const lancedb::table::Table) -> *const Transaction {
+    let ctx = common::FfiTestContext::new();
+    let on = CString::new(r#"["id"]"#).unwrap();
+    table_begin_transaction(table_ptr, on.as_ptr(), common::ffi_callback, ctx.user_data());
+    ctx.wait_success() as *const Transaction
+}
+
+#[test]
+fn test_transaction_commit_applies_staged_ops_as_one_version() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let initial = create_id_value_batch(&[1, 2, 3], &["doc-a", "doc-a", "doc-b"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "txn_commit_ffi", vec![initial]);
+
+    let txn = begin_sync(table_ptr);
+    let base_version = transaction_base_version(txn);
+
+    // Replace the chunks of doc-a and retag doc-b.
+    let ctx = common::FfiTestContext::new();
+    let predicate = CString::new("value = 'doc-a'").unwrap();
+    transaction_delete(txn, predicate.as_ptr(), common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+
+    let ctx = common::FfiTestContext::new();
+    let (mut ffi_array, mut ffi_schema) =
+        batch_to_cdata(&create_id_value_batch(&[10, 11], &["doc-a", "doc-a"]));
+    transaction_add(txn, &mut ffi_array, &mut ffi_schema, 1, common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+
+    let ctx = common::FfiTestContext::new();
+    let filter = CString::new("id = 3").unwrap();
+    let columns = CString::new(r#"[["value","'doc-b2'"]]"#).unwrap();
+    transaction_update(txn, filter.as_ptr(), columns.as_ptr(), common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+
+    // Nothing is visible before the commit.
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id >= 10".into())), 0);
+
+    let ctx = common::FfiTestContext::new();
+    transaction_commit(txn, common::ffi_callback, ctx.user_data());
+    let result = ctx.wait_success() as *mut FfiMergeResult;
+    let merge_result = unsafe { &*result };
+    assert_eq!(merge_result.version, base_version + 1);
+    assert_eq!(merge_result.num_inserted_rows, 2);
+    assert_eq!(merge_result.num_updated_rows, 1);
+    assert_eq!(merge_result.num_deleted_rows, 2);
+    table_merge_result_free(result);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 3);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("value = 'doc-a'".into())), 2);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 3 AND value = 'doc-b2'".into())), 1);
+
+    transaction_close(txn);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+fn commit_sync(txn: *const Transaction) -> (*const std::ffi::c_void, *const libc::c_char) {
+    let ctx = common::FfiTestContext::new();
+    transaction_commit(txn, common::ffi_callback, ctx.user_data());
+    ctx.wait_raw()
+}
+
+#[test]
+fn test_transaction_commit_after_concurrent_change_to_same_row_conflicts() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let initial = create_id_value_batch(&[1, 2], &["a", "b"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "txn_conflict_ffi", vec![initial]);
+
+    let txn = begin_sync(table_ptr);
+    let ctx = common::FfiTestContext::new();
+    let filter = CString::new("id = 1").unwrap();
+    let columns = CString::new(r#"[["value","'a2'"]]"#).unwrap();
+    transaction_update(txn, filter.as_ptr(), columns.as_ptr(), common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+
+    // Another writer deletes the same row after the transaction began.
+    let other = begin_sync(table_ptr);
+    let ctx = common::FfiTestContext::new();
+    let predicate = CString::new("id = 1").unwrap();
+    transaction_delete(other, predicate.as_ptr(), common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+    let (result, error) = commit_sync(other);
+    assert!(error.is_null());
+    table_merge_result_free(result as *mut FfiMergeResult);
+
+    let (result, error) = commit_sync(txn);
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(error) }.to_string_lossy().to_lowercase();
+    assert!(message.contains("conflict"));
+    free_string(error as *mut libc::c_char);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 1);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("value = 'a2'".into())), 0);
+
+    transaction_close(other);
+    transaction_close(txn);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_transaction_commit_after_concurrent_append_keeps_both() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let initial = create_id_value_batch(&[1], &["a"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "txn_append_ffi", vec![initial]);
+
+    let txn = begin_sync(table_ptr);
+    let ctx = common::FfiTestContext::new();
+    let predicate = CString::new("id = 1").unwrap();
+    transaction_delete(txn, predicate.as_ptr(), common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+
+    common::add_sync(table_ptr, vec![create_id_value_batch(&[2], &["b"])]);
+
+    let (result, error) = commit_sync(txn);
+    assert!(error.is_null());
+    let merge_result = unsafe { &*(result as *mut FfiMergeResult) };
+    assert_eq!(merge_result.num_deleted_rows, 1);
+    table_merge_result_free(result as *mut FfiMergeResult);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 1);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 2".into())), 1);
+
+    transaction_close(txn);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_transaction_add_with_existing_key_errors() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let initial = create_id_value_batch(&[1], &["a"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "txn_existing_key_ffi", vec![initial]);
+
+    let txn = begin_sync(table_ptr);
+    let ctx = common::FfiTestContext::new();
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[1], &["dup"]));
+    transaction_add(txn, &mut ffi_array, &mut ffi_schema, 1, common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+
+    let (result, error) = commit_sync(txn);
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    free_string(error as *mut libc::c_char);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 1);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("value = 'a'".into())), 1);
+
+    transaction_close(txn);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_transaction_rollback_discards_ops_and_closes() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let initial = create_id_value_batch(&[1], &["a"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "txn_rollback_ffi", vec![initial]);
+
+    let txn = begin_sync(table_ptr);
+    let ctx = common::FfiTestContext::new();
+    let predicate = CString::new("id = 1").unwrap();
+    transaction_delete(txn, predicate.as_ptr(), common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+
+    let ctx = common::FfiTestContext::new();
+    transaction_rollback(txn, common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+
+    let ctx = common::FfiTestContext::new();
+    transaction_commit(txn, common::ffi_callback, ctx.user_data());
+    let (result, error) = ctx.wait_raw();
+    assert!(result.is_null());
+    assert!(!error.is_null());
+    free_string(error as *mut libc::c_char);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 1);
+
+    transaction_close(txn);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+fn begin_without_keys_sync(table_ptr: *const lancedb::table::Table) -> *const Transaction {
+    let ctx = common::FfiTestContext::new();
+    table_begin_transaction(table_ptr, std::ptr::null(), common::ffi_callback, ctx.user_data());
+    ctx.wait_success() as *const Transaction
+}
+
+#[test]
+fn test_transaction_without_keys_allows_repeated_values() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let initial = create_id_value_batch(&[1], &["a"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "txn_no_keys_ffi", vec![initial]);
+
+    let txn = begin_without_keys_sync(table_ptr);
+    let ctx = common::FfiTestContext::new();
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[1, 1], &["b", "c"]));
+    transaction_add(txn, &mut ffi_array, &mut ffi_schema, 1, common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+
+    let (result, error) = commit_sync(txn);
+    assert!(error.is_null());
+    let merge_result = unsafe { &*(result as *mut FfiMergeResult) };
+    assert_eq!(merge_result.num_inserted_rows, 2);
+    table_merge_result_free(result as *mut FfiMergeResult);
+
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 1".into())), 3);
+
+    transaction_close(txn);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+#[test]
+fn test_transaction_add_columns_applies_to_staged_rows() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let initial = create_id_value_batch(&[1, 2], &["a", "b"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "txn_add_columns_ffi", vec![initial]);
+
+    let txn = begin_sync(table_ptr);
+    let ctx = common::FfiTestContext::new();
+    let predicate = CString::new("id = 2").unwrap();
+    transaction_delete(txn, predicate.as_ptr(), common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+
+    let ctx = common::FfiTestContext::new();
+    let transforms = CString::new(r#"[["doubled","id * 2"]]"#).unwrap();
+    transaction_add_columns(txn, transforms.as_ptr(), common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+
+    // Added after the new column, so it is filled with a null.
+    let ctx = common::FfiTestContext::new();
+    let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[3], &["c"]));
+    transaction_add(txn, &mut ffi_array, &mut ffi_schema, 1, common::ffi_callback, ctx.user_data());
+    ctx.wait_success();
+
+    // Nothing is visible before the commit.
+    assert_eq!(common::count_rows_sync(table_ptr, None), 2);
+
+    let (result, error) = commit_sync(txn);
+    assert!(error.is_null());
+    let merge_result = unsafe { &*(result as *mut FfiMergeResult) };
+    assert_eq!(merge_result.num_inserted_rows, 1);
+    assert_eq!(merge_result.num_deleted_rows, 1);
+    table_merge_result_free(result as *mut FfiMergeResult);
+
+    assert_eq!(common::count_rows_sync(table_ptr, None), 2);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 1 AND doubled = 2".into())), 1);
+    assert_eq!(common::count_rows_sync(table_ptr, Some("id = 3 AND doubled IS NULL".into())), 1);
+
+    transaction_close(txn);
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
+
+fn commit_with_options_sync(
+    txn: *const Transaction,
+    write_options: &str,
+    commit_metadata: &str,
+) -> *mut FfiMergeResult {
+    let write_options = CString::new(write_options).unwrap();
+    let commit_metadata = CString::new(commit_metadata).unwrap();
+    let ctx = common::FfiTestContext::new();
+    transaction_commit_with_options(
+        txn,
+        write_options.as_ptr(),
+        commit_metadata.as_ptr(),
+        common::ffi_callback,
+        ctx.user_data(),
+    );
+    ctx.wait_success() as *mut FfiMergeResult
+}
+
+#[test]
+fn test_transaction_commit_with_idempotency_key_applies_once() {
+    let tmp = TempDir::new().unwrap();
+    let conn_ptr = common::connect_sync(tmp.path().to_str().unwrap());
+    let initial = create_id_value_batch(&[1], &["a"]);
+    let table_ptr = common::create_table_with_data_sync(conn_ptr, "txn_idempotent_ffi", vec![initial]);
+
+    let mut versions = Vec::new();
+    for expected_inserted in [1, 0] {
+        let txn = begin_sync(table_ptr);
+        let ctx = common::FfiTestContext::new();
+        let (mut ffi_array, mut ffi_schema) = batch_to_cdata(&create_id_value_batch(&[2], &["b"]));
+        transaction_add(txn, &mut ffi_array, &mut ffi_schema, 1, common::ffi_callback, ctx.user_data());
+        ctx.wait_success();
+
+        let result = commit_with_options_sync(txn, r#"{"idempotency_key":"txn-7"}"#, r#"{"job_id":"sync-7"}"#);
+        let merge_result = unsafe { &*result };
+        assert_eq!(merge_result.num_inserted_rows, expected_inserted);
+        versions.push(merge_result.version);
+        table_merge_result_free(result);
+        transaction_close(txn);
+    }
+
+    // The replay reports the version of the first commit and writes nothing.
+    assert_eq!(versions[0], versions[1]);
+    assert_eq!(common::count_rows_sync(table_ptr, None), 2);
+
+    table_close(table_ptr);
+    connection_close(conn_ptr);
+}
@@ -0,0 +1,88 @@
+namespace lancedb
+{
+    using System.Collections.Generic;
+
+    /// <summary>
+    /// A single aggregate computed by <see cref="Table.Aggregate"/>.
+    /// </summary>
+    /// <remarks>
+    /// Create instances with the static factory methods and optionally rename the
+    /// output column with <see cref="As"/>. Without an alias the output column is
+    /// named <c>&lt;function&gt;_&lt;column&gt;</c>, or <c>count</c> for <see cref="Count()"/>.
+    /// </remarks>
+    public sealed class Aggregate
+    {
+        private readonly string _function;
+        private readonly string? _column;
+        private readonly string? _alias;
+
+        private Aggregate(string function, string? column, string? alias = null)
+        {
+            _function = function;
+            _column = column;
+            _alias = alias;
+        }
+
+        /// <summary>
+        /// Count all rows (<c>COUNT(*)</c>).
+        /// </summary>
+        public static Aggregate Count() => new Aggregate("count", null);
+
+        /// <summary>
+        /// Count the non-null values of a column.
+        /// </summary>
+        /// <param name="column">The column to count.</param>
+        public static Aggregate Count(string column) => new Aggregate("count", column);
+
+        /// <summary>
+        /// Count the distinct non-null values of a column.
+        /// </summary>
+        /// <param name="column">The column to count.</param>
+        public static Aggregate CountDistinct(string column) => new Aggregate("count_distinct", column);
+
+        /// <summary>
+        /// Sum a numeric column.
+        /// </summary>
+        /// <param name="column">The column to sum.</param>
+        public static Aggregate Sum(string column) => new Aggregate("sum", column);
+
+        /// <summary>
+        /// The minimum value of a column.
+        /// </summary>
+        /// <param name="column">The column to inspect.</param>
+        public static Aggregate Min(string column) => new Aggregate("min", column);
+
+        /// <summary>
+        /// The maximum value of a column.
+        /// </summary>
+        /// <param name="column">The column to inspect.</param>
+        public static Aggregate Max(string column) => new Aggregate("max", column);
+
+        /// <summary>
+        /// The average of a numeric column.
+        /// </summary>
+        /// <param name="column">The column to average.</param>
+        public static Aggregate Avg(string column) => new Aggregate("avg", column);
+
+        /// <summary>
+        /// Name the output column of this aggregate.
+        /// </summary>
+        /// <param name="alias">The output column name.</param>
+        /// <returns>A copy of this aggregate with the alias applied.</returns>
+        public Aggregate As(string alias) => new Aggregate(_function, _column, alias);
+
+        internal Dictionary<string, object> ToJsonDict()
+        {
+            var dict = new Dictionary<string, object> { ["function"] = _function };
+            if (_column != null)
+            {
+                dict["column"] = _column;
+            }
+            if (_alias != null)
+            {
+                dict["alias"] = _alias;
+            }
+            return dict;
+        }
+    }
+}
@@ -37,6 +37,15 @@ namespace lancedb
             return new RecordBatch(batch.Schema, columns, batch.Length);
         }
 
+        /// <summary>
+        /// Deep-clones a single array for non-destructive C Data Interface export,
+        /// as <see cref="CloneBatchForExport"/> does for each column of a batch.
+        /// </summary>
+        internal static IArrowArray CloneArrayForExport(IArrowArray array)
+        {
+            return ArrowArrayFactory.BuildArray(CloneArrayData(array.Data));
+        }
+
         /// <summary>
         /// Imports a RecordBatch from an FfiCData pointer produced by Rust
         /// and frees the pointer via <c>free_ffi_cdata</c> before returning.
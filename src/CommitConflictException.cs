@@ -0,0 +1,29 @@
+namespace lancedb
+{
+    /// <summary>
+    /// Exception thrown when a write could not be committed because a concurrent
+    /// writer committed a conflicting change, and no retries remained.
+    /// </summary>
+    /// <remarks>
+    /// The number of retries is configured with <see cref="WriteOptions"/>.
+    /// </remarks>
+    public class CommitConflictException : LanceDbException
+    {
+        /// <summary>
+        /// The version committed by the concurrent writer, if the storage layer reported it.
+        /// </summary>
+        public ulong? ConflictingVersion { get; }
+
+        /// <summary>
+        /// The number of times the write was attempted before giving up.
+        /// </summary>
+        public int Attempts { get; }
+
+        public CommitConflictException(string message, ulong? conflictingVersion, int attempts)
+            : base(message)
+        {
+            ConflictingVersion = conflictingVersion;
+            Attempts = attempts;
+        }
+    }
+}
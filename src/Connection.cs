@@ -32,14 +32,20 @@ namespace lancedb
         private static extern void connection_open_table(IntPtr connection_ptr, IntPtr table_name, IntPtr storage_options_json, uint index_cache_size, IntPtr location, IntPtr namespace_json, NativeCall.FfiCallback completion, IntPtr userData);
 
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
-        private static extern unsafe void connection_create_empty_table(IntPtr connection_ptr, IntPtr table_name, CArrowSchema* schema_cdata, IntPtr mode, IntPtr storage_options_json, IntPtr location, IntPtr namespace_json, [MarshalAs(UnmanagedType.U1)] bool exist_ok, NativeCall.FfiCallback completion, IntPtr userData);
+        private static extern unsafe void connection_create_empty_table_with_options(IntPtr connection_ptr, IntPtr table_name, CArrowSchema* schema_cdata, IntPtr mode, IntPtr storage_options_json, IntPtr location, IntPtr namespace_json, [MarshalAs(UnmanagedType.U1)] bool exist_ok, IntPtr create_options_json, NativeCall.FfiCallback completion, IntPtr userData);
 
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
-        private static extern unsafe void connection_create_table(IntPtr connection_ptr, IntPtr table_name, CArrowArray* arrays, CArrowSchema* schema, nuint batch_count, IntPtr mode, IntPtr storage_options_json, IntPtr location, IntPtr namespace_json, [MarshalAs(UnmanagedType.U1)] bool exist_ok, NativeCall.FfiCallback completion, IntPtr userData);
+        private static extern unsafe void connection_create_table_with_options(IntPtr connection_ptr, IntPtr table_name, CArrowArray* arrays, CArrowSchema* schema, nuint batch_count, IntPtr mode, IntPtr storage_options_json, IntPtr location, IntPtr namespace_json, [MarshalAs(UnmanagedType.U1)] bool exist_ok, CArrowSchema* target_schema, IntPtr create_options_json, NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern unsafe void connection_create_table_from_files(IntPtr connection_ptr, IntPtr table_name, IntPtr paths_json, IntPtr format, IntPtr read_options_json, CArrowSchema* target_schema, IntPtr mode, IntPtr storage_options_json, IntPtr location, IntPtr namespace_json, IntPtr create_options_json, [MarshalAs(UnmanagedType.U1)] bool exist_ok, NativeCall.FfiCallback completion, IntPtr userData);
 
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern void connection_table_names(IntPtr connection_ptr, IntPtr start_after, uint limit, IntPtr namespace_json, NativeCall.FfiCallback completion, IntPtr userData);
 
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void connection_sql(IntPtr connection_ptr, IntPtr sql, IntPtr namespace_json, NativeCall.FfiCallback completion, IntPtr userData);
+
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern void connection_list_tables(IntPtr connection_ptr, IntPtr page_token, uint limit, IntPtr namespace_json, NativeCall.FfiCallback completion, IntPtr userData);
 
@@ -68,6 +74,7 @@ namespace lancedb
         private static extern void connection_describe_namespace(IntPtr connection_ptr, IntPtr namespace_json, NativeCall.FfiCallback completion, IntPtr userData);
 
         private ConnectionHandle? _handle;
+        private Dictionary<string, string>? _storageOptions;
 
         public Connection()
         {
@@ -120,6 +127,7 @@ namespace lancedb
                 }
             }).ConfigureAwait(false);
             _handle = new ConnectionHandle(ptr);
+            _storageOptions = options?.StorageOptions;
         }
 
         /// <summary>
@@ -172,6 +180,7 @@ namespace lancedb
                 }
             }).ConfigureAwait(false);
             _handle = new ConnectionHandle(ptr);
+            _storageOptions = options?.StorageOptions;
         }
 
         /// <summary>
@@ -276,6 +285,7 @@ namespace lancedb
                 ? JsonSerializer.SerializeToUtf8Bytes(options.Namespace)
                 : null;
             bool existOk = options?.ExistOk ?? false;
+            byte[]? createOptionsJson = options?.CreateOptionsJsonUtf8();
 
             IntPtr tablePtr = await NativeCall.Async((callback, userData) =>
             {
@@ -286,6 +296,7 @@ namespace lancedb
                     fixed (byte* pStorage = storageJson)
                     fixed (byte* pLocation = locationBytes)
                     fixed (byte* pNamespace = namespaceJson)
+                    fixed (byte* pCreateOptions = createOptionsJson)
                     {
                         var cSchemaArr = new CArrowSchema[1];
                         fixed (CArrowSchema* pSchema = cSchemaArr)
@@ -296,7 +307,7 @@ namespace lancedb
                                 CArrowSchemaExporter.ExportSchema(options.Schema, pSchema);
                                 schemaPtr = pSchema;
                             }
-                            connection_create_empty_table(
+                            connection_create_empty_table_with_options(
                                 _handle!.DangerousGetHandle(),
                                 new IntPtr(pName),
                                 schemaPtr,
@@ -305,6 +316,7 @@ namespace lancedb
                                 locationBytes != null ? new IntPtr(pLocation) : IntPtr.Zero,
                                 namespaceJson != null ? new IntPtr(pNamespace) : IntPtr.Zero,
                                 existOk,
+                                (IntPtr)pCreateOptions,
                                 callback, userData);
                         }
                     }
@@ -353,6 +365,7 @@ namespace lancedb
             byte[]? namespaceJson = options.Namespace != null
                 ? JsonSerializer.SerializeToUtf8Bytes(options.Namespace)
                 : null;
+            byte[]? createOptionsJson = options.CreateOptionsJsonUtf8();
 
             IntPtr tablePtr = await NativeCall.Async((callback, userData) =>
             {
@@ -363,11 +376,18 @@ namespace lancedb
                     fixed (byte* pStorage = storageJson)
                     fixed (byte* pLocation = locationBytes)
                     fixed (byte* pNamespace = namespaceJson)
+                    fixed (byte* pCreateOptions = createOptionsJson)
                     {
                         var cArrays = new CArrowArray[options.Data.Count];
-                        var cSchemaArr = new CArrowSchema[1];
+                        var cSchemaArr = new CArrowSchema[2];
                         fixed (CArrowSchema* pSchema = cSchemaArr)
                         {
+                            CArrowSchema* targetSchemaPtr = null;
+                            if (options.Schema != null)
+                            {
+                                targetSchemaPtr = pSchema + 1;
+                                CArrowSchemaExporter.ExportSchema(options.Schema, targetSchemaPtr);
+                            }
                             CArrowSchemaExporter.ExportSchema(options.Data[0].Schema, pSchema);
                             for (int i = 0; i < options.Data.Count; i++)
                             {
@@ -380,7 +400,7 @@ namespace lancedb
                             }
                             fixed (CArrowArray* pArrays = cArrays)
                             {
-                                connection_create_table(
+                                connection_create_table_with_options(
                                     _handle!.DangerousGetHandle(),
                                     (IntPtr)pName,
                                     pArrays, pSchema, (nuint)options.Data.Count,
@@ -389,6 +409,8 @@ namespace lancedb
                                     locationBytes != null ? new IntPtr(pLocation) : IntPtr.Zero,
                                     namespaceJson != null ? new IntPtr(pNamespace) : IntPtr.Zero,
                                     options.ExistOk,
+                                    targetSchemaPtr,
+                                    (IntPtr)pCreateOptions,
                                     callback, userData);
                             }
                         }
@@ -426,6 +448,110 @@ namespace lancedb
             return CreateTable(name, new CreateTableOptions { Data = new[] { data }, Mode = mode });
         }
 
+        /// <summary>
+        /// Create a <see cref="Table"/> from Parquet, CSV or newline-delimited JSON files.
+        /// </summary>
+        /// <param name="name">The name of the table.</param>
+        /// <param name="paths">
+        /// The files or directories to read: local paths or object store URIs. They are
+        /// read natively with the connection's storage options, overridden by
+        /// <see cref="CreateTableOptions.StorageOptions"/>.
+        /// </param>
+        /// <param name="readOptions">The file format and how to parse it. Default is Parquet.</param>
+        /// <param name="options">
+        /// Options to control the create behavior. <see cref="CreateTableOptions.Schema"/>
+        /// sets the table schema: CSV and JSON files are parsed with it and the data is
+        /// cast into it. Without it, Parquet files keep their schema and the column types
+        /// of CSV and JSON files are inferred. <see cref="CreateTableOptions.Data"/> is
+        /// not used.
+        /// </param>
+        /// <returns>A <see cref="Table"/> representing the newly created table.</returns>
+        /// <exception cref="LanceDbException">
+        /// Thrown if a table with the same name already exists and mode is <c>"create"</c>.
+        /// </exception>
+        /// <remarks>
+        /// A new table is created empty and the file data is added as its next version;
+        /// if the import fails, the table is dropped again. With mode <c>"overwrite"</c>,
+        /// an existing table is replaced by the file data in a single version, so it keeps
+        /// its data if the import fails. With <see cref="CreateTableOptions.ExistOk"/>, an
+        /// existing table is returned without reading the files. Only local tables are
+        /// supported.
+        /// </remarks>
+        public async Task<Table> CreateTableFromFiles(
+            string name, IReadOnlyList<string> paths, FileReadOptions? readOptions = null,
+            CreateTableOptions? options = null)
+        {
+            readOptions ??= new FileReadOptions();
+            byte[] nameBytes = NativeCall.ToUtf8(name);
+            byte[] pathsJson = JsonSerializer.SerializeToUtf8Bytes(paths);
+            byte[] formatBytes = NativeCall.ToUtf8(readOptions.Format);
+            byte[] readOptionsJson = readOptions.ToJsonUtf8();
+            byte[] modeBytes = NativeCall.ToUtf8(options?.Mode ?? "create");
+            Dictionary<string, string>? storageOptions = null;
+            if (_storageOptions != null || options?.StorageOptions != null)
+            {
+                storageOptions = new Dictionary<string, string>(_storageOptions ?? new Dictionary<string, string>());
+                foreach (var entry in options?.StorageOptions ?? new Dictionary<string, string>())
+                {
+                    storageOptions[entry.Key] = entry.Value;
+                }
+            }
+            byte[]? storageJson = storageOptions != null
+                ? JsonSerializer.SerializeToUtf8Bytes(storageOptions)
+                : null;
+            byte[]? locationBytes = options?.Location != null
+                ? NativeCall.ToUtf8(options.Location)
+                : null;
+            byte[]? namespaceJson = options?.Namespace != null
+                ? JsonSerializer.SerializeToUtf8Bytes(options.Namespace)
+                : null;
+            byte[]? createOptionsJson = options?.CreateOptionsJsonUtf8();
+            bool existOk = options?.ExistOk ?? false;
+
+            IntPtr tablePtr = await NativeCall.Async((callback, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* pName = nameBytes)
+                    fixed (byte* pPaths = pathsJson)
+                    fixed (byte* pFormat = formatBytes)
+                    fixed (byte* pReadOptions = readOptionsJson)
+                    fixed (byte* pMode = modeBytes)
+                    fixed (byte* pStorage = storageJson)
+                    fixed (byte* pLocation = locationBytes)
+                    fixed (byte* pNamespace = namespaceJson)
+                    fixed (byte* pCreateOptions = createOptionsJson)
+                    {
+                        var cSchemaArr = new CArrowSchema[1];
+                        fixed (CArrowSchema* pSchema = cSchemaArr)
+                        {
+                            CArrowSchema* schemaPtr = null;
+                            if (options?.Schema != null)
+                            {
+                                CArrowSchemaExporter.ExportSchema(options.Schema, pSchema);
+                                schemaPtr = pSchema;
+                            }
+                            connection_create_table_from_files(
+                                _handle!.DangerousGetHandle(),
+                                (IntPtr)pName,
+                                (IntPtr)pPaths,
+                                (IntPtr)pFormat,
+                                (IntPtr)pReadOptions,
+                                schemaPtr,
+                                (IntPtr)pMode,
+                                (IntPtr)pStorage,
+                                (IntPtr)pLocation,
+                                (IntPtr)pNamespace,
+                                (IntPtr)pCreateOptions,
+                                existOk,
+                                callback, userData);
+                        }
+                    }
+                }
+            }).ConfigureAwait(false);
+            return new Table(tablePtr);
+        }
+
         /// <summary>
         /// Get the names of all tables in the database, in sorted order.
         /// </summary>
@@ -474,6 +600,48 @@ namespace lancedb
             return joined.Split('\n');
         }
 
+        /// <summary>
+        /// Execute a SQL statement over the tables of this database.
+        /// </summary>
+        /// <remarks>
+        /// Every table in the namespace is registered under its table name, so the
+        /// statement can use joins, aggregations, <c>GROUP BY</c> and <c>ORDER BY</c>.
+        /// The query runs natively and only the result is transferred.
+        /// The returned reader must be disposed after use to release the
+        /// underlying native stream handle.
+        /// </remarks>
+        /// <param name="sql">The SQL statement to execute.</param>
+        /// <param name="ns">
+        /// The namespace whose tables are visible to the statement, specified as a
+        /// hierarchical path. <c>null</c> or an empty list represents the root namespace.
+        /// </param>
+        /// <returns>An <see cref="AsyncRecordBatchReader"/> that yields the result batches.</returns>
+        /// <exception cref="LanceDbException">Thrown if the statement fails to plan or execute.</exception>
+        public async Task<AsyncRecordBatchReader> Sql(string sql, IReadOnlyList<string>? ns = null)
+        {
+            byte[] sqlBytes = NativeCall.ToUtf8(sql);
+            byte[]? namespaceJson = ns != null
+                ? JsonSerializer.SerializeToUtf8Bytes(ns)
+                : null;
+
+            IntPtr streamPtr = await NativeCall.Async((callback, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* pSql = sqlBytes)
+                    fixed (byte* pNamespace = namespaceJson)
+                    {
+                        connection_sql(
+                            _handle!.DangerousGetHandle(),
+                            new IntPtr(pSql),
+                            namespaceJson != null ? new IntPtr(pNamespace) : IntPtr.Zero,
+                            callback, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+            return AsyncRecordBatchReader.FromNativeStream(streamPtr);
+        }
+
         /// <summary>
         /// List tables in the database with pagination support.
         /// </summary>
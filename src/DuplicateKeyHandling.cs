@@ -0,0 +1,28 @@
+namespace lancedb
+{
+    /// <summary>
+    /// Describes what an add does with rows whose primary key already exists.
+    /// </summary>
+    /// <remarks>
+    /// The primary key is the one set with <see cref="Table.SetUnenforcedPrimaryKey"/>.
+    /// Rows that repeat a key within the added data collapse to the first occurrence
+    /// (<see cref="Skip"/>) or the last (<see cref="Overwrite"/>).
+    /// </remarks>
+    public enum DuplicateKeyHandling
+    {
+        /// <summary>
+        /// Existing rows are left untouched and the new rows with their keys are dropped.
+        /// </summary>
+        Skip,
+
+        /// <summary>
+        /// The add fails without writing anything if any key already exists.
+        /// </summary>
+        Error,
+
+        /// <summary>
+        /// Existing rows are replaced by the new rows with the same key.
+        /// </summary>
+        Overwrite,
+    }
+}
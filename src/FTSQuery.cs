@@ -1,6 +1,7 @@
 namespace lancedb
 {
     using System;
+    using System.Collections.Generic;
     using System.Runtime.InteropServices;
     using System.Threading.Tasks;
     using Apache.Arrow;
@@ -50,6 +51,7 @@ namespace lancedb
             IntPtr table_ptr, IntPtr params_json, NativeCall.FfiCallback completion, IntPtr userData);
 
         private IReranker? _reranker;
+        private HighlightOptions? _highlight;
 
         internal FTSQuery(IntPtr tablePtr)
             : base(tablePtr)
@@ -75,6 +77,43 @@ namespace lancedb
         /// </remarks>
         public string QueryString => _fullTextSearchQuery ?? string.Empty;
 
+        /// <inheritdoc/>
+        internal override Dictionary<string, object> BuildParamsDict()
+        {
+            var dict = base.BuildParamsDict();
+            if (_highlight != null)
+            {
+                dict["highlight"] = _highlight.ToDictionary();
+            }
+            return dict;
+        }
+
+        /// <summary>
+        /// Add highlighted snippets of the matched text to the results.
+        /// </summary>
+        /// <remarks>
+        /// <para>
+        /// For each highlighted column a <c>_highlight_&lt;column&gt;</c> column is added,
+        /// holding a list of fragments of the column's text in which every token
+        /// matching the search is wrapped in <see cref="HighlightOptions.PreTag"/> and
+        /// <see cref="HighlightOptions.PostTag"/>. Rows with a null text get a null list.
+        /// </para>
+        /// <para>
+        /// Tokens are matched with the tokenizer settings of the column's full-text
+        /// search index, such as stemming, lower casing and ASCII folding, so
+        /// "Running" is highlighted for a search on "runs" when the index stems.
+        /// Highlighting is only supported on local tables and is not carried over to
+        /// hybrid queries.
+        /// </para>
+        /// </remarks>
+        /// <param name="options">The highlight options, or <c>null</c> for the defaults.</param>
+        /// <returns>This query instance for method chaining.</returns>
+        public FTSQuery Highlight(HighlightOptions? options = null)
+        {
+            _highlight = options ?? new HighlightOptions();
+            return this;
+        }
+
         /// <inheritdoc/>
         private protected override void NativeConsolidatedExecute(
             IntPtr tablePtr, IntPtr paramsJson, long timeoutMs, uint maxBatchLength,
@@ -0,0 +1,45 @@
+namespace lancedb
+{
+    using System.Text.RegularExpressions;
+
+    /// <summary>
+    /// Exception thrown when <see cref="FullTextQuery.Parse"/> cannot parse a query string.
+    /// </summary>
+    public class FtsQueryParseException : LanceDbException
+    {
+        private static readonly Regex s_pattern = new Regex(
+            @"^Query string parse error at position (\d+): (.*)$",
+            RegexOptions.Compiled | RegexOptions.Singleline);
+
+        /// <summary>
+        /// The zero-based character offset in the query string where the error was found.
+        /// </summary>
+        public int Position { get; }
+
+        /// <summary>
+        /// The description of the error, without the position prefix.
+        /// </summary>
+        public string Reason { get; }
+
+        public FtsQueryParseException(string message, int position, string reason)
+            : base(message)
+        {
+            Position = position;
+            Reason = reason;
+        }
+
+        /// <summary>
+        /// Returns a <see cref="FtsQueryParseException"/> when the native error message
+        /// describes a query string parse error, otherwise <c>null</c>.
+        /// </summary>
+        internal static FtsQueryParseException? TryParse(string message)
+        {
+            var match = s_pattern.Match(message);
+            if (!match.Success)
+            {
+                return null;
+            }
+            return new FtsQueryParseException(message, int.Parse(match.Groups[1].Value), match.Groups[2].Value);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+namespace lancedb
+{
+    using System.Collections.Generic;
+    using System.Text;
+    using System.Text.Json.Nodes;
+
+    /// <summary>
+    /// A token produced by a full-text search tokenizer, returned by
+    /// <see cref="FtsIndex.Analyze"/> and <see cref="Table.AnalyzeFts"/>.
+    /// </summary>
+    public class FtsToken
+    {
+        /// <summary>The token as stored in the index, after lower casing, stemming and folding.</summary>
+        public string Text { get; set; } = string.Empty;
+
+        /// <summary>The position of the token in the token stream, as used by phrase queries.</summary>
+        public int Position { get; set; }
+
+        /// <summary>The index in the analyzed string of the first character of the token.</summary>
+        public int StartOffset { get; set; }
+
+        /// <summary>The index in the analyzed string just past the last character of the token.</summary>
+        public int EndOffset { get; set; }
+
+        /// <summary>
+        /// Parses the native token JSON, converting its UTF-8 byte offsets into
+        /// indices in <paramref name="text"/>.
+        /// </summary>
+        internal static IReadOnlyList<FtsToken> ParseList(string json, string text)
+        {
+            byte[] utf8 = Encoding.UTF8.GetBytes(text);
+            var tokens = new List<FtsToken>();
+            foreach (var node in JsonNode.Parse(json)!.AsArray())
+            {
+                tokens.Add(new FtsToken
+                {
+                    Text = (string)node!["text"]!,
+                    Position = (int)node["position"]!,
+                    StartOffset = Encoding.UTF8.GetCharCount(utf8, 0, (int)node["start"]!),
+                    EndOffset = Encoding.UTF8.GetCharCount(utf8, 0, (int)node["end"]!),
+                });
+            }
+            return tokens;
+        }
+    }
+}
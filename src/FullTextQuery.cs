@@ -3,6 +3,8 @@ namespace lancedb
     using System;
     using System.Collections.Generic;
     using System.Globalization;
+    using System.Runtime.InteropServices;
+    using System.Text.Json;
     using System.Text.Json.Nodes;
 
     /// <summary>
@@ -40,7 +42,9 @@ namespace lancedb
     /// A <see cref="FullTextQuery"/> describes a full-text search beyond a simple
     /// string. Concrete query types include <see cref="MatchQuery"/>,
     /// <see cref="PhraseQuery"/>, <see cref="BoostQuery"/>,
-    /// <see cref="MultiMatchQuery"/>, and <see cref="BooleanQuery"/>.
+    /// <see cref="MultiMatchQuery"/>, <see cref="BooleanQuery"/>,
+    /// <see cref="PrefixQuery"/>, <see cref="WildcardQuery"/>, <see cref="RegexQuery"/>,
+    /// <see cref="TermRangeQuery"/>, and <see cref="ExistsQuery"/>.
     /// </para>
     /// <para>
     /// Pass an instance to <see cref="Query.NearestToText(FullTextQuery)"/> (or
@@ -50,6 +54,9 @@ namespace lancedb
     /// </remarks>
     public abstract class FullTextQuery
     {
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern IntPtr fts_parse_query_string(IntPtr query, IntPtr default_columns_json);
+
         /// <summary>
         /// Builds the JSON representation of this query node, matching the
         /// serialization format expected by the native LanceDB engine.
@@ -74,6 +81,170 @@ namespace lancedb
             return ToJsonNode().ToJsonString();
         }
 
+        /// <summary>
+        /// Parse a Lucene-style query string into a structured query.
+        /// </summary>
+        /// <remarks>
+        /// <para>
+        /// The syntax supports terms and quoted phrases, field prefixes
+        /// (<c>title:rust</c>, <c>title:(rust csharp)</c>), <c>AND</c> / <c>&amp;&amp;</c>,
+        /// <c>OR</c> / <c>||</c>, <c>NOT</c> / <c>!</c>, the <c>+</c> (must) and
+        /// <c>-</c> (must not) prefixes, parentheses, term fuzziness (<c>java~2</c>,
+        /// or <c>java~</c> for 2), phrase slop (<c>"vector db"~3</c>) and term boosts
+        /// (<c>rust^2</c>). Clauses without an operator between them are combined with
+        /// OR, and AND binds tighter than OR.
+        /// </para>
+        /// <para>
+        /// Terms and phrases without a field prefix search every column in
+        /// <paramref name="defaultColumns"/>.
+        /// </para>
+        /// </remarks>
+        /// <param name="queryString">The query string, e.g. <c>title:"vector db" AND (rust OR csharp) -java~2</c>.</param>
+        /// <param name="defaultColumns">The columns searched by terms without a field prefix.</param>
+        /// <returns>The parsed query.</returns>
+        /// <exception cref="FtsQueryParseException">The query string is not valid.</exception>
+        public static FullTextQuery Parse(string queryString, IReadOnlyList<string>? defaultColumns = null)
+        {
+            if (queryString == null)
+            {
+                throw new ArgumentNullException(nameof(queryString));
+            }
+            byte[] queryBytes = NativeCall.ToUtf8(queryString);
+            byte[]? columnsJson = defaultColumns != null
+                ? NativeCall.ToUtf8(JsonSerializer.Serialize(defaultColumns))
+                : null;
+
+            IntPtr ptr;
+            unsafe
+            {
+                fixed (byte* pQuery = queryBytes)
+                fixed (byte* pColumns = columnsJson)
+                {
+                    ptr = fts_parse_query_string(new IntPtr(pQuery), new IntPtr(pColumns));
+                }
+            }
+            NativeCall.ThrowIfNullWithError(ptr, "Failed to parse query string");
+            var node = JsonNode.Parse(NativeCall.ReadStringAndFree(ptr))!;
+            return FromJsonNode(node);
+        }
+
+        /// <summary>
+        /// Builds a query from the JSON produced by <see cref="ToJsonNode"/> or by the
+        /// native query-string parser. Fields missing from the JSON take the constructor
+        /// defaults, except a missing <c>fuzziness</c>, which means automatic fuzziness.
+        /// </summary>
+        internal static FullTextQuery FromJsonNode(JsonNode node)
+        {
+            var obj = node.AsObject();
+            foreach (var property in obj)
+            {
+                string kind = property.Key;
+                var inner = property.Value!.AsObject();
+                switch (kind)
+                {
+                    case "match":
+                        return new MatchQuery(
+                            (string)inner["terms"]!,
+                            (string)inner["column"]!,
+                            (float?)inner["boost"] ?? 1.0f,
+                            (int?)inner["fuzziness"],
+                            (int?)inner["max_expansions"] ?? 50,
+                            OperatorFromJson(inner["operator"]),
+                            (int?)inner["prefix_length"] ?? 0);
+                    case "phrase":
+                        return new PhraseQuery(
+                            (string)inner["terms"]!,
+                            (string)inner["column"]!,
+                            (int?)inner["slop"] ?? 0);
+                    case "boost":
+                        return new BoostQuery(
+                            FromJsonNode(inner["positive"]!),
+                            FromJsonNode(inner["negative"]!),
+                            (float?)inner["negative_boost"] ?? 0.5f);
+                    case "multi_match":
+                        var columns = new List<string>();
+                        foreach (var c in inner["columns"]!.AsArray())
+                        {
+                            columns.Add((string)c!);
+                        }
+                        List<float>? boosts = null;
+                        if (inner["boost"] is JsonArray boostArray)
+                        {
+                            boosts = new List<float>();
+                            foreach (var b in boostArray)
+                            {
+                                boosts.Add((float)b!);
+                            }
+                        }
+                        return new MultiMatchQuery(
+                            (string)inner["query"]!, columns, boosts, OperatorFromJson(inner["operator"]));
+                    case "boolean":
+                        var queries = new List<(Occur, FullTextQuery)>();
+                        AddClauses(queries, inner["should"], Occur.Should);
+                        AddClauses(queries, inner["must"], Occur.Must);
+                        AddClauses(queries, inner["must_not"], Occur.MustNot);
+                        string? minimumShouldMatch = null;
+                        if (inner["minimum_should_match"] is JsonValue minimum)
+                        {
+                            minimumShouldMatch = minimum.TryGetValue(out string? text) ? text : minimum.ToJsonString();
+                        }
+                        return new BooleanQuery(queries, minimumShouldMatch);
+                    case "prefix":
+                        return new PrefixQuery(
+                            (string)inner["prefix"]!,
+                            (string)inner["column"]!,
+                            (float?)inner["boost"] ?? 1.0f,
+                            (int?)inner["max_expansions"] ?? 50);
+                    case "wildcard":
+                        return new WildcardQuery(
+                            (string)inner["pattern"]!,
+                            (string)inner["column"]!,
+                            (float?)inner["boost"] ?? 1.0f,
+                            (int?)inner["max_expansions"] ?? 50);
+                    case "regex":
+                        return new RegexQuery(
+                            (string)inner["pattern"]!,
+                            (string)inner["column"]!,
+                            (float?)inner["boost"] ?? 1.0f,
+                            (int?)inner["max_expansions"] ?? 50);
+                    case "term_range":
+                        string? lower = (string?)inner["gte"] ?? (string?)inner["gt"];
+                        string? upper = (string?)inner["lte"] ?? (string?)inner["lt"];
+                        return new TermRangeQuery(
+                            (string)inner["column"]!,
+                            lower,
+                            upper,
+                            inner["gt"] == null,
+                            inner["lt"] == null,
+                            (float?)inner["boost"] ?? 1.0f,
+                            (int?)inner["max_expansions"] ?? 50);
+                    case "exists":
+                        return new ExistsQuery((string)inner["column"]!);
+                    default:
+                        throw new LanceDbException($"Unknown full-text query type '{kind}'");
+                }
+            }
+            throw new LanceDbException("Empty full-text query JSON");
+        }
+
+        private static void AddClauses(List<(Occur, FullTextQuery)> queries, JsonNode? clauses, Occur occur)
+        {
+            if (clauses is JsonArray array)
+            {
+                foreach (var clause in array)
+                {
+                    queries.Add((occur, FromJsonNode(clause!)));
+                }
+            }
+        }
+
+        private static FullTextOperator OperatorFromJson(JsonNode? value)
+        {
+            return value != null && string.Equals((string?)value, "And", StringComparison.OrdinalIgnoreCase)
+                ? FullTextOperator.And
+                : FullTextOperator.Or;
+        }
+
         /// <summary>
         /// Combine this query with another using a logical AND operation.
         /// </summary>
@@ -154,6 +325,15 @@ namespace lancedb
                 throw new ArgumentException("Value must be a finite number.", paramName);
             }
         }
+
+        private protected static void EnsurePositive(double value, string paramName)
+        {
+            EnsureFinite(value, paramName);
+            if (value <= 0)
+            {
+                throw new ArgumentOutOfRangeException(paramName, "Value must be positive.");
+            }
+        }
     }
 
     /// <summary>
@@ -462,7 +642,9 @@ namespace lancedb
     /// <remarks>
     /// Combines multiple sub-queries, each with an <see cref="Occur"/> requirement
     /// (<see cref="Occur.Must"/>, <see cref="Occur.Should"/>, or
-    /// <see cref="Occur.MustNot"/>).
+    /// <see cref="Occur.MustNot"/>). Without <c>minimumShouldMatch</c>, should
+    /// clauses are optional when there is a must clause, and at least one of them
+    /// has to match otherwise.
     /// </remarks>
     public sealed class BooleanQuery : FullTextQuery
     {
@@ -477,7 +659,16 @@ namespace lancedb
         /// <see cref="Occur.Should"/>, or <see cref="Occur.MustNot"/>) with a
         /// <see cref="FullTextQuery"/> to apply.
         /// </param>
-        public BooleanQuery(IEnumerable<(Occur Occur, FullTextQuery Query)> queries)
+        /// <param name="minimumShouldMatch">
+        /// How many <see cref="Occur.Should"/> clauses must match: a count such as
+        /// <c>"2"</c>, a negative count of clauses that may be missing such as
+        /// <c>"-1"</c>, or a percentage of the should clauses, rounded down, such as
+        /// <c>"75%"</c> or <c>"-25%"</c>. It is checked against the number of should
+        /// clauses when the query is run. Defaults to <c>null</c>.
+        /// </param>
+        public BooleanQuery(
+            IEnumerable<(Occur Occur, FullTextQuery Query)> queries,
+            string? minimumShouldMatch = null)
         {
             if (queries == null)
             {
@@ -491,11 +682,19 @@ namespace lancedb
                     throw new ArgumentException("Sub-queries must not be null.", nameof(queries));
                 }
             }
+            if (minimumShouldMatch != null && minimumShouldMatch.Trim().Length == 0)
+            {
+                throw new ArgumentException("minimumShouldMatch must not be empty.", nameof(minimumShouldMatch));
+            }
+            MinimumShouldMatch = minimumShouldMatch;
         }
 
         /// <summary>The list of sub-queries with their occurrence requirements.</summary>
         public IReadOnlyList<(Occur Occur, FullTextQuery Query)> Queries => _queries;
 
+        /// <summary>How many should clauses must match, or <c>null</c> for the default.</summary>
+        public string? MinimumShouldMatch { get; }
+
         internal override string QueryText => string.Empty;
 
         internal override JsonNode ToJsonNode()
@@ -524,7 +723,256 @@ namespace lancedb
                 ["must"] = must,
                 ["must_not"] = mustNot,
             };
+            if (MinimumShouldMatch != null)
+            {
+                inner["minimum_should_match"] = MinimumShouldMatch;
+            }
             return new JsonObject { ["boolean"] = inner };
         }
     }
+
+    /// <summary>
+    /// Base class for queries that match every indexed term of a column selected by
+    /// a pattern.
+    /// </summary>
+    /// <remarks>
+    /// LanceDB expands the query into the matching terms of the column when the query
+    /// runs. It reads the column and tokenizes it with the settings of the column's
+    /// FTS index, so patterns apply to terms as they are indexed: lower-cased,
+    /// stemmed, and ASCII-folded when the index does so. The column is read once per
+    /// table version. A query matching more than <c>maxExpansions</c> terms fails
+    /// rather than dropping terms. These queries are supported by plain queries, not
+    /// by vector queries.
+    /// </remarks>
+    public abstract class MultiTermQuery : FullTextQuery
+    {
+        private protected MultiTermQuery(string column, float boost, int maxExpansions)
+        {
+            Column = column ?? throw new ArgumentNullException(nameof(column));
+            EnsurePositive(boost, nameof(boost));
+            if (maxExpansions <= 0)
+            {
+                throw new ArgumentOutOfRangeException(nameof(maxExpansions), "maxExpansions must be positive.");
+            }
+            Boost = boost;
+            MaxExpansions = maxExpansions;
+        }
+
+        /// <summary>The name of the column to match against.</summary>
+        public string Column { get; }
+
+        /// <summary>The boost factor applied to documents matching each term.</summary>
+        public float Boost { get; }
+
+        /// <summary>The maximum number of terms the query expands into.</summary>
+        public int MaxExpansions { get; }
+
+        private protected JsonNode ToJsonNode(string kind, JsonObject inner)
+        {
+            inner["column"] = Column;
+            inner["boost"] = Boost;
+            inner["max_expansions"] = MaxExpansions;
+            return new JsonObject { [kind] = inner };
+        }
+    }
+
+    /// <summary>
+    /// Prefix query for full-text search: matches terms that start with a prefix.
+    /// </summary>
+    public sealed class PrefixQuery : MultiTermQuery
+    {
+        /// <summary>
+        /// Creates a prefix query.
+        /// </summary>
+        /// <param name="prefix">The prefix of the matching terms.</param>
+        /// <param name="column">The name of the column to match against.</param>
+        /// <param name="boost">The boost factor for each matching term. Defaults to <c>1.0</c>.</param>
+        /// <param name="maxExpansions">The maximum number of matching terms. Defaults to <c>50</c>.</param>
+        public PrefixQuery(string prefix, string column, float boost = 1.0f, int maxExpansions = 50)
+            : base(column, boost, maxExpansions)
+        {
+            Prefix = prefix ?? throw new ArgumentNullException(nameof(prefix));
+            if (Prefix.Length == 0)
+            {
+                throw new ArgumentException("The prefix must not be empty.", nameof(prefix));
+            }
+        }
+
+        /// <summary>The prefix of the matching terms.</summary>
+        public string Prefix { get; }
+
+        internal override string QueryText => Prefix + "*";
+
+        internal override JsonNode ToJsonNode()
+        {
+            return ToJsonNode("prefix", new JsonObject { ["prefix"] = Prefix });
+        }
+    }
+
+    /// <summary>
+    /// Wildcard query for full-text search: matches terms against a pattern where
+    /// <c>*</c> stands for any sequence of characters and <c>?</c> for a single
+    /// character. A backslash escapes the next character.
+    /// </summary>
+    public sealed class WildcardQuery : MultiTermQuery
+    {
+        /// <summary>
+        /// Creates a wildcard query.
+        /// </summary>
+        /// <param name="pattern">The pattern the whole term must match, e.g. <c>contr*ct</c>.</param>
+        /// <param name="column">The name of the column to match against.</param>
+        /// <param name="boost">The boost factor for each matching term. Defaults to <c>1.0</c>.</param>
+        /// <param name="maxExpansions">The maximum number of matching terms. Defaults to <c>50</c>.</param>
+        public WildcardQuery(string pattern, string column, float boost = 1.0f, int maxExpansions = 50)
+            : base(column, boost, maxExpansions)
+        {
+            Pattern = pattern ?? throw new ArgumentNullException(nameof(pattern));
+            if (Pattern.Length == 0)
+            {
+                throw new ArgumentException("The pattern must not be empty.", nameof(pattern));
+            }
+        }
+
+        /// <summary>The pattern the whole term must match.</summary>
+        public string Pattern { get; }
+
+        internal override string QueryText => Pattern;
+
+        internal override JsonNode ToJsonNode()
+        {
+            return ToJsonNode("wildcard", new JsonObject { ["pattern"] = Pattern });
+        }
+    }
+
+    /// <summary>
+    /// Regular expression query for full-text search: matches terms entirely
+    /// matched by a regular expression.
+    /// </summary>
+    /// <remarks>
+    /// The expression uses the syntax of the Rust <c>regex</c> crate, which has no
+    /// look-around or backreferences. An invalid expression is reported when the
+    /// query is run.
+    /// </remarks>
+    public sealed class RegexQuery : MultiTermQuery
+    {
+        /// <summary>
+        /// Creates a regular expression query.
+        /// </summary>
+        /// <param name="pattern">The expression the whole term must match, e.g. <c>contract(s|or)?</c>.</param>
+        /// <param name="column">The name of the column to match against.</param>
+        /// <param name="boost">The boost factor for each matching term. Defaults to <c>1.0</c>.</param>
+        /// <param name="maxExpansions">The maximum number of matching terms. Defaults to <c>50</c>.</param>
+        public RegexQuery(string pattern, string column, float boost = 1.0f, int maxExpansions = 50)
+            : base(column, boost, maxExpansions)
+        {
+            Pattern = pattern ?? throw new ArgumentNullException(nameof(pattern));
+        }
+
+        /// <summary>The expression the whole term must match.</summary>
+        public string Pattern { get; }
+
+        internal override string QueryText => Pattern;
+
+        internal override JsonNode ToJsonNode()
+        {
+            return ToJsonNode("regex", new JsonObject { ["pattern"] = Pattern });
+        }
+    }
+
+    /// <summary>
+    /// Term range query for full-text search: matches terms between two bounds,
+    /// compared as ordinal strings.
+    /// </summary>
+    public sealed class TermRangeQuery : MultiTermQuery
+    {
+        /// <summary>
+        /// Creates a term range query.
+        /// </summary>
+        /// <param name="column">The name of the column to match against.</param>
+        /// <param name="lower">The lower bound, or <c>null</c> for no lower bound.</param>
+        /// <param name="upper">The upper bound, or <c>null</c> for no upper bound.</param>
+        /// <param name="includeLower">Whether a term equal to <paramref name="lower"/> matches. Defaults to <c>true</c>.</param>
+        /// <param name="includeUpper">Whether a term equal to <paramref name="upper"/> matches. Defaults to <c>false</c>.</param>
+        /// <param name="boost">The boost factor for each matching term. Defaults to <c>1.0</c>.</param>
+        /// <param name="maxExpansions">The maximum number of matching terms. Defaults to <c>50</c>.</param>
+        public TermRangeQuery(
+            string column,
+            string? lower,
+            string? upper,
+            bool includeLower = true,
+            bool includeUpper = false,
+            float boost = 1.0f,
+            int maxExpansions = 50)
+            : base(column, boost, maxExpansions)
+        {
+            if (lower == null && upper == null)
+            {
+                throw new ArgumentException("At least one bound is required.", nameof(lower));
+            }
+            Lower = lower;
+            Upper = upper;
+            IncludeLower = includeLower;
+            IncludeUpper = includeUpper;
+        }
+
+        /// <summary>The lower bound, or <c>null</c> for no lower bound.</summary>
+        public string? Lower { get; }
+
+        /// <summary>The upper bound, or <c>null</c> for no upper bound.</summary>
+        public string? Upper { get; }
+
+        /// <summary>Whether a term equal to <see cref="Lower"/> matches.</summary>
+        public bool IncludeLower { get; }
+
+        /// <summary>Whether a term equal to <see cref="Upper"/> matches.</summary>
+        public bool IncludeUpper { get; }
+
+        internal override string QueryText => string.Empty;
+
+        internal override JsonNode ToJsonNode()
+        {
+            var inner = new JsonObject();
+            if (Lower != null)
+            {
+                inner[IncludeLower ? "gte" : "gt"] = Lower;
+            }
+            if (Upper != null)
+            {
+                inner[IncludeUpper ? "lte" : "lt"] = Upper;
+            }
+            return ToJsonNode("term_range", inner);
+        }
+    }
+
+    /// <summary>
+    /// Exists query for full-text search: matches documents whose column is not null.
+    /// </summary>
+    /// <remarks>
+    /// It runs as a row filter rather than a scored clause, so it can only be the
+    /// query itself or a <see cref="Occur.Must"/> or <see cref="Occur.MustNot"/>
+    /// clause of a <see cref="BooleanQuery"/> that is; as a must-not clause it keeps rows
+    /// whose column is null. A query made only of exists clauses returns no
+    /// <c>_score</c> column. Only supported by plain queries.
+    /// </remarks>
+    public sealed class ExistsQuery : FullTextQuery
+    {
+        /// <summary>
+        /// Creates an exists query.
+        /// </summary>
+        /// <param name="column">The name of the column to check.</param>
+        public ExistsQuery(string column)
+        {
+            Column = column ?? throw new ArgumentNullException(nameof(column));
+        }
+
+        /// <summary>The name of the column to check.</summary>
+        public string Column { get; }
+
+        internal override string QueryText => string.Empty;
+
+        internal override JsonNode ToJsonNode()
+        {
+            return new JsonObject { ["exists"] = new JsonObject { ["column"] = Column } };
+        }
+    }
 }
@@ -1,5 +1,8 @@
 namespace lancedb
 {
+    using System;
+    using System.Collections.Generic;
+    using System.Runtime.InteropServices;
     using System.Text.Json;
 
     /// <summary>
@@ -15,22 +18,99 @@ namespace lancedb
         internal abstract byte[] ToConfigJsonUtf8();
     }
 
+    /// <summary>
+    /// Base class for scalar indices with build options.
+    /// </summary>
+    /// <remarks>
+    /// Setting any build option builds the index directly on the table's Lance
+    /// dataset, which is only supported for local tables.
+    /// </remarks>
+    public abstract class ScalarIndex : Index
+    {
+        /// <summary>
+        /// The fragments a partial build indexes. Default is <c>null</c> (all
+        /// fragments).
+        /// </summary>
+        /// <remarks>
+        /// Rows in other fragments stay unindexed, and are scanned by queries,
+        /// until the index is optimized with <see cref="Table.Optimize"/>.
+        /// </remarks>
+        public IReadOnlyList<int>? PartialBuildFragments { get; set; }
+
+        /// <summary>
+        /// The memory, in megabytes, the index build may use before spilling to
+        /// disk. Default is <c>null</c> (the Lance default).
+        /// </summary>
+        public int? MemoryLimitMb { get; set; }
+
+        internal override byte[] ToConfigJsonUtf8()
+        {
+            var dict = ToConfigDictionary();
+            if (PartialBuildFragments != null) { dict["partial_build_fragments"] = PartialBuildFragments; }
+            if (MemoryLimitMb.HasValue) { dict["memory_limit_mb"] = MemoryLimitMb.Value; }
+            return dict.Count == 0 ? EmptyConfig : JsonSerializer.SerializeToUtf8Bytes(dict);
+        }
+
+        private protected virtual Dictionary<string, object> ToConfigDictionary()
+        {
+            return new Dictionary<string, object>();
+        }
+    }
+
     /// <summary>
     /// A BTree index on scalar columns.
     /// </summary>
     /// <remarks>
     /// The index stores a copy of the column in sorted order. A header entry is
-    /// created for each block of rows (currently the block size is fixed at 4096).
-    /// These header entries are stored in a separate cacheable structure (a btree).
+    /// created for each block of rows (see <see cref="ZoneSize"/>). These header
+    /// entries are stored in a separate cacheable structure (a btree).
     ///
     /// This index is good for scalar columns with mostly distinct values and does
     /// best when the query is highly selective. It works with numeric, temporal,
-    /// and string columns.
+    /// and string columns. String columns can use a <see cref="Variant"/> instead.
     /// </remarks>
-    public class BTreeIndex : Index
+    public class BTreeIndex : ScalarIndex
     {
+        /// <summary>
+        /// The number of rows per block. Default is <c>null</c> (4096 rows).
+        /// </summary>
+        /// <remarks>
+        /// Smaller blocks make highly selective queries read less data, at the
+        /// cost of a larger btree.
+        /// </remarks>
+        public int? ZoneSize { get; set; }
+
+        /// <summary>
+        /// The index to build on a string column instead of a BTree.
+        /// Default is <c>null</c> (a BTree).
+        /// </summary>
+        public StringIndexVariant? Variant { get; set; }
+
+        /// <summary>
+        /// The tokenizer settings of a <see cref="StringIndexVariant.Inverted"/>
+        /// index. Default is <c>null</c> (the <see cref="FtsIndex"/> defaults).
+        /// </summary>
+        public FtsIndex? Tokenizer { get; set; }
+
         internal override IndexType IndexType => IndexType.BTree;
-        internal override byte[] ToConfigJsonUtf8() => EmptyConfig;
+
+        private protected override Dictionary<string, object> ToConfigDictionary()
+        {
+            var dict = new Dictionary<string, object>();
+            if (ZoneSize.HasValue) { dict["zone_size"] = ZoneSize.Value; }
+            switch (Variant)
+            {
+                case StringIndexVariant.Ngram:
+                    dict["variant"] = "ngram";
+                    break;
+                case StringIndexVariant.Inverted:
+                    dict = (Tokenizer ?? new FtsIndex()).ToConfigDictionary();
+                    if (ZoneSize.HasValue) { dict["zone_size"] = ZoneSize.Value; }
+                    dict["variant"] = "inverted";
+                    break;
+            }
+            return dict;
+        }
     }
 
     /// <summary>
@@ -48,10 +128,9 @@ namespace lancedb
     /// <item><description><c>IS NULL</c></description></item>
     /// </list>
     /// </remarks>
-    public class BitmapIndex : Index
+    public class BitmapIndex : ScalarIndex
     {
         internal override IndexType IndexType => IndexType.Bitmap;
-        internal override byte[] ToConfigJsonUtf8() => EmptyConfig;
     }
 
     /// <summary>
@@ -61,10 +140,9 @@ namespace lancedb
     /// Supports queries with <c>array_contains_all</c> and <c>array_contains_any</c>
     /// using an underlying bitmap index. Useful for tags, categories, keywords, etc.
     /// </remarks>
-    public class LabelListIndex : Index
+    public class LabelListIndex : ScalarIndex
     {
         internal override IndexType IndexType => IndexType.LabelList;
-        internal override byte[] ToConfigJsonUtf8() => EmptyConfig;
     }
 
     /// <summary>
@@ -75,10 +153,9 @@ namespace lancedb
     /// tokenized <see cref="FtsIndex"/>, it matches arbitrary substrings of the raw
     /// bytes. For example, it works with <c>url</c>, <c>path</c>, <c>content</c>, etc.
     /// </remarks>
-    public class FmIndex : Index
+    public class FmIndex : ScalarIndex
     {
         internal override IndexType IndexType => IndexType.Fm;
-        internal override byte[] ToConfigJsonUtf8() => EmptyConfig;
     }
 
     /// <summary>
@@ -155,9 +232,50 @@ namespace lancedb
 
         internal override IndexType IndexType => IndexType.FTS;
 
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern IntPtr fts_analyze(IntPtr config_json, IntPtr text);
+
+        /// <summary>
+        /// Tokenize text the way an index built with these settings tokenizes documents.
+        /// </summary>
+        /// <remarks>
+        /// Use this to see why a search matches or misses a row, for example which
+        /// stop words are dropped or what a word is stemmed to. To inspect an existing
+        /// index, use <see cref="Table.AnalyzeFts"/>.
+        /// </remarks>
+        /// <param name="text">The text to tokenize.</param>
+        /// <returns>The tokens, in order.</returns>
+        public IReadOnlyList<FtsToken> Analyze(string text)
+        {
+            if (text == null)
+            {
+                throw new ArgumentNullException(nameof(text));
+            }
+            byte[] configBytes = ToConfigJsonUtf8();
+            Array.Resize(ref configBytes, configBytes.Length + 1);
+            byte[] textBytes = NativeCall.ToUtf8(text);
+
+            IntPtr ptr;
+            unsafe
+            {
+                fixed (byte* pConfig = configBytes)
+                fixed (byte* pText = textBytes)
+                {
+                    ptr = fts_analyze(new IntPtr(pConfig), new IntPtr(pText));
+                }
+            }
+            NativeCall.ThrowIfNullWithError(ptr, "Failed to analyze text");
+            return FtsToken.ParseList(NativeCall.ReadStringAndFree(ptr), text);
+        }
+
         internal override byte[] ToConfigJsonUtf8()
         {
-            var dict = new System.Collections.Generic.Dictionary<string, object>
+            return JsonSerializer.SerializeToUtf8Bytes(ToConfigDictionary());
+        }
+
+        internal Dictionary<string, object> ToConfigDictionary()
+        {
+            var dict = new Dictionary<string, object>
             {
                 ["with_position"] = WithPosition,
                 ["base_tokenizer"] = BaseTokenizer,
@@ -171,7 +289,7 @@ namespace lancedb
                 ["prefix_only"] = PrefixOnly,
             };
             if (MaxTokenLength.HasValue) { dict["max_token_length"] = MaxTokenLength.Value; }
-            return JsonSerializer.SerializeToUtf8Bytes(dict);
+            return dict;
         }
     }
 
@@ -460,6 +578,13 @@ namespace lancedb
         /// </summary>
         public int SampleRate { get; set; } = 256;
 
+        /// <summary>
+        /// The number of bits to quantize each dimension to. Default is <c>null</c>
+        /// (8 bits). Setting it builds the index on the table's Lance dataset, which
+        /// is only supported for local tables.
+        /// </summary>
+        public int? NumBits { get; set; }
+
         /// <summary>
         /// The target size of each partition.
         /// </summary>
@@ -477,6 +602,7 @@ namespace lancedb
             };
             if (NumPartitions.HasValue) { dict["num_partitions"] = NumPartitions.Value; }
             if (TargetPartitionSize.HasValue) { dict["target_partition_size"] = TargetPartitionSize.Value; }
+            if (NumBits.HasValue) { dict["num_bits"] = NumBits.Value; }
             return JsonSerializer.SerializeToUtf8Bytes(dict);
         }
     }
@@ -518,6 +644,13 @@ namespace lancedb
         /// </summary>
         public int SampleRate { get; set; } = 256;
 
+        /// <summary>
+        /// How vectors are rotated before quantization. Default is <c>null</c> (the
+        /// Lance default). Setting it builds the index on the table's Lance dataset,
+        /// which is only supported for local tables.
+        /// </summary>
+        public RqRotationType? RotationType { get; set; }
+
         /// <summary>
         /// The target size of each partition. Default is 8192.
         /// </summary>
@@ -536,6 +669,10 @@ namespace lancedb
             };
             if (NumPartitions.HasValue) { dict["num_partitions"] = NumPartitions.Value; }
             if (TargetPartitionSize.HasValue) { dict["target_partition_size"] = TargetPartitionSize.Value; }
+            if (RotationType.HasValue)
+            {
+                dict["rotation_type"] = RotationType.Value == RqRotationType.Fast ? "fast" : "matrix";
+            }
             return JsonSerializer.SerializeToUtf8Bytes(dict);
         }
     }
@@ -582,6 +719,13 @@ namespace lancedb
         /// </summary>
         public int EfConstruction { get; set; } = 300;
 
+        /// <summary>
+        /// The number of bits to quantize each dimension to. Default is <c>null</c>
+        /// (8 bits). Setting it builds the index on the table's Lance dataset, which
+        /// is only supported for local tables.
+        /// </summary>
+        public int? NumBits { get; set; }
+
         /// <summary>
         /// The target size of each partition. Default is 1,048,576.
         /// </summary>
@@ -601,6 +745,7 @@ namespace lancedb
             };
             if (NumPartitions.HasValue) { dict["num_partitions"] = NumPartitions.Value; }
             if (TargetPartitionSize.HasValue) { dict["target_partition_size"] = TargetPartitionSize.Value; }
+            if (NumBits.HasValue) { dict["num_bits"] = NumBits.Value; }
             return JsonSerializer.SerializeToUtf8Bytes(dict);
         }
     }
@@ -2,6 +2,7 @@ namespace lancedb
 {
     using System;
     using System.Collections.Generic;
+    using System.Linq;
     using System.Threading.Tasks;
     using Apache.Arrow;
 
@@ -19,26 +20,31 @@ namespace lancedb
     /// </para>
     /// <list type="bullet">
     /// <item><description><see cref="WhenMatchedUpdateAll"/> — Update matched rows with source data.</description></item>
+    /// <item><description><see cref="WhenMatchedUpdate"/> — Update selected columns of matched rows with SQL expressions.</description></item>
+    /// <item><description><see cref="WhenMatchedDelete"/> — Delete matched rows.</description></item>
     /// <item><description><see cref="WhenNotMatchedInsertAll"/> — Insert source rows that don't match.</description></item>
     /// <item><description><see cref="WhenNotMatchedBySourceDelete"/> — Delete target rows not in source.</description></item>
+    /// <item><description><see cref="WhenNotMatchedBySourceUpdate"/> — Update selected columns of target rows not in source.</description></item>
     /// </list>
     /// <para>
-    /// At least one of these behaviors must be configured before calling
-    /// <see cref="Execute(IReadOnlyList{RecordBatch})"/>.
+    /// At most one matched clause (an update or a delete), one insert clause and one
+    /// not-matched-by-source clause may be configured, and at least one clause must be
+    /// configured before calling <see cref="Execute(IReadOnlyList{RecordBatch})"/>.
+    /// The clauses run as a single merge, so conditions are evaluated against the
+    /// rows the merge commits over, including when it is retried after a conflict.
     /// </para>
     /// </remarks>
     public class MergeInsertBuilder
     {
         private readonly Table _table;
         private readonly IReadOnlyList<string> _onColumns;
-        private bool _whenMatchedUpdateAll;
-        private string? _whenMatchedUpdateAllFilter;
-        private bool _whenNotMatchedInsertAll;
-        private bool _whenNotMatchedBySourceDelete;
-        private string? _whenNotMatchedBySourceDeleteFilter;
+        private readonly List<Dictionary<string, object?>> _clauses = new();
         private bool _useIndex = true;
         private TimeSpan? _timeout;
         private bool? _useLsmWrite;
+        private IReadOnlyDictionary<string, string>? _commitMetadata;
+        private bool _castToTableSchema;
+        private WriteOptions? _writeOptions;
 
         internal MergeInsertBuilder(Table table, IReadOnlyList<string> onColumns)
         {
@@ -65,9 +71,45 @@ namespace lancedb
         /// <returns>This builder for chaining.</returns>
         public MergeInsertBuilder WhenMatchedUpdateAll(string? condition = null)
         {
-            _whenMatchedUpdateAll = true;
-            _whenMatchedUpdateAllFilter = condition;
-            return this;
+            return AddClause("when_matched_update", condition);
+        }
+
+        /// <summary>
+        /// Rows that exist in both the source and the target table will have only
+        /// the given columns updated, each set to the result of a SQL expression.
+        /// </summary>
+        /// <remarks>
+        /// Only supported for local tables.
+        /// </remarks>
+        /// <param name="updatesSql">
+        /// A dictionary mapping column names to SQL expressions, which may refer to
+        /// <c>source.</c> and <c>target.</c> columns. For example:
+        /// <c>{ { "version", "target.version + 1" }, { "name", "source.name" } }</c>.
+        /// Key columns cannot be set.
+        /// </param>
+        /// <param name="condition">
+        /// An optional SQL filter condition, which may refer to <c>target.</c> and
+        /// <c>source.</c> columns. Only matched rows that satisfy the condition will
+        /// be updated.
+        /// </param>
+        /// <returns>This builder for chaining.</returns>
+        public MergeInsertBuilder WhenMatchedUpdate(
+            IReadOnlyDictionary<string, string> updatesSql, string? condition = null)
+        {
+            return AddClause("when_matched_update", condition, updatesSql);
+        }
+
+        /// <summary>
+        /// Rows that exist in both the source and the target table will be deleted
+        /// from the target table.
+        /// </summary>
+        /// <remarks>
+        /// Only supported for local tables.
+        /// </remarks>
+        /// <returns>This builder for chaining.</returns>
+        public MergeInsertBuilder WhenMatchedDelete()
+        {
+            return AddClause("when_matched_delete", null);
         }
 
         /// <summary>
@@ -77,8 +119,7 @@ namespace lancedb
         /// <returns>This builder for chaining.</returns>
         public MergeInsertBuilder WhenNotMatchedInsertAll()
         {
-            _whenNotMatchedInsertAll = true;
-            return this;
+            return AddClause("when_not_matched_insert", null);
         }
 
         /// <summary>
@@ -92,9 +133,32 @@ namespace lancedb
         /// <returns>This builder for chaining.</returns>
         public MergeInsertBuilder WhenNotMatchedBySourceDelete(string? condition = null)
         {
-            _whenNotMatchedBySourceDelete = true;
-            _whenNotMatchedBySourceDeleteFilter = condition;
-            return this;
+            return AddClause("when_not_matched_by_source_delete", condition);
+        }
+
+        /// <summary>
+        /// Rows that exist only in the target table (old data) will have the given
+        /// columns updated, each set to the result of a SQL expression over the
+        /// target row. For example, to soft-delete rows missing from the source:
+        /// <c>{ { "deleted", "true" } }</c>.
+        /// </summary>
+        /// <remarks>
+        /// This needs a full scan of the target table, cannot be combined with
+        /// <see cref="WhenMatchedDelete"/> and is only supported for local tables.
+        /// </remarks>
+        /// <param name="updatesSql">
+        /// A dictionary mapping column names to SQL expressions over the target
+        /// columns. Key columns cannot be set.
+        /// </param>
+        /// <param name="condition">
+        /// An optional SQL filter condition over the target columns. Only unmatched
+        /// target rows that satisfy the condition will be updated.
+        /// </param>
+        /// <returns>This builder for chaining.</returns>
+        public MergeInsertBuilder WhenNotMatchedBySourceUpdate(
+            IReadOnlyDictionary<string, string> updatesSql, string? condition = null)
+        {
+            return AddClause("when_not_matched_by_source_update", condition, updatesSql);
         }
 
         /// <summary>
@@ -164,6 +228,54 @@ namespace lancedb
             return this;
         }
 
+        /// <summary>
+        /// Attach key-value metadata to the commit, such as a job id, user or source file.
+        /// </summary>
+        /// <remarks>
+        /// The metadata is reported in <see cref="VersionInfo.CommitMetadata"/> by
+        /// <see cref="Table.ListVersions"/>.
+        /// </remarks>
+        /// <param name="metadata">The metadata to record with the commit.</param>
+        /// <returns>This builder for chaining.</returns>
+        public MergeInsertBuilder CommitMetadata(IReadOnlyDictionary<string, string> metadata)
+        {
+            _commitMetadata = metadata;
+            return this;
+        }
+
+        /// <summary>
+        /// Control how the merge is committed, including retries on conflicts and an
+        /// idempotency key.
+        /// </summary>
+        /// <remarks>
+        /// With an <see cref="lancedb.WriteOptions.IdempotencyKey"/> the merge is only
+        /// supported for local tables. When the key was already committed nothing is
+        /// merged and the result reports the original version with zero row counts.
+        /// </remarks>
+        /// <param name="options">The write options.</param>
+        /// <returns>This builder for chaining.</returns>
+        public MergeInsertBuilder WriteOptions(WriteOptions options)
+        {
+            _writeOptions = options;
+            return this;
+        }
+
+        /// <summary>
+        /// Cast the source data to the table schema before merging.
+        /// </summary>
+        /// <remarks>
+        /// Source columns are matched by name, reordered and converted as described for
+        /// <see cref="AddOptions.CastToTableSchema"/>. Unlike an add, columns missing from
+        /// the source are not filled with nulls, so updates leave them unchanged.
+        /// </remarks>
+        /// <param name="cast">Whether to cast the source data.</param>
+        /// <returns>This builder for chaining.</returns>
+        public MergeInsertBuilder CastToTableSchema(bool cast = true)
+        {
+            _castToTableSchema = cast;
+            return this;
+        }
+
         /// <summary>
         /// Execute the merge insert operation with the provided data.
         /// </summary>
@@ -176,12 +288,11 @@ namespace lancedb
         /// </returns>
         public async Task<MergeResult> Execute(IReadOnlyList<RecordBatch> data)
         {
-            return await _table.ExecuteMergeInsert(
-                _onColumns,
-                _whenMatchedUpdateAll, _whenMatchedUpdateAllFilter,
-                _whenNotMatchedInsertAll,
-                _whenNotMatchedBySourceDelete, _whenNotMatchedBySourceDeleteFilter,
-                data, _useIndex, _timeout, _useLsmWrite).ConfigureAwait(false);
+            var (result, _) = await _table.ExecuteMergeInsert(
+                _onColumns, _clauses, data, _useIndex, _timeout, _useLsmWrite,
+                commitMetadata: _commitMetadata, castToTableSchema: _castToTableSchema,
+                writeOptions: _writeOptions).ConfigureAwait(false);
+            return result;
         }
 
         /// <summary>
@@ -195,5 +306,61 @@ namespace lancedb
         {
             return Execute(new[] { data });
         }
+
+        /// <summary>
+        /// Execute the merge insert operation and report which rows it affected.
+        /// </summary>
+        /// <remarks>
+        /// The affected rows are returned as a batch with the <c>on</c> key columns, an
+        /// <c>action</c> column (<c>inserted</c>, <c>updated</c> or <c>deleted</c>) and
+        /// the <c>_rowid</c> each written row has after the commit (<c>null</c> for
+        /// deleted rows), read from the data the commit wrote. Only supported for local
+        /// tables, and cannot be combined with <see cref="UseLsmWrite(bool)"/> set to
+        /// <c>true</c>. A conditional <see cref="WhenMatchedUpdate"/> that runs through
+        /// an index on the key updates rows in place, where the rows its condition
+        /// skipped cannot be told apart; it fails before committing unless
+        /// <see cref="UseIndex(bool)"/> is set to <c>false</c>.
+        /// </remarks>
+        /// <param name="data">The new data to merge.</param>
+        /// <returns>
+        /// A <see cref="MergeOutput"/> with the merge statistics and the affected rows.
+        /// </returns>
+        public async Task<MergeOutput> ExecuteWithAffectedRows(IReadOnlyList<RecordBatch> data)
+        {
+            var (result, affected) = await _table.ExecuteMergeInsert(
+                _onColumns, _clauses, data, _useIndex, _timeout, _useLsmWrite,
+                returnAffected: true, commitMetadata: _commitMetadata,
+                castToTableSchema: _castToTableSchema, writeOptions: _writeOptions).ConfigureAwait(false);
+            return new MergeOutput(result, affected!);
+        }
+
+        /// <summary>
+        /// Execute the merge insert operation with a single RecordBatch and report
+        /// which rows it affected.
+        /// </summary>
+        /// <param name="data">The new data to merge.</param>
+        /// <returns>
+        /// A <see cref="MergeOutput"/> with the merge statistics and the affected rows.
+        /// </returns>
+        public Task<MergeOutput> ExecuteWithAffectedRows(RecordBatch data)
+        {
+            return ExecuteWithAffectedRows(new[] { data });
+        }
+
+        private MergeInsertBuilder AddClause(
+            string kind, string? condition, IReadOnlyDictionary<string, string>? updatesSql = null)
+        {
+            var clause = new Dictionary<string, object?> { ["kind"] = kind };
+            if (condition != null)
+            {
+                clause["condition"] = condition;
+            }
+            if (updatesSql != null)
+            {
+                clause["set"] = updatesSql.Select(kv => new[] { kv.Key, kv.Value }).ToArray();
+            }
+            _clauses.Add(clause);
+            return this;
+        }
     }
 }
@@ -34,6 +34,27 @@ namespace lancedb
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern IntPtr ffi_get_last_error();
 
+        /// <summary>
+        /// Machine-readable details of the error passed to a callback.
+        /// Mirrors <c>FfiErrorDetails</c> in the native library.
+        /// </summary>
+        [StructLayout(LayoutKind.Sequential)]
+        private struct FfiErrorDetails
+        {
+            public int Code;
+            public long ConflictVersion;
+            public uint Attempts;
+        }
+
+        private const int ErrorCodeCommitConflict = 1;
+
+        /// <summary>
+        /// Returns the details of the error being passed to the current callback.
+        /// Only valid when called from inside the callback.
+        /// </summary>
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern FfiErrorDetails ffi_error_details();
+
         /// <summary>
         /// Single static dispatcher used by every async FFI call. The Rust side
         /// returns the GCHandle pointer we passed in via <c>userData</c>; we
@@ -63,6 +84,8 @@ namespace lancedb
 
                 if (error != IntPtr.Zero)
                 {
+                    // Read the details first: they are only set while the callback runs.
+                    FfiErrorDetails details = ffi_error_details();
                     string message;
                     try
                     {
@@ -73,7 +96,7 @@ namespace lancedb
                         tcs.TrySetException(ex);
                         return;
                     }
-                    tcs.TrySetException(new LanceDbException(message));
+                    tcs.TrySetException(CreateException(message, details));
                 }
                 else
                 {
@@ -90,6 +113,18 @@ namespace lancedb
             }
         }
 
+        private static LanceDbException CreateException(string message, FfiErrorDetails details)
+        {
+            switch (details.Code)
+            {
+                case ErrorCodeCommitConflict:
+                    ulong? version = details.ConflictVersion >= 0 ? (ulong?)details.ConflictVersion : null;
+                    return new CommitConflictException(message, version, (int)details.Attempts);
+                default:
+                    return new LanceDbException(message);
+            }
+        }
+
         /// <summary>
         /// Calls an async FFI function that uses the unified (result, error, userData) callback pattern.
         /// Returns the result IntPtr on success, or throws LanceDbException on error.
@@ -184,7 +219,7 @@ namespace lancedb
             if (errorPtr != IntPtr.Zero)
             {
                 string message = ReadStringAndFree(errorPtr);
-                throw new LanceDbException(message);
+                throw FtsQueryParseException.TryParse(message) ?? new LanceDbException(message);
             }
 
             throw new LanceDbException(fallbackMessage);
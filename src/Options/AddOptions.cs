@@ -28,5 +28,32 @@ namespace lancedb
         /// Default is <c>0.0f</c>.
         /// </summary>
         public float FillValue { get; set; } = 0.0f;
+
+        /// <summary>
+        /// Options controlling how the write is committed, such as how commit conflicts
+        /// are retried. If <c>null</c>, the table's default commit behavior is used.
+        /// </summary>
+        public WriteOptions? Write { get; set; }
+
+        /// <summary>
+        /// What to do with rows whose primary key already exists in the table.
+        /// Requires a primary key set with <see cref="Table.SetUnenforcedPrimaryKey"/> and
+        /// the <c>"append"</c> mode. If <c>null</c> (the default), rows are appended
+        /// without consulting the primary key.
+        /// </summary>
+        public DuplicateKeyHandling? OnDuplicateKey { get; set; }
+
+        /// <summary>
+        /// Whether to cast the data to the table schema before writing.
+        /// </summary>
+        /// <remarks>
+        /// Columns are matched by name and reordered, nullable columns missing from the
+        /// data are filled with nulls, and only lossless conversions are applied: numeric
+        /// widening, <c>utf8</c> to <c>large_utf8</c> and back, and lists to fixed-size
+        /// lists when every list has the table's dimension. Any other mismatch fails with
+        /// an error naming the column. Default is <c>false</c>, which passes the data
+        /// through unchanged.
+        /// </remarks>
+        public bool CastToTableSchema { get; set; }
     }
 }
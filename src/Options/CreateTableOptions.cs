@@ -1,6 +1,7 @@
 namespace lancedb
 {
     using System.Collections.Generic;
+    using System.Text.Json;
     using Apache.Arrow;
 
     /// <summary>
@@ -20,10 +21,26 @@ namespace lancedb
         /// The schema of the table. Used to create an empty table without data.
         ///
         /// Either <see cref="Data"/> or <see cref="Schema"/> must be provided.
-        /// If both are provided, <see cref="Data"/> takes precedence.
+        /// If both are provided, the table gets this schema and the data is cast into it:
+        /// columns are matched by name, nullable columns missing from the data are filled
+        /// with nulls, and only lossless conversions are applied (see
+        /// <see cref="AddOptions.CastToTableSchema"/>).
         /// </summary>
         public Schema? Schema { get; set; }
 
+        /// <summary>
+        /// Vector columns and their dimensions. Each column must be a list of floats and
+        /// is stored as a fixed-size list of the given dimension; data with a vector of
+        /// any other length is rejected with an error naming the column and row.
+        /// </summary>
+        public Dictionary<string, int>? VectorColumns { get; set; }
+
+        /// <summary>
+        /// Key-value metadata stored in the table schema, merged over the metadata of
+        /// <see cref="Schema"/> or of the data.
+        /// </summary>
+        public Dictionary<string, string>? Metadata { get; set; }
+
         /// <summary>
         /// The mode to use when creating the table. Default is <c>"create"</c>.
         /// <list type="bullet">
@@ -88,5 +105,27 @@ namespace lancedb
         /// the "team/project" namespace.
         /// </summary>
         public IReadOnlyList<string>? Namespace { get; set; }
+
+        /// <summary>
+        /// Serializes <see cref="VectorColumns"/> and <see cref="Metadata"/> as the
+        /// native <c>create_options_json</c> object, or <c>null</c> when neither is set.
+        /// </summary>
+        internal byte[]? CreateOptionsJsonUtf8()
+        {
+            if (VectorColumns == null && Metadata == null)
+            {
+                return null;
+            }
+            var json = new Dictionary<string, object>();
+            if (VectorColumns != null)
+            {
+                json["vector_columns"] = VectorColumns;
+            }
+            if (Metadata != null)
+            {
+                json["metadata"] = Metadata;
+            }
+            return JsonSerializer.SerializeToUtf8Bytes(json);
+        }
     }
 }
@@ -0,0 +1,74 @@
+namespace lancedb
+{
+    using System.Collections.Generic;
+    using System.Text.Json;
+
+    /// <summary>
+    /// Options controlling how <see cref="Table.Export"/> and <see cref="Query.Export"/>
+    /// write files.
+    /// </summary>
+    public class FileExportOptions
+    {
+        /// <summary>
+        /// The format of the files.
+        /// <list type="bullet">
+        /// <item><description><c>"parquet"</c> (default) — Parquet files.</description></item>
+        /// <item><description><c>"csv"</c> — CSV files.</description></item>
+        /// <item><description><c>"json"</c> — newline-delimited JSON, one object per line.</description></item>
+        /// </list>
+        /// </summary>
+        public string Format { get; set; } = "parquet";
+
+        /// <summary>
+        /// The maximum number of rows per Parquet row group. If <c>null</c>, the
+        /// Parquet writer's default is used.
+        /// </summary>
+        public int? RowGroupSize { get; set; }
+
+        /// <summary>
+        /// The Parquet compression codec, such as <c>"snappy"</c>, <c>"zstd(3)"</c>,
+        /// <c>"gzip(6)"</c> or <c>"uncompressed"</c>. If <c>null</c>, the Parquet
+        /// writer's default is used.
+        /// </summary>
+        public string? Compression { get; set; }
+
+        /// <summary>
+        /// The maximum number of rows per file. Larger outputs are split into several
+        /// files. If <c>null</c>, everything is written to a single file.
+        /// </summary>
+        public long? MaxRowsPerFile { get; set; }
+
+        /// <summary>
+        /// Whether CSV files start with a header row. Default is <c>true</c>.
+        /// </summary>
+        public bool HasHeader { get; set; } = true;
+
+        /// <summary>
+        /// The CSV field delimiter. Default is <c>','</c>.
+        /// </summary>
+        public char Delimiter { get; set; } = ',';
+
+        internal byte[] ToJsonUtf8()
+        {
+            var json = new Dictionary<string, object>();
+            if (RowGroupSize.HasValue)
+            {
+                json["row_group_size"] = RowGroupSize.Value;
+            }
+            if (Compression != null)
+            {
+                json["compression"] = Compression;
+            }
+            if (MaxRowsPerFile.HasValue)
+            {
+                json["max_rows_per_file"] = MaxRowsPerFile.Value;
+            }
+            if (Format == "csv")
+            {
+                json["has_header"] = HasHeader;
+                json["delimiter"] = Delimiter.ToString();
+            }
+            return JsonSerializer.SerializeToUtf8Bytes(json);
+        }
+    }
+}
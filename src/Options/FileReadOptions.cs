@@ -0,0 +1,42 @@
+namespace lancedb
+{
+    using System.Collections.Generic;
+    using System.Text.Json;
+
+    /// <summary>
+    /// Options controlling how files are read by <see cref="Table.AddFromFiles"/> and
+    /// <see cref="Connection.CreateTableFromFiles"/>.
+    /// </summary>
+    public class FileReadOptions
+    {
+        /// <summary>
+        /// The format of the files.
+        /// <list type="bullet">
+        /// <item><description><c>"parquet"</c> (default) — Parquet files.</description></item>
+        /// <item><description><c>"csv"</c> — CSV files.</description></item>
+        /// <item><description><c>"json"</c> — newline-delimited JSON, one object per line.</description></item>
+        /// </list>
+        /// </summary>
+        public string Format { get; set; } = "parquet";
+
+        /// <summary>
+        /// Whether CSV files start with a header row. Default is <c>true</c>.
+        /// </summary>
+        public bool HasHeader { get; set; } = true;
+
+        /// <summary>
+        /// The CSV field delimiter. Default is <c>','</c>.
+        /// </summary>
+        public char Delimiter { get; set; } = ',';
+
+        internal byte[] ToJsonUtf8()
+        {
+            var json = new Dictionary<string, object>
+            {
+                ["has_header"] = HasHeader,
+                ["delimiter"] = Delimiter.ToString(),
+            };
+            return JsonSerializer.SerializeToUtf8Bytes(json);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+namespace lancedb
+{
+    using System.Collections.Generic;
+
+    /// <summary>
+    /// Options controlling the highlighted snippets added by <see cref="FTSQuery.Highlight"/>.
+    /// </summary>
+    public class HighlightOptions
+    {
+        /// <summary>
+        /// The text columns to highlight. If <c>null</c>, every column searched by the
+        /// query is highlighted. Each column must have a full-text search index and be
+        /// part of the selected columns.
+        /// </summary>
+        public IReadOnlyList<string>? Columns { get; set; }
+
+        /// <summary>
+        /// The tag inserted before each matched token. Default is <c>"&lt;em&gt;"</c>.
+        /// </summary>
+        public string PreTag { get; set; } = "<em>";
+
+        /// <summary>
+        /// The tag inserted after each matched token. Default is <c>"&lt;/em&gt;"</c>.
+        /// </summary>
+        public string PostTag { get; set; } = "</em>";
+
+        /// <summary>
+        /// The approximate length of each fragment in UTF-8 bytes, before tags are
+        /// added. Fragments are trimmed to whole words. Default is 100.
+        /// </summary>
+        public int FragmentSize { get; set; } = 100;
+
+        /// <summary>
+        /// The maximum number of fragments per row and column. Default is 3.
+        /// </summary>
+        public int MaxFragments { get; set; } = 3;
+
+        internal Dictionary<string, object> ToDictionary()
+        {
+            var dict = new Dictionary<string, object>
+            {
+                ["pre_tag"] = PreTag,
+                ["post_tag"] = PostTag,
+                ["fragment_size"] = FragmentSize,
+                ["max_fragments"] = MaxFragments,
+            };
+            if (Columns != null)
+            {
+                dict["columns"] = Columns;
+            }
+            return dict;
+        }
+    }
+}
@@ -0,0 +1,124 @@
+namespace lancedb
+{
+    using System;
+    using System.Collections.Generic;
+    using System.Text.Json;
+
+    /// <summary>
+    /// How a write reacts when its commit conflicts with a concurrent writer.
+    /// </summary>
+    public enum ConflictMode
+    {
+        /// <summary>
+        /// Re-run the write up to <see cref="WriteOptions.MaxRetries"/> times,
+        /// backing off between attempts. This is the default.
+        /// </summary>
+        Retry,
+
+        /// <summary>
+        /// Throw a <see cref="CommitConflictException"/> on the first conflict.
+        /// </summary>
+        FailFast,
+    }
+
+    /// <summary>
+    /// Options controlling how a write (add, update, delete or merge insert) is committed.
+    /// </summary>
+    /// <remarks>
+    /// The retry settings wrap lancedb's own writes. An idempotency key, or commit
+    /// metadata on an update or delete, is only supported for local tables.
+    /// </remarks>
+    public class WriteOptions
+    {
+        /// <summary>
+        /// The number of times to re-run the write when its commit conflicts with a
+        /// concurrent writer. If <c>null</c>, 10 retries are used.
+        /// </summary>
+        public int? MaxRetries { get; set; }
+
+        /// <summary>
+        /// The delay before the first retry. Each further retry doubles the delay,
+        /// up to <see cref="MaxBackoff"/>. If <c>null</c>, 50 milliseconds is used.
+        /// </summary>
+        public TimeSpan? InitialBackoff { get; set; }
+
+        /// <summary>
+        /// The longest delay between two retries. If <c>null</c>, 5 seconds is used.
+        /// </summary>
+        public TimeSpan? MaxBackoff { get; set; }
+
+        /// <summary>
+        /// Whether conflicts are retried or reported immediately.
+        /// Default is <see cref="lancedb.ConflictMode.Retry"/>.
+        /// </summary>
+        public ConflictMode ConflictMode { get; set; } = ConflictMode.Retry;
+
+        /// <summary>
+        /// A client-supplied token identifying this write, stored with its commit.
+        /// If a commit with the same key already exists, the write is not applied again
+        /// and the version of the original commit is returned instead, with zero row
+        /// counts. Supported by adds, updates, deletes and merge inserts on local tables.
+        /// </summary>
+        /// <remarks>
+        /// <para>
+        /// The latest <see cref="IdempotencyWindow"/> versions are searched for the key
+        /// before the write, and the commit itself checks every version committed since,
+        /// so concurrent writes with the same key are applied once.
+        /// </para>
+        /// <para>
+        /// Use <see cref="Table.FindIdempotencyKey(string, ulong?)"/> to check whether a key was committed.
+        /// </para>
+        /// </remarks>
+        public string? IdempotencyKey { get; set; }
+
+        /// <summary>
+        /// How many of the latest versions are searched for an earlier commit of
+        /// <see cref="IdempotencyKey"/>. A retry made after more versions than this were
+        /// committed is applied again. Each version searched reads that version's
+        /// transaction file, so a write with a new key costs up to this many small reads
+        /// before it is applied. If <c>null</c>, 100 versions are searched.
+        /// </summary>
+        public ulong? IdempotencyWindow { get; set; }
+
+        /// <summary>
+        /// Key-value metadata recorded with the commit, such as a job id, user or source
+        /// file. It is reported in <see cref="VersionInfo.CommitMetadata"/> by
+        /// <see cref="Table.ListVersions"/>. Only supported for local tables.
+        /// </summary>
+        public IReadOnlyDictionary<string, string>? CommitMetadata { get; set; }
+
+        internal byte[]? CommitMetadataJsonUtf8() => SerializeCommitMetadata(CommitMetadata);
+
+        internal static byte[]? SerializeCommitMetadata(IReadOnlyDictionary<string, string>? metadata) =>
+            metadata == null ? null : JsonSerializer.SerializeToUtf8Bytes(metadata);
+
+        internal byte[] ToJsonUtf8()
+        {
+            var dict = new Dictionary<string, object>
+            {
+                ["conflict_mode"] = ConflictMode == ConflictMode.FailFast ? "fail_fast" : "retry",
+            };
+            if (MaxRetries.HasValue)
+            {
+                dict["max_retries"] = MaxRetries.Value;
+            }
+            if (InitialBackoff.HasValue)
+            {
+                dict["initial_backoff_ms"] = (ulong)InitialBackoff.Value.TotalMilliseconds;
+            }
+            if (MaxBackoff.HasValue)
+            {
+                dict["max_backoff_ms"] = (ulong)MaxBackoff.Value.TotalMilliseconds;
+            }
+            if (IdempotencyKey != null)
+            {
+                dict["idempotency_key"] = IdempotencyKey;
+            }
+            if (IdempotencyWindow.HasValue)
+            {
+                dict["idempotency_window"] = IdempotencyWindow.Value;
+            }
+            return JsonSerializer.SerializeToUtf8Bytes(dict);
+        }
+    }
+}
@@ -1,7 +1,9 @@
 namespace lancedb
 {
     using System;
+    using System.Collections.Generic;
     using System.Runtime.InteropServices;
+    using System.Threading.Tasks;
 
     /// <summary>
     /// A builder for LanceDB queries.
@@ -44,11 +46,69 @@ namespace lancedb
         private static extern void query_output_schema(
             IntPtr table_ptr, IntPtr params_json, NativeCall.FfiCallback completion, IntPtr userData);
 
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void query_execute_page(
+            IntPtr table_ptr, IntPtr params_json, IntPtr page_token, long timeout_ms, uint max_batch_length,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void query_page_free(IntPtr ptr);
+
+        // Sort keys in priority order; serialized as "order_by"
+        private readonly List<Dictionary<string, object>> _orderBy = new List<Dictionary<string, object>>();
+
         internal Query(IntPtr tablePtr)
             : base(tablePtr)
         {
         }
 
+        /// <inheritdoc/>
+        internal override Dictionary<string, object> BuildParamsDict()
+        {
+            var dict = base.BuildParamsDict();
+            if (_orderBy.Count > 0)
+            {
+                dict["order_by"] = _orderBy;
+            }
+            return dict;
+        }
+
+        /// <summary>
+        /// Sort the results by the given column.
+        /// </summary>
+        /// <remarks>
+        /// <para>
+        /// Call this multiple times to sort by several columns; earlier calls take
+        /// priority. The sort runs natively, and <see cref="QueryBase{T}.Limit"/> and
+        /// <see cref="QueryBase{T}.Offset"/> are applied after it, so pages are
+        /// deterministic. When a limit is set only the top rows are kept while sorting.
+        /// </para>
+        /// <para>
+        /// Sorting is not supported once the query is converted to a vector query.
+        /// </para>
+        /// </remarks>
+        /// <param name="column">The column to sort by.</param>
+        /// <param name="ascending">Sort ascending (default) or descending.</param>
+        /// <param name="nullsFirst">
+        /// Whether nulls sort before other values. If <c>null</c>, nulls come last for
+        /// ascending sorts and first for descending sorts.
+        /// </param>
+        /// <returns>This query instance for method chaining.</returns>
+        public Query OrderBy(string column, bool ascending = true, bool? nullsFirst = null)
+        {
+            var entry = new Dictionary<string, object>
+            {
+                ["column"] = column,
+                ["ascending"] = ascending,
+            };
+            if (nullsFirst.HasValue)
+            {
+                entry["nulls_first"] = nullsFirst.Value;
+            }
+            _orderBy.Add(entry);
+            return this;
+        }
+
         /// <inheritdoc/>
         private protected override void NativeConsolidatedExecute(
             IntPtr tablePtr, IntPtr paramsJson, long timeoutMs, uint maxBatchLength,
@@ -76,6 +136,95 @@ namespace lancedb
             NativeCall.FfiCallback callback, IntPtr userData)
             => query_execute_stream(tablePtr, paramsJson, timeoutMs, maxBatchLength, callback, userData);
 
+        /// <summary>
+        /// Execute the query and write the result to Parquet, CSV or newline-delimited
+        /// JSON files.
+        /// </summary>
+        /// <remarks>
+        /// The result is streamed from the native query straight to the files, without
+        /// passing through managed memory. Files are named <c>part-00000.parquet</c>,
+        /// <c>part-00001.parquet</c>, and so on, and are written with the table's storage
+        /// options. If the export fails part way, the files written so far are deleted.
+        /// </remarks>
+        /// <param name="uri">The local directory or object store URI to write to.</param>
+        /// <param name="options">The file format, compression and file splitting.</param>
+        /// <returns>The files written and the number of rows.</returns>
+        public Task<ExportResult> Export(string uri, FileExportOptions? options = null)
+        {
+            return ExportCore(null, uri, options);
+        }
+
+        /// <summary>
+        /// Execute one page of the query using keyset (cursor) pagination.
+        /// </summary>
+        /// <remarks>
+        /// <para>
+        /// <see cref="QueryBase{T}.Limit"/> sets the page size and is required.
+        /// Rows are ordered by <see cref="OrderBy"/> (if any) and then by row ID.
+        /// Each page continues from the keys of the previous page's last row rather
+        /// than skipping an offset, so deep pages are as fast as the first one.
+        /// <see cref="QueryBase{T}.Offset"/> cannot be combined with pagination.
+        /// </para>
+        /// <para>
+        /// A page token is tied to the table version it was read at: later pages
+        /// read that same version, so paging walks a consistent snapshot while
+        /// writes continue.
+        /// </para>
+        /// </remarks>
+        /// <param name="pageToken">
+        /// The <see cref="QueryPage.NextPageToken"/> of the previous page, or <c>null</c>
+        /// for the first page.
+        /// </param>
+        /// <param name="timeout">
+        /// Optional maximum time for the query to run. If <c>null</c>, no timeout is applied.
+        /// </param>
+        /// <param name="maxBatchLength">
+        /// Optional maximum number of rows per batch. If <c>null</c>, uses the default (1024).
+        /// </param>
+        /// <returns>The page of results and the token for the next page.</returns>
+        public async Task<QueryPage> ToPage(
+            string? pageToken = null, TimeSpan? timeout = null, int? maxBatchLength = null)
+        {
+            long timeoutMs = timeout.HasValue ? (long)timeout.Value.TotalMilliseconds : -1;
+            uint batchLen = maxBatchLength.HasValue ? (uint)maxBatchLength.Value : 0;
+            byte[] jsonBytes = SerializeParamsUtf8();
+            byte[]? tokenBytes = pageToken != null ? NativeCall.ToUtf8(pageToken) : null;
+
+            IntPtr resultPtr = await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* pJson = jsonBytes)
+                    fixed (byte* pToken = tokenBytes)
+                    {
+                        query_execute_page(
+                            _tablePtr,
+                            new IntPtr(pJson),
+                            tokenBytes != null ? new IntPtr(pToken) : IntPtr.Zero,
+                            timeoutMs, batchLen, completion, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+
+            try
+            {
+                var page = Marshal.PtrToStructure<FfiQueryPage>(resultPtr);
+                // Ownership of the data and token moves to the managed side; clear
+                // them so query_page_free only releases the outer struct.
+                Marshal.WriteIntPtr(resultPtr, 0, IntPtr.Zero);
+                Marshal.WriteIntPtr(resultPtr, IntPtr.Size, IntPtr.Zero);
+                string? nextToken = page.NextPageToken != IntPtr.Zero
+                    ? NativeCall.ReadStringAndFree(page.NextPageToken)
+                    : null;
+                var batch = ArrowCDataHelper.ImportRecordBatchFromCData(page.Data);
+                return new QueryPage(batch, nextToken);
+            }
+            finally
+            {
+                query_page_free(resultPtr);
+            }
+        }
+
         /// <summary>
         /// Find the nearest vectors to the given query vector.
         /// </summary>
@@ -469,6 +469,45 @@ namespace lancedb
             return ArrowCDataHelper.ImportSchemaFromCData(ffiSchemaPtr);
         }
 
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void query_export(
+            IntPtr table_ptr, float[]? vector, UIntPtr vector_len, IntPtr params_json,
+            IntPtr uri, IntPtr format, IntPtr export_options_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
+        /// <summary>
+        /// Executes the query natively and writes the result to files under
+        /// <paramref name="uri"/>; <paramref name="vector"/> is <c>null</c> for a plain query.
+        /// </summary>
+        private protected async Task<ExportResult> ExportCore(
+            float[]? vector, string uri, FileExportOptions? options)
+        {
+            options ??= new FileExportOptions();
+            byte[] jsonBytes = SerializeParamsUtf8();
+            byte[] uriBytes = NativeCall.ToUtf8(uri);
+            byte[] formatBytes = NativeCall.ToUtf8(options.Format);
+            byte[] optionsJson = options.ToJsonUtf8();
+            var jsonHandle = PinJson(jsonBytes, out IntPtr pJson);
+
+            IntPtr result = await CallWithPinnedJson(jsonHandle, (completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* pUri = uriBytes)
+                    fixed (byte* pFormat = formatBytes)
+                    fixed (byte* pOptions = optionsJson)
+                    {
+                        query_export(
+                            _tablePtr, vector, (UIntPtr)(vector?.Length ?? 0), pJson,
+                            (IntPtr)pUri, (IntPtr)pFormat, (IntPtr)pOptions,
+                            completion, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+            string json = NativeCall.ReadStringAndFree(result);
+            return JsonSerializer.Deserialize<ExportResult>(json) ?? new ExportResult();
+        }
+
         /// <summary>
         /// Pins a byte array and calls the FFI function synchronously, returning the async result.
         /// The byte array is pinned using GCHandle and unpinned after the FFI function is called.
@@ -0,0 +1,40 @@
+namespace lancedb
+{
+    using System;
+    using System.Runtime.InteropServices;
+    using Apache.Arrow;
+
+    /// <summary>
+    /// Native FFI struct matching Rust FfiQueryPage layout.
+    /// </summary>
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct FfiQueryPage
+    {
+        public IntPtr Data;
+        public IntPtr NextPageToken;
+    }
+
+    /// <summary>
+    /// One page of results from keyset pagination.
+    /// Returned by <see cref="Query.ToPage"/>.
+    /// </summary>
+    public class QueryPage
+    {
+        /// <summary>
+        /// The rows of this page.
+        /// </summary>
+        public RecordBatch Batch { get; }
+
+        /// <summary>
+        /// An opaque token that resumes reading after the last row of this page,
+        /// or <c>null</c> when there are no more rows.
+        /// </summary>
+        public string? NextPageToken { get; }
+
+        internal QueryPage(RecordBatch batch, string? nextPageToken)
+        {
+            Batch = batch;
+            NextPageToken = nextPageToken;
+        }
+    }
+}
@@ -1,10 +1,12 @@
 namespace lancedb
 {
     using System.Text.Json.Serialization;
+    using System.Runtime.InteropServices;
 
     /// <summary>
     /// The result of an add (insert) operation.
     /// </summary>
+    [StructLayout(LayoutKind.Sequential)]
     public struct AddResult
     {
         /// <summary>
@@ -14,5 +16,26 @@ namespace lancedb
         /// </summary>
         [JsonPropertyName("version")]
         public ulong Version;
+
+        /// <summary>
+        /// The number of rows written as new rows.
+        /// </summary>
+        [JsonPropertyName("num_inserted_rows")]
+        public ulong NumInsertedRows;
+
+        /// <summary>
+        /// The number of existing rows replaced because their primary key matched.
+        /// Only non-zero with <see cref="DuplicateKeyHandling.Overwrite"/>.
+        /// </summary>
+        [JsonPropertyName("num_updated_rows")]
+        public ulong NumUpdatedRows;
+
+        /// <summary>
+        /// The number of added rows dropped because their primary key already existed
+        /// or repeated within the data. Only set when
+        /// <see cref="AddOptions.OnDuplicateKey"/> is used.
+        /// </summary>
+        [JsonPropertyName("num_skipped_rows")]
+        public ulong NumSkippedRows;
     }
 }
@@ -0,0 +1,47 @@
+namespace lancedb
+{
+    using System;
+    using System.Runtime.InteropServices;
+    using Apache.Arrow;
+
+    /// <summary>
+    /// Native FFI struct matching Rust FfiDeleteRowIdsResult layout.
+    /// </summary>
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct FfiDeleteRowIdsResult
+    {
+        public ulong Version;
+        public ulong NumDeletedRows;
+        public IntPtr DeletedRows;
+    }
+
+    /// <summary>
+    /// The result of deleting rows by row ID.
+    /// Returned by <see cref="Table.DeleteRowIds"/>.
+    /// </summary>
+    public class DeleteRowIdsResult
+    {
+        /// <summary>
+        /// The commit version associated with the operation.
+        /// </summary>
+        public ulong Version { get; }
+
+        /// <summary>
+        /// The number of rows that were deleted.
+        /// </summary>
+        public ulong NumDeletedRows { get; }
+
+        /// <summary>
+        /// The rows that were deleted, with the requested columns, or <c>null</c>
+        /// if the rows were not requested.
+        /// </summary>
+        public RecordBatch? DeletedRows { get; }
+
+        internal DeleteRowIdsResult(ulong version, ulong numDeletedRows, RecordBatch? deletedRows)
+        {
+            Version = version;
+            NumDeletedRows = numDeletedRows;
+            DeletedRows = deletedRows;
+        }
+    }
+}
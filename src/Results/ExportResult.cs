@@ -0,0 +1,23 @@
+namespace lancedb
+{
+    using System.Collections.Generic;
+    using System.Text.Json.Serialization;
+
+    /// <summary>
+    /// The result of exporting a table or query result to files.
+    /// </summary>
+    public class ExportResult
+    {
+        /// <summary>
+        /// The URIs of the files written, in order.
+        /// </summary>
+        [JsonPropertyName("files")]
+        public IReadOnlyList<string> Files { get; set; } = System.Array.Empty<string>();
+
+        /// <summary>
+        /// The number of rows written across all files.
+        /// </summary>
+        [JsonPropertyName("num_rows")]
+        public ulong NumRows { get; set; }
+    }
+}
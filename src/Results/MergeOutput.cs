@@ -0,0 +1,29 @@
+namespace lancedb
+{
+    using Apache.Arrow;
+
+    /// <summary>
+    /// The result of a merge insert that also reports the rows it affected.
+    /// Returned by <see cref="MergeInsertBuilder.ExecuteWithAffectedRows(RecordBatch)"/>.
+    /// </summary>
+    public class MergeOutput
+    {
+        /// <summary>
+        /// Statistics about the merge operation.
+        /// </summary>
+        public MergeResult Result { get; }
+
+        /// <summary>
+        /// One row per affected key: the <c>on</c> key columns, an <c>action</c>
+        /// column (<c>inserted</c>, <c>updated</c> or <c>deleted</c>) and the new
+        /// <c>_rowid</c> of written rows (<c>null</c> for deleted rows).
+        /// </summary>
+        public RecordBatch AffectedRows { get; }
+
+        internal MergeOutput(MergeResult result, RecordBatch affectedRows)
+        {
+            Result = result;
+            AffectedRows = affectedRows;
+        }
+    }
+}
@@ -1,5 +1,6 @@
 namespace lancedb
 {
+    using System;
     using System.Runtime.InteropServices;
     using System.Text.Json.Serialization;
 
@@ -63,5 +64,12 @@ namespace lancedb
         /// </remarks>
         [JsonPropertyName("num_rows")]
         public ulong NumRows;
+
+        /// <summary>
+        /// Native Arrow C Data pointer to the affected rows, or zero when they were
+        /// not requested. Read by <see cref="Table"/> and reported through
+        /// <see cref="MergeOutput.AffectedRows"/>.
+        /// </summary>
+        internal IntPtr AffectedRows;
     }
 }
@@ -0,0 +1,18 @@
+namespace lancedb
+{
+    /// <summary>
+    /// How an <see cref="IvfRqIndex"/> rotates vectors before quantizing them.
+    /// </summary>
+    public enum RqRotationType
+    {
+        /// <summary>
+        /// A fast structured rotation, cheaper to apply to high-dimensional vectors.
+        /// </summary>
+        Fast,
+
+        /// <summary>
+        /// A dense random rotation matrix.
+        /// </summary>
+        Matrix,
+    }
+}
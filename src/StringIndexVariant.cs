@@ -0,0 +1,22 @@
+namespace lancedb
+{
+    /// <summary>
+    /// An index to build on a string column in place of a BTree.
+    /// </summary>
+    /// <remarks>
+    /// Set on <see cref="BTreeIndex.Variant"/>.
+    /// </remarks>
+    public enum StringIndexVariant
+    {
+        /// <summary>
+        /// An n-gram index, which accelerates <c>contains(col, 'needle')</c> filters.
+        /// </summary>
+        Ngram,
+
+        /// <summary>
+        /// An inverted index of the column's tokens, configured with
+        /// <see cref="BTreeIndex.Tokenizer"/>.
+        /// </summary>
+        Inverted,
+    }
+}
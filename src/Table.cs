@@ -42,17 +42,51 @@ namespace lancedb
         private static extern void table_delete(
             IntPtr table_ptr, IntPtr predicate, NativeCall.FfiCallback completion, IntPtr userData);
 
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void table_delete_with_options(
+            IntPtr table_ptr, IntPtr predicate, IntPtr write_options_json, IntPtr commit_metadata_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void table_aggregate(
+            IntPtr table_ptr, IntPtr spec_json, NativeCall.FfiCallback completion, IntPtr userData);
+
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern void table_delete_result_free(IntPtr ptr);
 
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void table_delete_row_ids(
+            IntPtr table_ptr, IntPtr row_ids, nuint row_ids_len,
+            [MarshalAs(UnmanagedType.U1)] bool return_rows, IntPtr columns_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void table_delete_row_ids_result_free(IntPtr ptr);
+
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern void table_update(
             IntPtr table_ptr, IntPtr filter, IntPtr columns_json,
             NativeCall.FfiCallback completion, IntPtr userData);
 
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void table_update_with_options(
+            IntPtr table_ptr, IntPtr filter, IntPtr columns_json, IntPtr write_options_json,
+            IntPtr commit_metadata_json, NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern unsafe void table_update_from_batch(
+            IntPtr table_ptr, IntPtr on_columns_json,
+            CArrowArray* arrays, CArrowSchema* schema, nuint batch_count, IntPtr write_options_json,
+            IntPtr commit_metadata_json, NativeCall.FfiCallback completion, IntPtr userData);
+
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern void table_update_result_free(IntPtr ptr);
 
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void table_begin_transaction(
+            IntPtr table_ptr, IntPtr on_columns_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern void table_merge_result_free(IntPtr ptr);
 
@@ -65,10 +99,42 @@ namespace lancedb
             IntPtr table_ptr, CArrowArray* arrays, CArrowSchema* schema, nuint batch_count,
             IntPtr mode, NativeCall.FfiCallback completion, IntPtr userData);
 
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern unsafe void table_add_with_options(
+            IntPtr table_ptr, CArrowArray* arrays, CArrowSchema* schema, nuint batch_count,
+            IntPtr mode, IntPtr write_options_json,
+            [MarshalAs(UnmanagedType.U1)] bool cast_to_table_schema, IntPtr commit_metadata_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void table_export(
+            IntPtr table_ptr, long version, IntPtr uri, IntPtr format, IntPtr export_options_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void table_add_from_files(
+            IntPtr table_ptr, IntPtr paths_json, IntPtr format, IntPtr read_options_json, IntPtr mode,
+            [MarshalAs(UnmanagedType.U1)] bool cast_to_table_schema, IntPtr commit_metadata_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern void table_version(
             IntPtr table_ptr, NativeCall.FfiCallback completion, IntPtr userData);
 
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern unsafe void table_add_deduplicated(
+            IntPtr table_ptr, CArrowArray* arrays, CArrowSchema* schema, nuint batch_count,
+            IntPtr on_duplicate, IntPtr write_options_json,
+            [MarshalAs(UnmanagedType.U1)] bool cast_to_table_schema, IntPtr commit_metadata_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void table_add_result_free(IntPtr ptr);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void table_find_idempotency_key(
+            IntPtr table_ptr, IntPtr key, ulong window, NativeCall.FfiCallback completion, IntPtr userData);
+
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern void table_uses_v2_manifest_paths(
             IntPtr table_ptr, NativeCall.FfiCallback completion, IntPtr userData);
@@ -113,10 +179,24 @@ namespace lancedb
             [MarshalAs(UnmanagedType.U1)] bool train, long wait_timeout_ms,
             NativeCall.FfiCallback completion, IntPtr userData);
 
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern unsafe void table_create_index_with_model(
+            IntPtr table_ptr, IntPtr columns_json, int index_type, IntPtr config_json,
+            [MarshalAs(UnmanagedType.U1)] bool replace, IntPtr name,
+            [MarshalAs(UnmanagedType.U1)] bool train,
+            CArrowArray* centroids_array, CArrowSchema* centroids_schema,
+            CArrowArray* codebook_array, CArrowSchema* codebook_schema,
+            IntPtr source_table_ptr, IntPtr source_index_name,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern void table_list_indices(
             IntPtr table_ptr, NativeCall.FfiCallback completion, IntPtr userData);
 
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void table_fts_analyze(
+            IntPtr table_ptr, IntPtr index_name, IntPtr text, NativeCall.FfiCallback completion, IntPtr userData);
+
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern void table_drop_index(
             IntPtr table_ptr, IntPtr name, NativeCall.FfiCallback completion, IntPtr userData);
@@ -157,20 +237,24 @@ namespace lancedb
         private static extern void table_index_stats_free(IntPtr ptr);
 
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
-        private static extern void table_add_columns(
-            IntPtr table_ptr, IntPtr transforms_json, NativeCall.FfiCallback completion, IntPtr userData);
+        private static extern void table_add_columns_with_options(
+            IntPtr table_ptr, IntPtr transforms_json, IntPtr commit_metadata_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
 
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
-        private static extern unsafe void table_add_columns_null(
-            IntPtr table_ptr, CArrowSchema* schema, NativeCall.FfiCallback completion, IntPtr userData);
+        private static extern unsafe void table_add_columns_null_with_options(
+            IntPtr table_ptr, CArrowSchema* schema, IntPtr commit_metadata_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
 
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
-        private static extern void table_alter_columns(
-            IntPtr table_ptr, IntPtr alterations_json, NativeCall.FfiCallback completion, IntPtr userData);
+        private static extern void table_alter_columns_with_options(
+            IntPtr table_ptr, IntPtr alterations_json, IntPtr commit_metadata_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
 
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
-        private static extern void table_drop_columns(
-            IntPtr table_ptr, IntPtr columns_json, NativeCall.FfiCallback completion, IntPtr userData);
+        private static extern void table_drop_columns_with_options(
+            IntPtr table_ptr, IntPtr columns_json, IntPtr commit_metadata_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
 
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern void table_optimize(
@@ -206,15 +290,13 @@ namespace lancedb
             IntPtr table_ptr, IntPtr tag, NativeCall.FfiCallback completion, IntPtr userData);
 
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
-        private static extern unsafe void table_merge_insert(
-            IntPtr table_ptr, IntPtr on_columns_json,
-            [MarshalAs(UnmanagedType.U1)] bool when_matched_update_all, IntPtr when_matched_update_all_filter,
-            [MarshalAs(UnmanagedType.U1)] bool when_not_matched_insert_all,
-            [MarshalAs(UnmanagedType.U1)] bool when_not_matched_by_source_delete, IntPtr when_not_matched_by_source_delete_filter,
+        private static extern unsafe void table_merge_insert_clauses(
+            IntPtr table_ptr, IntPtr on_columns_json, IntPtr clauses_json,
             CArrowArray* arrays, CArrowSchema* schema, nuint batch_count,
             [MarshalAs(UnmanagedType.U1)] bool use_index, long timeout_ms,
-            int use_lsm_write,
-            NativeCall.FfiCallback completion, IntPtr userData);
+            int use_lsm_write, [MarshalAs(UnmanagedType.U1)] bool return_affected,
+            IntPtr write_options_json, [MarshalAs(UnmanagedType.U1)] bool cast_to_table_schema,
+            IntPtr commit_metadata_json, NativeCall.FfiCallback completion, IntPtr userData);
 
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern void table_take_offsets(
@@ -228,6 +310,11 @@ namespace lancedb
             [MarshalAs(UnmanagedType.U1)] bool with_row_id,
             NativeCall.FfiCallback completion, IntPtr userData);
 
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern unsafe void table_get_by_keys(
+            IntPtr table_ptr, CArrowArray* keys_array, CArrowSchema* keys_schema, IntPtr columns_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
         [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
         private static extern void table_initial_storage_options(
             IntPtr table_ptr, NativeCall.FfiCallback completion, IntPtr userData);
@@ -539,6 +626,54 @@ namespace lancedb
             return result.ToInt64();
         }
 
+        /// <summary>
+        /// Compute aggregates over the table, optionally filtered and grouped.
+        /// </summary>
+        /// <remarks>
+        /// The aggregation runs natively and only the result is returned, so large
+        /// columns are never transferred. The filter is pushed down into the scan and
+        /// can use scalar indices.
+        /// </remarks>
+        /// <param name="aggregates">The aggregates to compute.</param>
+        /// <param name="filter">
+        /// A SQL where clause selecting the rows to aggregate. If <c>null</c>, all rows are used.
+        /// </param>
+        /// <param name="groupBy">
+        /// Columns to group by. If <c>null</c> or empty, a single row is returned.
+        /// </param>
+        /// <returns>
+        /// A <see cref="RecordBatch"/> with the group columns followed by one column per aggregate.
+        /// </returns>
+        public async Task<RecordBatch> Aggregate(
+            IReadOnlyList<Aggregate> aggregates, string? filter = null, IReadOnlyList<string>? groupBy = null)
+        {
+            var spec = new Dictionary<string, object>
+            {
+                ["aggregates"] = aggregates.Select(a => a.ToJsonDict()).ToList(),
+            };
+            if (filter != null)
+            {
+                spec["filter"] = filter;
+            }
+            if (groupBy != null)
+            {
+                spec["group_by"] = groupBy;
+            }
+            byte[] specJson = NativeCall.ToUtf8(JsonSerializer.Serialize(spec));
+
+            IntPtr result = await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* p = specJson)
+                    {
+                        table_aggregate(_handle!.DangerousGetHandle(), (IntPtr)p, completion, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+            return ArrowCDataHelper.ImportRecordBatchFromCData(result);
+        }
+
         /// <summary>
         /// Return statistics about the table.
         /// </summary>
@@ -580,20 +715,40 @@ namespace lancedb
         /// For example, <c>"x = 2"</c> or <c>"x IN (1, 2, 3)"</c>.
         /// The filter must not be empty, or it will error.
         /// </param>
+        /// <param name="options">
+        /// Optional options controlling how the delete is committed, such as how commit
+        /// conflicts are retried. An idempotency key is only supported for local tables.
+        /// If <c>null</c>, the table's default commit behavior is used.
+        /// </param>
         /// <returns>
         /// A <see cref="DeleteResult"/> containing the commit version of the operation.
         /// </returns>
-        public async Task<DeleteResult> Delete(string predicate)
+        /// <exception cref="CommitConflictException">
+        /// The delete conflicted with a concurrent write and no retries remained.
+        /// </exception>
+        public async Task<DeleteResult> Delete(string predicate, WriteOptions? options = null)
         {
             byte[] utf8Predicate = NativeCall.ToUtf8(predicate);
+            byte[]? utf8Options = options?.ToJsonUtf8();
+            byte[]? utf8Metadata = options?.CommitMetadataJsonUtf8();
             IntPtr resultPtr = await NativeCall.Async((completion, userData) =>
             {
                 unsafe
                 {
                     fixed (byte* p = utf8Predicate)
+                    fixed (byte* po = utf8Options)
+                    fixed (byte* pm = utf8Metadata)
                     {
-                        table_delete(
-                            _handle!.DangerousGetHandle(), (IntPtr)p, completion, userData);
+                        if (options == null)
+                        {
+                            table_delete(_handle!.DangerousGetHandle(), (IntPtr)p, completion, userData);
+                        }
+                        else
+                        {
+                            table_delete_with_options(
+                                _handle!.DangerousGetHandle(), (IntPtr)p, (IntPtr)po, (IntPtr)pm,
+                                completion, userData);
+                        }
                     }
                 }
             }).ConfigureAwait(false);
@@ -608,6 +763,72 @@ namespace lancedb
             }
         }
 
+        /// <summary>
+        /// Delete rows from the table by their row IDs.
+        /// </summary>
+        /// <remarks>
+        /// Row IDs are obtained by scanning or searching the table with
+        /// <see cref="QueryBase{T}.WithRowId"/>. IDs that do not match a row are ignored.
+        /// When <paramref name="returnRows"/> is <c>true</c>, the deleted rows are returned,
+        /// read from the same table version the delete applies to, for example to record
+        /// them in an audit log. Only supported for local tables.
+        /// </remarks>
+        /// <param name="rowIds">The row IDs of the rows to delete.</param>
+        /// <param name="returnRows">Whether to return the deleted rows.</param>
+        /// <param name="columns">
+        /// The columns to return for deleted rows, such as just the primary key.
+        /// If <c>null</c>, all table columns are returned; list <c>_rowid</c> to also return the
+        /// row IDs. Ignored unless <paramref name="returnRows"/> is set.
+        /// </param>
+        /// <returns>
+        /// A <see cref="DeleteRowIdsResult"/> with the commit version, the number of deleted
+        /// rows and, if requested, the deleted rows.
+        /// </returns>
+        public async Task<DeleteRowIdsResult> DeleteRowIds(
+            IReadOnlyList<ulong> rowIds, bool returnRows = false, IReadOnlyList<string>? columns = null)
+        {
+            ulong[] ids = rowIds.ToArray();
+            byte[]? columnsBytes = columns != null
+                ? NativeCall.ToUtf8(JsonSerializer.Serialize(columns)) : null;
+
+            IntPtr resultPtr = await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (ulong* pIds = ids)
+                    fixed (byte* pColumns = columnsBytes)
+                    {
+                        table_delete_row_ids(
+                            _handle!.DangerousGetHandle(),
+                            (IntPtr)pIds, (nuint)ids.Length, returnRows,
+                            columnsBytes != null ? (IntPtr)pColumns : IntPtr.Zero,
+                            completion, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+
+            try
+            {
+                var ffi = Marshal.PtrToStructure<FfiDeleteRowIdsResult>(resultPtr);
+                RecordBatch? deleted = null;
+                if (ffi.DeletedRows != IntPtr.Zero)
+                {
+                    // ImportRecordBatchFromCData frees the Arrow data; clear the field so the
+                    // native free below only releases the outer struct.
+                    Marshal.WriteIntPtr(
+                        resultPtr,
+                        (int)Marshal.OffsetOf<FfiDeleteRowIdsResult>(nameof(FfiDeleteRowIdsResult.DeletedRows)),
+                        IntPtr.Zero);
+                    deleted = ArrowCDataHelper.ImportRecordBatchFromCData(ffi.DeletedRows);
+                }
+                return new DeleteRowIdsResult(ffi.Version, ffi.NumDeletedRows, deleted);
+            }
+            finally
+            {
+                table_delete_row_ids_result_free(resultPtr);
+            }
+        }
+
         /// <summary>
         /// Update rows in the table with literal values.
         /// </summary>
@@ -623,7 +844,7 @@ namespace lancedb
         /// <see cref="SqlValue.ValueToSql"/>, so embedded characters such as single
         /// quotes are handled safely. To update a column using an expression based on
         /// the previous value (e.g. <c>"x + 1"</c>), use
-        /// <see cref="Update(Dictionary{string, string}, string)"/> instead.
+        /// <see cref="Update(Dictionary{string, string}, string, WriteOptions)"/> instead.
         /// </para>
         /// </remarks>
         /// <param name="updates">
@@ -672,14 +893,25 @@ namespace lancedb
         /// An optional SQL filter that controls which rows are updated
         /// (e.g., <c>"x = 2"</c>). If <c>null</c>, all rows are updated.
         /// </param>
+        /// <param name="options">
+        /// Optional options controlling how the update is committed, such as how commit
+        /// conflicts are retried. An idempotency key is only supported for local tables.
+        /// If <c>null</c>, the table's default commit behavior is used.
+        /// </param>
         /// <returns>
         /// An <see cref="UpdateResult"/> containing the number of rows updated and the
         /// commit version of the operation.
         /// </returns>
-        public async Task<UpdateResult> Update(Dictionary<string, string> updatesSql, string? @where = null)
+        /// <exception cref="CommitConflictException">
+        /// The update conflicted with a concurrent write and no retries remained.
+        /// </exception>
+        public async Task<UpdateResult> Update(
+            Dictionary<string, string> updatesSql, string? @where = null, WriteOptions? options = null)
         {
             var columnSqlExprs = updatesSql.Select(kv => new[] { kv.Key, kv.Value }).ToArray();
             byte[] utf8ColumnSqlExprs = JsonSerializer.SerializeToUtf8Bytes(columnSqlExprs);
+            byte[]? utf8Options = options?.ToJsonUtf8();
+            byte[]? utf8Metadata = options?.CommitMetadataJsonUtf8();
 
             IntPtr resultPtr;
             if (@where == null)
@@ -689,9 +921,10 @@ namespace lancedb
                     unsafe
                     {
                         fixed (byte* pc = utf8ColumnSqlExprs)
+                        fixed (byte* po = utf8Options)
+                        fixed (byte* pm = utf8Metadata)
                         {
-                            table_update(
-                                _handle!.DangerousGetHandle(), IntPtr.Zero, (IntPtr)pc,
+                            CallUpdate(IntPtr.Zero, (IntPtr)pc, (IntPtr)po, (IntPtr)pm, options != null,
                                 completion, userData);
                         }
                     }
@@ -706,9 +939,10 @@ namespace lancedb
                     {
                         fixed (byte* pw = utf8Where)
                         fixed (byte* pc = utf8ColumnSqlExprs)
+                        fixed (byte* po = utf8Options)
+                        fixed (byte* pm = utf8Metadata)
                         {
-                            table_update(
-                                _handle!.DangerousGetHandle(), (IntPtr)pw, (IntPtr)pc,
+                            CallUpdate((IntPtr)pw, (IntPtr)pc, (IntPtr)po, (IntPtr)pm, options != null,
                                 completion, userData);
                         }
                     }
@@ -725,6 +959,133 @@ namespace lancedb
             }
         }
 
+        private void CallUpdate(
+            IntPtr filter, IntPtr columnsJson, IntPtr writeOptionsJson, IntPtr commitMetadataJson,
+            bool withOptions, NativeCall.FfiCallback completion, IntPtr userData)
+        {
+            if (withOptions)
+            {
+                table_update_with_options(
+                    _handle!.DangerousGetHandle(), filter, columnsJson, writeOptionsJson,
+                    commitMetadataJson, completion, userData);
+            }
+            else
+            {
+                table_update(_handle!.DangerousGetHandle(), filter, columnsJson, completion, userData);
+            }
+        }
+
+        /// <summary>
+        /// Update rows in the table from Arrow values, matched by key.
+        /// </summary>
+        /// <remarks>
+        /// <para>
+        /// Each row in <paramref name="values"/> carries the key columns named by
+        /// <paramref name="on"/> plus the new values for the columns to update. Rows of
+        /// the table whose keys match a source row have those columns overwritten; source
+        /// rows without a match are ignored and nothing is inserted.
+        /// </para>
+        /// <para>
+        /// Unlike <see cref="Update(Dictionary{string, string}, string, WriteOptions)"/>, values are
+        /// never formatted into SQL, so vectors, binary data and floating-point values are
+        /// written exactly.
+        /// </para>
+        /// </remarks>
+        /// <param name="on">The key columns used to match source rows to table rows.</param>
+        /// <param name="values">
+        /// The batches holding the key columns and the new values. All batches must share
+        /// the same schema.
+        /// </param>
+        /// <param name="options">
+        /// Optional options controlling how the update is committed, such as how commit
+        /// conflicts are retried, an idempotency key and commit metadata. If <c>null</c>,
+        /// the table's default commit behavior is used.
+        /// </param>
+        /// <returns>
+        /// An <see cref="UpdateResult"/> containing the number of rows updated and the
+        /// commit version of the operation.
+        /// </returns>
+        /// <exception cref="CommitConflictException">
+        /// The update conflicted with a concurrent write and no retries remained.
+        /// </exception>
+        public async Task<UpdateResult> Update(
+            IReadOnlyList<string> on, IReadOnlyList<RecordBatch> values, WriteOptions? options = null)
+        {
+            if (on.Count == 0)
+            {
+                throw new ArgumentException("At least one key column is required.", nameof(on));
+            }
+            if (values.Count == 0)
+            {
+                throw new ArgumentException("At least one batch is required.", nameof(values));
+            }
+
+            byte[] onColumnsBytes = JsonSerializer.SerializeToUtf8Bytes(on);
+            byte[]? optionsBytes = options?.ToJsonUtf8();
+            byte[]? metadataBytes = options?.CommitMetadataJsonUtf8();
+
+            IntPtr resultPtr = await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* pOnColumns = onColumnsBytes)
+                    fixed (byte* pOptions = optionsBytes)
+                    fixed (byte* pMetadata = metadataBytes)
+                    {
+                        var cArrays = new CArrowArray[values.Count];
+                        var cSchemaArr = new CArrowSchema[1];
+                        fixed (CArrowSchema* pSchema = cSchemaArr)
+                        {
+                            CArrowSchemaExporter.ExportSchema(values[0].Schema, pSchema);
+                            for (int i = 0; i < values.Count; i++)
+                            {
+                                cArrays[i] = default;
+                                var clone = ArrowCDataHelper.CloneBatchForExport(values[i]);
+                                fixed (CArrowArray* pArr = &cArrays[i])
+                                {
+                                    CArrowArrayExporter.ExportRecordBatch(clone, pArr);
+                                }
+                            }
+                            fixed (CArrowArray* pArrays = cArrays)
+                            {
+                                table_update_from_batch(
+                                    _handle!.DangerousGetHandle(), (IntPtr)pOnColumns,
+                                    pArrays, pSchema, (nuint)values.Count, (IntPtr)pOptions,
+                                    (IntPtr)pMetadata, completion, userData);
+                            }
+                        }
+                    }
+                }
+            }).ConfigureAwait(false);
+
+            try
+            {
+                return Marshal.PtrToStructure<UpdateResult>(resultPtr);
+            }
+            finally
+            {
+                table_update_result_free(resultPtr);
+            }
+        }
+
+        /// <summary>
+        /// Update rows in the table from a single Arrow batch, matched by key.
+        /// </summary>
+        /// <param name="on">The key columns used to match source rows to table rows.</param>
+        /// <param name="values">The key columns and the new values.</param>
+        /// <param name="options">
+        /// Optional options controlling how the update is committed. If <c>null</c>,
+        /// the table's default commit behavior is used.
+        /// </param>
+        /// <returns>
+        /// An <see cref="UpdateResult"/> containing the number of rows updated and the
+        /// commit version of the operation.
+        /// </returns>
+        public Task<UpdateResult> Update(IReadOnlyList<string> on, RecordBatch values, WriteOptions? options = null)
+        {
+            return Update(on, new[] { values }, options);
+        }
+
         /// <summary>
         /// Return the <see cref="Apache.Arrow.Schema">Arrow Schema</see> of this table.
         /// </summary>
@@ -783,13 +1144,31 @@ namespace lancedb
                     data[i], options.OnBadVectors, options.FillValue);
             }
 
+            byte[]? utf8WriteOptions = options.Write?.ToJsonUtf8();
+            byte[]? utf8Metadata = options.Write?.CommitMetadataJsonUtf8();
+            if (options.OnDuplicateKey.HasValue)
+            {
+                if (options.Mode != "append")
+                {
+                    throw new ArgumentException(
+                        "OnDuplicateKey can only be used with the \"append\" mode.", nameof(options));
+                }
+                return await AddDeduplicated(
+                    processed, options.OnDuplicateKey.Value, utf8WriteOptions,
+                    options.CastToTableSchema, utf8Metadata).ConfigureAwait(false);
+            }
+
             byte[] utf8Mode = NativeCall.ToUtf8(options.Mode);
+            bool castToTableSchema = options.CastToTableSchema;
+            bool withOptions = options.Write != null || castToTableSchema;
 
             IntPtr resultPtr = await NativeCall.Async((completion, userData) =>
             {
                 unsafe
                 {
                     fixed (byte* pMode = utf8Mode)
+                    fixed (byte* pWrite = utf8WriteOptions)
+                    fixed (byte* pMetadata = utf8Metadata)
                     {
                         var cArrays = new CArrowArray[processed.Length];
                         var cSchemaArr = new CArrowSchema[1];
@@ -807,10 +1186,88 @@ namespace lancedb
                             }
                             fixed (CArrowArray* pArrays = cArrays)
                             {
-                                table_add(
+                                if (withOptions)
+                                {
+                                    table_add_with_options(
+                                        _handle!.DangerousGetHandle(),
+                                        pArrays, pSchema, (nuint)processed.Length,
+                                        (IntPtr)pMode, (IntPtr)pWrite, castToTableSchema, (IntPtr)pMetadata,
+                                        completion, userData);
+                                }
+                                else
+                                {
+                                    table_add(
+                                        _handle!.DangerousGetHandle(),
+                                        pArrays, pSchema, (nuint)processed.Length,
+                                        (IntPtr)pMode, completion, userData);
+                                }
+                            }
+                        }
+                    }
+                }
+            }).ConfigureAwait(false);
+
+            if (!withOptions)
+            {
+                return new AddResult
+                {
+                    Version = (ulong)resultPtr.ToInt64(),
+                    NumInsertedRows = (ulong)processed.Sum(b => (long)b.Length),
+                };
+            }
+            // The native result reports zero inserted rows when an idempotency key
+            // was already committed and nothing was written.
+            try
+            {
+                return Marshal.PtrToStructure<AddResult>(resultPtr);
+            }
+            finally
+            {
+                table_add_result_free(resultPtr);
+            }
+        }
+
+        private async Task<AddResult> AddDeduplicated(
+            RecordBatch[] data, DuplicateKeyHandling onDuplicate, byte[]? utf8WriteOptions,
+            bool castToTableSchema, byte[]? utf8Metadata)
+        {
+            string mode = onDuplicate switch
+            {
+                DuplicateKeyHandling.Skip => "skip",
+                DuplicateKeyHandling.Error => "error",
+                DuplicateKeyHandling.Overwrite => "overwrite",
+                _ => throw new ArgumentOutOfRangeException(nameof(onDuplicate)),
+            };
+            byte[] utf8OnDuplicate = NativeCall.ToUtf8(mode);
+
+            IntPtr resultPtr = await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* pOnDuplicate = utf8OnDuplicate)
+                    fixed (byte* pWrite = utf8WriteOptions)
+                    fixed (byte* pMetadata = utf8Metadata)
+                    {
+                        var cArrays = new CArrowArray[data.Length];
+                        var cSchemaArr = new CArrowSchema[1];
+                        fixed (CArrowSchema* pSchema = cSchemaArr)
+                        {
+                            CArrowSchemaExporter.ExportSchema(data[0].Schema, pSchema);
+                            for (int i = 0; i < data.Length; i++)
+                            {
+                                cArrays[i] = default;
+                                var clone = ArrowCDataHelper.CloneBatchForExport(data[i]);
+                                fixed (CArrowArray* pArr = &cArrays[i])
+                                {
+                                    CArrowArrayExporter.ExportRecordBatch(clone, pArr);
+                                }
+                            }
+                            fixed (CArrowArray* pArrays = cArrays)
+                            {
+                                table_add_deduplicated(
                                     _handle!.DangerousGetHandle(),
-                                    pArrays, pSchema, (nuint)processed.Length,
-                                    (IntPtr)pMode,
+                                    pArrays, pSchema, (nuint)data.Length,
+                                    (IntPtr)pOnDuplicate, (IntPtr)pWrite, castToTableSchema, (IntPtr)pMetadata,
                                     completion, userData);
                             }
                         }
@@ -818,7 +1275,14 @@ namespace lancedb
                 }
             }).ConfigureAwait(false);
 
-            return new AddResult { Version = (ulong)resultPtr.ToInt64() };
+            try
+            {
+                return Marshal.PtrToStructure<AddResult>(resultPtr);
+            }
+            finally
+            {
+                table_add_result_free(resultPtr);
+            }
         }
 
         /// <summary>
@@ -853,6 +1317,148 @@ namespace lancedb
             return Add(new[] { data }, options);
         }
 
+        /// <summary>
+        /// Add the contents of Parquet, CSV or newline-delimited JSON files to the
+        /// <see cref="Table"/>.
+        /// </summary>
+        /// <remarks>
+        /// The files are read natively and streamed into the table as a single commit,
+        /// without passing through managed memory. Paths may be local files, directories
+        /// or object store URIs, which are read with the table's storage options.
+        /// CSV and JSON files are parsed with the table schema (CSV columns in table
+        /// order, JSON fields by name); Parquet files must match the table schema unless
+        /// <see cref="AddOptions.CastToTableSchema"/> is set.
+        /// Only local tables are supported.
+        /// </remarks>
+        /// <param name="paths">The files or directories to read.</param>
+        /// <param name="readOptions">The file format and how to parse it. Default is Parquet.</param>
+        /// <param name="options">
+        /// The write mode, <see cref="AddOptions.CastToTableSchema"/> and the commit
+        /// metadata of <see cref="AddOptions.Write"/>. Idempotency keys, conflict retries,
+        /// bad vector handling and <see cref="AddOptions.OnDuplicateKey"/> are not supported.
+        /// </param>
+        /// <returns>
+        /// An <see cref="AddResult"/> containing the commit version of the operation.
+        /// The number of rows is not reported.
+        /// </returns>
+        public async Task<AddResult> AddFromFiles(
+            IReadOnlyList<string> paths, FileReadOptions? readOptions = null, AddOptions? options = null)
+        {
+            if (options?.OnDuplicateKey != null || options?.Write?.IdempotencyKey != null)
+            {
+                throw new ArgumentException(
+                    "OnDuplicateKey and idempotency keys are not supported when adding from files.",
+                    nameof(options));
+            }
+            readOptions ??= new FileReadOptions();
+            byte[] utf8Paths = JsonSerializer.SerializeToUtf8Bytes(paths);
+            byte[] utf8Format = NativeCall.ToUtf8(readOptions.Format);
+            byte[] utf8ReadOptions = readOptions.ToJsonUtf8();
+            byte[] utf8Mode = NativeCall.ToUtf8(options?.Mode ?? "append");
+            byte[]? utf8Metadata = options?.Write?.CommitMetadataJsonUtf8();
+            bool castToTableSchema = options?.CastToTableSchema ?? false;
+
+            IntPtr result = await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* pPaths = utf8Paths)
+                    fixed (byte* pFormat = utf8Format)
+                    fixed (byte* pReadOptions = utf8ReadOptions)
+                    fixed (byte* pMode = utf8Mode)
+                    fixed (byte* pMetadata = utf8Metadata)
+                    {
+                        table_add_from_files(
+                            _handle!.DangerousGetHandle(),
+                            (IntPtr)pPaths, (IntPtr)pFormat, (IntPtr)pReadOptions, (IntPtr)pMode,
+                            castToTableSchema, (IntPtr)pMetadata,
+                            completion, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+            return new AddResult { Version = (ulong)result.ToInt64() };
+        }
+
+        /// <summary>
+        /// Write the whole table to Parquet, CSV or newline-delimited JSON files.
+        /// </summary>
+        /// <remarks>
+        /// The data is streamed from the table straight to the files, without passing
+        /// through managed memory. Files are named <c>part-00000.parquet</c>,
+        /// <c>part-00001.parquet</c>, and so on, and are written with the table's storage
+        /// options. If the export fails part way, the files written so far are deleted.
+        /// Use <see cref="Query.Export"/> to export a filtered or projected result.
+        /// </remarks>
+        /// <param name="uri">The local directory or object store URI to write to.</param>
+        /// <param name="options">The file format, compression and file splitting.</param>
+        /// <param name="version">
+        /// The table version to export, leaving this table's checked-out version
+        /// unchanged. If <c>null</c>, the current version is exported. Past versions can
+        /// only be exported from local tables.
+        /// </param>
+        /// <returns>The files written and the number of rows.</returns>
+        public async Task<ExportResult> Export(
+            string uri, FileExportOptions? options = null, ulong? version = null)
+        {
+            options ??= new FileExportOptions();
+            byte[] utf8Uri = NativeCall.ToUtf8(uri);
+            byte[] utf8Format = NativeCall.ToUtf8(options.Format);
+            byte[] utf8Options = options.ToJsonUtf8();
+            long nativeVersion = version.HasValue ? (long)version.Value : -1;
+
+            IntPtr result = await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* pUri = utf8Uri)
+                    fixed (byte* pFormat = utf8Format)
+                    fixed (byte* pOptions = utf8Options)
+                    {
+                        table_export(
+                            _handle!.DangerousGetHandle(), nativeVersion,
+                            (IntPtr)pUri, (IntPtr)pFormat, (IntPtr)pOptions,
+                            completion, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+            string json = NativeCall.ReadStringAndFree(result);
+            return JsonSerializer.Deserialize<ExportResult>(json) ?? new ExportResult();
+        }
+
+        /// <summary>
+        /// Look up the version committed by a write with the given idempotency key.
+        /// </summary>
+        /// <remarks>
+        /// Use this after a write whose outcome is unknown, for example because the
+        /// process crashed before the call returned, to check whether it was committed.
+        /// Versions removed by <see cref="Optimize"/> cleanup are no longer found.
+        /// </remarks>
+        /// <param name="idempotencyKey">The key passed in <see cref="WriteOptions.IdempotencyKey"/>.</param>
+        /// <param name="window">
+        /// How many of the latest versions to search. If <c>null</c>, the same 100 versions
+        /// a write searches by default are used.
+        /// </param>
+        /// <returns>
+        /// The version committed with the key, or <c>null</c> if no searched commit recorded it.
+        /// </returns>
+        public async Task<ulong?> FindIdempotencyKey(string idempotencyKey, ulong? window = null)
+        {
+            byte[] utf8Key = NativeCall.ToUtf8(idempotencyKey);
+            IntPtr result = await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* p = utf8Key)
+                    {
+                        table_find_idempotency_key(
+                            _handle!.DangerousGetHandle(), (IntPtr)p, window ?? 0, completion, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+            ulong version = (ulong)result.ToInt64();
+            return version == 0 ? null : (ulong?)version;
+        }
+
         /// <summary>
         /// Retrieve the version of the table.
         /// </summary>
@@ -1178,6 +1784,94 @@ namespace lancedb
             }).ConfigureAwait(false);
         }
 
+        /// <summary>
+        /// Create an IVF vector index from a trained model, skipping kmeans training.
+        /// </summary>
+        /// <remarks>
+        /// The index is built on the table's Lance dataset, which is only supported
+        /// for local tables, and is complete when the returned task finishes.
+        /// </remarks>
+        /// <param name="columns">The vector column to index.</param>
+        /// <param name="index">
+        /// The index configuration: <see cref="IvfFlatIndex"/>, <see cref="IvfSqIndex"/>,
+        /// <see cref="IvfPqIndex"/>, <see cref="IvfRqIndex"/>, <see cref="HnswPqIndex"/>
+        /// or <see cref="HnswSqIndex"/>. The number of partitions comes from the model.
+        /// </param>
+        /// <param name="model">The centroids, and optionally PQ codebook, to use.</param>
+        /// <param name="replace">
+        /// Whether to replace an existing index on the same columns. Default is <c>true</c>.
+        /// </param>
+        /// <param name="name">
+        /// An optional custom name for the index. If <c>null</c>, the name is auto-generated.
+        /// </param>
+        /// <param name="train">
+        /// Whether to index the existing data. Default is <c>true</c>.
+        /// </param>
+        /// <exception cref="ArgumentNullException">Thrown if <paramref name="model"/> is null.</exception>
+        public async Task CreateIndex(
+            IReadOnlyList<string> columns, Index index, VectorIndexModel model,
+            bool replace = true, string? name = null, bool train = true)
+        {
+            if (model == null)
+            {
+                throw new ArgumentNullException(nameof(model));
+            }
+            byte[] columnsBytes = JsonSerializer.SerializeToUtf8Bytes(columns);
+            int indexType = (int)index.IndexType;
+            byte[] configBytes = index.ToConfigJsonUtf8();
+            byte[]? nameBytes = name != null ? NativeCall.ToUtf8(name) : null;
+            byte[]? sourceIndexBytes = model.SourceIndexName != null
+                ? NativeCall.ToUtf8(model.SourceIndexName) : null;
+            IntPtr sourceTable = model.SourceTable != null
+                ? model.SourceTable._handle!.DangerousGetHandle() : IntPtr.Zero;
+
+            await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* pColumns = columnsBytes)
+                    fixed (byte* pConfig = configBytes)
+                    fixed (byte* pName = nameBytes)
+                    fixed (byte* pSourceIndex = sourceIndexBytes)
+                    {
+                        var cArrays = new CArrowArray[2];
+                        var cSchemas = new CArrowSchema[2];
+                        fixed (CArrowArray* pArrays = cArrays)
+                        fixed (CArrowSchema* pSchemas = cSchemas)
+                        {
+                            CArrowArray* pCentroids = null;
+                            CArrowSchema* pCentroidsSchema = null;
+                            CArrowArray* pCodebook = null;
+                            CArrowSchema* pCodebookSchema = null;
+                            if (model.Centroids != null)
+                            {
+                                pCentroids = &pArrays[0];
+                                pCentroidsSchema = &pSchemas[0];
+                                CArrowSchemaExporter.ExportType(model.Centroids.Data.DataType, pCentroidsSchema);
+                                CArrowArrayExporter.ExportArray(
+                                    ArrowCDataHelper.CloneArrayForExport(model.Centroids), pCentroids);
+                            }
+                            if (model.PqCodebook != null)
+                            {
+                                pCodebook = &pArrays[1];
+                                pCodebookSchema = &pSchemas[1];
+                                CArrowSchemaExporter.ExportType(model.PqCodebook.Data.DataType, pCodebookSchema);
+                                CArrowArrayExporter.ExportArray(
+                                    ArrowCDataHelper.CloneArrayForExport(model.PqCodebook), pCodebook);
+                            }
+                            table_create_index_with_model(
+                                _handle!.DangerousGetHandle(),
+                                (IntPtr)pColumns, indexType, (IntPtr)pConfig,
+                                replace, (IntPtr)pName, train,
+                                pCentroids, pCentroidsSchema, pCodebook, pCodebookSchema,
+                                sourceTable, (IntPtr)pSourceIndex,
+                                completion, userData);
+                        }
+                    }
+                }
+            }).ConfigureAwait(false);
+        }
+
         /// <summary>
         /// List all indices that have been created on this table.
         /// </summary>
@@ -1193,6 +1887,37 @@ namespace lancedb
                 ?? new List<IndexConfig>();
         }
 
+        /// <summary>
+        /// Tokenize text the way an existing full-text search index tokenized the
+        /// table's documents.
+        /// </summary>
+        /// <remarks>
+        /// The tokenizer settings are read from the index, so this shows the tokens a
+        /// search compares against, which helps explain unexpected matches or misses.
+        /// Use <see cref="FtsIndex.Analyze"/> to try settings before building an index.
+        /// </remarks>
+        /// <param name="indexName">The name of a full-text search index on the table.</param>
+        /// <param name="text">The text to tokenize.</param>
+        /// <returns>The tokens, in order.</returns>
+        public async Task<IReadOnlyList<FtsToken>> AnalyzeFts(string indexName, string text)
+        {
+            byte[] nameBytes = NativeCall.ToUtf8(indexName);
+            byte[] textBytes = NativeCall.ToUtf8(text);
+            IntPtr result = await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* pName = nameBytes)
+                    fixed (byte* pText = textBytes)
+                    {
+                        table_fts_analyze(
+                            _handle!.DangerousGetHandle(), (IntPtr)pName, (IntPtr)pText, completion, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+            return FtsToken.ParseList(NativeCall.ReadStringAndFree(result), text);
+        }
+
         /// <summary>
         /// Drop an index from the table.
         /// </summary>
@@ -1488,22 +2213,29 @@ namespace lancedb
         /// A dictionary mapping new column names to SQL expressions.
         /// For example, <c>new Dictionary&lt;string, string&gt; { { "doubled", "id * 2" } }</c>.
         /// </param>
+        /// <param name="commitMetadata">
+        /// Optional key-value metadata recorded with the commit and reported by
+        /// <see cref="ListVersions"/>.
+        /// </param>
         /// <returns>
         /// An <see cref="AddColumnsResult"/> containing the commit version of the operation.
         /// </returns>
-        public async Task<AddColumnsResult> AddColumns(Dictionary<string, string> transforms)
+        public async Task<AddColumnsResult> AddColumns(
+            Dictionary<string, string> transforms, IReadOnlyDictionary<string, string>? commitMetadata = null)
         {
             var pairs = transforms.Select(kv => new[] { kv.Key, kv.Value }).ToArray();
             byte[] utf8Json = JsonSerializer.SerializeToUtf8Bytes(pairs);
+            byte[]? utf8Metadata = WriteOptions.SerializeCommitMetadata(commitMetadata);
 
             IntPtr resultPtr = await NativeCall.Async((completion, userData) =>
             {
                 unsafe
                 {
                     fixed (byte* p = utf8Json)
+                    fixed (byte* pm = utf8Metadata)
                     {
-                        table_add_columns(
-                            _handle!.DangerousGetHandle(), (IntPtr)p, completion, userData);
+                        table_add_columns_with_options(
+                            _handle!.DangerousGetHandle(), (IntPtr)p, (IntPtr)pm, completion, userData);
                     }
                 }
             }).ConfigureAwait(false);
@@ -1523,21 +2255,28 @@ namespace lancedb
         /// An Arrow <see cref="Apache.Arrow.Schema"/> whose fields define the new columns
         /// to add. Each field's name, data type, and nullability are preserved.
         /// </param>
+        /// <param name="commitMetadata">
+        /// Optional key-value metadata recorded with the commit and reported by
+        /// <see cref="ListVersions"/>.
+        /// </param>
         /// <returns>
         /// An <see cref="AddColumnsResult"/> containing the commit version of the operation.
         /// </returns>
-        public async Task<AddColumnsResult> AddColumns(Apache.Arrow.Schema schema)
+        public async Task<AddColumnsResult> AddColumns(
+            Apache.Arrow.Schema schema, IReadOnlyDictionary<string, string>? commitMetadata = null)
         {
+            byte[]? utf8Metadata = WriteOptions.SerializeCommitMetadata(commitMetadata);
             IntPtr resultPtr = await NativeCall.Async((completion, userData) =>
             {
                 unsafe
                 {
                     var cSchema = new CArrowSchema[1];
                     fixed (CArrowSchema* pSchema = cSchema)
+                    fixed (byte* pm = utf8Metadata)
                     {
                         CArrowSchemaExporter.ExportSchema(schema, pSchema);
-                        table_add_columns_null(
-                            _handle!.DangerousGetHandle(), pSchema, completion, userData);
+                        table_add_columns_null_with_options(
+                            _handle!.DangerousGetHandle(), pSchema, (IntPtr)pm, completion, userData);
                     }
                 }
             }).ConfigureAwait(false);
@@ -1561,21 +2300,29 @@ namespace lancedb
         /// A list of alterations, each as a dictionary with keys: <c>"path"</c> (required),
         /// <c>"rename"</c> (optional), <c>"nullable"</c> (optional).
         /// </param>
+        /// <param name="commitMetadata">
+        /// Optional key-value metadata recorded with the commit and reported by
+        /// <see cref="ListVersions"/>.
+        /// </param>
         /// <returns>
         /// An <see cref="AlterColumnsResult"/> containing the commit version of the operation.
         /// </returns>
-        public async Task<AlterColumnsResult> AlterColumns(IReadOnlyList<Dictionary<string, object>> alterations)
+        public async Task<AlterColumnsResult> AlterColumns(
+            IReadOnlyList<Dictionary<string, object>> alterations,
+            IReadOnlyDictionary<string, string>? commitMetadata = null)
         {
             byte[] utf8Json = JsonSerializer.SerializeToUtf8Bytes(alterations);
+            byte[]? utf8Metadata = WriteOptions.SerializeCommitMetadata(commitMetadata);
 
             IntPtr resultPtr = await NativeCall.Async((completion, userData) =>
             {
                 unsafe
                 {
                     fixed (byte* p = utf8Json)
+                    fixed (byte* pm = utf8Metadata)
                     {
-                        table_alter_columns(
-                            _handle!.DangerousGetHandle(), (IntPtr)p, completion, userData);
+                        table_alter_columns_with_options(
+                            _handle!.DangerousGetHandle(), (IntPtr)p, (IntPtr)pm, completion, userData);
                     }
                 }
             }).ConfigureAwait(false);
@@ -1587,21 +2334,28 @@ namespace lancedb
         /// Drop columns from the table.
         /// </summary>
         /// <param name="columns">The names of the columns to drop.</param>
+        /// <param name="commitMetadata">
+        /// Optional key-value metadata recorded with the commit and reported by
+        /// <see cref="ListVersions"/>.
+        /// </param>
         /// <returns>
         /// A <see cref="DropColumnsResult"/> containing the commit version of the operation.
         /// </returns>
-        public async Task<DropColumnsResult> DropColumns(IReadOnlyList<string> columns)
+        public async Task<DropColumnsResult> DropColumns(
+            IReadOnlyList<string> columns, IReadOnlyDictionary<string, string>? commitMetadata = null)
         {
             byte[] utf8Json = JsonSerializer.SerializeToUtf8Bytes(columns);
+            byte[]? utf8Metadata = WriteOptions.SerializeCommitMetadata(commitMetadata);
 
             IntPtr resultPtr = await NativeCall.Async((completion, userData) =>
             {
                 unsafe
                 {
                     fixed (byte* p = utf8Json)
+                    fixed (byte* pm = utf8Metadata)
                     {
-                        table_drop_columns(
-                            _handle!.DangerousGetHandle(), (IntPtr)p, completion, userData);
+                        table_drop_columns_with_options(
+                            _handle!.DangerousGetHandle(), (IntPtr)p, (IntPtr)pm, completion, userData);
                     }
                 }
             }).ConfigureAwait(false);
@@ -1701,20 +2455,19 @@ namespace lancedb
             return new MergeInsertBuilder(this, on);
         }
 
-        internal async Task<MergeResult> ExecuteMergeInsert(
+        internal async Task<(MergeResult Result, RecordBatch? AffectedRows)> ExecuteMergeInsert(
             IReadOnlyList<string> onColumns,
-            bool whenMatchedUpdateAll, string? whenMatchedUpdateAllFilter,
-            bool whenNotMatchedInsertAll,
-            bool whenNotMatchedBySourceDelete, string? whenNotMatchedBySourceDeleteFilter,
+            IReadOnlyList<Dictionary<string, object?>> clauses,
             IReadOnlyList<RecordBatch> data,
             bool useIndex = true, TimeSpan? timeout = null,
-            bool? useLsmWrite = null)
+            bool? useLsmWrite = null, bool returnAffected = false,
+            IReadOnlyDictionary<string, string>? commitMetadata = null,
+            bool castToTableSchema = false, WriteOptions? writeOptions = null)
         {
             byte[] onColumnsBytes = JsonSerializer.SerializeToUtf8Bytes(onColumns);
-            byte[]? matchedFilterBytes = whenMatchedUpdateAllFilter != null
-                ? NativeCall.ToUtf8(whenMatchedUpdateAllFilter) : null;
-            byte[]? sourceDeleteFilterBytes = whenNotMatchedBySourceDeleteFilter != null
-                ? NativeCall.ToUtf8(whenNotMatchedBySourceDeleteFilter) : null;
+            byte[] clausesBytes = NativeCall.ToUtf8(JsonSerializer.Serialize(clauses));
+            byte[]? metadataBytes = WriteOptions.SerializeCommitMetadata(commitMetadata);
+            byte[]? writeOptionsBytes = writeOptions?.ToJsonUtf8();
             long timeoutMs = timeout.HasValue ? (long)timeout.Value.TotalMilliseconds : -1;
             // Sentinel: -1 = leave default, 0 = false, 1 = true.
             int useLsmWriteFlag = useLsmWrite.HasValue ? (useLsmWrite.Value ? 1 : 0) : -1;
@@ -1724,8 +2477,9 @@ namespace lancedb
                 unsafe
                 {
                     fixed (byte* pOnColumns = onColumnsBytes)
-                    fixed (byte* pMatchedFilter = matchedFilterBytes)
-                    fixed (byte* pSourceDeleteFilter = sourceDeleteFilterBytes)
+                    fixed (byte* pClauses = clausesBytes)
+                    fixed (byte* pMetadata = metadataBytes)
+                    fixed (byte* pWriteOptions = writeOptionsBytes)
                     {
                         var cArrays = new CArrowArray[data.Count];
                         var cSchemaArr = new CArrowSchema[1];
@@ -1743,15 +2497,13 @@ namespace lancedb
                             }
                             fixed (CArrowArray* pArrays = cArrays)
                             {
-                                table_merge_insert(
+                                table_merge_insert_clauses(
                                     _handle!.DangerousGetHandle(),
-                                    (IntPtr)pOnColumns,
-                                    whenMatchedUpdateAll, (IntPtr)pMatchedFilter,
-                                    whenNotMatchedInsertAll,
-                                    whenNotMatchedBySourceDelete, (IntPtr)pSourceDeleteFilter,
+                                    (IntPtr)pOnColumns, (IntPtr)pClauses,
                                     pArrays, pSchema, (nuint)data.Count,
                                     useIndex, timeoutMs,
-                                    useLsmWriteFlag,
+                                    useLsmWriteFlag, returnAffected, (IntPtr)pWriteOptions,
+                                    castToTableSchema, (IntPtr)pMetadata,
                                     completion, userData);
                             }
                         }
@@ -1761,7 +2513,18 @@ namespace lancedb
 
             try
             {
-                return Marshal.PtrToStructure<MergeResult>(resultPtr);
+                var result = Marshal.PtrToStructure<MergeResult>(resultPtr);
+                RecordBatch? affected = null;
+                if (result.AffectedRows != IntPtr.Zero)
+                {
+                    // ImportRecordBatchFromCData frees the Arrow data; clear the field so the
+                    // native free below does not release it a second time.
+                    IntPtr affectedPtr = result.AffectedRows;
+                    result.AffectedRows = IntPtr.Zero;
+                    Marshal.StructureToPtr(result, resultPtr, false);
+                    affected = ArrowCDataHelper.ImportRecordBatchFromCData(affectedPtr);
+                }
+                return (result, affected);
             }
             finally
             {
@@ -1769,6 +2532,35 @@ namespace lancedb
             }
         }
 
+        /// <summary>
+        /// Begin a transaction that stages adds, deletes, updates and new columns and
+        /// commits them as a single table version.
+        /// </summary>
+        /// <param name="on">
+        /// Optional key columns that identify rows. With keys, the commit checks that the
+        /// rows it writes have unique keys and updates cannot change them. If <c>null</c>,
+        /// rows are not checked.
+        /// </param>
+        /// <returns>A <see cref="Transaction"/> based on the current table version.</returns>
+        public async Task<Transaction> BeginTransaction(IReadOnlyList<string>? on = null)
+        {
+            byte[]? onColumnsBytes = on != null ? NativeCall.ToUtf8(JsonSerializer.Serialize(on)) : null;
+            IntPtr txnPtr = await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* pOnColumns = onColumnsBytes)
+                    {
+                        table_begin_transaction(
+                            _handle!.DangerousGetHandle(),
+                            onColumnsBytes != null ? (IntPtr)pOnColumns : IntPtr.Zero,
+                            completion, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+            return new Transaction(txnPtr);
+        }
+
         /// <summary>
         /// Take rows at the given offset positions from the table.
         /// </summary>
@@ -1823,6 +2615,60 @@ namespace lancedb
             return new TakeQuery(this, rowIds, isByRowId: true);
         }
 
+        /// <summary>
+        /// Fetch rows by the value of their primary key.
+        /// </summary>
+        /// <remarks>
+        /// The key is the one set with <see cref="SetUnenforcedPrimaryKey(IEnumerable{string})"/>.
+        /// A BTree or bitmap index on a single-column key is used when present, so
+        /// create one for fast lookups.
+        /// </remarks>
+        /// <param name="keys">
+        /// The key values to look up. For a composite key, a <see cref="StructArray"/>
+        /// with one field per key column. Values are cast to the key column types.
+        /// </param>
+        /// <param name="columns">
+        /// The columns to return. If <c>null</c>, all columns are returned.
+        /// </param>
+        /// <returns>
+        /// A <see cref="RecordBatch"/> with one row per key, in the order of <paramref name="keys"/>.
+        /// Keys that match no row, and null keys, yield a row of nulls.
+        /// </returns>
+        /// <exception cref="ArgumentNullException">Thrown if <paramref name="keys"/> is null.</exception>
+        public async Task<RecordBatch> GetByKeys(IArrowArray keys, IReadOnlyList<string>? columns = null)
+        {
+            if (keys == null)
+            {
+                throw new ArgumentNullException(nameof(keys));
+            }
+
+            byte[]? columnsBytes = columns != null
+                ? JsonSerializer.SerializeToUtf8Bytes(columns) : null;
+
+            IntPtr ffiCDataPtr = await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* pColumns = columnsBytes)
+                    {
+                        var cArray = new CArrowArray[1];
+                        var cSchema = new CArrowSchema[1];
+                        fixed (CArrowArray* pArray = cArray)
+                        fixed (CArrowSchema* pSchema = cSchema)
+                        {
+                            CArrowSchemaExporter.ExportType(keys.Data.DataType, pSchema);
+                            CArrowArrayExporter.ExportArray(ArrowCDataHelper.CloneArrayForExport(keys), pArray);
+                            table_get_by_keys(
+                                _handle!.DangerousGetHandle(), pArray, pSchema,
+                                (IntPtr)pColumns, completion, userData);
+                        }
+                    }
+                }
+            }).ConfigureAwait(false);
+
+            return ArrowCDataHelper.ImportRecordBatchFromCData(ffiCDataPtr);
+        }
+
         internal async Task<RecordBatch> ExecuteTake(
             ulong[] ids, bool isByRowId, IReadOnlyList<string>? columns, bool withRowId)
         {
@@ -0,0 +1,294 @@
+namespace lancedb
+{
+    using System;
+    using System.Collections.Generic;
+    using System.Linq;
+    using System.Runtime.InteropServices;
+    using System.Text.Json;
+    using System.Threading.Tasks;
+    using Apache.Arrow;
+    using Apache.Arrow.C;
+
+    /// <summary>
+    /// A set of writes against a <see cref="Table"/> that is committed as a single
+    /// table version.
+    /// </summary>
+    /// <remarks>
+    /// <para>
+    /// Created with <see cref="Table.BeginTransaction(IReadOnlyList{string})"/>. Adds,
+    /// deletes, updates and new columns are staged in order and nothing is visible to
+    /// readers until <see cref="Commit"/> writes them all at once. <see cref="Rollback"/>
+    /// discards them.
+    /// </para>
+    /// <para>
+    /// The transaction reads the table version current when it began and commits against
+    /// it. Writes committed since then are kept; if one of them changed a row the
+    /// transaction deletes or updates, the commit fails with a conflict error and nothing
+    /// is written.
+    /// </para>
+    /// <para>
+    /// When key columns are given as the transaction begins, the rows it writes must have
+    /// unique keys: keys cannot be updated inside the transaction, and adding a row whose
+    /// key belongs to a row the transaction leaves in place fails the commit. Without keys,
+    /// rows are not checked. Transactions are only supported for local tables.
+    /// </para>
+    /// </remarks>
+    public class Transaction : IDisposable
+    {
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern ulong transaction_base_version(IntPtr txn_ptr);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern unsafe void transaction_add(
+            IntPtr txn_ptr, CArrowArray* arrays, CArrowSchema* schema, nuint batch_count,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void transaction_delete(
+            IntPtr txn_ptr, IntPtr predicate, NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void transaction_update(
+            IntPtr txn_ptr, IntPtr filter, IntPtr columns_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void transaction_add_columns(
+            IntPtr txn_ptr, IntPtr transforms_json, NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void transaction_commit(
+            IntPtr txn_ptr, NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void transaction_commit_with_options(
+            IntPtr txn_ptr, IntPtr write_options_json, IntPtr commit_metadata_json,
+            NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void transaction_rollback(
+            IntPtr txn_ptr, NativeCall.FfiCallback completion, IntPtr userData);
+
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void table_merge_result_free(IntPtr ptr);
+
+        private TransactionHandle? _handle;
+
+        internal Transaction(IntPtr txnPtr)
+        {
+            _handle = new TransactionHandle(txnPtr);
+        }
+
+        /// <summary>
+        /// The table version this transaction is based on.
+        /// </summary>
+        public ulong BaseVersion => transaction_base_version(_handle!.DangerousGetHandle());
+
+        /// <summary>
+        /// Stage an append of the given batches.
+        /// </summary>
+        /// <param name="data">The data to insert into the table.</param>
+        public async Task Add(IReadOnlyList<RecordBatch> data)
+        {
+            await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    var cArrays = new CArrowArray[data.Count];
+                    var cSchemaArr = new CArrowSchema[1];
+                    fixed (CArrowSchema* pSchema = cSchemaArr)
+                    {
+                        CArrowSchemaExporter.ExportSchema(data[0].Schema, pSchema);
+                        for (int i = 0; i < data.Count; i++)
+                        {
+                            cArrays[i] = default;
+                            var clone = ArrowCDataHelper.CloneBatchForExport(data[i]);
+                            fixed (CArrowArray* pArr = &cArrays[i])
+                            {
+                                CArrowArrayExporter.ExportRecordBatch(clone, pArr);
+                            }
+                        }
+                        fixed (CArrowArray* pArrays = cArrays)
+                        {
+                            transaction_add(
+                                _handle!.DangerousGetHandle(),
+                                pArrays, pSchema, (nuint)data.Count,
+                                completion, userData);
+                        }
+                    }
+                }
+            }).ConfigureAwait(false);
+        }
+
+        /// <summary>
+        /// Stage an append of a single batch.
+        /// </summary>
+        /// <param name="data">The data to insert into the table.</param>
+        public Task Add(RecordBatch data)
+        {
+            return Add(new[] { data });
+        }
+
+        /// <summary>
+        /// Stage a delete of the rows matching <paramref name="predicate"/>.
+        /// </summary>
+        /// <remarks>
+        /// The predicate also applies to rows staged by earlier <see cref="Add(RecordBatch)"/>
+        /// calls in this transaction.
+        /// </remarks>
+        /// <param name="predicate">The SQL where clause selecting the rows to delete.</param>
+        public async Task Delete(string predicate)
+        {
+            byte[] utf8Predicate = NativeCall.ToUtf8(predicate);
+            await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* p = utf8Predicate)
+                    {
+                        transaction_delete(
+                            _handle!.DangerousGetHandle(), (IntPtr)p, completion, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+        }
+
+        /// <summary>
+        /// Stage an update of the rows matching <paramref name="where"/> using SQL
+        /// expressions.
+        /// </summary>
+        /// <param name="updatesSql">
+        /// A dictionary mapping column names to SQL expressions describing the
+        /// updated value. Key columns cannot be updated.
+        /// </param>
+        /// <param name="where">
+        /// An optional SQL filter that controls which rows are updated. If <c>null</c>,
+        /// all rows are updated.
+        /// </param>
+        public async Task Update(Dictionary<string, string> updatesSql, string? @where = null)
+        {
+            var columnSqlExprs = updatesSql.Select(kv => new[] { kv.Key, kv.Value }).ToArray();
+            byte[] utf8ColumnSqlExprs = NativeCall.ToUtf8(JsonSerializer.Serialize(columnSqlExprs));
+            byte[]? utf8Where = @where != null ? NativeCall.ToUtf8(@where) : null;
+            await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* pw = utf8Where)
+                    fixed (byte* pc = utf8ColumnSqlExprs)
+                    {
+                        transaction_update(
+                            _handle!.DangerousGetHandle(), (IntPtr)pw, (IntPtr)pc,
+                            completion, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+        }
+
+        /// <summary>
+        /// Stage adding columns computed from SQL expressions over each row.
+        /// </summary>
+        /// <remarks>
+        /// Rows staged before are given the new columns, and rows added after may omit
+        /// them to have them filled with nulls. Adding columns rewrites every fragment of
+        /// the table, so the commit conflicts with any write since <see cref="BaseVersion"/>.
+        /// </remarks>
+        /// <param name="columns">
+        /// A dictionary mapping new column names to SQL expressions, which may refer to
+        /// existing columns.
+        /// </param>
+        public async Task AddColumns(Dictionary<string, string> columns)
+        {
+            var transforms = columns.Select(kv => new[] { kv.Key, kv.Value }).ToArray();
+            byte[] utf8Transforms = NativeCall.ToUtf8(JsonSerializer.Serialize(transforms));
+            await NativeCall.Async((completion, userData) =>
+            {
+                unsafe
+                {
+                    fixed (byte* p = utf8Transforms)
+                    {
+                        transaction_add_columns(
+                            _handle!.DangerousGetHandle(), (IntPtr)p, completion, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+        }
+
+        /// <summary>
+        /// Commit the staged operations as a single new table version.
+        /// </summary>
+        /// <remarks>
+        /// The transaction is closed afterwards, whether or not the commit succeeds.
+        /// Conflicts are never retried, since the staged operations were read at
+        /// <see cref="BaseVersion"/>; the retry settings of <paramref name="options"/> are
+        /// ignored.
+        /// </remarks>
+        /// <param name="options">
+        /// Optional write options carrying an idempotency key and commit metadata. If the
+        /// idempotency key was already committed, nothing is written and the original
+        /// version is returned with zero row counts.
+        /// </param>
+        /// <returns>
+        /// A <see cref="MergeResult"/> with the new version and the number of rows
+        /// inserted, updated and deleted.
+        /// </returns>
+        /// <exception cref="LanceDbException">
+        /// A write since <see cref="BaseVersion"/> changed a row the transaction deletes or
+        /// updates, an added row's key already exists, or the transaction was already
+        /// committed or rolled back.
+        /// </exception>
+        public async Task<MergeResult> Commit(WriteOptions? options = null)
+        {
+            byte[]? utf8WriteOptions = options?.ToJsonUtf8();
+            byte[]? utf8Metadata = options?.CommitMetadataJsonUtf8();
+            IntPtr resultPtr = await NativeCall.Async((completion, userData) =>
+            {
+                if (options == null)
+                {
+                    transaction_commit(_handle!.DangerousGetHandle(), completion, userData);
+                    return;
+                }
+                unsafe
+                {
+                    fixed (byte* pWrite = utf8WriteOptions)
+                    fixed (byte* pMetadata = utf8Metadata)
+                    {
+                        transaction_commit_with_options(
+                            _handle!.DangerousGetHandle(), (IntPtr)pWrite, (IntPtr)pMetadata,
+                            completion, userData);
+                    }
+                }
+            }).ConfigureAwait(false);
+
+            try
+            {
+                return Marshal.PtrToStructure<MergeResult>(resultPtr);
+            }
+            finally
+            {
+                table_merge_result_free(resultPtr);
+            }
+        }
+
+        /// <summary>
+        /// Discard the staged operations and close the transaction.
+        /// </summary>
+        public async Task Rollback()
+        {
+            await NativeCall.Async((completion, userData) =>
+            {
+                transaction_rollback(_handle!.DangerousGetHandle(), completion, userData);
+            }).ConfigureAwait(false);
+        }
+
+        /// <summary>
+        /// Release the native transaction. Uncommitted operations are discarded.
+        /// </summary>
+        public void Dispose()
+        {
+            _handle?.Dispose();
+            _handle = null;
+            GC.SuppressFinalize(this);
+        }
+    }
+}
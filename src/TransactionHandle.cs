@@ -0,0 +1,29 @@
+namespace lancedb
+{
+    using System;
+    using System.Runtime.InteropServices;
+
+    /// <summary>
+    /// SafeHandle wrapper for a Rust Transaction pointer.
+    /// Automatically calls transaction_close when the handle is released.
+    /// </summary>
+    internal class TransactionHandle : SafeHandle
+    {
+        [DllImport(NativeLibrary.Name, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void transaction_close(IntPtr txn_ptr);
+
+        public TransactionHandle() : base(IntPtr.Zero, true) { }
+        public TransactionHandle(IntPtr ptr) : base(ptr, true) { }
+
+        public override bool IsInvalid => handle == IntPtr.Zero;
+
+        protected override bool ReleaseHandle()
+        {
+            if (!IsInvalid)
+            {
+                transaction_close(handle);
+            }
+            return true;
+        }
+    }
+}
@@ -0,0 +1,80 @@
+namespace lancedb
+{
+    using System;
+    using Apache.Arrow;
+
+    /// <summary>
+    /// A trained IVF model to build a vector index from, instead of training one
+    /// with kmeans.
+    /// </summary>
+    /// <remarks>
+    /// Training the IVF centroids, and the PQ codebook, is most of the cost of building
+    /// a vector index. Tables holding embeddings from the same model can share one
+    /// trained model: pass it to
+    /// <see cref="Table.CreateIndex(System.Collections.Generic.IReadOnlyList{string}, Index, VectorIndexModel, bool, string?, bool)"/>.
+    /// </remarks>
+    public sealed class VectorIndexModel
+    {
+        private VectorIndexModel(
+            FixedSizeListArray? centroids, FixedSizeListArray? pqCodebook,
+            Table? sourceTable, string? sourceIndexName)
+        {
+            Centroids = centroids;
+            PqCodebook = pqCodebook;
+            SourceTable = sourceTable;
+            SourceIndexName = sourceIndexName;
+        }
+
+        internal FixedSizeListArray? Centroids { get; }
+        internal FixedSizeListArray? PqCodebook { get; }
+        internal Table? SourceTable { get; }
+        internal string? SourceIndexName { get; }
+
+        /// <summary>
+        /// A model with the given IVF centroids and, for PQ indices, codebook.
+        /// </summary>
+        /// <param name="centroids">
+        /// One centroid per IVF partition, with the dimension of the indexed column.
+        /// The index gets one partition per centroid.
+        /// </param>
+        /// <param name="pqCodebook">
+        /// The PQ codebook trained with <paramref name="centroids"/>, for
+        /// <see cref="IvfPqIndex"/> and <see cref="HnswPqIndex"/>. If <c>null</c>,
+        /// the codebook is trained.
+        /// </param>
+        /// <exception cref="ArgumentNullException">Thrown if <paramref name="centroids"/> is null.</exception>
+        public static VectorIndexModel FromCentroids(FixedSizeListArray centroids, FixedSizeListArray? pqCodebook = null)
+        {
+            if (centroids == null)
+            {
+                throw new ArgumentNullException(nameof(centroids));
+            }
+            return new VectorIndexModel(centroids, pqCodebook, null, null);
+        }
+
+        /// <summary>
+        /// The model of an existing vector index, usually on another table.
+        /// </summary>
+        /// <remarks>
+        /// The centroids are copied, and PQ indices also copy the codebook. The new
+        /// index must use the same distance type as the source index.
+        /// </remarks>
+        /// <param name="table">The table with the source index.</param>
+        /// <param name="indexName">The name of the source index.</param>
+        /// <exception cref="ArgumentNullException">
+        /// Thrown if <paramref name="table"/> or <paramref name="indexName"/> is null.
+        /// </exception>
+        public static VectorIndexModel FromIndex(Table table, string indexName)
+        {
+            if (table == null)
+            {
+                throw new ArgumentNullException(nameof(table));
+            }
+            if (indexName == null)
+            {
+                throw new ArgumentNullException(nameof(indexName));
+            }
+            return new VectorIndexModel(null, null, table, indexName);
+        }
+    }
+}
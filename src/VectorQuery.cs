@@ -378,6 +378,28 @@ namespace lancedb
             return this;
         }
 
+        /// <summary>
+        /// Execute the query and write the result to Parquet, CSV or newline-delimited
+        /// JSON files.
+        /// </summary>
+        /// <remarks>
+        /// The result is streamed from the native query straight to the files, as with
+        /// <see cref="Query.Export"/>. Rerankers run in managed code, so a query with a
+        /// reranker cannot be exported.
+        /// </remarks>
+        /// <param name="uri">The local directory or object store URI to write to.</param>
+        /// <param name="options">The file format, compression and file splitting.</param>
+        /// <returns>The files written and the number of rows.</returns>
+        /// <exception cref="InvalidOperationException">Thrown if a reranker is set.</exception>
+        public Task<ExportResult> Export(string uri, FileExportOptions? options = null)
+        {
+            if (_reranker != null)
+            {
+                throw new InvalidOperationException("A query with a reranker cannot be exported.");
+            }
+            return ExportCore(_vector, uri, options);
+        }
+
         /// <inheritdoc/>
         public override async Task<RecordBatch> ToArrow(
             TimeSpan? timeout = null, int? maxBatchLength = null)
@@ -25,5 +25,34 @@ namespace lancedb
         /// </summary>
         [JsonPropertyName("metadata")]
         public Dictionary<string, string> Metadata { get; set; } = new();
+
+        /// <summary>
+        /// The operation that created this version, for example <c>"Append"</c>,
+        /// <c>"Delete"</c>, <c>"Update"</c> or <c>"Merge"</c>.
+        /// <c>null</c> for remote tables or when the transaction was not recorded.
+        /// </summary>
+        [JsonPropertyName("operation")]
+        public string? Operation { get; set; }
+
+        /// <summary>
+        /// The number of rows in the table at this version.
+        /// <c>null</c> for remote tables.
+        /// </summary>
+        [JsonPropertyName("num_rows")]
+        public ulong? NumRows { get; set; }
+
+        /// <summary>
+        /// The change in row count from the previous listed version.
+        /// <c>null</c> for remote tables.
+        /// </summary>
+        [JsonPropertyName("num_rows_delta")]
+        public long? NumRowsDelta { get; set; }
+
+        /// <summary>
+        /// Metadata attached by the writer that created this version, such as a job id
+        /// or user. See <see cref="WriteOptions.CommitMetadata"/>.
+        /// </summary>
+        [JsonPropertyName("commit_metadata")]
+        public Dictionary<string, string> CommitMetadata { get; set; } = new();
     }
 }
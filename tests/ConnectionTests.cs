@@ -298,6 +298,79 @@ namespace lancedb.tests
             }
         }
 
+        /// <summary>
+        /// CreateTable with both Data and Schema should cast the data into the schema
+        /// and store the metadata.
+        /// </summary>
+        [Fact]
+        public async Task CreateTable_WithTargetSchema_CastsDataAndStoresMetadata()
+        {
+            var tmpDir = Path.Combine(Path.GetTempPath(), "lancedb_test_" + Guid.NewGuid().ToString("N"));
+            try
+            {
+                var connection = new Connection();
+                await connection.Connect(tmpDir);
+
+                var schema = new Apache.Arrow.Schema.Builder()
+                    .Field(new Apache.Arrow.Field("id", Apache.Arrow.Types.Int64Type.Default, nullable: false))
+                    .Field(new Apache.Arrow.Field("label", Apache.Arrow.Types.StringType.Default, nullable: true))
+                    .Build();
+                using var table = await connection.CreateTable("target_schema", new CreateTableOptions
+                {
+                    Data = new[] { CreateTestBatch(3) },
+                    Schema = schema,
+                    Metadata = new Dictionary<string, string> { ["owner"] = "search" },
+                });
+
+                var created = await table.Schema();
+                Assert.IsType<Apache.Arrow.Types.Int64Type>(created.GetFieldByName("id").DataType);
+                Assert.Equal("search", created.Metadata["owner"]);
+                Assert.Equal(3, await table.CountRows("label IS NULL"));
+
+                connection.Dispose();
+            }
+            finally
+            {
+                if (Directory.Exists(tmpDir))
+                {
+                    Directory.Delete(tmpDir, true);
+                }
+            }
+        }
+
+        /// <summary>
+        /// CreateTableFromFiles should infer the schema of newline-delimited JSON files
+        /// and load their rows.
+        /// </summary>
+        [Fact]
+        public async Task CreateTableFromFiles_Json_InfersSchemaAndLoadsRows()
+        {
+            var tmpDir = Path.Combine(Path.GetTempPath(), "lancedb_test_" + Guid.NewGuid().ToString("N"));
+            try
+            {
+                Directory.CreateDirectory(tmpDir);
+                var json = Path.Combine(tmpDir, "rows.json");
+                File.WriteAllText(json, "{\"id\": 1, \"name\": \"a\"}\n{\"id\": 2, \"name\": \"b\"}\n");
+                var connection = new Connection();
+                await connection.Connect(Path.Combine(tmpDir, "db"));
+
+                using var table = await connection.CreateTableFromFiles(
+                    "from_files", new[] { json }, new FileReadOptions { Format = "json" });
+
+                Assert.Equal(2, await table.CountRows());
+                Assert.Equal(1, await table.CountRows("name = 'b'"));
+
+                connection.Dispose();
+            }
+            finally
+            {
+                if (Directory.Exists(tmpDir))
+                {
+                    Directory.Delete(tmpDir, true);
+                }
+            }
+        }
+
         /// <summary>
         /// CreateTable with overwrite mode should replace existing table data.
         /// </summary>
@@ -694,6 +767,43 @@ namespace lancedb.tests
             Assert.DoesNotContain("extra_table", names);
         }
 
+        // -----------------------------------------------------------------------
+        // Sql
+        // -----------------------------------------------------------------------
+
+        /// <summary>
+        /// Sql should run an aggregation natively over a registered table.
+        /// </summary>
+        [Fact]
+        public async Task Sql_CountRows_ReturnsAggregate()
+        {
+            using var fixture = await TestFixture.CreateWithTable("sql_count", CreateTestBatch(10));
+
+            using var reader = await fixture.Connection.Sql(
+                "SELECT COUNT(*) AS n FROM sql_count WHERE id >= 4");
+            long total = 0;
+            await foreach (var batch in reader)
+            {
+                var n = (Apache.Arrow.Int64Array)batch.Column("n");
+                for (int i = 0; i < n.Length; i++)
+                {
+                    total += n.GetValue(i)!.Value;
+                }
+            }
+            Assert.Equal(6, total);
+        }
+
+        /// <summary>
+        /// Sql should throw when the statement references an unknown table.
+        /// </summary>
+        [Fact]
+        public async Task Sql_UnknownTable_ThrowsLanceDbException()
+        {
+            using var fixture = await TestFixture.CreateWithTable("sql_unknown");
+            await Assert.ThrowsAsync<LanceDbException>(
+                () => fixture.Connection.Sql("SELECT * FROM missing"));
+        }
+
         // -----------------------------------------------------------------------
         // IsOpen
         // -----------------------------------------------------------------------
@@ -150,5 +150,93 @@ namespace lancedb.tests
             Assert.NotEmpty(rows);
             Assert.Contains(rows, r => (string?)r["content"] == "apple banana fruit");
         }
+
+        [Fact]
+        public async Task Highlight_WrapsMatchedTokensInTags()
+        {
+            using var fixture = await TestFixture.CreateTextFixture("ftq_highlight");
+            await fixture.Table.CreateIndex(new[] { "content" }, new FtsIndex());
+
+            using var query = fixture.Table.Query()
+                .NearestToText(new MatchQuery("cherries", "content"))
+                .Highlight(new HighlightOptions { PreTag = "**", PostTag = "**" });
+            var batch = await query.ToArrow();
+
+            Assert.Equal(1, batch.Length);
+            var highlights = (Apache.Arrow.ListArray)batch.Column("_highlight_content");
+            var fragments = (Apache.Arrow.StringArray)highlights.GetSlicedValues(0);
+            Assert.Equal(1, fragments.Length);
+            Assert.Equal("**cherry** date", fragments.GetString(0));
+        }
+
+        [Fact]
+        public async Task NearestToText_MultiTermQueries_ExpandIndexedTerms()
+        {
+            using var fixture = await TestFixture.CreateTextFixture("ftq_multi_term");
+            await fixture.Table.CreateIndex(new[] { "content" }, new FtsIndex());
+
+            async Task<string[]> Search(FullTextQuery query)
+            {
+                using var q = fixture.Table.Query().NearestToText(query);
+                var rows = await q.ToList();
+                return rows.Select(r => (string)r["content"]!).OrderBy(c => c).ToArray();
+            }
+
+            Assert.Equal(new[] { "cherry date" }, await Search(new PrefixQuery("ch", "content")));
+            Assert.Equal(new[] { "elderberry fig" }, await Search(new WildcardQuery("elder*", "content")));
+            Assert.Equal(new[] { "apple banana", "elderberry fig" }, await Search(new RegexQuery("banana|fig", "content")));
+            Assert.Equal(new[] { "apple banana" }, await Search(new TermRangeQuery("content", "a", "c")));
+            Assert.Equal(3, (await Search(new ExistsQuery("content"))).Length);
+            Assert.Empty(await Search(new PrefixQuery("zzz", "content")));
+
+            var ex = await Assert.ThrowsAsync<LanceDbException>(
+                () => Search(new RegexQuery("banana|fig", "content", maxExpansions: 1)));
+            Assert.Contains("more than max_expansions", ex.Message);
+        }
+
+        [Fact]
+        public async Task NearestToText_MinimumShouldMatch_RequiresClauses()
+        {
+            using var fixture = await TestFixture.CreateTextFixture("ftq_min_should");
+            await fixture.Table.CreateIndex(new[] { "content" }, new FtsIndex());
+
+            var query = new BooleanQuery(
+                new[]
+                {
+                    (Occur.Should, (FullTextQuery)new MatchQuery("apple", "content")),
+                    (Occur.Should, new MatchQuery("banana", "content")),
+                    (Occur.Should, new MatchQuery("cherry", "content")),
+                },
+                minimumShouldMatch: "2");
+
+            using var q = fixture.Table.Query().NearestToText(query);
+            var rows = await q.ToList();
+
+            Assert.Single(rows);
+            Assert.Equal("apple banana", rows[0]["content"]);
+        }
+
+        [Fact]
+        public void FtsIndexAnalyze_ReportsStemmedTokensWithCharOffsets()
+        {
+            var tokens = new FtsIndex().Analyze("Déjà running");
+
+            Assert.Equal(new[] { "deja", "run" }, tokens.Select(t => t.Text));
+            Assert.Equal(new[] { 0, 1 }, tokens.Select(t => t.Position));
+            Assert.Equal((5, 12), (tokens[1].StartOffset, tokens[1].EndOffset));
+        }
+
+        [Fact]
+        public async Task AnalyzeFts_UsesIndexSettings()
+        {
+            using var fixture = await TestFixture.CreateTextFixture("ftq_analyze");
+            await fixture.Table.CreateIndex(new[] { "content" }, new FtsIndex { LowerCase = false, Stem = false });
+
+            var tokens = await fixture.Table.AnalyzeFts("content_idx", "Running Dogs");
+
+            Assert.Equal(new[] { "Running", "Dogs" }, tokens.Select(t => t.Text));
+            await Assert.ThrowsAsync<LanceDbException>(
+                () => fixture.Table.AnalyzeFts("missing_idx", "Running"));
+        }
     }
 }
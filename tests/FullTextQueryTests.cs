@@ -184,5 +184,89 @@ namespace lancedb.tests
         {
             Assert.Throws<ArgumentNullException>(() => new MatchQuery("cat", "text").And(null!));
         }
+
+        [Fact]
+        public void ParseQueryString_BuildsTypedQueryTree()
+        {
+            var query = FullTextQuery.Parse(
+                "title:\"vector db\" AND (rust OR csharp) -java~2", new[] { "body" });
+
+            var root = Assert.IsType<BooleanQuery>(query);
+            Assert.Equal(2, root.Queries.Count);
+            Assert.Equal(Occur.Should, root.Queries[0].Occur);
+            var and = Assert.IsType<BooleanQuery>(root.Queries[0].Query);
+            var phrase = Assert.IsType<PhraseQuery>(and.Queries[0].Query);
+            Assert.Equal(("title", "vector db"), (phrase.Column, phrase.Query));
+            var either = Assert.IsType<BooleanQuery>(and.Queries[1].Query);
+            Assert.Equal("csharp", ((MatchQuery)either.Queries[1].Query).Query);
+
+            Assert.Equal(Occur.MustNot, root.Queries[1].Occur);
+            var java = Assert.IsType<MatchQuery>(root.Queries[1].Query);
+            Assert.Equal("body", java.Column);
+            Assert.Equal(2, java.Fuzziness);
+        }
+
+        [Fact]
+        public void MultiTermQueries_ToJson_MatchExpectedShape()
+        {
+            var prefix = (JsonObject)Parse(new PrefixQuery("contr", "text", maxExpansions: 10))["prefix"]!;
+            Assert.Equal("contr", (string?)prefix["prefix"]);
+            Assert.Equal("text", (string?)prefix["column"]);
+            Assert.Equal(10, (int)prefix["max_expansions"]!);
+
+            var wildcard = (JsonObject)Parse(new WildcardQuery("c?t*", "text"))["wildcard"]!;
+            Assert.Equal("c?t*", (string?)wildcard["pattern"]);
+
+            var range = (JsonObject)Parse(new TermRangeQuery("text", "a", "m", includeUpper: true))["term_range"]!;
+            Assert.Equal("a", (string?)range["gte"]);
+            Assert.Equal("m", (string?)range["lte"]);
+            Assert.False(range.ContainsKey("gt"));
+
+            var exists = (JsonObject)Parse(new ExistsQuery("text"))["exists"]!;
+            Assert.Equal("text", (string?)exists["column"]);
+        }
+
+        [Fact]
+        public void MultiTermQueries_InvalidArguments_Throw()
+        {
+            Assert.Throws<ArgumentException>(() => new PrefixQuery("", "text"));
+            Assert.Throws<ArgumentOutOfRangeException>(() => new WildcardQuery("a*", "text", maxExpansions: 0));
+            Assert.Throws<ArgumentOutOfRangeException>(() => new RegexQuery("a.*", "text", boost: 0f));
+            Assert.Throws<ArgumentException>(() => new TermRangeQuery("text", null, null));
+        }
+
+        [Fact]
+        public void BooleanQuery_MinimumShouldMatch_IsSerialized()
+        {
+            var query = new BooleanQuery(
+                new[]
+                {
+                    (Occur.Should, (FullTextQuery)new MatchQuery("a", "text")),
+                    (Occur.Should, new MatchQuery("b", "text")),
+                },
+                minimumShouldMatch: "75%");
+
+            var boolean = (JsonObject)Parse(query)["boolean"]!;
+            Assert.Equal("75%", (string?)boolean["minimum_should_match"]);
+            Assert.False(((JsonObject)Parse(new PhraseQuery("a b", "text"))["phrase"]!).ContainsKey("minimum_should_match"));
+        }
+
+        [Fact]
+        public void ParseQueryString_PhraseBoost_Throws()
+        {
+            var ex = Assert.Throws<FtsQueryParseException>(
+                () => FullTextQuery.Parse("\"vector db\"^2", new[] { "text" }));
+            Assert.Equal(11, ex.Position);
+            Assert.Equal("'^' is not supported on a phrase", ex.Reason);
+        }
+
+        [Fact]
+        public void ParseQueryString_InvalidQuery_ThrowsWithPosition()
+        {
+            var ex = Assert.Throws<FtsQueryParseException>(
+                () => FullTextQuery.Parse("body:rust AND", new[] { "body" }));
+            Assert.Equal(13, ex.Position);
+            Assert.Equal("unexpected end of query", ex.Reason);
+        }
     }
 }
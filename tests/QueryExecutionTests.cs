@@ -59,6 +59,34 @@ namespace lancedb.tests
             Assert.Equal(2, rows[2]["id"]);
         }
 
+        /// <summary>
+        /// Export should write the filtered result to newline-delimited JSON files.
+        /// </summary>
+        [Fact]
+        public async Task Export_WithFilter_WritesJsonFiles()
+        {
+            using var fixture = await TestFixture.CreateWithTable("export_query", CreateTestBatch(10));
+            var outDir = Path.Combine(Path.GetTempPath(), "lancedb_export_" + Guid.NewGuid().ToString("N"));
+            try
+            {
+                using var query = fixture.Table.Query().Where("id < 3");
+                var result = await query.Export(outDir, new FileExportOptions { Format = "json" });
+
+                Assert.Equal(3UL, result.NumRows);
+                Assert.Single(result.Files);
+                var lines = File.ReadAllLines(Path.Combine(outDir, "part-00000.json"));
+                Assert.Equal(3, lines.Length);
+                Assert.Equal("{\"id\":0}", lines[0]);
+            }
+            finally
+            {
+                if (Directory.Exists(outDir))
+                {
+                    Directory.Delete(outDir, true);
+                }
+            }
+        }
+
         /// <summary>
         /// Select with column names should only return the specified columns.
         /// </summary>
@@ -137,6 +165,118 @@ namespace lancedb.tests
             Assert.Equal(3, batch.Length);
         }
 
+        /// <summary>
+        /// OrderBy descending with Limit and Offset should return a deterministic page.
+        /// </summary>
+        [Fact]
+        public async Task OrderBy_DescendingWithLimitOffset_ReturnsSortedPage()
+        {
+            using var fixture = await TestFixture.CreateWithTable("order_by_test", CreateTestBatch(20));
+
+            using var query = fixture.Table.Query().OrderBy("id", ascending: false).Limit(3).Offset(2);
+            var batch = await query.ToArrow();
+
+            var ids = (Apache.Arrow.Int32Array)batch.Column("id");
+            Assert.Equal(new int?[] { 17, 16, 15 }, ids.ToArray());
+        }
+
+        /// <summary>
+        /// OrderBy on a column that does not exist should throw.
+        /// </summary>
+        [Fact]
+        public async Task OrderBy_UnknownColumn_ThrowsLanceDbException()
+        {
+            using var fixture = await TestFixture.CreateWithTable("order_by_bad", CreateTestBatch(5));
+
+            using var query = fixture.Table.Query().OrderBy("missing");
+            await Assert.ThrowsAsync<LanceDbException>(() => query.ToArrow());
+        }
+
+        /// <summary>
+        /// ToPage should walk every row exactly once, following page tokens.
+        /// </summary>
+        [Fact]
+        public async Task ToPage_FollowingTokens_ReturnsAllRowsInOrder()
+        {
+            using var fixture = await TestFixture.CreateWithTable("page_test", CreateTestBatch(10));
+
+            var ids = new List<int>();
+            string? token = null;
+            do
+            {
+                using var query = fixture.Table.Query().OrderBy("id").Limit(4);
+                var page = await query.ToPage(token);
+                var column = (Apache.Arrow.Int32Array)page.Batch.Column("id");
+                ids.AddRange(column.Values.ToArray());
+                token = page.NextPageToken;
+            }
+            while (token != null);
+
+            Assert.Equal(Enumerable.Range(0, 10), ids);
+        }
+
+        /// <summary>
+        /// A page token should resume at the version it was issued at, ignoring later writes.
+        /// </summary>
+        [Fact]
+        public async Task ToPage_AfterConcurrentAdd_ReadsTokenVersion()
+        {
+            using var fixture = await TestFixture.CreateWithTable("page_version_test", CreateTestBatch(10));
+
+            using var first = fixture.Table.Query().OrderBy("id").Limit(4);
+            var page = await first.ToPage();
+            var ids = new List<int>(((Apache.Arrow.Int32Array)page.Batch.Column("id")).Values.ToArray());
+            await fixture.Table.Add(CreateTestBatch(5, startId: 10));
+
+            var token = page.NextPageToken;
+            while (token != null)
+            {
+                using var query = fixture.Table.Query().OrderBy("id").Limit(4);
+                page = await query.ToPage(token);
+                ids.AddRange(((Apache.Arrow.Int32Array)page.Batch.Column("id")).Values.ToArray());
+                token = page.NextPageToken;
+            }
+
+            Assert.Equal(Enumerable.Range(0, 10), ids);
+        }
+
+        /// <summary>
+        /// A page token that was not issued by ToPage should be rejected.
+        /// </summary>
+        [Fact]
+        public async Task ToPage_InvalidToken_ThrowsLanceDbException()
+        {
+            using var fixture = await TestFixture.CreateWithTable("page_bad_token", CreateTestBatch(5));
+
+            using var query = fixture.Table.Query().OrderBy("id").Limit(2);
+            await Assert.ThrowsAsync<LanceDbException>(() => query.ToPage("not-a-token"));
+        }
+
+        /// <summary>
+        /// Paging should follow an order_by on an alias of a computed selection.
+        /// </summary>
+        [Fact]
+        public async Task ToPage_OrderByComputedAlias_ReturnsAllRowsInOrder()
+        {
+            using var fixture = await TestFixture.CreateWithTable("page_alias_test", CreateTestBatch(10));
+
+            var ids = new List<int>();
+            string? token = null;
+            do
+            {
+                using var query = fixture.Table.Query()
+                    .Select(new Dictionary<string, string> { { "id", "id" }, { "negated", "-id" } })
+                    .OrderBy("negated")
+                    .Limit(3);
+                var page = await query.ToPage(token);
+                ids.AddRange(((Apache.Arrow.Int32Array)page.Batch.Column("id")).Values.ToArray());
+                token = page.NextPageToken;
+            }
+            while (token != null);
+
+            Assert.Equal(Enumerable.Range(0, 10).Reverse(), ids);
+        }
+
         /// <summary>
         /// Builder methods should be chainable.
         /// </summary>
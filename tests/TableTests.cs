@@ -127,6 +127,24 @@ namespace lancedb.tests
             Assert.Equal(0, count);
         }
 
+        /// <summary>
+        /// Aggregate should compute count, sum and max over the filtered rows natively.
+        /// </summary>
+        [Fact]
+        public async Task Aggregate_WithFilter_ReturnsSingleRow()
+        {
+            using var fixture = await TestFixture.CreateWithTable("aggregate_test", CreateTestBatch(10));
+
+            var batch = await fixture.Table.Aggregate(
+                new[] { Aggregate.Count().As("n"), Aggregate.Sum("id"), Aggregate.Max("id") },
+                filter: "id < 5");
+
+            Assert.Equal(1, batch.Length);
+            Assert.Equal(5, ((Apache.Arrow.Int64Array)batch.Column("n")).GetValue(0));
+            Assert.Equal(10, ((Apache.Arrow.Int64Array)batch.Column("sum_id")).GetValue(0));
+            Assert.Equal(4, ((Apache.Arrow.Int32Array)batch.Column("max_id")).GetValue(0));
+        }
+
         /// <summary>
         /// Fires N FFI calls concurrently against distinct tables with distinct row counts
         /// and asserts each Task resolves with its own table's count. Validates that the
@@ -241,6 +259,247 @@ namespace lancedb.tests
             Assert.True(result.Version > 0);
         }
 
+        /// <summary>
+        /// Writes should accept a conflict retry policy.
+        /// </summary>
+        [Fact]
+        public async Task Delete_WithWriteOptions_DeletesRows()
+        {
+            using var fixture = await TestFixture.CreateWithTable("delete_write_options", CreateTestBatch(3));
+            var table = fixture.Table;
+            var options = new WriteOptions
+            {
+                MaxRetries = 3,
+                InitialBackoff = TimeSpan.FromMilliseconds(10),
+                MaxBackoff = TimeSpan.FromMilliseconds(100),
+            };
+
+            await table.Add(CreateTestBatch(2), new AddOptions { Write = options });
+            var result = await table.Delete("id = 0", options);
+
+            Assert.Equal(2UL, result.NumDeletedRows);
+            Assert.Equal(3, await table.CountRows());
+        }
+
+        /// <summary>
+        /// An update given write options should be committed by the staged write path.
+        /// </summary>
+        [Fact]
+        public async Task Update_WithFailFastWriteOptions_UpdatesRows()
+        {
+            using var fixture = await TestFixture.CreateWithTable("update_write_options", CreateTestBatch(3));
+            var table = fixture.Table;
+            var options = new WriteOptions { ConflictMode = ConflictMode.FailFast };
+
+            var result = await table.Update(
+                new Dictionary<string, string> { { "id", "id + 10" } }, "id >= 1", options);
+
+            Assert.Equal(2UL, result.RowsUpdated);
+            Assert.Equal(2, await table.CountRows("id >= 10"));
+        }
+
+        /// <summary>
+        /// Retrying an Add with the same idempotency key should resolve to the original commit
+        /// and report no inserted rows.
+        /// </summary>
+        [Fact]
+        public async Task Add_WithIdempotencyKey_AppliesOnce()
+        {
+            using var fixture = await TestFixture.CreateWithTable("add_idempotent", CreateTestBatch(2));
+            var table = fixture.Table;
+            var options = new AddOptions { Write = new WriteOptions { IdempotencyKey = "load-42" } };
+
+            Assert.Null(await table.FindIdempotencyKey("load-42"));
+
+            var first = await table.Add(new[] { CreateTestBatch(3) }, options);
+            var retried = await table.Add(new[] { CreateTestBatch(3) }, options);
+
+            Assert.Equal(first.Version, retried.Version);
+            Assert.Equal(3UL, first.NumInsertedRows);
+            Assert.Equal(0UL, retried.NumInsertedRows);
+            Assert.Equal(5, await table.CountRows());
+            Assert.Equal(first.Version, await table.FindIdempotencyKey("load-42"));
+        }
+
+        /// <summary>
+        /// Retrying a Delete with the same idempotency key should not delete rows added since.
+        /// </summary>
+        [Fact]
+        public async Task Delete_WithIdempotencyKey_AppliesOnce()
+        {
+            using var fixture = await TestFixture.CreateWithTable("delete_idempotent", CreateTestBatch(3));
+            var table = fixture.Table;
+            var options = new WriteOptions { IdempotencyKey = "purge-1" };
+
+            var first = await table.Delete("id = 0", options);
+            await table.Add(new[] { CreateTestBatch(1) });
+            var retried = await table.Delete("id = 0", options);
+
+            Assert.Equal(1UL, first.NumDeletedRows);
+            Assert.Equal(first.Version, retried.Version);
+            Assert.Equal(0UL, retried.NumDeletedRows);
+            Assert.Equal(3, await table.CountRows());
+        }
+
+        /// <summary>
+        /// Add with OnDuplicateKey = Skip should only append rows whose primary key is new.
+        /// </summary>
+        [Fact]
+        public async Task Add_OnDuplicateKeySkip_AppendsOnlyNewKeys()
+        {
+            using var fixture = await TestFixture.CreateWithTable("add_dedup_skip", CreateTestBatch(3));
+            var table = fixture.Table;
+            await table.SetUnenforcedPrimaryKey(new[] { "id" });
+
+            var result = await table.Add(
+                new[] { CreateTestBatch(3, startId: 2) },
+                new AddOptions { OnDuplicateKey = DuplicateKeyHandling.Skip });
+
+            Assert.Equal(2UL, result.NumInsertedRows);
+            Assert.Equal(1UL, result.NumSkippedRows);
+            Assert.Equal(5, await table.CountRows());
+            Assert.Equal(1, await table.CountRows("id = 2"));
+        }
+
+        /// <summary>
+        /// Add with CastToTableSchema should widen int32 ids into an int64 table column.
+        /// </summary>
+        [Fact]
+        public async Task Add_CastToTableSchema_WidensColumns()
+        {
+            var schema = new Apache.Arrow.Schema.Builder()
+                .Field(new Apache.Arrow.Field("id", Apache.Arrow.Types.Int64Type.Default, nullable: false))
+                .Build();
+            var initial = new Apache.Arrow.RecordBatch(schema,
+                new Apache.Arrow.IArrowArray[] { new Apache.Arrow.Int64Array.Builder().Append(1).Build() }, 1);
+            using var fixture = await TestFixture.CreateWithTable("add_cast", initial);
+            var table = fixture.Table;
+
+            await table.Add(new[] { CreateTestBatch(2, startId: 5) }, new AddOptions { CastToTableSchema = true });
+
+            Assert.Equal(3, await table.CountRows());
+            Assert.Equal(2, await table.CountRows("id >= 5"));
+        }
+
+        /// <summary>
+        /// AddFromFiles should parse CSV rows with the table schema and append them.
+        /// </summary>
+        [Fact]
+        public async Task AddFromFiles_Csv_AppendsRows()
+        {
+            using var fixture = await TestFixture.CreateWithTable("add_from_files", CreateTestBatch(2));
+            var table = fixture.Table;
+            var csv = Path.Combine(Path.GetTempPath(), "lancedb_test_" + Guid.NewGuid().ToString("N") + ".csv");
+            try
+            {
+                File.WriteAllText(csv, "id\n10\n11\n12\n");
+
+                var result = await table.AddFromFiles(new[] { csv }, new FileReadOptions { Format = "csv" });
+
+                Assert.Equal(await table.Version(), result.Version);
+                Assert.Equal(5, await table.CountRows());
+                Assert.Equal(3, await table.CountRows("id >= 10"));
+            }
+            finally
+            {
+                File.Delete(csv);
+            }
+        }
+
+        /// <summary>
+        /// Export should write a past version to CSV, split across files.
+        /// </summary>
+        [Fact]
+        public async Task Export_PastVersion_SplitsCsvFiles()
+        {
+            using var fixture = await TestFixture.CreateWithTable("export_table", CreateTestBatch(5));
+            var table = fixture.Table;
+            ulong version = await table.Version();
+            await table.Add(CreateTestBatch(3, startId: 100));
+            var outDir = Path.Combine(Path.GetTempPath(), "lancedb_export_" + Guid.NewGuid().ToString("N"));
+            try
+            {
+                var result = await table.Export(
+                    outDir, new FileExportOptions { Format = "csv", MaxRowsPerFile = 2 }, version);
+
+                Assert.Equal(5UL, result.NumRows);
+                Assert.Equal(3, result.Files.Count);
+                Assert.Equal(new[] { "id", "4" }, File.ReadAllLines(Path.Combine(outDir, "part-00002.csv")));
+                Assert.Equal(8, await table.CountRows());
+            }
+            finally
+            {
+                if (Directory.Exists(outDir))
+                {
+                    Directory.Delete(outDir, true);
+                }
+            }
+        }
+
+        /// <summary>
+        /// GetByKeys should return rows in key order, with null rows for missing keys.
+        /// </summary>
+        [Fact]
+        public async Task GetByKeys_ReturnsRowsInKeyOrderWithNullsForMisses()
+        {
+            using var fixture = await TestFixture.CreateWithTable("get_by_keys", CreateTestBatch(3));
+            var table = fixture.Table;
+            await table.SetUnenforcedPrimaryKey(new[] { "id" });
+
+            var keys = new Apache.Arrow.Int32Array.Builder().Append(2).Append(7).Append(0).Build();
+            var batch = await table.GetByKeys(keys, new[] { "id" });
+
+            Assert.Equal(3, batch.Length);
+            var ids = (Apache.Arrow.Int32Array)batch.Column("id");
+            Assert.Equal(2, ids.GetValue(0));
+            Assert.Null(ids.GetValue(1));
+            Assert.Equal(0, ids.GetValue(2));
+        }
+
+        /// <summary>
+        /// DeleteRowIds with returnRows should delete exactly the given rows and return them.
+        /// </summary>
+        [Fact]
+        public async Task DeleteRowIds_ReturnRows_ReturnsDeletedRows()
+        {
+            using var fixture = await TestFixture.CreateWithTable("delete_rowids", CreateTestBatch(5));
+            var table = fixture.Table;
+
+            using var query = table.Query().Where("id >= 3").WithRowId();
+            var batch = await query.ToArrow();
+            var rowIds = ((Apache.Arrow.UInt64Array)batch.Column("_rowid")).Values.ToArray();
+
+            var result = await table.DeleteRowIds(rowIds, returnRows: true, columns: new[] { "id" });
+
+            Assert.Equal(2UL, result.NumDeletedRows);
+            Assert.NotNull(result.DeletedRows);
+            var ids = ((Apache.Arrow.Int32Array)result.DeletedRows!.Column("id")).Values.ToArray();
+            Assert.Equal(new[] { 3, 4 }, ids.OrderBy(i => i));
+            Assert.Equal(3, await table.CountRows());
+        }
+
+        /// <summary>
+        /// DeleteRowIds should return only the requested columns, with _rowid when it is listed.
+        /// </summary>
+        [Fact]
+        public async Task DeleteRowIds_ReturnRows_IncludesRowIdOnlyWhenRequested()
+        {
+            using var fixture = await TestFixture.CreateWithTable("delete_rowids_columns", CreateTestBatch(5));
+            var table = fixture.Table;
+
+            using var query = table.Query().WithRowId();
+            var rowIds = ((Apache.Arrow.UInt64Array)(await query.ToArrow()).Column("_rowid")).Values.ToArray();
+
+            var withoutRowId = await table.DeleteRowIds(new[] { rowIds[0] }, returnRows: true);
+            Assert.Null(withoutRowId.DeletedRows!.Schema.GetFieldByName("_rowid"));
+
+            var withRowId = await table.DeleteRowIds(
+                new[] { rowIds[1] }, returnRows: true, columns: new[] { "id", "_rowid" });
+            var returned = (Apache.Arrow.UInt64Array)withRowId.DeletedRows!.Column("_rowid");
+            Assert.Equal(rowIds[1], returned.GetValue(0));
+            Assert.Equal(3, await table.CountRows());
+        }
+
         /// <summary>
         /// Update with no matching rows should not throw.
         /// </summary>
@@ -369,6 +628,26 @@ namespace lancedb.tests
             Assert.Equal(5, count);
         }
 
+        /// <summary>
+        /// Update from an Arrow batch overwrites matched rows by key and
+        /// ignores source rows that have no match.
+        /// </summary>
+        [Fact]
+        public async Task Update_FromBatch_UpdatesMatchedRowsOnly()
+        {
+            using var fixture = await TestFixture.CreateWithTable("update_from_batch",
+                CreateIdValueBatch(new[] { 1, 2, 3 }, new[] { "a", "b", "c" }));
+
+            var result = await fixture.Table.Update(new[] { "id" },
+                CreateIdValueBatch(new[] { 2, 4 }, new[] { "B", "D" }));
+
+            Assert.Equal(1UL, result.RowsUpdated);
+            Assert.True(result.Version > 0);
+            Assert.Equal(3, await fixture.Table.CountRows());
+            Assert.Equal(1, await fixture.Table.CountRows("id = 2 AND value = 'B'"));
+            Assert.Equal(0, await fixture.Table.CountRows("id = 4"));
+        }
+
         /// <summary>
         /// Add a single RecordBatch and verify row count increases.
         /// </summary>
@@ -588,6 +867,72 @@ namespace lancedb.tests
             Assert.True(parsed.Year >= 2024, $"Timestamp year {parsed.Year} seems too old");
         }
 
+        /// <summary>
+        /// ListVersions should report the operation, row delta and commit metadata of each write.
+        /// </summary>
+        [Fact]
+        public async Task ListVersions_ReportsOperationRowsAndCommitMetadata()
+        {
+            using var fixture = await TestFixture.CreateWithTable("version_audit", CreateTestBatch(3));
+            var table = fixture.Table;
+
+            var added = await table.Add(new[] { CreateTestBatch(2, startId: 3) }, new AddOptions
+            {
+                Write = new WriteOptions
+                {
+                    CommitMetadata = new Dictionary<string, string> { ["job_id"] = "ingest-7" },
+                },
+            });
+            var deleted = await table.Delete("id = 0", new WriteOptions
+            {
+                CommitMetadata = new Dictionary<string, string> { ["user"] = "alice" },
+            });
+
+            var versions = await table.ListVersions();
+
+            var addVersion = versions.Single(v => v.Version == added.Version);
+            Assert.Equal("Append", addVersion.Operation);
+            Assert.Equal(5UL, addVersion.NumRows);
+            Assert.Equal(2L, addVersion.NumRowsDelta);
+            Assert.Equal("ingest-7", addVersion.CommitMetadata["job_id"]);
+
+            var deleteVersion = versions.Single(v => v.Version == deleted.Version);
+            Assert.Equal("Delete", deleteVersion.Operation);
+            Assert.Equal(-1L, deleteVersion.NumRowsDelta);
+            Assert.Equal("alice", deleteVersion.CommitMetadata["user"]);
+        }
+
+        /// <summary>
+        /// Schema changes with commit metadata should store it in their own commit.
+        /// </summary>
+        [Fact]
+        public async Task ListVersions_ReportsCommitMetadataOfSchemaChanges()
+        {
+            using var fixture = await TestFixture.CreateWithTable("schema_audit", CreateTestBatch(3));
+            var table = fixture.Table;
+
+            var added = await table.AddColumns(
+                new Dictionary<string, string> { { "doubled", "id * 2" } },
+                new Dictionary<string, string> { ["job_id"] = "backfill-1" });
+            var dropped = await table.DropColumns(
+                new[] { "doubled" },
+                new Dictionary<string, string> { ["job_id"] = "rollback-1" });
+
+            var versions = await table.ListVersions();
+
+            var addVersion = versions.Single(v => v.Version == added.Version);
+            Assert.Equal("Merge", addVersion.Operation);
+            Assert.Equal(0L, addVersion.NumRowsDelta);
+            Assert.Equal("backfill-1", addVersion.CommitMetadata["job_id"]);
+
+            var dropVersion = versions.Single(v => v.Version == dropped.Version);
+            Assert.Equal("Project", dropVersion.Operation);
+            Assert.Equal("rollback-1", dropVersion.CommitMetadata["job_id"]);
+
+            await table.Checkout(added.Version);
+            Assert.Equal(1L, await table.CountRows("doubled = 4"));
+        }
+
         /// <summary>
         /// Checkout a previous version and verify row count matches that version.
         /// </summary>
@@ -676,6 +1021,51 @@ namespace lancedb.tests
             Assert.Contains(indices, i => i.Columns.Contains("id") && i.IndexType == IndexType.Bitmap);
         }
 
+        /// <summary>
+        /// A partial BTree build with a zone size should only index the
+        /// listed fragments.
+        /// </summary>
+        [Fact]
+        public async Task CreateIndex_BTreeBuildOptions_IndexesListedFragments()
+        {
+            using var fixture = await TestFixture.CreateWithTable("btree_build_options");
+            await fixture.Table.Add(CreateTestBatch(100));
+            await fixture.Table.Add(CreateTestBatch(50, startId: 100));
+
+            var index = new BTreeIndex { ZoneSize = 16, MemoryLimitMb = 64, PartialBuildFragments = new[] { 0 } };
+            await fixture.Table.CreateIndex(new[] { "id" }, index, name: "id_idx");
+
+            var stats = await fixture.Table.IndexStats("id_idx");
+            Assert.NotNull(stats);
+            Assert.Equal(100, (int)stats!.NumIndexedRows);
+            Assert.Equal(50, (int)stats.NumUnindexedRows);
+        }
+
+        /// <summary>
+        /// The string variants of a BTree index should build on string columns and
+        /// be rejected on other columns.
+        /// </summary>
+        [Fact]
+        public async Task CreateIndex_BTreeStringVariants_RequireStringColumn()
+        {
+            using var fixture = await TestFixture.CreateWithTable("btree_variants", CreateTwoColumnBatch(20));
+
+            await fixture.Table.CreateIndex(
+                new[] { "name" }, new BTreeIndex { Variant = StringIndexVariant.Ngram }, name: "name_ngram");
+            await fixture.Table.CreateIndex(
+                new[] { "name" },
+                new BTreeIndex { Variant = StringIndexVariant.Inverted, Tokenizer = new FtsIndex { Stem = false } },
+                name: "name_inverted");
+
+            var indices = await fixture.Table.ListIndices();
+            Assert.Contains(indices, i => i.Name == "name_ngram");
+            Assert.Contains(indices, i => i.Name == "name_inverted");
+
+            var ex = await Assert.ThrowsAsync<LanceDbException>(() => fixture.Table.CreateIndex(
+                new[] { "id" }, new BTreeIndex { Variant = StringIndexVariant.Ngram }));
+            Assert.Contains("needs a string column", ex.Message);
+        }
+
         /// <summary>
         /// ListIndices on a table with no indices should return an empty list.
         /// </summary>
@@ -1840,6 +2230,255 @@ namespace lancedb.tests
             Assert.Equal(5, await fixture.Table.CountRows());
         }
 
+        /// <summary>
+        /// A conditional column update and a conditional by-source delete run as
+        /// one merge; unmatched columns keep their values.
+        /// </summary>
+        [Fact]
+        public async Task MergeInsert_ConditionalUpdateWithSet_UpdatesOnlySetColumns()
+        {
+            using var fixture = await TestFixture.CreateWithTable("merge_clauses",
+                CreateIdValueBatch(new[] { 1, 2, 3 }, new[] { "a", "b", "c" }));
+
+            var newData = CreateIdValueBatch(new[] { 1, 2 }, new[] { "x", "skip" });
+            var result = await fixture.Table.MergeInsert("id")
+                .WhenMatchedUpdate(
+                    new Dictionary<string, string> { { "value", "upper(source.value)" } },
+                    "source.value <> 'skip'")
+                .WhenNotMatchedBySourceDelete("target.id = 3")
+                .Execute(newData);
+
+            Assert.Equal(1UL, result.NumUpdatedRows);
+            Assert.Equal(1UL, result.NumDeletedRows);
+            Assert.Equal(1, await fixture.Table.CountRows("id = 1 AND value = 'X'"));
+            Assert.Equal(1, await fixture.Table.CountRows("id = 2 AND value = 'b'"));
+            Assert.Equal(0, await fixture.Table.CountRows("id = 3"));
+        }
+
+        /// <summary>
+        /// WhenNotMatchedBySourceUpdate sets columns of target rows the source no longer holds.
+        /// </summary>
+        [Fact]
+        public async Task MergeInsert_WhenNotMatchedBySourceUpdate_UpdatesUnmatchedTargetRows()
+        {
+            using var fixture = await TestFixture.CreateWithTable("merge_by_source_update",
+                CreateIdValueBatch(new[] { 1, 2, 3 }, new[] { "a", "b", "c" }));
+
+            var result = await fixture.Table.MergeInsert("id")
+                .WhenMatchedUpdateAll()
+                .WhenNotMatchedBySourceUpdate(
+                    new Dictionary<string, string> { { "value", "concat(value, '-gone')" } }, "id > 2")
+                .Execute(CreateIdValueBatch(new[] { 1 }, new[] { "x" }));
+
+            Assert.Equal(2UL, result.NumUpdatedRows);
+            Assert.Equal(1, await fixture.Table.CountRows("id = 1 AND value = 'x'"));
+            Assert.Equal(1, await fixture.Table.CountRows("id = 2 AND value = 'b'"));
+            Assert.Equal(1, await fixture.Table.CountRows("id = 3 AND value = 'c-gone'"));
+        }
+
+        /// <summary>
+        /// WhenMatchedUpdate expressions can read target columns while new rows are inserted.
+        /// </summary>
+        [Fact]
+        public async Task MergeInsert_WhenMatchedUpdateReadingTarget_WithInsert()
+        {
+            using var fixture = await TestFixture.CreateWithTable("merge_set_target",
+                CreateIdValueBatch(new[] { 1, 2 }, new[] { "a", "b" }));
+
+            var result = await fixture.Table.MergeInsert("id")
+                .WhenMatchedUpdate(
+                    new Dictionary<string, string> { { "value", "concat(target.value, source.value)" } })
+                .WhenNotMatchedInsertAll()
+                .Execute(CreateIdValueBatch(new[] { 1, 3 }, new[] { "x", "c" }));
+
+            Assert.Equal(1UL, result.NumUpdatedRows);
+            Assert.Equal(1UL, result.NumInsertedRows);
+            Assert.Equal(1, await fixture.Table.CountRows("id = 1 AND value = 'ax'"));
+            Assert.Equal(1, await fixture.Table.CountRows("id = 3 AND value = 'c'"));
+        }
+
+        /// <summary>
+        /// WhenMatchedDelete removes matched rows while unmatched source rows are inserted.
+        /// </summary>
+        [Fact]
+        public async Task MergeInsert_WhenMatchedDelete_DeletesMatchesAndInsertsRest()
+        {
+            using var fixture = await TestFixture.CreateWithTable("merge_delete",
+                CreateIdValueBatch(new[] { 1, 2, 3 }, new[] { "a", "b", "c" }));
+
+            var result = await fixture.Table.MergeInsert("id")
+                .WhenMatchedDelete()
+                .WhenNotMatchedInsertAll()
+                .Execute(CreateIdValueBatch(new[] { 1, 4 }, new[] { "x", "d" }));
+
+            Assert.Equal(1UL, result.NumInsertedRows);
+            Assert.Equal(1UL, result.NumDeletedRows);
+            Assert.Equal(0, await fixture.Table.CountRows("id = 1"));
+            Assert.Equal(3, await fixture.Table.CountRows());
+        }
+
+        /// <summary>
+        /// Two matched clauses cannot run as one merge and are rejected.
+        /// </summary>
+        [Fact]
+        public async Task MergeInsert_TwoMatchedClauses_Throws()
+        {
+            using var fixture = await TestFixture.CreateWithTable("merge_two_matched",
+                CreateIdValueBatch(new[] { 1 }, new[] { "a" }));
+
+            await Assert.ThrowsAsync<LanceDbException>(() => fixture.Table.MergeInsert("id")
+                .WhenMatchedDelete()
+                .WhenMatchedUpdateAll()
+                .Execute(CreateIdValueBatch(new[] { 1 }, new[] { "b" })));
+        }
+
+        /// <summary>
+        /// ExecuteWithAffectedRows reports the action taken for each key.
+        /// </summary>
+        [Fact]
+        public async Task MergeInsert_ExecuteWithAffectedRows_ReportsActionsPerKey()
+        {
+            using var fixture = await TestFixture.CreateWithTable("merge_affected",
+                CreateIdValueBatch(new[] { 1, 2, 3 }, new[] { "a", "b", "c" }));
+
+            var output = await fixture.Table.MergeInsert("id")
+                .WhenMatchedUpdateAll()
+                .WhenNotMatchedInsertAll()
+                .WhenNotMatchedBySourceDelete("target.id = 1")
+                .ExecuteWithAffectedRows(CreateIdValueBatch(new[] { 2, 4 }, new[] { "B", "D" }));
+
+            Assert.Equal(1UL, output.Result.NumInsertedRows);
+            Assert.Equal(1UL, output.Result.NumUpdatedRows);
+            Assert.Equal(1UL, output.Result.NumDeletedRows);
+
+            var batch = output.AffectedRows;
+            var ids = (Apache.Arrow.Int32Array)batch.Column("id");
+            var actions = (Apache.Arrow.StringArray)batch.Column("action");
+            var rowIds = (Apache.Arrow.UInt64Array)batch.Column("_rowid");
+            var byId = new Dictionary<int, (string Action, bool HasRowId)>();
+            for (int i = 0; i < batch.Length; i++)
+            {
+                byId[ids.GetValue(i)!.Value] = (actions.GetString(i), rowIds.IsValid(i));
+            }
+
+            Assert.Equal(3, byId.Count);
+            Assert.Equal(("deleted", false), byId[1]);
+            Assert.Equal(("updated", true), byId[2]);
+            Assert.Equal(("inserted", true), byId[4]);
+        }
+
+        /// <summary>
+        /// A transaction's staged delete, add and update land in one version.
+        /// </summary>
+        [Fact]
+        public async Task Transaction_Commit_AppliesStagedOpsAsOneVersion()
+        {
+            using var fixture = await TestFixture.CreateWithTable("txn_commit",
+                CreateIdValueBatch(new[] { 1, 2, 3 }, new[] { "doc-a", "doc-a", "doc-b" }));
+
+            using var txn = await fixture.Table.BeginTransaction(new[] { "id" });
+            await txn.Delete("value = 'doc-a'");
+            await txn.Add(CreateIdValueBatch(new[] { 10, 11 }, new[] { "doc-a", "doc-a" }));
+            await txn.Update(new Dictionary<string, string> { { "value", "'doc-b2'" } }, "id = 3");
+
+            Assert.Equal(0, await fixture.Table.CountRows("id >= 10"));
+
+            var result = await txn.Commit();
+
+            Assert.Equal(txn.BaseVersion + 1, result.Version);
+            Assert.Equal(3, await fixture.Table.CountRows());
+            Assert.Equal(2, await fixture.Table.CountRows("id >= 10"));
+            Assert.Equal(1, await fixture.Table.CountRows("value = 'doc-b2'"));
+        }
+
+        /// <summary>
+        /// Committing after another writer changed a touched row reports a conflict.
+        /// </summary>
+        [Fact]
+        public async Task Transaction_CommitAfterConcurrentChangeToSameRow_Throws()
+        {
+            using var fixture = await TestFixture.CreateWithTable("txn_conflict",
+                CreateIdValueBatch(new[] { 1, 2 }, new[] { "a", "b" }));
+
+            using var txn = await fixture.Table.BeginTransaction(new[] { "id" });
+            await txn.Update(new Dictionary<string, string> { { "value", "'a2'" } }, "id = 1");
+            using (var other = await fixture.Table.BeginTransaction(new[] { "id" }))
+            {
+                await other.Delete("id = 1");
+                await other.Commit();
+            }
+
+            var ex = await Assert.ThrowsAsync<LanceDbException>(() => txn.Commit());
+            Assert.Contains("conflict", ex.Message, StringComparison.OrdinalIgnoreCase);
+            Assert.Equal(1, await fixture.Table.CountRows());
+        }
+
+        /// <summary>
+        /// A concurrent append to other rows does not conflict with the transaction.
+        /// </summary>
+        [Fact]
+        public async Task Transaction_CommitAfterConcurrentAppend_KeepsBoth()
+        {
+            using var fixture = await TestFixture.CreateWithTable("txn_append",
+                CreateIdValueBatch(new[] { 1 }, new[] { "a" }));
+
+            using var txn = await fixture.Table.BeginTransaction(new[] { "id" });
+            await txn.Delete("id = 1");
+            await fixture.Table.Add(CreateIdValueBatch(new[] { 2 }, new[] { "b" }));
+
+            var result = await txn.Commit();
+
+            Assert.Equal(1UL, result.NumDeletedRows);
+            Assert.Equal(1, await fixture.Table.CountRows());
+            Assert.Equal(1, await fixture.Table.CountRows("id = 2"));
+        }
+
+        /// <summary>
+        /// Adding a row whose key already exists fails the commit.
+        /// </summary>
+        [Fact]
+        public async Task Transaction_AddExistingKey_Throws()
+        {
+            using var fixture = await TestFixture.CreateWithTable("txn_existing_key",
+                CreateIdValueBatch(new[] { 1 }, new[] { "a" }));
+
+            using var txn = await fixture.Table.BeginTransaction(new[] { "id" });
+            await txn.Add(CreateIdValueBatch(new[] { 1 }, new[] { "dup" }));
+
+            await Assert.ThrowsAsync<LanceDbException>(() => txn.Commit());
+            Assert.Equal(1, await fixture.Table.CountRows("value = 'a'"));
+        }
+
+        /// <summary>
+        /// A transaction without keys stages new columns and commits once per idempotency key.
+        /// </summary>
+        [Fact]
+        public async Task Transaction_WithoutKeys_AddColumnsAndIdempotencyKey()
+        {
+            using var fixture = await TestFixture.CreateWithTable("txn_add_columns",
+                CreateIdValueBatch(new[] { 1, 2 }, new[] { "a", "b" }));
+            var options = new WriteOptions { IdempotencyKey = "txn-7" };
+
+            using (var txn = await fixture.Table.BeginTransaction())
+            {
+                await txn.AddColumns(new Dictionary<string, string> { { "doubled", "id * 2" } });
+                await txn.Add(CreateIdValueBatch(new[] { 1 }, new[] { "c" }));
+                var result = await txn.Commit(options);
+                Assert.Equal(1UL, result.NumInsertedRows);
+            }
+
+            using (var txn = await fixture.Table.BeginTransaction())
+            {
+                await txn.Add(CreateIdValueBatch(new[] { 1 }, new[] { "c" }));
+                var replayed = await txn.Commit(options);
+                Assert.Equal(0UL, replayed.NumInsertedRows);
+            }
+
+            Assert.Equal(3, await fixture.Table.CountRows());
+            Assert.Equal(1, await fixture.Table.CountRows("doubled = 4"));
+            Assert.Equal(1, await fixture.Table.CountRows("id = 1 AND doubled IS NULL"));
+        }
+
         /// <summary>
         /// CreateIndex with IvfFlat on a vector column should succeed.
         /// </summary>
@@ -1949,6 +2588,86 @@ namespace lancedb.tests
             Assert.Contains(indices, i => i.Columns.Contains("vector") && i.IndexType == IndexType.IvfRq);
         }
 
+        /// <summary>
+        /// Setting the number of SQ bits or the RQ rotation should build the index.
+        /// </summary>
+        [Fact]
+        public async Task CreateIndex_SqBitsAndRqRotation_Succeeds()
+        {
+            using var fixture = await TestFixture.CreateWithTable("sq_rq_options",
+                CreateVectorBatch(256));
+
+            await fixture.Table.CreateIndex(new[] { "vector" }, new IvfSqIndex { NumPartitions = 2, NumBits = 8 });
+            await fixture.Table.CreateIndex(
+                new[] { "vector" }, new IvfRqIndex { NumPartitions = 2, RotationType = RqRotationType.Fast },
+                name: "vector_rq");
+
+            var indices = await fixture.Table.ListIndices();
+            Assert.Contains(indices, i => i.IndexType == IndexType.IvfSq);
+            Assert.Contains(indices, i => i.Name == "vector_rq" && i.IndexType == IndexType.IvfRq);
+        }
+
+        /// <summary>
+        /// An index built from another table's index model should reuse its partitions.
+        /// </summary>
+        [Fact]
+        public async Task CreateIndex_WithModelFromIndex_Succeeds()
+        {
+            using var fixture = await TestFixture.CreateWithTable("model_source",
+                CreateVectorBatch(256));
+            await fixture.Table.CreateIndex(
+                new[] { "vector" }, new IvfPqIndex { NumPartitions = 2, NumSubVectors = 2 }, name: "vector_idx");
+            using var target = await fixture.Connection.CreateTable("model_target", CreateVectorBatch(128));
+
+            await target.CreateIndex(
+                new[] { "vector" }, new IvfPqIndex { NumSubVectors = 2 },
+                VectorIndexModel.FromIndex(fixture.Table, "vector_idx"), name: "vector_idx");
+
+            var stats = await target.IndexStats("vector_idx");
+            Assert.NotNull(stats);
+            Assert.Equal(IndexType.IvfPq, stats!.IndexType);
+            Assert.Equal(128, (int)stats.NumIndexedRows);
+        }
+
+        /// <summary>
+        /// An index built from given centroids should get one partition per centroid,
+        /// and centroids of the wrong dimension should be rejected.
+        /// </summary>
+        [Fact]
+        public async Task CreateIndex_WithModelFromCentroids_Succeeds()
+        {
+            using var fixture = await TestFixture.CreateWithTable("model_centroids",
+                CreateVectorBatch(256));
+
+            await fixture.Table.CreateIndex(
+                new[] { "vector" }, new IvfFlatIndex(),
+                VectorIndexModel.FromCentroids(CreateCentroids(3, 8)), name: "vector_idx");
+
+            var stats = await fixture.Table.IndexStats("vector_idx");
+            Assert.NotNull(stats);
+            Assert.Equal(256, (int)stats!.NumIndexedRows);
+
+            var ex = await Assert.ThrowsAsync<LanceDbException>(() => fixture.Table.CreateIndex(
+                new[] { "vector" }, new IvfFlatIndex(), VectorIndexModel.FromCentroids(CreateCentroids(3, 4))));
+            Assert.Contains("dimension", ex.Message);
+        }
+
+        private static Apache.Arrow.FixedSizeListArray CreateCentroids(int count, int dimension)
+        {
+            var valueField = new Apache.Arrow.Field("item", Apache.Arrow.Types.FloatType.Default, nullable: false);
+            var builder = new Apache.Arrow.FixedSizeListArray.Builder(valueField, dimension);
+            var values = (Apache.Arrow.FloatArray.Builder)builder.ValueBuilder;
+            for (int i = 0; i < count; i++)
+            {
+                builder.Append();
+                for (int d = 0; d < dimension; d++)
+                {
+                    values.Append((float)i / count);
+                }
+            }
+            return builder.Build();
+        }
+
         /// <summary>
         /// CreateIndex with HnswSq on a vector column should succeed and report IvfHnswSq.
         /// This is the index type used in the README quick-start example.